@@ -1,15 +1,26 @@
-use arb_core::{RiskEngine, SharedState};
+use crate::control;
+use arb_core::config::OperatorConfig;
+use arb_core::{
+    ArbitrageOpportunity, AuditLog, EventMetrics, KillSwitchState, OpportunityFilter,
+    OpportunityStatus, Position, RiskEngine, SharedState, StrategyType, Venue,
+};
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::StatusCode,
+    response::sse::{Event as SseEvent, KeepAlive, Sse},
     response::Json,
     routing::get,
     Router,
 };
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use futures::stream::{self, Stream};
 use rust_decimal::prelude::*;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
+use connectors::ConnectorRegistry;
 use std::sync::Arc;
+use strategies::{StrategyManager, StrategyStatus};
 use tower_http::cors::CorsLayer;
 
 #[derive(Clone)]
@@ -18,6 +29,14 @@ pub struct ApiState {
     pub risk_engine: Arc<RiskEngine>,
     pub strategies_enabled: StrategiesConfig,
     pub dry_run: bool,
+    pub event_metrics: EventMetrics,
+    pub strategies: Arc<StrategyManager>,
+    pub connectors: Arc<ConnectorRegistry>,
+    /// Venues a flatten-all closes positions on.
+    pub perp_venues: Vec<Venue>,
+    /// Who may use the control endpoints.
+    pub operators: Arc<Vec<OperatorConfig>>,
+    pub audit: Arc<AuditLog>,
 }
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -32,26 +51,105 @@ pub struct StatusResponse {
     pub status: String,
     pub strategies: StrategiesConfig,
     pub kill_switch_active: bool,
+    pub kill_switch: KillSwitchResponse,
+    pub daily_pnl: f64,
+    pub next_rollover: String,
     pub dry_run: bool,
     pub timestamp: String,
 }
 
+#[derive(Serialize)]
+pub struct KillSwitchResponse {
+    pub state: String,
+    pub reason: Option<String>,
+    pub tripped_at: Option<String>,
+    pub cooling_down_until: Option<String>,
+}
+
+impl From<KillSwitchState> for KillSwitchResponse {
+    fn from(state: KillSwitchState) -> Self {
+        match state {
+            KillSwitchState::Armed => Self {
+                state: "armed".to_string(),
+                reason: None,
+                tripped_at: None,
+                cooling_down_until: None,
+            },
+            KillSwitchState::Tripped { reason, at } => Self {
+                state: "tripped".to_string(),
+                reason: Some(reason.to_string()),
+                tripped_at: Some(at.to_rfc3339()),
+                cooling_down_until: None,
+            },
+            KillSwitchState::CoolingDown { reason, until } => Self {
+                state: "cooling_down".to_string(),
+                reason: Some(reason.to_string()),
+                tripped_at: None,
+                cooling_down_until: Some(until.to_rfc3339()),
+            },
+        }
+    }
+}
+
+/// Opportunities served per page when `limit` is not given.
+const DEFAULT_OPPORTUNITY_PAGE: usize = 100;
+const MAX_OPPORTUNITY_PAGE: usize = 1000;
+
+/// `/api/opportunities` filters; `since` and `until` are RFC 3339 timestamps.
+#[derive(Debug, Deserialize)]
+pub struct OpportunitiesQuery {
+    pub strategy: Option<StrategyType>,
+    pub symbol: Option<String>,
+    pub status: Option<OpportunityStatus>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
 #[derive(Serialize)]
 pub struct OpportunitiesResponse {
     pub opportunities: Vec<OpportunityResponse>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
 }
 
 #[derive(Serialize)]
 pub struct OpportunityResponse {
+    pub id: u64,
     pub strategy: String,
     pub symbol: String,
     pub venue_a: String,
     pub venue_b: String,
+    pub price_a: f64,
+    pub price_b: f64,
     pub spread_bps: i64,
     pub estimated_profit: f64,
+    pub status: OpportunityStatus,
+    pub reason: Option<String>,
     pub timestamp: String,
 }
 
+impl From<&ArbitrageOpportunity> for OpportunityResponse {
+    fn from(o: &ArbitrageOpportunity) -> Self {
+        OpportunityResponse {
+            id: o.id,
+            strategy: format!("{:?}", o.strategy),
+            symbol: o.symbol.clone(),
+            venue_a: format!("{:?}", o.venue_a),
+            venue_b: format!("{:?}", o.venue_b),
+            price_a: o.price_a.to_f64().unwrap_or(0.0),
+            price_b: o.price_b.to_f64().unwrap_or(0.0),
+            spread_bps: o.spread_bps,
+            estimated_profit: o.estimated_profit.to_f64().unwrap_or(0.0),
+            status: o.status,
+            reason: o.reason.clone(),
+            timestamp: o.timestamp.to_rfc3339(),
+        }
+    }
+}
+
 #[derive(Serialize)]
 pub struct PositionsResponse {
     pub positions: Vec<PositionResponse>,
@@ -67,26 +165,106 @@ pub struct PositionResponse {
     pub leverage: f64,
 }
 
+#[derive(Serialize)]
+pub struct HedgesResponse {
+    pub hedges: Vec<HedgeResponse>,
+}
+
+#[derive(Serialize)]
+pub struct HedgeResponse {
+    pub id: String,
+    pub symbol: String,
+    pub short_venue: String,
+    pub long_venue: String,
+    pub size: f64,
+    pub opened_at: String,
+    pub closed_at: Option<String>,
+    pub short_funding: f64,
+    pub long_funding: f64,
+    pub cumulative_funding: f64,
+    pub last_funding_at: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct DiscrepanciesResponse {
+    pub discrepancies: Vec<DiscrepancyResponse>,
+    pub orphans: Vec<OrphanResponse>,
+}
+
+#[derive(Serialize)]
+pub struct OrphanResponse {
+    pub hedge_id: String,
+    pub symbol: String,
+    pub venue: String,
+    pub side: String,
+    pub size: f64,
+    pub missing_venue: String,
+    pub detected_at: String,
+    pub flattened: bool,
+}
+
+#[derive(Serialize)]
+pub struct DiscrepancyResponse {
+    pub venue: String,
+    pub symbol: String,
+    pub expected: Option<PositionResponse>,
+    pub reported: Option<PositionResponse>,
+    pub detected_at: String,
+}
+
+impl From<&Position> for PositionResponse {
+    fn from(pos: &Position) -> Self {
+        PositionResponse {
+            symbol: pos.symbol.clone(),
+            venue: format!("{:?}", pos.venue),
+            side: format!("{:?}", pos.side),
+            size: pos.size.to_f64().unwrap_or(0.0),
+            entry_price: pos.entry_price.to_f64().unwrap_or(0.0),
+            leverage: pos.leverage.to_f64().unwrap_or(0.0),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct MetricsResponse {
+    /// Events seen by kind since startup.
+    pub events: BTreeMap<&'static str, u64>,
+    /// Events the metrics subscriber skipped after falling behind.
+    pub events_missed: u64,
+    pub event_subscribers: usize,
+    pub opportunities_held: usize,
+}
+
 pub fn create_router(state: ApiState) -> Router {
     Router::new()
         .route("/api/status", get(get_status))
         .route("/api/opportunities", get(get_opportunities))
         .route("/api/positions", get(get_positions))
+        .route("/api/hedges", get(get_hedges))
+        .route("/api/discrepancies", get(get_discrepancies))
+        .route("/api/strategies", get(get_strategies))
+        .route("/api/metrics", get(get_metrics))
+        .route("/api/events", get(stream_events))
+        .merge(control::routes())
         .layer(CorsLayer::permissive())
         .with_state(state)
 }
 
 async fn get_status(State(state): State<ApiState>) -> Result<Json<StatusResponse>, StatusCode> {
-    let status = if state.risk_engine.is_kill_switch_active() {
-        "error"
-    } else {
-        "running"
+    let kill_switch = state.risk_engine.kill_switch_state();
+    let status = match &kill_switch {
+        KillSwitchState::Armed => "running".to_string(),
+        KillSwitchState::Tripped { reason, .. } => format!("halted: {}", reason),
+        KillSwitchState::CoolingDown { reason, .. } => format!("cooling down: {}", reason),
     };
 
     Ok(Json(StatusResponse {
-        status: status.to_string(),
+        status,
         strategies: state.strategies_enabled.clone(),
-        kill_switch_active: state.risk_engine.is_kill_switch_active(),
+        kill_switch_active: kill_switch != KillSwitchState::Armed,
+        kill_switch: kill_switch.into(),
+        daily_pnl: state.risk_engine.daily_pnl().to_f64().unwrap_or(0.0),
+        next_rollover: state.risk_engine.next_rollover().to_rfc3339(),
         dry_run: state.dry_run,
         timestamp: Utc::now().to_rfc3339(),
     }))
@@ -94,11 +272,27 @@ async fn get_status(State(state): State<ApiState>) -> Result<Json<StatusResponse
 
 async fn get_opportunities(
     State(state): State<ApiState>,
+    Query(query): Query<OpportunitiesQuery>,
 ) -> Result<Json<OpportunitiesResponse>, StatusCode> {
-    // In a real implementation, we'd track opportunities in SharedState
-    // For now, return empty array - opportunities would be logged but not stored
+    let filter = OpportunityFilter {
+        strategy: query.strategy,
+        symbol: query.symbol,
+        status: query.status,
+        since: query.since,
+        until: query.until,
+        offset: query.offset.unwrap_or(0),
+        limit: query
+            .limit
+            .unwrap_or(DEFAULT_OPPORTUNITY_PAGE)
+            .min(MAX_OPPORTUNITY_PAGE),
+    };
+    let page = state.shared_state.opportunities.query(&filter);
+
     Ok(Json(OpportunitiesResponse {
-        opportunities: vec![],
+        opportunities: page.opportunities.iter().map(OpportunityResponse::from).collect(),
+        total: page.total,
+        offset: filter.offset,
+        limit: filter.limit,
     }))
 }
 
@@ -109,18 +303,111 @@ async fn get_positions(
         .shared_state
         .positions
         .iter()
+        .map(|entry| PositionResponse::from(entry.value()))
+        .collect();
+
+    Ok(Json(PositionsResponse { positions }))
+}
+
+async fn get_hedges(State(state): State<ApiState>) -> Result<Json<HedgesResponse>, StatusCode> {
+    let mut hedges: Vec<HedgeResponse> = state
+        .shared_state
+        .hedges
+        .iter()
         .map(|entry| {
-            let pos = entry.value();
-            PositionResponse {
-                symbol: pos.symbol.clone(),
-                venue: format!("{:?}", pos.venue),
-                side: format!("{:?}", pos.side),
-                size: pos.size.to_f64().unwrap_or(0.0),
-                entry_price: pos.entry_price.to_f64().unwrap_or(0.0),
-                leverage: pos.leverage.to_f64().unwrap_or(0.0),
+            let hedge = entry.value();
+            HedgeResponse {
+                id: hedge.id.clone(),
+                symbol: hedge.symbol.clone(),
+                short_venue: format!("{:?}", hedge.short_venue),
+                long_venue: format!("{:?}", hedge.long_venue),
+                size: hedge.size.to_f64().unwrap_or(0.0),
+                opened_at: hedge.opened_at.to_rfc3339(),
+                closed_at: hedge.closed_at.map(|t| t.to_rfc3339()),
+                short_funding: hedge.short_funding.to_f64().unwrap_or(0.0),
+                long_funding: hedge.long_funding.to_f64().unwrap_or(0.0),
+                cumulative_funding: hedge.funding_pnl().to_f64().unwrap_or(0.0),
+                last_funding_at: hedge.last_funding_at.map(|t| t.to_rfc3339()),
             }
         })
         .collect();
+    hedges.sort_by(|a, b| a.opened_at.cmp(&b.opened_at));
 
-    Ok(Json(PositionsResponse { positions }))
+    Ok(Json(HedgesResponse { hedges }))
+}
+
+async fn get_discrepancies(
+    State(state): State<ApiState>,
+) -> Result<Json<DiscrepanciesResponse>, StatusCode> {
+    let mut discrepancies: Vec<DiscrepancyResponse> = state
+        .shared_state
+        .discrepancies
+        .iter()
+        .map(|entry| {
+            let d = entry.value();
+            DiscrepancyResponse {
+                venue: format!("{:?}", d.venue),
+                symbol: d.symbol.clone(),
+                expected: d.expected.as_ref().map(PositionResponse::from),
+                reported: d.reported.as_ref().map(PositionResponse::from),
+                detected_at: d.detected_at.to_rfc3339(),
+            }
+        })
+        .collect();
+    discrepancies.sort_by(|a, b| b.detected_at.cmp(&a.detected_at));
+
+    let mut orphans: Vec<OrphanResponse> = state
+        .shared_state
+        .orphans
+        .iter()
+        .map(|entry| {
+            let o = entry.value();
+            OrphanResponse {
+                hedge_id: o.hedge_id.clone(),
+                symbol: o.symbol.clone(),
+                venue: format!("{:?}", o.venue),
+                side: format!("{:?}", o.side),
+                size: o.size.to_f64().unwrap_or(0.0),
+                missing_venue: format!("{:?}", o.missing_venue),
+                detected_at: o.detected_at.to_rfc3339(),
+                flattened: o.flattened,
+            }
+        })
+        .collect();
+    orphans.sort_by(|a, b| b.detected_at.cmp(&a.detected_at));
+
+    Ok(Json(DiscrepanciesResponse {
+        discrepancies,
+        orphans,
+    }))
+}
+
+async fn get_strategies(State(state): State<ApiState>) -> Json<Vec<StrategyStatus>> {
+    Json(state.strategies.statuses())
+}
+
+async fn get_metrics(State(state): State<ApiState>) -> Json<MetricsResponse> {
+    Json(MetricsResponse {
+        events: state.event_metrics.counts(),
+        events_missed: state.event_metrics.missed(),
+        event_subscribers: state.shared_state.events.subscriber_count(),
+        opportunities_held: state.shared_state.opportunities.len(),
+    })
+}
+
+/// Streams every bus event as server-sent events named by kind. A client that
+/// cannot keep up skips the oldest events rather than slowing the bus.
+async fn stream_events(
+    State(state): State<ApiState>,
+) -> Sse<impl Stream<Item = Result<SseEvent, Infallible>>> {
+    let events = state.shared_state.events.subscribe("api stream");
+    let stream = stream::unfold(events, |mut events| async move {
+        let event = events.recv().await?;
+        let sse = SseEvent::default()
+            .event(event.kind())
+            .json_data(&event)
+            .unwrap_or_else(|_| SseEvent::default().comment("unserializable event"));
+        Some((Ok(sse), events))
+    });
+    Sse::new(stream).keep_alive(KeepAlive::default())
 }
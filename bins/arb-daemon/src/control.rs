@@ -0,0 +1,334 @@
+use crate::api::ApiState;
+use arb_core::{AuditEntry, AuditFilter, ControlAction, KillSwitchReason};
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{header::AUTHORIZATION, request::Parts, StatusCode},
+    response::{IntoResponse, Json, Response},
+    routing::{get, post},
+    Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use strategies::flatten_all;
+use tracing::{error, info, warn};
+
+/// Audit entries served per page when `limit` is not given.
+const DEFAULT_AUDIT_PAGE: usize = 100;
+const MAX_AUDIT_PAGE: usize = 1000;
+
+/// Authenticated operator actions, each recorded in the audit log whether it
+/// succeeds or not, plus the read-only `/api/audit` query.
+pub fn routes() -> Router<ApiState> {
+    Router::new()
+        .route("/api/control/kill-switch/trip", post(trip_kill_switch))
+        .route("/api/control/kill-switch/rearm", post(rearm_kill_switch))
+        .route("/api/control/strategies/:name/pause", post(pause_strategy))
+        .route("/api/control/strategies/:name/resume", post(resume_strategy))
+        .route("/api/control/strategies/:name/dry-run", post(set_dry_run))
+        .route("/api/control/flatten-all", post(flatten))
+        .route("/api/audit", get(get_audit))
+}
+
+/// Why the operator is acting; required on every control request.
+#[derive(Debug, Deserialize)]
+pub struct ControlRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DryRunRequest {
+    pub dry_run: bool,
+    pub reason: String,
+}
+
+#[derive(Serialize)]
+pub struct ControlResponse {
+    pub audit_id: u64,
+    pub action: &'static str,
+    pub detail: Option<String>,
+}
+
+/// `/api/audit` filters; `since` and `until` are RFC 3339 timestamps.
+#[derive(Debug, Deserialize)]
+pub struct AuditQuery {
+    pub actor: Option<String>,
+    pub action: Option<String>,
+    pub since: Option<DateTime<Utc>>,
+    pub until: Option<DateTime<Utc>>,
+    pub offset: Option<usize>,
+    pub limit: Option<usize>,
+}
+
+#[derive(Serialize)]
+pub struct AuditResponse {
+    pub entries: Vec<AuditEntry>,
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// A refused or failed control request, returned as `{"error": ...}`.
+#[derive(Debug)]
+pub struct ControlError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ControlError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ControlError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+/// The operator a request authenticated as, from `Authorization: Bearer <token>`.
+pub struct Operator(pub String);
+
+#[async_trait]
+impl FromRequestParts<ApiState> for Operator {
+    type Rejection = ControlError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &ApiState,
+    ) -> Result<Self, Self::Rejection> {
+        if state.operators.is_empty() {
+            return Err(ControlError::new(
+                StatusCode::FORBIDDEN,
+                "Control API is disabled: no operators configured",
+            ));
+        }
+        let token = parts
+            .headers
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::trim);
+        let operator = token.and_then(|token| {
+            state
+                .operators
+                .iter()
+                .find(|operator| tokens_match(&operator.token, token))
+        });
+        match operator {
+            Some(operator) => Ok(Operator(operator.name.clone())),
+            None => {
+                warn!("Rejected control request to {} with a bad or missing token", parts.uri);
+                Err(ControlError::new(StatusCode::UNAUTHORIZED, "Invalid or missing bearer token"))
+            }
+        }
+    }
+}
+
+/// Compares in time independent of where the first difference is.
+fn tokens_match(expected: &str, given: &str) -> bool {
+    let (expected, given) = (expected.as_bytes(), given.as_bytes());
+    expected.len() == given.len()
+        && expected.iter().zip(given).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn require_reason(reason: String) -> Result<String, ControlError> {
+    let reason = reason.trim().to_string();
+    if reason.is_empty() {
+        return Err(ControlError::new(StatusCode::BAD_REQUEST, "A reason is required"));
+    }
+    Ok(reason)
+}
+
+/// Records the action and its outcome, then returns that outcome. An action
+/// that cannot be written to the audit log is reported as a server error.
+fn audited(
+    state: &ApiState,
+    actor: String,
+    action: ControlAction,
+    reason: String,
+    outcome: Result<Option<String>, ControlError>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let name = action.name();
+    let (succeeded, detail) = match &outcome {
+        Ok(detail) => (true, detail.clone()),
+        Err(e) => (false, Some(e.message.clone())),
+    };
+    info!("Control action {} by {} ({}): succeeded={}", name, actor, reason, succeeded);
+
+    let entry = state
+        .audit
+        .record(AuditEntry {
+            id: 0,
+            at: Utc::now(),
+            actor,
+            action,
+            reason,
+            succeeded,
+            detail,
+        })
+        .map_err(|e| {
+            error!("Failed to audit control action {}: {:#}", name, e);
+            ControlError::new(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                format!("Action {} was not written to the audit log: {:#}", name, e),
+            )
+        })?;
+
+    outcome.map(|detail| {
+        Json(ControlResponse {
+            audit_id: entry.id,
+            action: name,
+            detail,
+        })
+    })
+}
+
+async fn trip_kill_switch(
+    State(state): State<ApiState>,
+    Operator(actor): Operator,
+    Json(request): Json<ControlRequest>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let reason = require_reason(request.reason)?;
+    let why = format!("{} (by {})", reason, actor);
+    let outcome = if state.risk_engine.trip(KillSwitchReason::Manual(why)) {
+        Ok(None)
+    } else {
+        Ok(Some("already tripped".to_string()))
+    };
+    audited(&state, actor, ControlAction::TripKillSwitch, reason, outcome)
+}
+
+async fn rearm_kill_switch(
+    State(state): State<ApiState>,
+    Operator(actor): Operator,
+    Json(request): Json<ControlRequest>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let reason = require_reason(request.reason)?;
+    let outcome = state
+        .risk_engine
+        .rearm()
+        .map(|_| None)
+        .map_err(|e| ControlError::new(StatusCode::CONFLICT, e.to_string()));
+    audited(&state, actor, ControlAction::RearmKillSwitch, reason, outcome)
+}
+
+async fn pause_strategy(
+    State(state): State<ApiState>,
+    Operator(actor): Operator,
+    Path(name): Path<String>,
+    Json(request): Json<ControlRequest>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let reason = require_reason(request.reason)?;
+    let outcome = strategy_outcome(&state, &name, |strategies| strategies.pause(&name));
+    let action = ControlAction::PauseStrategy { strategy: name };
+    audited(&state, actor, action, reason, outcome)
+}
+
+async fn resume_strategy(
+    State(state): State<ApiState>,
+    Operator(actor): Operator,
+    Path(name): Path<String>,
+    Json(request): Json<ControlRequest>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let reason = require_reason(request.reason)?;
+    let outcome = strategy_outcome(&state, &name, |strategies| strategies.resume(&name));
+    let action = ControlAction::ResumeStrategy { strategy: name };
+    audited(&state, actor, action, reason, outcome)
+}
+
+async fn set_dry_run(
+    State(state): State<ApiState>,
+    Operator(actor): Operator,
+    Path(name): Path<String>,
+    Json(request): Json<DryRunRequest>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let reason = require_reason(request.reason)?;
+    let dry_run = request.dry_run;
+    let outcome = strategy_outcome(&state, &name, |strategies| {
+        strategies.set_dry_run(&name, dry_run)
+    });
+    let action = ControlAction::SetDryRun {
+        strategy: name,
+        dry_run,
+    };
+    audited(&state, actor, action, reason, outcome)
+}
+
+/// 404 for a strategy the manager does not run, 409 if it refuses the change.
+fn strategy_outcome(
+    state: &ApiState,
+    name: &str,
+    apply: impl FnOnce(&strategies::StrategyManager) -> anyhow::Result<()>,
+) -> Result<Option<String>, ControlError> {
+    if state.strategies.status(name).is_none() {
+        return Err(ControlError::new(
+            StatusCode::NOT_FOUND,
+            format!("Unknown strategy {}", name),
+        ));
+    }
+    apply(&state.strategies)
+        .map(|_| None)
+        .map_err(|e| ControlError::new(StatusCode::CONFLICT, format!("{:#}", e)))
+}
+
+/// Trips the kill switch so nothing reopens, then closes every position.
+async fn flatten(
+    State(state): State<ApiState>,
+    Operator(actor): Operator,
+    Json(request): Json<ControlRequest>,
+) -> Result<Json<ControlResponse>, ControlError> {
+    let reason = require_reason(request.reason)?;
+    let why = format!("flatten all: {} (by {})", reason, actor);
+    state.risk_engine.trip(KillSwitchReason::Manual(why));
+
+    let report = flatten_all(
+        &state.connectors,
+        &state.perp_venues,
+        &state.shared_state,
+        &state.risk_engine,
+        state.dry_run,
+    )
+    .await;
+    let closed = format!(
+        "{} {} position(s)",
+        if report.dry_run { "would close" } else { "closed" },
+        report.closed.len()
+    );
+    let outcome = if report.is_complete() {
+        Ok(Some(closed))
+    } else {
+        Err(ControlError::new(
+            StatusCode::BAD_GATEWAY,
+            format!("{}; failed: {}", closed, report.failures.join("; ")),
+        ))
+    };
+    audited(&state, actor, ControlAction::FlattenAll, reason, outcome)
+}
+
+async fn get_audit(
+    State(state): State<ApiState>,
+    Query(query): Query<AuditQuery>,
+) -> Json<AuditResponse> {
+    let filter = AuditFilter {
+        actor: query.actor,
+        action: query.action,
+        since: query.since,
+        until: query.until,
+        offset: query.offset.unwrap_or(0),
+        limit: query.limit.unwrap_or(DEFAULT_AUDIT_PAGE).min(MAX_AUDIT_PAGE),
+    };
+    let page = state.audit.query(&filter);
+
+    Json(AuditResponse {
+        entries: page.entries,
+        total: page.total,
+        offset: filter.offset,
+        limit: filter.limit,
+    })
+}
@@ -1,12 +1,24 @@
 mod api;
+mod control;
 
-use arb_core::{Config, RiskEngine, SharedState};
+use arb_core::audit::DEFAULT_AUDIT_CAPACITY;
+use arb_core::events::run_alerts;
+use arb_core::{
+    AuditLog, Config, EventMetrics, RiskEngine, SharedState, StatePersister, StateStore, Venue,
+};
 use api::{ApiState, StrategiesConfig};
-use connectors::{BinanceConnector, BybitConnector, HyperEvmConnector, HyperliquidConnector, JupiterConnector};
+use connectors::streaming::{run_feed, BinanceFeed, BybitFeed, HyperliquidFeed, StreamSettings};
+use connectors::{
+    BinanceConnector, BybitConnector, ConnectorRegistry, HyperEvmConnector, HyperliquidConnector,
+    JupiterConnector,
+};
 use std::sync::Arc;
-use strategies::{FundingArbStrategy, HyperEvmSpotStrategy, SolanaJupiterStrategy};
+use strategies::{
+    builtin_strategies, FundingLedger, MarginMonitor, PositionReconciler, StrategyContext,
+    StrategyManager,
+};
 use tokio::signal;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use axum::serve;
 use tokio::net::TcpListener;
 
@@ -27,6 +39,8 @@ async fn main() -> anyhow::Result<()> {
         .unwrap_or_else(|_| "config/local.toml".to_string());
     
     let config = Config::load(&config_path)?;
+    config.api.validate()?;
+    config.risk.validate()?;
     
     if config.general.dry_run {
         info!("DRY RUN MODE: No trades will be executed");
@@ -34,34 +48,71 @@ async fn main() -> anyhow::Result<()> {
 
     // Initialize shared state
     let state = Arc::new(SharedState::new());
-    let risk_engine = Arc::new(RiskEngine::new(config.risk.clone()));
+    let risk_engine =
+        Arc::new(RiskEngine::new(config.risk.clone()).with_events(state.events.clone()));
+
+    // Subscribe before anything publishes so no early events are missed
+    let event_metrics = EventMetrics::default();
+    let metrics_events = state.events.subscribe("metrics");
+    let metrics_task = {
+        let event_metrics = event_metrics.clone();
+        tokio::spawn(async move { event_metrics.run(metrics_events).await })
+    };
+    let alerts_task = tokio::spawn(run_alerts(state.events.subscribe("alerts")));
+
+    // Restore risk and position state from the last run
+    let persister = if config.persistence.enabled {
+        let mut store = StateStore::open(&config.persistence.state_dir)?;
+        let restored = store.load()?;
+        info!(
+            "Restored {} position(s), {} hedge(s) and {} trade(s) from {}",
+            restored.positions.len(),
+            restored.hedges.len(),
+            restored.trades.len(),
+            config.persistence.state_dir
+        );
+        restored.restore_into(&state, &risk_engine);
+        Some(Arc::new(StatePersister::new(
+            store,
+            state.clone(),
+            risk_engine.clone(),
+            config.persistence.persist_interval_ms,
+            config.persistence.compact_after_entries,
+            config.persistence.retain_closed_hours,
+        )))
+    } else {
+        None
+    };
 
     // Initialize connectors
-    let hyperliquid = HyperliquidConnector::new(
+    let hyperliquid = match HyperliquidConnector::new(
         config.hyperliquid.api_url.clone(),
         config.hyperliquid.private_key.clone(),
-    );
+    ) {
+        Ok(conn) => conn.with_instruments(state.instruments.clone()),
+        Err(e) => {
+            error!("Failed to initialize Hyperliquid connector: {}", e);
+            return Err(e);
+        }
+    };
 
     let binance = BinanceConnector::new(
         config.binance.api_key.clone(),
         config.binance.api_secret.clone(),
         config.binance.base_url.clone(),
-    );
+        config.binance.recv_window_ms,
+    )
+    .with_instruments(state.instruments.clone());
 
     let bybit = BybitConnector::new(
         config.bybit.api_key.clone(),
         config.bybit.api_secret.clone(),
         config.bybit.base_url.clone(),
-    );
+        config.bybit.recv_window_ms,
+    )
+    .with_instruments(state.instruments.clone());
 
-    let hyperevm = match HyperEvmConnector::new(
-        config.hyperevm.rpc_url.clone(),
-        if config.hyperevm.dex_router_address.is_empty() {
-            None
-        } else {
-            Some(config.hyperevm.dex_router_address.clone())
-        },
-    ) {
+    let hyperevm = match HyperEvmConnector::new(config.hyperevm.clone()) {
         Ok(conn) => conn,
         Err(e) => {
             error!("Failed to initialize HyperEVM connector: {}", e);
@@ -69,15 +120,7 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    let jupiter = match JupiterConnector::new(
-        config.solana.rpc_url.clone(),
-        config.solana.jupiter_api_url.clone(),
-        if config.solana.private_key.is_empty() {
-            None
-        } else {
-            Some(config.solana.private_key.clone())
-        },
-    ) {
+    let jupiter = match JupiterConnector::new(config.solana.clone()) {
         Ok(conn) => conn,
         Err(e) => {
             error!("Failed to initialize Jupiter connector: {}", e);
@@ -85,64 +128,134 @@ async fn main() -> anyhow::Result<()> {
         }
     };
 
-    // Initialize strategies
-    let mut strategy_tasks = Vec::new();
+    let mut registry = ConnectorRegistry::new();
+    registry.register(Arc::new(hyperliquid.clone()));
+    registry.register(Arc::new(binance));
+    registry.register(Arc::new(bybit));
+    registry.register(Arc::new(hyperevm.clone()));
+    registry.register(Arc::new(jupiter.clone()));
+    let registry = Arc::new(registry);
+    let perp_venues = vec![Venue::Hyperliquid, Venue::Binance, Venue::Bybit];
 
-    if config.strategies.funding_arb_enabled {
-        info!("Enabling funding arbitrage strategy");
-        let funding_strategy = FundingArbStrategy::new(
-            hyperliquid.clone(),
-            binance.clone(),
-            bybit.clone(),
-            state.clone(),
-            risk_engine.clone(),
-            config.strategies.funding_arb.min_annualized_spread,
-            config.strategies.funding_arb.check_interval_ms,
-            config.general.dry_run,
-        );
-        strategy_tasks.push(tokio::spawn(async move {
-            funding_strategy.run_loop().await;
-        }));
+    // Map canonical asset ids to each venue's native symbol and contract spec
+    registry.load_instruments(&perp_venues, &state.instruments).await;
+
+    let persist_task = persister.clone().map(|persister| {
+        let events = state.events.subscribe("persistence");
+        tokio::spawn(async move {
+            persister.run_loop(events).await;
+        })
+    });
+
+    // Check restored positions against what the venues actually hold. Spot
+    // venues settle into balances, so their booked fills have nothing to match.
+    let reconciler = PositionReconciler::new(
+        registry.clone(),
+        perp_venues.clone(),
+        state.clone(),
+        risk_engine.clone(),
+        config.reconciliation.clone(),
+        config.general.dry_run,
+    );
+    let discrepancies = reconciler.reconcile().await;
+    if !discrepancies.is_empty() {
+        warn!("{} position discrepancies found at startup", discrepancies.len());
     }
+    let reconcile_task = tokio::spawn(async move {
+        reconciler.run_loop().await;
+    });
+
+    // Start market-data streams
+    let mut stream_tasks = Vec::new();
 
-    if config.strategies.hyperevm_spot_enabled {
-        info!("Enabling HyperEVM spot arbitrage strategy");
-        let hyperevm_strategy = HyperEvmSpotStrategy::new(
-            hyperliquid.clone(),
-            hyperevm.clone(),
+    if config.streaming.enabled {
+        info!("Streaming market data for {:?}", config.streaming.symbols);
+        let settings = StreamSettings::from(&config.streaming);
+        let coins = config.streaming.symbols.clone();
+
+        stream_tasks.push(tokio::spawn(run_feed(
+            HyperliquidFeed::new(config.hyperliquid.ws_url.clone(), coins.clone())
+                .with_instruments(state.instruments.clone()),
             state.clone(),
-            risk_engine.clone(),
-            config.strategies.hyperevm_spot.min_spread_bps,
-            config.strategies.hyperevm_spot.check_interval_ms,
-            config.general.dry_run,
-        );
-        strategy_tasks.push(tokio::spawn(async move {
-            hyperevm_strategy.run_loop().await;
-        }));
+            settings.clone(),
+        )));
+        stream_tasks.push(tokio::spawn(run_feed(
+            BinanceFeed::new(
+                config.binance.ws_url.clone(),
+                config.binance.base_url.clone(),
+                coins.clone(),
+            )
+            .with_instruments(state.instruments.clone()),
+            state.clone(),
+            settings.clone(),
+        )));
+        stream_tasks.push(tokio::spawn(run_feed(
+            BybitFeed::new(config.bybit.ws_url.clone(), coins)
+                .with_instruments(state.instruments.clone()),
+            state.clone(),
+            settings,
+        )));
     }
 
-    if config.strategies.solana_jupiter_enabled {
-        info!("Enabling Solana Jupiter arbitrage strategy");
-        let solana_strategy = SolanaJupiterStrategy::new(
-            jupiter.clone(),
+    // Keep per-venue margin snapshots fresh for the risk engine
+    let margin_monitor = MarginMonitor::new(
+        registry.clone(),
+        perp_venues.clone(),
+        risk_engine.clone(),
+        config.risk.margin_refresh_interval_ms,
+    );
+    let margin_task = tokio::spawn(async move {
+        margin_monitor.run_loop().await;
+    });
+
+    // Reset daily PnL at the UTC rollover and drive the kill-switch cooldown
+    let rollover_engine = risk_engine.clone();
+    let rollover_task = tokio::spawn(async move {
+        rollover_engine.run_rollover_loop().await;
+    });
+
+    // Funding accrual is booked whether or not new hedges are being opened
+    let ledger_task = config.strategies.funding_arb_enabled.then(|| {
+        let funding_ledger = FundingLedger::new(
+            registry.clone(),
             state.clone(),
             risk_engine.clone(),
-            config.strategies.solana_jupiter.min_profit_bps,
-            config.strategies.solana_jupiter.max_slippage_bps,
-            config.strategies.solana_jupiter.check_interval_ms,
-            config.general.dry_run,
+            config.strategies.funding_arb.funding_poll_interval_ms,
         );
-        strategy_tasks.push(tokio::spawn(async move {
-            solana_strategy.run_loop().await;
-        }));
-    }
+        tokio::spawn(async move {
+            funding_ledger.run_loop().await;
+        })
+    });
 
-    if strategy_tasks.is_empty() {
+    // Start the enabled strategies under supervision
+    let strategy_context = StrategyContext {
+        connectors: registry.clone(),
+        perp_venues: perp_venues.clone(),
+        hyperevm: hyperevm.clone(),
+        jupiter: jupiter.clone(),
+        state: state.clone(),
+        risk_engine: risk_engine.clone(),
+        config: config.strategies.clone(),
+        dry_run: config.general.dry_run,
+    };
+    let strategy_manager = Arc::new(StrategyManager::new(
+        state.events.clone(),
+        config.strategies.restart.clone(),
+    ));
+    let factories = builtin_strategies(&strategy_context);
+    if factories.is_empty() {
         error!("No strategies enabled. Please enable at least one strategy in config.");
         return Err(anyhow::anyhow!("No strategies enabled"));
     }
+    for factory in factories {
+        strategy_manager.start(factory)?;
+    }
 
     // Start API server
+    let audit = Arc::new(AuditLog::open(&config.api.audit_log_path, DEFAULT_AUDIT_CAPACITY)?);
+    if config.api.operators.is_empty() {
+        warn!("No API operators configured; control endpoints are disabled");
+    }
     let api_state = ApiState {
         shared_state: state.clone(),
         risk_engine: risk_engine.clone(),
@@ -152,6 +265,12 @@ async fn main() -> anyhow::Result<()> {
             solana_jupiter: config.strategies.solana_jupiter_enabled,
         },
         dry_run: config.general.dry_run,
+        event_metrics,
+        strategies: strategy_manager.clone(),
+        connectors: registry.clone(),
+        perp_venues: perp_venues.clone(),
+        operators: Arc::new(config.api.operators.clone()),
+        audit,
     };
 
     let api_router = api::create_router(api_state);
@@ -186,9 +305,28 @@ async fn main() -> anyhow::Result<()> {
 
     // Cancel all tasks
     api_server.abort();
-    for task in strategy_tasks {
+    margin_task.abort();
+    rollover_task.abort();
+    reconcile_task.abort();
+    metrics_task.abort();
+    alerts_task.abort();
+    if let Some(task) = ledger_task {
         task.abort();
     }
+    strategy_manager.shutdown().await;
+    for task in stream_tasks {
+        task.abort();
+    }
+
+    // Flush the final state so the next run starts from it
+    if let Some(task) = persist_task {
+        task.abort();
+    }
+    if let Some(persister) = &persister {
+        if let Err(e) = persister.snapshot().await {
+            error!("Failed to persist final state: {:#}", e);
+        }
+    }
 
     info!("Arbitrage daemon stopped");
     Ok(())
@@ -0,0 +1,228 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::{Mutex, RwLock};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::Path;
+use tracing::warn;
+
+/// Entries kept in memory; the file keeps everything.
+pub const DEFAULT_AUDIT_CAPACITY: usize = 10_000;
+
+/// An operator action taken through the control API.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum ControlAction {
+    TripKillSwitch,
+    RearmKillSwitch,
+    PauseStrategy { strategy: String },
+    ResumeStrategy { strategy: String },
+    SetDryRun { strategy: String, dry_run: bool },
+    FlattenAll,
+}
+
+impl ControlAction {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TripKillSwitch => "trip_kill_switch",
+            Self::RearmKillSwitch => "rearm_kill_switch",
+            Self::PauseStrategy { .. } => "pause_strategy",
+            Self::ResumeStrategy { .. } => "resume_strategy",
+            Self::SetDryRun { .. } => "set_dry_run",
+            Self::FlattenAll => "flatten_all",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Assigned by `AuditLog::record`.
+    pub id: u64,
+    pub at: DateTime<Utc>,
+    /// Operator name the request authenticated as.
+    pub actor: String,
+    #[serde(flatten)]
+    pub action: ControlAction,
+    pub reason: String,
+    pub succeeded: bool,
+    /// Error or summary of what the action did.
+    pub detail: Option<String>,
+}
+
+/// Selects entries from the log, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct AuditFilter {
+    pub actor: Option<String>,
+    /// `ControlAction::name`.
+    pub action: Option<String>,
+    /// Inclusive lower bound on `at`.
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `at`.
+    pub until: Option<DateTime<Utc>>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditPage {
+    pub total: usize,
+    pub entries: Vec<AuditEntry>,
+}
+
+#[derive(Debug)]
+struct Entries {
+    next_id: u64,
+    /// Ordered by id, oldest first.
+    recent: VecDeque<AuditEntry>,
+}
+
+/// Append-only record of control actions: the most recent `capacity` are
+/// held for queries and, when opened on a file, every entry is written
+/// through to it before `record` returns.
+#[derive(Debug)]
+pub struct AuditLog {
+    capacity: usize,
+    entries: RwLock<Entries>,
+    file: Option<Mutex<File>>,
+}
+
+impl AuditLog {
+    /// An in-memory log, for tests and runs without an audit file.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            entries: RwLock::new(Entries {
+                next_id: 1,
+                recent: VecDeque::new(),
+            }),
+            file: None,
+        }
+    }
+
+    /// Opens `path` for appending, loading its latest entries so queries and
+    /// ids carry on across restarts.
+    pub fn open(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create audit log directory {:?}", dir))?;
+        }
+
+        let mut log = Self::new(capacity);
+        // Byte length of the file up to and including its last good line
+        let mut good_len = 0;
+        let mut content = Vec::new();
+        if path.exists() {
+            content =
+                fs::read(path).with_context(|| format!("Failed to read audit log {:?}", path))?;
+            let mut entries = log.entries.write();
+            let mut offset = 0;
+            for (number, line) in content.split_inclusive(|&b| b == b'\n').enumerate() {
+                offset += line.len();
+                if line.trim_ascii().is_empty() {
+                    good_len = offset;
+                    continue;
+                }
+                match serde_json::from_slice::<AuditEntry>(line) {
+                    Ok(entry) => {
+                        entries.next_id = entries.next_id.max(entry.id + 1);
+                        push(&mut entries.recent, entry, log.capacity);
+                        good_len = offset;
+                    }
+                    // A torn final line from a crash mid-write
+                    Err(e) => warn!("Skipping audit log line {}: {}", number + 1, e),
+                }
+            }
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open audit log {:?}", path))?;
+        // Cut a torn tail off so the next entry starts on a fresh line
+        let torn = good_len < content.len();
+        if torn {
+            file.set_len(good_len as u64)
+                .context("Failed to truncate torn audit log entry")?;
+        }
+        let unterminated = good_len > 0 && content[good_len - 1] != b'\n';
+        if unterminated {
+            // The last good entry was written without its newline
+            file.write_all(b"\n").context("Failed to repair audit log")?;
+        }
+        if torn || unterminated {
+            file.sync_all().context("Failed to sync audit log")?;
+        }
+        log.file = Some(Mutex::new(file));
+        Ok(log)
+    }
+
+    /// Stamps `entry` with a new id and the current time and appends it.
+    /// The entry is queryable even if writing it to the file fails.
+    pub fn record(&self, mut entry: AuditEntry) -> Result<AuditEntry> {
+        let mut entries = self.entries.write();
+        entry.id = entries.next_id;
+        entries.next_id += 1;
+        entry.at = match entries.recent.back() {
+            Some(last) => Utc::now().max(last.at),
+            None => Utc::now(),
+        };
+        push(&mut entries.recent, entry.clone(), self.capacity);
+
+        if let Some(file) = &self.file {
+            let mut line = serde_json::to_string(&entry)?;
+            line.push('\n');
+            let mut file = file.lock();
+            file.write_all(line.as_bytes())
+                .and_then(|_| file.sync_data())
+                .context("Failed to write audit log")?;
+        }
+        Ok(entry)
+    }
+
+    pub fn query(&self, filter: &AuditFilter) -> AuditPage {
+        let entries = self.entries.read();
+        let recent = &entries.recent;
+        let start = filter
+            .since
+            .map_or(0, |since| recent.partition_point(|e| e.at < since));
+        let end = filter
+            .until
+            .map_or(recent.len(), |until| recent.partition_point(|e| e.at < until));
+
+        let matching: Vec<&AuditEntry> = recent
+            .range(start..end.max(start))
+            .rev()
+            .filter(|e| filter.actor.as_ref().is_none_or(|a| &e.actor == a))
+            .filter(|e| filter.action.as_deref().is_none_or(|a| e.action.name() == a))
+            .collect();
+
+        AuditPage {
+            total: matching.len(),
+            entries: matching
+                .into_iter()
+                .skip(filter.offset)
+                .take(filter.limit)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().recent.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.read().recent.is_empty()
+    }
+}
+
+fn push(recent: &mut VecDeque<AuditEntry>, entry: AuditEntry, capacity: usize) {
+    if recent.len() == capacity {
+        recent.pop_front();
+    }
+    recent.push_back(entry);
+}
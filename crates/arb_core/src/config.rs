@@ -12,6 +12,14 @@ pub struct Config {
     pub hyperevm: HyperEvmConfig,
     pub solana: SolanaConfig,
     pub strategies: StrategiesConfig,
+    #[serde(default)]
+    pub streaming: StreamingConfig,
+    #[serde(default)]
+    pub persistence: PersistenceConfig,
+    #[serde(default)]
+    pub reconciliation: ReconciliationConfig,
+    #[serde(default)]
+    pub api: ApiConfig,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +49,64 @@ pub struct RiskConfig {
     pub max_open_positions_per_venue: usize,
     pub max_leverage: f64,
     pub kill_switch_daily_loss_threshold: f64,
+    /// Share of equity that must stay above maintenance margin on every venue.
+    #[serde(default = "default_liquidation_buffer")]
+    pub liquidation_buffer: f64,
+    #[serde(default = "default_margin_refresh_interval_ms")]
+    pub margin_refresh_interval_ms: u64,
+    /// UTC hour (0-23) at which daily PnL resets.
+    #[serde(default)]
+    pub daily_rollover_hour_utc: u32,
+    /// How long a tripped kill switch stays off after the rollover clears it.
+    #[serde(default = "default_kill_switch_cooldown_secs")]
+    pub kill_switch_cooldown_secs: u64,
+    /// Leave a tripped kill switch tripped until re-armed by hand. Manual
+    /// trips always wait for a re-arm.
+    #[serde(default)]
+    pub kill_switch_manual_rearm: bool,
+}
+
+impl RiskConfig {
+    /// Refuses limits the risk engine cannot turn into decimals or that
+    /// would make every check meaningless.
+    pub fn validate(&self) -> Result<()> {
+        let finite = [
+            ("max_notional_per_asset", self.max_notional_per_asset),
+            ("max_leverage", self.max_leverage),
+            ("kill_switch_daily_loss_threshold", self.kill_switch_daily_loss_threshold),
+            ("liquidation_buffer", self.liquidation_buffer),
+        ];
+        for (name, value) in finite {
+            if !value.is_finite() {
+                anyhow::bail!("risk.{} must be a finite number, got {}", name, value);
+            }
+        }
+        if self.max_notional_per_asset < 0.0 {
+            anyhow::bail!("risk.max_notional_per_asset must not be negative");
+        }
+        if self.max_leverage <= 0.0 {
+            anyhow::bail!("risk.max_leverage must be positive");
+        }
+        if !(0.0..1.0).contains(&self.liquidation_buffer) {
+            anyhow::bail!("risk.liquidation_buffer must be at least 0 and below 1");
+        }
+        if self.daily_rollover_hour_utc >= 24 {
+            anyhow::bail!("risk.daily_rollover_hour_utc must be an hour from 0 to 23");
+        }
+        Ok(())
+    }
+}
+
+fn default_liquidation_buffer() -> f64 {
+    0.5
+}
+
+fn default_margin_refresh_interval_ms() -> u64 {
+    30000
+}
+
+fn default_kill_switch_cooldown_secs() -> u64 {
+    3600
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -48,6 +114,12 @@ pub struct HyperliquidConfig {
     pub api_url: String,
     #[serde(default)]
     pub private_key: String,
+    #[serde(default = "default_hyperliquid_ws_url")]
+    pub ws_url: String,
+}
+
+fn default_hyperliquid_ws_url() -> String {
+    "wss://api.hyperliquid.xyz/ws".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +129,18 @@ pub struct BinanceConfig {
     #[serde(default)]
     pub api_secret: String,
     pub base_url: String,
+    #[serde(default = "default_recv_window_ms")]
+    pub recv_window_ms: u64,
+    #[serde(default = "default_binance_ws_url")]
+    pub ws_url: String,
+}
+
+fn default_recv_window_ms() -> u64 {
+    5000
+}
+
+fn default_binance_ws_url() -> String {
+    "wss://fstream.binance.com".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +150,14 @@ pub struct BybitConfig {
     #[serde(default)]
     pub api_secret: String,
     pub base_url: String,
+    #[serde(default = "default_recv_window_ms")]
+    pub recv_window_ms: u64,
+    #[serde(default = "default_bybit_ws_url")]
+    pub ws_url: String,
+}
+
+fn default_bybit_ws_url() -> String {
+    "wss://stream.bybit.com/v5/public/linear".to_string()
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -73,6 +165,73 @@ pub struct HyperEvmConfig {
     pub rpc_url: String,
     #[serde(default)]
     pub dex_router_address: String,
+    #[serde(default)]
+    pub dex_kind: DexKind,
+    /// V3 quoter contract; quotes go through the router for V2 DEXes.
+    #[serde(default)]
+    pub quoter_address: String,
+    /// V3 pool fee in hundredths of a bip (3000 = 0.3%).
+    #[serde(default = "default_fee_tier")]
+    pub fee_tier: u32,
+    /// Base-token amount used for executable bid/ask quotes.
+    #[serde(default = "default_quote_size")]
+    pub quote_size: f64,
+    #[serde(default)]
+    pub tokens: Vec<TokenConfig>,
+    #[serde(default)]
+    pub private_key: String,
+    #[serde(default = "default_hyperevm_chain_id")]
+    pub chain_id: u64,
+    /// Tolerance below the quoted output used for `amountOutMin`.
+    #[serde(default = "default_hyperevm_slippage_bps")]
+    pub max_slippage_bps: u64,
+    #[serde(default = "default_swap_deadline_secs")]
+    pub swap_deadline_secs: u64,
+    #[serde(default = "default_receipt_timeout_ms")]
+    pub receipt_timeout_ms: u64,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DexKind {
+    /// Uniswap V2-style router (`getAmountsOut`).
+    #[default]
+    V2,
+    /// Uniswap V3-style quoter (`quoteExactInputSingle`).
+    V3,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenConfig {
+    pub symbol: String,
+    pub address: String,
+    /// Read from the token contract when omitted.
+    #[serde(default)]
+    pub decimals: Option<u8>,
+}
+
+fn default_fee_tier() -> u32 {
+    3000
+}
+
+fn default_quote_size() -> f64 {
+    1.0
+}
+
+fn default_hyperevm_chain_id() -> u64 {
+    999
+}
+
+fn default_hyperevm_slippage_bps() -> u64 {
+    50
+}
+
+fn default_swap_deadline_secs() -> u64 {
+    60
+}
+
+fn default_receipt_timeout_ms() -> u64 {
+    60000
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -81,9 +240,24 @@ pub struct SolanaConfig {
     #[serde(default)]
     pub private_key: String,
     pub jupiter_api_url: String,
+    #[serde(default)]
+    pub skip_preflight: bool,
+    /// Resends attempted by the RPC node before it drops a transaction.
+    #[serde(default = "default_solana_max_retries")]
+    pub max_retries: usize,
+    #[serde(default = "default_confirm_timeout_ms")]
+    pub confirm_timeout_ms: u64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+fn default_solana_max_retries() -> usize {
+    3
+}
+
+fn default_confirm_timeout_ms() -> u64 {
+    60000
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct StrategiesConfig {
     #[serde(default)]
     pub funding_arb_enabled: bool,
@@ -97,27 +271,64 @@ pub struct StrategiesConfig {
     pub hyperevm_spot: HyperEvmSpotConfig,
     #[serde(default)]
     pub solana_jupiter: SolanaJupiterConfig,
+    #[serde(default)]
+    pub restart: RestartConfig,
 }
 
-impl Default for StrategiesConfig {
+/// Backoff between restarts of a strategy that panicked or failed to start.
+/// It doubles per consecutive failure and resets once a tick completes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestartConfig {
+    #[serde(default = "default_restart_backoff_initial_ms")]
+    pub backoff_initial_ms: u64,
+    #[serde(default = "default_restart_backoff_max_ms")]
+    pub backoff_max_ms: u64,
+}
+
+impl Default for RestartConfig {
     fn default() -> Self {
         Self {
-            funding_arb_enabled: false,
-            hyperevm_spot_enabled: false,
-            solana_jupiter_enabled: false,
-            funding_arb: FundingArbConfig::default(),
-            hyperevm_spot: HyperEvmSpotConfig::default(),
-            solana_jupiter: SolanaJupiterConfig::default(),
+            backoff_initial_ms: default_restart_backoff_initial_ms(),
+            backoff_max_ms: default_restart_backoff_max_ms(),
         }
     }
 }
 
+fn default_restart_backoff_initial_ms() -> u64 {
+    1000
+}
+
+fn default_restart_backoff_max_ms() -> u64 {
+    60000
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FundingArbConfig {
     #[serde(default = "default_min_annualized_spread")]
     pub min_annualized_spread: f64,
     #[serde(default = "default_check_interval_ms")]
     pub check_interval_ms: u64,
+    /// How often realised funding is pulled from each venue's income history.
+    #[serde(default = "default_funding_poll_interval_ms")]
+    pub funding_poll_interval_ms: u64,
+    /// Hedges are unwound once the annualised spread falls below this.
+    #[serde(default = "default_exit_annualized_spread")]
+    pub exit_annualized_spread: f64,
+    #[serde(default = "default_true")]
+    pub exit_on_predicted_flip: bool,
+    #[serde(default)]
+    pub max_holding_hours: Option<u64>,
+    /// Maintenance margin over equity on either venue that forces an unwind.
+    #[serde(default = "default_max_margin_ratio")]
+    pub max_margin_ratio: f64,
+    /// Relative size difference between legs tolerated before re-hedging.
+    #[serde(default = "default_rebalance_tolerance")]
+    pub rebalance_tolerance: f64,
+    /// How far past the touch entry orders may walk either book.
+    #[serde(default = "default_max_depth_slippage_bps")]
+    pub max_depth_slippage_bps: u64,
+    #[serde(default)]
+    pub execution: ExecutionConfig,
 }
 
 impl Default for FundingArbConfig {
@@ -125,10 +336,91 @@ impl Default for FundingArbConfig {
         Self {
             min_annualized_spread: default_min_annualized_spread(),
             check_interval_ms: default_check_interval_ms(),
+            funding_poll_interval_ms: default_funding_poll_interval_ms(),
+            exit_annualized_spread: default_exit_annualized_spread(),
+            exit_on_predicted_flip: true,
+            max_holding_hours: None,
+            max_margin_ratio: default_max_margin_ratio(),
+            rebalance_tolerance: default_rebalance_tolerance(),
+            max_depth_slippage_bps: default_max_depth_slippage_bps(),
+            execution: ExecutionConfig::default(),
+        }
+    }
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_exit_annualized_spread() -> f64 {
+    0.01
+}
+
+fn default_max_margin_ratio() -> f64 {
+    0.8
+}
+
+fn default_rebalance_tolerance() -> f64 {
+    0.05
+}
+
+fn default_max_depth_slippage_bps() -> u64 {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegMode {
+    /// Send both legs at once.
+    Concurrent,
+    /// Send the second leg only once the first has filled.
+    #[default]
+    Sequential,
+}
+
+/// How two-legged executions handle a leg that does not fill: the remainder
+/// is re-sent `max_retries` times, then chased `chase_slippage_bps` further
+/// through the book, and if it still falls short the filled legs are unwound.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecutionConfig {
+    #[serde(default)]
+    pub leg_mode: LegMode,
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+    #[serde(default = "default_retry_delay_ms")]
+    pub retry_delay_ms: u64,
+    /// Zero disables chasing.
+    #[serde(default = "default_chase_slippage_bps")]
+    pub chase_slippage_bps: u64,
+}
+
+impl Default for ExecutionConfig {
+    fn default() -> Self {
+        Self {
+            leg_mode: LegMode::default(),
+            max_retries: default_max_retries(),
+            retry_delay_ms: default_retry_delay_ms(),
+            chase_slippage_bps: default_chase_slippage_bps(),
         }
     }
 }
 
+fn default_max_retries() -> u32 {
+    1
+}
+
+fn default_retry_delay_ms() -> u64 {
+    250
+}
+
+fn default_chase_slippage_bps() -> u64 {
+    20
+}
+
+fn default_funding_poll_interval_ms() -> u64 {
+    300000
+}
+
 fn default_min_annualized_spread() -> f64 {
     0.05
 }
@@ -141,15 +433,22 @@ fn default_check_interval_ms() -> u64 {
 pub struct HyperEvmSpotConfig {
     #[serde(default = "default_min_spread_bps")]
     pub min_spread_bps: u64,
+    /// An open position is unwound once the spread it was opened on is down to this.
+    #[serde(default = "default_exit_spread_bps")]
+    pub exit_spread_bps: i64,
     #[serde(default = "default_check_interval_ms")]
     pub check_interval_ms: u64,
+    #[serde(default)]
+    pub execution: ExecutionConfig,
 }
 
 impl Default for HyperEvmSpotConfig {
     fn default() -> Self {
         Self {
             min_spread_bps: default_min_spread_bps(),
+            exit_spread_bps: default_exit_spread_bps(),
             check_interval_ms: default_check_interval_ms(),
+            execution: ExecutionConfig::default(),
         }
     }
 }
@@ -158,6 +457,10 @@ fn default_min_spread_bps() -> u64 {
     10
 }
 
+fn default_exit_spread_bps() -> i64 {
+    2
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SolanaJupiterConfig {
     #[serde(default = "default_min_profit_bps")]
@@ -186,6 +489,179 @@ fn default_max_slippage_bps() -> u64 {
     50
 }
 
+/// WebSocket market-data streaming. `symbols` are base coins (e.g. `BTC`),
+/// subscribed as the coin on Hyperliquid and the USDT perpetual elsewhere.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StreamingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub symbols: Vec<String>,
+    #[serde(default = "default_heartbeat_interval_ms")]
+    pub heartbeat_interval_ms: u64,
+    #[serde(default = "default_stale_timeout_ms")]
+    pub stale_timeout_ms: u64,
+    #[serde(default = "default_reconnect_backoff_max_ms")]
+    pub reconnect_backoff_max_ms: u64,
+}
+
+impl Default for StreamingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            symbols: Vec::new(),
+            heartbeat_interval_ms: default_heartbeat_interval_ms(),
+            stale_timeout_ms: default_stale_timeout_ms(),
+            reconnect_backoff_max_ms: default_reconnect_backoff_max_ms(),
+        }
+    }
+}
+
+fn default_heartbeat_interval_ms() -> u64 {
+    15000
+}
+
+fn default_stale_timeout_ms() -> u64 {
+    60000
+}
+
+fn default_reconnect_backoff_max_ms() -> u64 {
+    30000
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PersistenceConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Directory holding the snapshot and the journal appended between snapshots.
+    #[serde(default = "default_state_dir")]
+    pub state_dir: String,
+    #[serde(default = "default_persist_interval_ms")]
+    pub persist_interval_ms: u64,
+    /// Journal entries after which the journal is folded into a fresh snapshot.
+    #[serde(default = "default_compact_after_entries")]
+    pub compact_after_entries: usize,
+    /// Hours closed hedges and settled trades are kept before a snapshot drops them.
+    #[serde(default = "default_retain_closed_hours")]
+    pub retain_closed_hours: u64,
+}
+
+impl Default for PersistenceConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            state_dir: default_state_dir(),
+            persist_interval_ms: default_persist_interval_ms(),
+            compact_after_entries: default_compact_after_entries(),
+            retain_closed_hours: default_retain_closed_hours(),
+        }
+    }
+}
+
+fn default_state_dir() -> String {
+    "state".to_string()
+}
+
+fn default_persist_interval_ms() -> u64 {
+    1000
+}
+
+fn default_compact_after_entries() -> usize {
+    1000
+}
+
+fn default_retain_closed_hours() -> u64 {
+    24
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReconciliationConfig {
+    #[serde(default = "default_reconcile_interval_ms")]
+    pub interval_ms: u64,
+    /// Close the surviving leg of a hedge whose other leg has disappeared.
+    #[serde(default)]
+    pub auto_flatten_orphans: bool,
+    /// How long a leg must stay orphaned before it is flattened, so a
+    /// strategy can finish closing a hedge it is unwinding itself.
+    #[serde(default = "default_orphan_grace_ms")]
+    pub orphan_grace_ms: u64,
+}
+
+impl Default for ReconciliationConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: default_reconcile_interval_ms(),
+            auto_flatten_orphans: false,
+            orphan_grace_ms: default_orphan_grace_ms(),
+        }
+    }
+}
+
+fn default_reconcile_interval_ms() -> u64 {
+    60000
+}
+
+fn default_orphan_grace_ms() -> u64 {
+    120000
+}
+
+/// Control endpoints accept a bearer token from `operators`; with none
+/// configured they refuse every request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiConfig {
+    #[serde(default)]
+    pub operators: Vec<OperatorConfig>,
+    /// JSON lines file every control action is appended to.
+    #[serde(default = "default_audit_log_path")]
+    pub audit_log_path: String,
+}
+
+impl Default for ApiConfig {
+    fn default() -> Self {
+        Self {
+            operators: Vec::new(),
+            audit_log_path: default_audit_log_path(),
+        }
+    }
+}
+
+fn default_audit_log_path() -> String {
+    "state/audit.jsonl".to_string()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperatorConfig {
+    /// Recorded as the actor in the audit log.
+    pub name: String,
+    pub token: String,
+}
+
+/// Shortest operator token accepted; the control API can flatten the book.
+pub const MIN_OPERATOR_TOKEN_LEN: usize = 32;
+
+/// Tokens copied from examples rather than generated.
+const PLACEHOLDER_TOKENS: &[&str] = &["change-me", "changeme", "token", "secret", "password"];
+
+impl ApiConfig {
+    /// Refuses operator tokens that are empty, short or known placeholders.
+    pub fn validate(&self) -> Result<()> {
+        for operator in &self.operators {
+            let token = operator.token.as_str();
+            if PLACEHOLDER_TOKENS.contains(&token.to_lowercase().as_str()) {
+                anyhow::bail!("API operator {} uses a placeholder token", operator.name);
+            }
+            if token.len() < MIN_OPERATOR_TOKEN_LEN {
+                anyhow::bail!(
+                    "API operator {} token must be at least {} characters",
+                    operator.name,
+                    MIN_OPERATOR_TOKEN_LEN
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
 impl Config {
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
         let content = std::fs::read_to_string(path.as_ref())
@@ -197,6 +673,9 @@ impl Config {
         if let Ok(key) = std::env::var("HYPERLIQUID_PRIVATE_KEY") {
             config.hyperliquid.private_key = key;
         }
+        if let Ok(key) = std::env::var("HYPEREVM_PRIVATE_KEY") {
+            config.hyperevm.private_key = key;
+        }
         if let Ok(key) = std::env::var("BINANCE_API_KEY") {
             config.binance.api_key = key;
         }
@@ -213,6 +692,11 @@ impl Config {
             config.solana.private_key = key;
         }
 
+        // Validation and the control API both see the token as stored
+        for operator in &mut config.api.operators {
+            operator.token = operator.token.trim().to_string();
+        }
+
         Ok(config)
     }
 }
@@ -0,0 +1,212 @@
+use crate::risk::KillSwitchReason;
+use crate::types::{
+    ArbitrageOpportunity, FundingRate, Hedge, MarketData, Position, PositionSide, Trade, Venue,
+};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::{RecvError, TryRecvError};
+use tracing::{error, warn};
+
+/// Events retained for the slowest subscriber before it starts missing them.
+pub const DEFAULT_EVENT_CAPACITY: usize = 4096;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    MarketUpdate(MarketData),
+    FundingUpdate(FundingRate),
+    OpportunityDetected(ArbitrageOpportunity),
+    OrderSubmitted {
+        venue: Venue,
+        symbol: String,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        reduce_only: bool,
+    },
+    OrderFilled(Trade),
+    RiskRejected {
+        venue: Venue,
+        symbol: String,
+        notional: Decimal,
+        reason: String,
+    },
+    KillSwitchTripped {
+        reason: KillSwitchReason,
+        at: DateTime<Utc>,
+    },
+    PositionClosed {
+        venue: Venue,
+        symbol: String,
+        side: PositionSide,
+        size: Decimal,
+    },
+    /// Reconciliation adopted the venue's own view of its positions.
+    PositionsReplaced {
+        venue: Venue,
+        positions: Vec<Position>,
+    },
+    /// A hedge was opened, resized, closed or credited funding.
+    HedgeUpdated(Hedge),
+}
+
+impl Event {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::MarketUpdate(_) => "market_update",
+            Self::FundingUpdate(_) => "funding_update",
+            Self::OpportunityDetected(_) => "opportunity_detected",
+            Self::OrderSubmitted { .. } => "order_submitted",
+            Self::OrderFilled(_) => "order_filled",
+            Self::RiskRejected { .. } => "risk_rejected",
+            Self::KillSwitchTripped { .. } => "kill_switch_tripped",
+            Self::PositionClosed { .. } => "position_closed",
+            Self::PositionsReplaced { .. } => "positions_replaced",
+            Self::HedgeUpdated(_) => "hedge_updated",
+        }
+    }
+
+    /// Whether the event changes what the persister writes.
+    pub fn changes_persisted_state(&self) -> bool {
+        matches!(
+            self,
+            Self::OrderFilled(_)
+                | Self::KillSwitchTripped { .. }
+                | Self::PositionClosed { .. }
+                | Self::PositionsReplaced { .. }
+                | Self::HedgeUpdated(_)
+        )
+    }
+}
+
+/// Typed broadcast channel between components. Publishing never blocks: a
+/// subscriber that falls `capacity` events behind skips the oldest instead
+/// of holding up the trading path.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Event>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity.max(1));
+        Self { sender }
+    }
+
+    pub fn publish(&self, event: Event) {
+        // Only fails when nobody is subscribed, which is fine
+        let _ = self.sender.send(event);
+    }
+
+    /// `name` identifies the subscriber in lag warnings.
+    pub fn subscribe(&self, name: impl Into<String>) -> EventSubscriber {
+        EventSubscriber {
+            name: name.into(),
+            receiver: self.sender.subscribe(),
+            missed: 0,
+        }
+    }
+
+    pub fn subscriber_count(&self) -> usize {
+        self.sender.receiver_count()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_CAPACITY)
+    }
+}
+
+/// Receives every event published after it subscribed.
+pub struct EventSubscriber {
+    name: String,
+    receiver: broadcast::Receiver<Event>,
+    missed: u64,
+}
+
+impl EventSubscriber {
+    /// Next event, or `None` once the bus is gone. After lagging, the
+    /// skipped events are counted and delivery resumes from the oldest held.
+    pub async fn recv(&mut self) -> Option<Event> {
+        loop {
+            match self.receiver.recv().await {
+                Ok(event) => return Some(event),
+                Err(RecvError::Lagged(skipped)) => self.lagged(skipped),
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Next event if one is already waiting.
+    pub fn try_recv(&mut self) -> Option<Event> {
+        loop {
+            match self.receiver.try_recv() {
+                Ok(event) => return Some(event),
+                Err(TryRecvError::Lagged(skipped)) => self.lagged(skipped),
+                Err(TryRecvError::Empty | TryRecvError::Closed) => return None,
+            }
+        }
+    }
+
+    /// Events skipped because this subscriber fell behind.
+    pub fn missed(&self) -> u64 {
+        self.missed
+    }
+
+    fn lagged(&mut self, skipped: u64) {
+        self.missed += skipped;
+        warn!("{} event subscriber lagged, skipped {} events", self.name, skipped);
+    }
+}
+
+/// Event counts by kind, for the metrics endpoint.
+#[derive(Debug, Clone, Default)]
+pub struct EventMetrics {
+    counts: Arc<RwLock<BTreeMap<&'static str, u64>>>,
+    missed: Arc<AtomicU64>,
+}
+
+impl EventMetrics {
+    pub async fn run(&self, mut events: EventSubscriber) {
+        while let Some(event) = events.recv().await {
+            *self.counts.write().entry(event.kind()).or_insert(0) += 1;
+            self.missed.store(events.missed(), Ordering::Relaxed);
+        }
+    }
+
+    pub fn counts(&self) -> BTreeMap<&'static str, u64> {
+        self.counts.read().clone()
+    }
+
+    /// Events the metrics subscriber itself missed, so counts are lower bounds.
+    pub fn missed(&self) -> u64 {
+        self.missed.load(Ordering::Relaxed)
+    }
+}
+
+/// Logs the events an operator has to act on.
+pub async fn run_alerts(mut events: EventSubscriber) {
+    while let Some(event) = events.recv().await {
+        match event {
+            Event::KillSwitchTripped { reason, at } => {
+                error!("ALERT: kill switch tripped at {}: {}", at.to_rfc3339(), reason);
+            }
+            Event::RiskRejected {
+                venue,
+                symbol,
+                notional,
+                reason,
+            } => {
+                warn!("Risk rejected {} {} on {:?}: {}", notional, symbol, venue, reason);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::types::{ContractSpec, Venue};
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+
+/// One venue's listing of a perpetual, keyed by the canonical asset id
+/// strategies trade under (e.g. `PEPE` for `1000PEPEUSDT` and `kPEPE`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Instrument {
+    pub asset: String,
+    pub venue: Venue,
+    pub native_symbol: String,
+    pub quote_asset: String,
+    /// Trading rules under the canonical symbol; `contract_multiplier` is the
+    /// number of asset units per native contract, 1000 for `1000PEPE`.
+    pub spec: ContractSpec,
+}
+
+/// Splits a venue's base asset into the canonical asset id and the number of
+/// units one native contract stands for: `1000PEPE`, `SHIB1000` and `kPEPE`
+/// are thousand-unit contracts, `1MBABYDOGE` a million-unit one.
+pub fn canonical_asset(base: &str) -> (String, Decimal) {
+    let starts_upper = |rest: &str| rest.starts_with(|c: char| c.is_ascii_uppercase());
+    // Hyperliquid marks thousand-unit contracts with a lowercase k
+    if let Some(rest) = base.strip_prefix('k').filter(|rest| starts_upper(rest)) {
+        return (rest.to_string(), Decimal::from(1000));
+    }
+    if let Some(rest) = base.strip_prefix("1M").filter(|rest| starts_upper(rest)) {
+        return (rest.to_string(), Decimal::from(1_000_000));
+    }
+
+    let leading = base.len() - base.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let (prefix, rest) = base.split_at(leading);
+    if let Some(multiplier) = multiplier(prefix).filter(|_| !rest.is_empty()) {
+        return (rest.to_string(), multiplier);
+    }
+    let trailing = base.trim_end_matches(|c: char| c.is_ascii_digit()).len();
+    let (rest, suffix) = base.split_at(trailing);
+    if let Some(multiplier) = multiplier(suffix).filter(|_| !rest.is_empty()) {
+        return (rest.to_string(), multiplier);
+    }
+    (base.to_string(), Decimal::ONE)
+}
+
+/// Only powers of ten from 1000 up count; `1INCH` is a token name.
+fn multiplier(digits: &str) -> Option<Decimal> {
+    let is_power = digits.len() >= 4
+        && digits.starts_with('1')
+        && digits[1..].chars().all(|c| c == '0');
+    is_power.then(|| digits.parse::<u64>().ok()).flatten().map(Decimal::from)
+}
+
+/// Maps canonical asset ids to each venue's native symbol and contract spec.
+#[derive(Debug, Default)]
+pub struct InstrumentRegistry {
+    instruments: RwLock<HashMap<(String, Venue), Instrument>>,
+    assets: RwLock<HashMap<(Venue, String), String>>,
+}
+
+impl InstrumentRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces everything known about `venue` with `listing`.
+    pub fn load_venue(&self, venue: &Venue, listing: Vec<Instrument>) -> usize {
+        let mut instruments = self.instruments.write();
+        let mut assets = self.assets.write();
+        instruments.retain(|(_, v), _| v != venue);
+        assets.retain(|(v, _), _| v != venue);
+
+        let mut loaded = 0;
+        for instrument in listing.into_iter().filter(|i| &i.venue == venue) {
+            assets.insert(
+                (venue.clone(), instrument.native_symbol.clone()),
+                instrument.asset.clone(),
+            );
+            instruments.insert((instrument.asset.clone(), venue.clone()), instrument);
+            loaded += 1;
+        }
+        loaded
+    }
+
+    pub fn get(&self, asset: &str, venue: &Venue) -> Option<Instrument> {
+        self.instruments
+            .read()
+            .get(&(asset.to_string(), venue.clone()))
+            .cloned()
+    }
+
+    pub fn native_symbol(&self, asset: &str, venue: &Venue) -> Option<String> {
+        self.get(asset, venue).map(|i| i.native_symbol)
+    }
+
+    /// Canonical asset id for a venue's native symbol.
+    pub fn asset(&self, venue: &Venue, native_symbol: &str) -> Option<String> {
+        self.assets
+            .read()
+            .get(&(venue.clone(), native_symbol.to_string()))
+            .cloned()
+    }
+
+    pub fn spec(&self, asset: &str, venue: &Venue) -> Option<ContractSpec> {
+        self.get(asset, venue).map(|i| i.spec)
+    }
+
+    /// Assets listed on every one of `venues`, sorted.
+    pub fn common_assets(&self, venues: &[Venue]) -> Vec<String> {
+        let instruments = self.instruments.read();
+        let Some((first, others)) = venues.split_first() else {
+            return Vec::new();
+        };
+        instruments
+            .keys()
+            .filter(|(asset, venue)| {
+                venue == first
+                    && others
+                        .iter()
+                        .all(|v| instruments.contains_key(&(asset.clone(), v.clone())))
+            })
+            .map(|(asset, _)| asset.clone())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.instruments.read().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.instruments.read().is_empty()
+    }
+}
@@ -1,7 +1,17 @@
+pub mod audit;
 pub mod config;
+pub mod events;
+pub mod instruments;
+pub mod opportunities;
+pub mod persistence;
 pub mod risk;
 pub mod types;
 
+pub use audit::{AuditEntry, AuditFilter, AuditLog, AuditPage, ControlAction};
 pub use config::Config;
-pub use risk::RiskEngine;
+pub use events::{Event, EventBus, EventMetrics, EventSubscriber};
+pub use instruments::{Instrument, InstrumentRegistry};
+pub use opportunities::{OpportunityFilter, OpportunityPage, OpportunityStore};
+pub use persistence::{JournalEntry, StatePersister, StateSnapshot, StateStore};
+pub use risk::{KillSwitchReason, KillSwitchState, RiskEngine, RiskReservation, RiskSnapshot};
 pub use types::*;
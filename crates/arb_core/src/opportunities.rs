@@ -0,0 +1,146 @@
+use crate::types::{ArbitrageOpportunity, OpportunityStatus, StrategyType};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use std::collections::VecDeque;
+
+/// Opportunities kept when `SharedState` builds the store.
+pub const DEFAULT_OPPORTUNITY_CAPACITY: usize = 10_000;
+
+/// Selects opportunities from the store, newest first.
+#[derive(Debug, Clone, Default)]
+pub struct OpportunityFilter {
+    pub strategy: Option<StrategyType>,
+    pub symbol: Option<String>,
+    pub status: Option<OpportunityStatus>,
+    /// Inclusive lower bound on `timestamp`.
+    pub since: Option<DateTime<Utc>>,
+    /// Exclusive upper bound on `timestamp`.
+    pub until: Option<DateTime<Utc>>,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// One page of a query and how many opportunities matched in total.
+#[derive(Debug, Clone)]
+pub struct OpportunityPage {
+    pub total: usize,
+    pub opportunities: Vec<ArbitrageOpportunity>,
+}
+
+#[derive(Debug)]
+struct Ring {
+    next_id: u64,
+    /// Ordered by id, and so by timestamp, oldest first.
+    entries: VecDeque<ArbitrageOpportunity>,
+}
+
+/// Bounded history of opportunities in detection order; the oldest are
+/// dropped once `capacity` is reached.
+#[derive(Debug)]
+pub struct OpportunityStore {
+    capacity: usize,
+    ring: RwLock<Ring>,
+}
+
+impl OpportunityStore {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ring: RwLock::new(Ring {
+                next_id: 1,
+                entries: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Stores `opportunity` under a new id, stamped now so the history stays
+    /// in time order.
+    pub fn record(&self, mut opportunity: ArbitrageOpportunity) -> u64 {
+        let mut ring = self.ring.write();
+        let id = ring.next_id;
+        ring.next_id += 1;
+
+        opportunity.id = id;
+        opportunity.timestamp = match ring.entries.back() {
+            Some(last) => Utc::now().max(last.timestamp),
+            None => Utc::now(),
+        };
+        if ring.entries.len() == self.capacity {
+            ring.entries.pop_front();
+        }
+        ring.entries.push_back(opportunity);
+        id
+    }
+
+    /// Applies `update` to the opportunity with `id`, if it is still held.
+    pub fn update(&self, id: u64, update: impl FnOnce(&mut ArbitrageOpportunity)) -> bool {
+        let mut ring = self.ring.write();
+        let Some(first) = ring.entries.front().map(|o| o.id) else {
+            return false;
+        };
+        match id.checked_sub(first).and_then(|i| ring.entries.get_mut(i as usize)) {
+            Some(opportunity) => {
+                update(opportunity);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn set_status(&self, id: u64, status: OpportunityStatus, reason: Option<String>) -> bool {
+        self.update(id, |opportunity| {
+            opportunity.status = status;
+            opportunity.reason = reason;
+        })
+    }
+
+    pub fn get(&self, id: u64) -> Option<ArbitrageOpportunity> {
+        let ring = self.ring.read();
+        let first = ring.entries.front()?.id;
+        ring.entries.get(id.checked_sub(first)? as usize).cloned()
+    }
+
+    pub fn query(&self, filter: &OpportunityFilter) -> OpportunityPage {
+        let ring = self.ring.read();
+        let entries = &ring.entries;
+        // Timestamps are non-decreasing, so the window is a contiguous range
+        let start = filter
+            .since
+            .map_or(0, |since| entries.partition_point(|o| o.timestamp < since));
+        let end = filter
+            .until
+            .map_or(entries.len(), |until| entries.partition_point(|o| o.timestamp < until));
+
+        let matching: Vec<&ArbitrageOpportunity> = entries
+            .range(start..end.max(start))
+            .rev()
+            .filter(|o| filter.strategy.as_ref().is_none_or(|s| &o.strategy == s))
+            .filter(|o| filter.symbol.as_ref().is_none_or(|s| &o.symbol == s))
+            .filter(|o| filter.status.is_none_or(|s| o.status == s))
+            .collect();
+
+        OpportunityPage {
+            total: matching.len(),
+            opportunities: matching
+                .into_iter()
+                .skip(filter.offset)
+                .take(filter.limit)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ring.read().entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ring.read().entries.is_empty()
+    }
+}
+
+impl Default for OpportunityStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_OPPORTUNITY_CAPACITY)
+    }
+}
@@ -0,0 +1,379 @@
+use crate::events::{Event, EventSubscriber};
+use crate::risk::{RiskEngine, RiskSnapshot};
+use crate::types::{Hedge, Position, SharedState, Trade, TradeStatus, Venue};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use dashmap::DashMap;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::spawn_blocking;
+use tokio::time::MissedTickBehavior;
+use tracing::{error, info, warn};
+
+const SNAPSHOT_FILE: &str = "snapshot.json";
+const JOURNAL_FILE: &str = "journal.jsonl";
+
+/// Risk and position state that survives a restart.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    pub risk: Option<RiskSnapshot>,
+    pub positions: HashMap<String, Position>,
+    /// Keyed by order id, as in `SharedState.trades`.
+    pub trades: HashMap<String, Trade>,
+    pub hedges: HashMap<String, Hedge>,
+}
+
+/// One change appended to the journal between snapshots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum JournalEntry {
+    Risk { risk: RiskSnapshot },
+    PositionUpserted { position: Position },
+    PositionRemoved { key: String },
+    /// Every position held on `venue`, as reconciliation left them.
+    PositionsReplaced { venue: Venue, positions: Vec<Position> },
+    TradeRecorded { order_id: String, trade: Trade },
+    HedgeUpserted { hedge: Hedge },
+}
+
+impl StateSnapshot {
+    pub fn capture(state: &SharedState, risk_engine: &RiskEngine) -> Self {
+        Self {
+            risk: Some(risk_engine.snapshot()),
+            positions: copy(&state.positions),
+            trades: copy(&state.trades),
+            hedges: copy(&state.hedges),
+        }
+    }
+
+    pub fn apply(&mut self, entry: JournalEntry) {
+        match entry {
+            JournalEntry::Risk { risk } => self.risk = Some(risk),
+            JournalEntry::PositionUpserted { position } => {
+                let key = Position::key(&position.venue, &position.symbol);
+                self.positions.insert(key, position);
+            }
+            JournalEntry::PositionRemoved { key } => {
+                self.positions.remove(&key);
+            }
+            JournalEntry::PositionsReplaced { venue, positions } => {
+                self.positions.retain(|_, p| p.venue != venue);
+                for position in positions {
+                    let key = Position::key(&position.venue, &position.symbol);
+                    self.positions.insert(key, position);
+                }
+            }
+            JournalEntry::TradeRecorded { order_id, trade } => {
+                self.trades.insert(order_id, trade);
+            }
+            JournalEntry::HedgeUpserted { hedge } => {
+                self.hedges.insert(hedge.id.clone(), hedge);
+            }
+        }
+    }
+
+    pub fn restore_into(self, state: &SharedState, risk_engine: &RiskEngine) {
+        if let Some(risk) = self.risk {
+            risk_engine.restore(risk);
+        }
+        for (key, position) in self.positions {
+            state.positions.insert(key, position);
+        }
+        for (order_id, trade) in self.trades {
+            state.trades.insert(order_id, trade);
+        }
+        for (id, hedge) in self.hedges {
+            state.hedges.insert(id, hedge);
+        }
+    }
+}
+
+fn copy<V: Clone>(map: &DashMap<String, V>) -> HashMap<String, V> {
+    map.iter().map(|e| (e.key().clone(), e.value().clone())).collect()
+}
+
+/// A snapshot file plus an append-only journal of the changes since it was
+/// written. Loading replays the journal over the snapshot.
+pub struct StateStore {
+    dir: PathBuf,
+    journal: File,
+    journal_entries: usize,
+}
+
+impl StateStore {
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create state directory {:?}", dir))?;
+        let journal = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(dir.join(JOURNAL_FILE))
+            .context("Failed to open state journal")?;
+        Ok(Self {
+            dir,
+            journal,
+            journal_entries: 0,
+        })
+    }
+
+    pub fn load(&mut self) -> Result<StateSnapshot> {
+        let snapshot_path = self.dir.join(SNAPSHOT_FILE);
+        let mut snapshot = if snapshot_path.exists() {
+            let content =
+                fs::read_to_string(&snapshot_path).context("Failed to read state snapshot")?;
+            serde_json::from_str(&content).context("Failed to parse state snapshot")?
+        } else {
+            StateSnapshot::default()
+        };
+
+        let content =
+            fs::read(self.dir.join(JOURNAL_FILE)).context("Failed to read state journal")?;
+        self.journal_entries = 0;
+        // Byte length of the journal up to and including its last good entry
+        let mut good_len = 0;
+        let mut start = 0;
+        let mut line_no = 0;
+        while start < content.len() {
+            let end = content[start..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(content.len(), |i| start + i);
+            let line = &content[start..end];
+            line_no += 1;
+            let last = end + 1 >= content.len();
+            match serde_json::from_slice::<JournalEntry>(line) {
+                Ok(entry) => {
+                    snapshot.apply(entry);
+                    self.journal_entries += 1;
+                    good_len = end + 1;
+                }
+                // A crash mid-append can leave a torn final line
+                Err(e) if last => {
+                    warn!("Ignoring torn final journal entry: {}", e);
+                }
+                Err(e) => {
+                    return Err(e)
+                        .with_context(|| format!("Corrupt journal entry at line {}", line_no));
+                }
+            }
+            start = end + 1;
+        }
+
+        // Cut the torn tail off so later appends start on a fresh line
+        if good_len != content.len() {
+            self.journal
+                .set_len(good_len.min(content.len()) as u64)
+                .context("Failed to truncate torn state journal entry")?;
+            if good_len > content.len() {
+                // The last good entry was written without its newline
+                self.journal.write_all(b"\n").context("Failed to repair state journal")?;
+            }
+            self.journal.sync_all().context("Failed to sync state journal")?;
+        }
+        Ok(snapshot)
+    }
+
+    pub fn append(&mut self, entries: &[JournalEntry]) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
+        }
+        let mut buffer = Vec::new();
+        for entry in entries {
+            serde_json::to_writer(&mut buffer, entry)?;
+            buffer.push(b'\n');
+        }
+        self.journal.write_all(&buffer).context("Failed to append to state journal")?;
+        self.journal.sync_data().context("Failed to sync state journal")?;
+        self.journal_entries += entries.len();
+        Ok(())
+    }
+
+    /// Writes `snapshot` atomically and empties the journal it supersedes.
+    pub fn compact(&mut self, snapshot: &StateSnapshot) -> Result<()> {
+        let tmp = self.dir.join(format!("{}.tmp", SNAPSHOT_FILE));
+        let mut file = File::create(&tmp).context("Failed to write state snapshot")?;
+        serde_json::to_writer(&mut file, snapshot)?;
+        file.sync_all().context("Failed to sync state snapshot")?;
+        fs::rename(&tmp, self.dir.join(SNAPSHOT_FILE))
+            .context("Failed to replace state snapshot")?;
+
+        self.journal.set_len(0).context("Failed to truncate state journal")?;
+        self.journal.sync_all()?;
+        self.journal_entries = 0;
+        Ok(())
+    }
+
+    pub fn journal_entries(&self) -> usize {
+        self.journal_entries
+    }
+}
+
+/// Journals changes to shared and risk state as their events arrive, folding
+/// the journal into a new snapshot once it grows past `compact_after_entries`.
+/// File IO runs on the blocking pool.
+pub struct StatePersister {
+    store: Arc<Mutex<StateStore>>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+    interval: Duration,
+    compact_after_entries: usize,
+    /// How long closed hedges and settled trades are kept in snapshots.
+    retain_closed: chrono::Duration,
+    /// Risk as last written, so an unchanged snapshot is not journaled again.
+    persisted_risk: Mutex<Option<RiskSnapshot>>,
+}
+
+impl StatePersister {
+    /// `store` should already hold what was restored into `state`; only
+    /// changes from here on are journaled.
+    pub fn new(
+        store: StateStore,
+        state: Arc<SharedState>,
+        risk_engine: Arc<RiskEngine>,
+        interval_ms: u64,
+        compact_after_entries: usize,
+        retain_closed_hours: u64,
+    ) -> Self {
+        Self {
+            store: Arc::new(Mutex::new(store)),
+            state,
+            risk_engine,
+            interval: Duration::from_millis(interval_ms),
+            compact_after_entries,
+            retain_closed: chrono::Duration::hours(retain_closed_hours as i64),
+            persisted_risk: Mutex::new(None),
+        }
+    }
+
+    /// Journals fills, closes and hedge updates as they arrive, and risk
+    /// whenever it moved. Missing events falls back to a full snapshot.
+    pub async fn run_loop(&self, mut events: EventSubscriber) {
+        info!("Starting state persistence loop");
+
+        let mut ticker = tokio::time::interval(self.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut missed = events.missed();
+        loop {
+            let mut batch = Vec::new();
+            tokio::select! {
+                _ = ticker.tick() => {}
+                // The bus lives in `state`, so it never closes under us
+                Some(event) = events.recv() => batch.push(event),
+            }
+            // Whatever else is already queued goes out in the same write
+            while let Some(event) = events.try_recv() {
+                batch.push(event);
+            }
+            batch.retain(Event::changes_persisted_state);
+
+            let result = if events.missed() > missed {
+                missed = events.missed();
+                warn!("State journal missed events, writing a full snapshot");
+                self.snapshot().await
+            } else {
+                self.record(&batch).await.map(|_| ())
+            };
+            if let Err(e) = result {
+                error!("Failed to persist state: {:#}", e);
+            }
+        }
+    }
+
+    /// Journals what `events` changed, and risk if it moved since it was last
+    /// written; returns the entry count.
+    pub async fn record(&self, events: &[Event]) -> Result<usize> {
+        let mut entries: Vec<JournalEntry> = events.iter().flat_map(|e| self.entries(e)).collect();
+        let risk = self.risk_engine.snapshot();
+        if self.persisted_risk.lock().as_ref() != Some(&risk) {
+            entries.push(JournalEntry::Risk { risk: risk.clone() });
+        }
+        if entries.is_empty() {
+            return Ok(0);
+        }
+
+        let count = entries.len();
+        let store = self.store.clone();
+        let compact_after_entries = self.compact_after_entries;
+        let compact = spawn_blocking(move || -> Result<bool> {
+            let mut store = store.lock();
+            store.append(&entries)?;
+            Ok(store.journal_entries() >= compact_after_entries)
+        })
+        .await
+        .context("State journal writer panicked")??;
+        *self.persisted_risk.lock() = Some(risk);
+
+        if compact {
+            self.snapshot().await?;
+        }
+        Ok(count)
+    }
+
+    /// Writes the whole state as a fresh snapshot, less closed hedges and
+    /// settled trades past retention, and empties the journal. Live state
+    /// keeps everything.
+    pub async fn snapshot(&self) -> Result<()> {
+        let cutoff = Utc::now() - self.retain_closed;
+        let mut snapshot = StateSnapshot::capture(&self.state, &self.risk_engine);
+        snapshot.hedges.retain(|_, h| h.closed_at.is_none_or(|closed| closed > cutoff));
+        snapshot
+            .trades
+            .retain(|_, t| t.status == TradeStatus::Pending || t.timestamp > cutoff);
+
+        let risk = snapshot.risk.clone();
+        let store = self.store.clone();
+        spawn_blocking(move || store.lock().compact(&snapshot))
+            .await
+            .context("State snapshot writer panicked")??;
+        *self.persisted_risk.lock() = risk;
+        Ok(())
+    }
+
+    /// The current state of whatever `event` touched.
+    fn entries(&self, event: &Event) -> Vec<JournalEntry> {
+        match event {
+            Event::OrderFilled(trade) => vec![
+                JournalEntry::TradeRecorded {
+                    order_id: trade.record_id(),
+                    trade: trade.clone(),
+                },
+                self.position_entry(&trade.venue, &trade.symbol),
+            ],
+            Event::PositionClosed { venue, symbol, .. } => vec![self.position_entry(venue, symbol)],
+            Event::PositionsReplaced { venue, .. } => {
+                let positions = self
+                    .state
+                    .positions
+                    .iter()
+                    .filter(|p| &p.venue == venue)
+                    .map(|p| p.value().clone())
+                    .collect();
+                vec![JournalEntry::PositionsReplaced {
+                    venue: venue.clone(),
+                    positions,
+                }]
+            }
+            Event::HedgeUpdated(hedge) => vec![JournalEntry::HedgeUpserted {
+                hedge: hedge.clone(),
+            }],
+            _ => Vec::new(),
+        }
+    }
+
+    fn position_entry(&self, venue: &Venue, symbol: &str) -> JournalEntry {
+        let key = Position::key(venue, symbol);
+        match self.state.positions.get(&key) {
+            Some(position) => JournalEntry::PositionUpserted {
+                position: position.clone(),
+            },
+            None => JournalEntry::PositionRemoved { key },
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use crate::config::RiskConfig;
-use crate::types::{Trade, Venue};
+use crate::events::{Event, EventBus};
+use crate::types::{AccountMargin, Position, Trade, Venue};
+use chrono::{DateTime, Duration, Utc};
 use parking_lot::RwLock;
 use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::fmt;
 use std::sync::Arc;
+use tracing::{info, warn};
 
 #[derive(Debug, Clone)]
 pub struct RiskEngine {
@@ -11,35 +16,200 @@ pub struct RiskEngine {
     daily_pnl: Arc<RwLock<Decimal>>,
     position_counts: Arc<RwLock<HashMap<Venue, usize>>>,
     notional_exposure: Arc<RwLock<HashMap<String, Decimal>>>,
-    kill_switch_active: Arc<RwLock<bool>>,
+    kill_switch: Arc<RwLock<KillSwitchState>>,
+    /// Start of the current risk day.
+    day_started: Arc<RwLock<DateTime<Utc>>>,
+    margins: Arc<RwLock<HashMap<Venue, AccountMargin>>>,
+    /// Positions and notional held by unsettled reservations.
+    in_flight: Arc<RwLock<HashMap<(Venue, String), InFlight>>>,
+    /// Notional committed on each venue, by time, that its last margin
+    /// snapshot may not include yet.
+    committed: Arc<RwLock<HashMap<Venue, Vec<Committed>>>>,
+    events: Option<EventBus>,
 }
 
+#[derive(Debug, Clone, Copy, Default)]
+struct InFlight {
+    positions: usize,
+    notional: Decimal,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Committed {
+    at: DateTime<Utc>,
+    notional: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum KillSwitchReason {
+    DailyLoss { pnl: Decimal, threshold: Decimal },
+    Manual(String),
+}
+
+impl fmt::Display for KillSwitchReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::DailyLoss { pnl, threshold } => {
+                write!(f, "daily PnL {} below threshold {}", pnl, threshold)
+            }
+            Self::Manual(note) => write!(f, "manual: {}", note),
+        }
+    }
+}
+
+/// Armed → Tripped → CoolingDown → Armed. A trip clears at the next daily
+/// rollover, then trading resumes once the cooldown has passed. Manual trips
+/// stay tripped until re-armed by hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum KillSwitchState {
+    Armed,
+    Tripped {
+        reason: KillSwitchReason,
+        at: DateTime<Utc>,
+    },
+    CoolingDown {
+        reason: KillSwitchReason,
+        until: DateTime<Utc>,
+    },
+}
+
+/// The engine's bookkeeping, as persisted across restarts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RiskSnapshot {
+    pub daily_pnl: Decimal,
+    pub day_started: DateTime<Utc>,
+    pub position_counts: HashMap<Venue, usize>,
+    pub notional_exposure: HashMap<String, Decimal>,
+    pub kill_switch: KillSwitchState,
+}
+
+/// Maintenance rate assumed for a venue with no open positions to infer it from.
+const FALLBACK_MAINTENANCE_MARGIN_RATE: Decimal = Decimal::from_parts(1, 0, 0, false, 2);
+
 impl RiskEngine {
     pub fn new(config: RiskConfig) -> Self {
+        let day_started = day_start(Utc::now(), config.daily_rollover_hour_utc);
         Self {
             config,
             daily_pnl: Arc::new(RwLock::new(Decimal::ZERO)),
             position_counts: Arc::new(RwLock::new(HashMap::new())),
             notional_exposure: Arc::new(RwLock::new(HashMap::new())),
-            kill_switch_active: Arc::new(RwLock::new(false)),
+            kill_switch: Arc::new(RwLock::new(KillSwitchState::Armed)),
+            day_started: Arc::new(RwLock::new(day_started)),
+            margins: Arc::new(RwLock::new(HashMap::new())),
+            in_flight: Arc::new(RwLock::new(HashMap::new())),
+            committed: Arc::new(RwLock::new(HashMap::new())),
+            events: None,
         }
     }
 
+    /// Publishes rejections and kill switch trips on `events`.
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn config(&self) -> &RiskConfig {
+        &self.config
+    }
+
+    /// Notional currently booked against `symbol`.
+    pub fn exposure(&self, symbol: &str) -> Decimal {
+        self.notional_exposure
+            .read()
+            .get(symbol)
+            .copied()
+            .unwrap_or(Decimal::ZERO)
+    }
+
     pub fn check_trade(&self, trade: &Trade) -> Result<(), RiskError> {
-        // Check kill switch
-        if *self.kill_switch_active.read() {
+        self.try_check_trade(trade)
+            .inspect_err(|err| self.rejected(trade, err))
+    }
+
+    fn try_check_trade(&self, trade: &Trade) -> Result<(), RiskError> {
+        self.check_kill_switch()?;
+        let counts = self.position_counts.write();
+        let exposure = self.notional_exposure.write();
+        self.check_limits(trade, &counts, &exposure)
+    }
+
+    /// Checks `trade` and books its exposure provisionally under the same locks,
+    /// so concurrent callers cannot both pass against the same headroom. The
+    /// booking is released unless the returned reservation is committed.
+    pub fn reserve(&self, trade: &Trade) -> Result<RiskReservation, RiskError> {
+        self.try_reserve(trade)
+            .inspect_err(|err| self.rejected(trade, err))
+    }
+
+    fn try_reserve(&self, trade: &Trade) -> Result<RiskReservation, RiskError> {
+        self.check_kill_switch()?;
+        let mut counts = self.position_counts.write();
+        let mut exposure = self.notional_exposure.write();
+        self.check_limits(trade, &counts, &exposure)?;
+
+        let notional = trade.size * trade.price;
+        *counts.entry(trade.venue.clone()).or_insert(0) += 1;
+        *exposure.entry(trade.symbol.clone()).or_insert(Decimal::ZERO) += notional;
+        let mut in_flight = self.in_flight.write();
+        let held = in_flight.entry((trade.venue.clone(), trade.symbol.clone())).or_default();
+        held.positions += 1;
+        held.notional += notional;
+
+        Ok(RiskReservation {
+            engine: self.clone(),
+            venue: trade.venue.clone(),
+            symbol: trade.symbol.clone(),
+            notional,
+            settled: false,
+        })
+    }
+
+    fn rejected(&self, trade: &Trade, err: &RiskError) {
+        if let Some(events) = &self.events {
+            events.publish(Event::RiskRejected {
+                venue: trade.venue.clone(),
+                symbol: trade.symbol.clone(),
+                notional: trade.size * trade.price,
+                reason: err.to_string(),
+            });
+        }
+    }
+
+    fn check_kill_switch(&self) -> Result<(), RiskError> {
+        self.tick(Utc::now());
+        if self.trip_on_daily_loss() {
+            return Err(RiskError::DailyLossThresholdExceeded);
+        }
+        if self.is_kill_switch_active() {
             return Err(RiskError::KillSwitchActive);
         }
+        Ok(())
+    }
 
-        // Check daily PnL threshold
+    fn daily_loss_threshold(&self) -> Decimal {
+        Decimal::from_f64_retain(self.config.kill_switch_daily_loss_threshold).unwrap()
+    }
+
+    /// Trips the switch if the daily loss is past the threshold. Returns true
+    /// only when this call tripped it.
+    fn trip_on_daily_loss(&self) -> bool {
         let pnl = *self.daily_pnl.read();
-        if pnl < Decimal::from_f64_retain(self.config.kill_switch_daily_loss_threshold).unwrap() {
-            *self.kill_switch_active.write() = true;
-            return Err(RiskError::DailyLossThresholdExceeded);
+        let threshold = self.daily_loss_threshold();
+        if pnl >= threshold || self.is_kill_switch_active() {
+            return false;
         }
+        self.trip(KillSwitchReason::DailyLoss { pnl, threshold });
+        true
+    }
 
+    fn check_limits(
+        &self,
+        trade: &Trade,
+        counts: &HashMap<Venue, usize>,
+        exposure: &HashMap<String, Decimal>,
+    ) -> Result<(), RiskError> {
         // Check position count per venue
-        let counts = self.position_counts.write();
         let count = counts.get(&trade.venue).copied().unwrap_or(0);
         if count >= self.config.max_open_positions_per_venue {
             return Err(RiskError::MaxPositionsExceeded);
@@ -47,19 +217,112 @@ impl RiskEngine {
 
         // Check notional exposure per asset
         let notional = trade.size * trade.price;
-        let exposure = self.notional_exposure.write();
         let current_exposure = exposure.get(&trade.symbol).copied().unwrap_or(Decimal::ZERO);
         let max_notional = Decimal::from_f64_retain(self.config.max_notional_per_asset).unwrap();
-        
+
         if current_exposure + notional > max_notional {
             return Err(RiskError::MaxNotionalExceeded);
         }
 
+        self.check_margin(&trade.venue, notional)
+    }
+
+    /// Leverage and liquidation-buffer checks against the venue's last margin
+    /// snapshot, plus whatever is reserved or committed on the venue since;
+    /// venues without a snapshot (e.g. spot) are not checked.
+    fn check_margin(&self, venue: &Venue, notional: Decimal) -> Result<(), RiskError> {
+        let margins = self.margins.read();
+        let Some(margin) = margins.get(venue) else {
+            return Ok(());
+        };
+        if margin.equity <= Decimal::ZERO {
+            return Err(RiskError::MaxLeverageExceeded);
+        }
+
+        let in_flight: Decimal = self
+            .in_flight
+            .read()
+            .iter()
+            .filter(|((v, _), _)| v == venue)
+            .map(|(_, held)| held.notional)
+            .sum();
+        let committed: Decimal = self
+            .committed
+            .read()
+            .get(venue)
+            .map_or(Decimal::ZERO, |fills| fills.iter().map(|fill| fill.notional).sum());
+        let notional = notional + in_flight + committed;
+
+        let max_leverage = Decimal::from_f64_retain(self.config.max_leverage).unwrap();
+        let leverage = (margin.position_notional + notional) / margin.equity;
+        if leverage > max_leverage {
+            return Err(RiskError::MaxLeverageExceeded);
+        }
+
+        // Assume the new position carries the account's current maintenance rate
+        let maintenance_rate = if margin.position_notional > Decimal::ZERO {
+            margin.maintenance_margin / margin.position_notional
+        } else {
+            FALLBACK_MAINTENANCE_MARGIN_RATE
+        };
+        let projected_ratio =
+            (margin.maintenance_margin + notional * maintenance_rate) / margin.equity;
+        let buffer = Decimal::from_f64_retain(self.config.liquidation_buffer).unwrap();
+        let max_ratio = Decimal::ONE - buffer;
+        if projected_ratio > max_ratio {
+            return Err(RiskError::LiquidationBufferBreached);
+        }
+
         Ok(())
     }
 
+    /// Rebuilds position counts and exposure from the positions the venues
+    /// report, keeping reservations still in flight on top. Returns whether
+    /// the books had drifted from the venues.
+    pub fn sync_positions(&self, positions: &[Position]) -> bool {
+        let mut counts = self.position_counts.write();
+        let mut exposure = self.notional_exposure.write();
+        let in_flight = self.in_flight.read();
+
+        let mut synced_counts: HashMap<Venue, usize> = HashMap::new();
+        let mut synced_exposure: HashMap<String, Decimal> = HashMap::new();
+        let held = in_flight
+            .iter()
+            .map(|((venue, symbol), held)| (venue, symbol, held.positions, held.notional));
+        let open = positions
+            .iter()
+            .filter(|p| !p.size.is_zero())
+            .map(|p| (&p.venue, &p.symbol, 1, p.size * p.entry_price));
+        for (venue, symbol, count, notional) in held.chain(open) {
+            *synced_counts.entry(venue.clone()).or_insert(0) += count;
+            *synced_exposure.entry(symbol.clone()).or_insert(Decimal::ZERO) += notional;
+        }
+
+        counts.retain(|_, count| *count > 0);
+        exposure.retain(|_, notional| !notional.is_zero());
+        synced_exposure.retain(|_, notional| !notional.is_zero());
+        let drifted = *counts != synced_counts || *exposure != synced_exposure;
+        *counts = synced_counts;
+        *exposure = synced_exposure;
+        drifted
+    }
+
+    /// Stores the venue's latest margin snapshot and forgets the commits it
+    /// already reflects.
+    pub fn update_margin(&self, margin: AccountMargin) {
+        let mut margins = self.margins.write();
+        if let Some(fills) = self.committed.write().get_mut(&margin.venue) {
+            fills.retain(|fill| fill.at > margin.timestamp);
+        }
+        margins.insert(margin.venue.clone(), margin);
+    }
+
+    pub fn margin(&self, venue: &Venue) -> Option<AccountMargin> {
+        self.margins.read().get(venue).cloned()
+    }
+
     pub fn record_trade(&self, trade: &Trade) {
-        if trade.status == crate::types::TradeStatus::Filled {
+        if trade.status.is_fill() {
             let mut counts = self.position_counts.write();
             *counts.entry(trade.venue.clone()).or_insert(0) += 1;
 
@@ -74,7 +337,12 @@ impl RiskEngine {
         if let Some(count) = counts.get_mut(venue) {
             *count = count.saturating_sub(1);
         }
+        drop(counts);
+        self.record_position_reduced(symbol, notional);
+    }
 
+    /// Releases `notional` of a position that stays open.
+    pub fn record_position_reduced(&self, symbol: &str, notional: Decimal) {
         let mut exposure = self.notional_exposure.write();
         if let Some(current) = exposure.get_mut(symbol) {
             *current = current.saturating_sub(notional);
@@ -82,17 +350,218 @@ impl RiskEngine {
     }
 
     pub fn update_daily_pnl(&self, pnl_delta: Decimal) {
-        let mut pnl = self.daily_pnl.write();
-        *pnl += pnl_delta;
+        *self.daily_pnl.write() += pnl_delta;
+        self.trip_on_daily_loss();
+    }
+
+    pub fn daily_pnl(&self) -> Decimal {
+        *self.daily_pnl.read()
     }
 
     pub fn is_kill_switch_active(&self) -> bool {
-        *self.kill_switch_active.read()
+        *self.kill_switch.read() != KillSwitchState::Armed
     }
 
+    pub fn kill_switch_state(&self) -> KillSwitchState {
+        self.kill_switch.read().clone()
+    }
+
+    /// Halts trading until the switch cools down or is re-armed. A manual trip
+    /// takes over from an automatic one so only a manual re-arm clears it;
+    /// anything else is ignored while tripped. Returns whether it took effect.
+    pub fn trip(&self, reason: KillSwitchReason) -> bool {
+        let mut state = self.kill_switch.write();
+        if let KillSwitchState::Tripped { reason: current, .. } = &*state {
+            let overrides = matches!(reason, KillSwitchReason::Manual(_))
+                && !matches!(current, KillSwitchReason::Manual(_));
+            if !overrides {
+                return false;
+            }
+        }
+        warn!("Kill switch tripped: {}", reason);
+        let at = Utc::now();
+        if let Some(events) = &self.events {
+            events.publish(Event::KillSwitchTripped {
+                reason: reason.clone(),
+                at,
+            });
+        }
+        *state = KillSwitchState::Tripped { reason, at };
+        true
+    }
+
+    /// Re-arms the switch by hand. Refused while today's loss is still past
+    /// the threshold, since the next check would trip it again.
+    pub fn rearm(&self) -> Result<(), RiskError> {
+        if *self.daily_pnl.read() < self.daily_loss_threshold() {
+            return Err(RiskError::DailyLossThresholdExceeded);
+        }
+        info!("Kill switch re-armed");
+        *self.kill_switch.write() = KillSwitchState::Armed;
+        Ok(())
+    }
+
+    /// Clears today's PnL and any trip it caused; a manual trip stays.
     pub fn reset_daily_pnl(&self) {
         *self.daily_pnl.write() = Decimal::ZERO;
-        *self.kill_switch_active.write() = false;
+        let mut state = self.kill_switch.write();
+        let manual = matches!(
+            &*state,
+            KillSwitchState::Tripped { reason: KillSwitchReason::Manual(_), .. }
+        );
+        if !manual {
+            *state = KillSwitchState::Armed;
+        }
+    }
+
+    pub fn snapshot(&self) -> RiskSnapshot {
+        RiskSnapshot {
+            daily_pnl: *self.daily_pnl.read(),
+            day_started: *self.day_started.read(),
+            position_counts: self.position_counts.read().clone(),
+            notional_exposure: self.notional_exposure.read().clone(),
+            kill_switch: self.kill_switch.read().clone(),
+        }
+    }
+
+    /// Restores persisted bookkeeping. A snapshot from an earlier risk day
+    /// rolls over straight away, so a restart never revives yesterday's loss.
+    pub fn restore(&self, snapshot: RiskSnapshot) {
+        *self.daily_pnl.write() = snapshot.daily_pnl;
+        *self.day_started.write() = snapshot.day_started;
+        *self.position_counts.write() = snapshot.position_counts;
+        *self.notional_exposure.write() = snapshot.notional_exposure;
+        *self.kill_switch.write() = snapshot.kill_switch;
+        self.tick(Utc::now());
+    }
+
+    /// When the current risk day ends.
+    pub fn next_rollover(&self) -> DateTime<Utc> {
+        *self.day_started.read() + Duration::days(1)
+    }
+
+    /// Rolls the risk day over once `now` passes the boundary and moves the
+    /// kill switch along: a trip starts cooling down at the rollover unless it
+    /// was manual or manual re-arm is configured, and a finished cooldown re-arms.
+    pub fn tick(&self, now: DateTime<Utc>) {
+        let mut day_started = self.day_started.write();
+        if now >= *day_started + Duration::days(1) {
+            let pnl = std::mem::replace(&mut *self.daily_pnl.write(), Decimal::ZERO);
+            *day_started = day_start(now, self.config.daily_rollover_hour_utc);
+            info!("Daily rollover: closed the day at {} PnL", pnl);
+
+            let mut state = self.kill_switch.write();
+            if let KillSwitchState::Tripped { reason, .. } = &*state {
+                let manual = matches!(reason, KillSwitchReason::Manual(_));
+                if !manual && !self.config.kill_switch_manual_rearm {
+                    let cooldown = Duration::seconds(self.config.kill_switch_cooldown_secs as i64);
+                    *state = KillSwitchState::CoolingDown {
+                        reason: reason.clone(),
+                        until: now + cooldown,
+                    };
+                }
+            }
+        }
+        drop(day_started);
+
+        let mut state = self.kill_switch.write();
+        if let KillSwitchState::CoolingDown { until, .. } = &*state {
+            if now >= *until {
+                info!("Kill switch cooldown finished, re-arming");
+                *state = KillSwitchState::Armed;
+            }
+        }
+    }
+
+    /// Drives [`Self::tick`] at each rollover and cooldown expiry.
+    pub async fn run_rollover_loop(&self) {
+        info!("Starting daily rollover loop");
+
+        loop {
+            let now = Utc::now();
+            let mut wake = self.next_rollover();
+            if let KillSwitchState::CoolingDown { until, .. } = self.kill_switch_state() {
+                wake = wake.min(until);
+            }
+            tokio::time::sleep((wake - now).to_std().unwrap_or_default()).await;
+            self.tick(Utc::now());
+        }
+    }
+}
+
+/// The most recent rollover boundary at or before `now`.
+fn day_start(now: DateTime<Utc>, rollover_hour: u32) -> DateTime<Utc> {
+    let boundary = now
+        .date_naive()
+        .and_hms_opt(rollover_hour, 0, 0)
+        .unwrap()
+        .and_utc();
+    if boundary > now {
+        boundary - Duration::days(1)
+    } else {
+        boundary
+    }
+}
+
+/// Provisional exposure booked by [`RiskEngine::reserve`]. Commit it with the
+/// executed trade; dropping it uncommitted releases the booking.
+#[derive(Debug)]
+pub struct RiskReservation {
+    engine: RiskEngine,
+    venue: Venue,
+    symbol: String,
+    notional: Decimal,
+    settled: bool,
+}
+
+impl RiskReservation {
+    pub fn notional(&self) -> Decimal {
+        self.notional
+    }
+
+    /// Replaces the provisional notional with what actually filled. A trade
+    /// that did not fill releases the reservation instead.
+    pub fn commit(mut self, trade: &Trade) {
+        if !trade.status.is_fill() {
+            return;
+        }
+
+        let filled = trade.size * trade.price;
+        let mut exposure = self.engine.notional_exposure.write();
+        let current = exposure.entry(self.symbol.clone()).or_insert(Decimal::ZERO);
+        *current = (*current - self.notional + filled).max(Decimal::ZERO);
+        drop(exposure);
+        // Counted against the venue's margin until a snapshot includes it
+        let margins = self.engine.margins.read();
+        if margins.contains_key(&self.venue) {
+            let mut committed = self.engine.committed.write();
+            committed.entry(self.venue.clone()).or_default().push(Committed {
+                at: Utc::now(),
+                notional: filled,
+            });
+        }
+        drop(margins);
+        self.settled = true;
+    }
+
+    pub fn release(self) {}
+}
+
+impl Drop for RiskReservation {
+    fn drop(&mut self) {
+        if !self.settled {
+            self.engine.record_position_closed(&self.venue, &self.symbol, self.notional);
+        }
+
+        let key = (self.venue.clone(), self.symbol.clone());
+        let mut in_flight = self.engine.in_flight.write();
+        if let Some(held) = in_flight.get_mut(&key) {
+            held.positions = held.positions.saturating_sub(1);
+            held.notional -= self.notional;
+            if held.positions == 0 {
+                in_flight.remove(&key);
+            }
+        }
     }
 }
 
@@ -106,4 +575,8 @@ pub enum RiskError {
     MaxPositionsExceeded,
     #[error("Maximum notional exposure exceeded for asset")]
     MaxNotionalExceeded,
+    #[error("Maximum leverage exceeded for venue")]
+    MaxLeverageExceeded,
+    #[error("Margin ratio would breach the liquidation buffer")]
+    LiquidationBufferBreached,
 }
@@ -1,15 +1,80 @@
+use crate::events::{Event, EventBus};
+use crate::instruments::InstrumentRegistry;
+use crate::opportunities::OpportunityStore;
 use chrono::{DateTime, Utc};
-use rust_decimal::Decimal;
+use dashmap::mapref::entry::Entry;
+use rust_decimal::{Decimal, RoundingStrategy};
 use serde::{Deserialize, Serialize};
+use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MarketData {
     pub symbol: String,
     pub venue: Venue,
+    /// Mid price for order-book venues, quoted price for swap venues.
     pub price: Decimal,
+    pub bid: Decimal,
+    pub ask: Decimal,
     pub timestamp: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BookLevel {
+    pub price: Decimal,
+    pub size: Decimal,
+}
+
+/// Depth snapshot with bids sorted best (highest) first and asks best (lowest) first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrderBook {
+    pub symbol: String,
+    pub venue: Venue,
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl OrderBook {
+    pub fn best_bid(&self) -> Option<&BookLevel> {
+        self.bids.first()
+    }
+
+    pub fn best_ask(&self) -> Option<&BookLevel> {
+        self.asks.first()
+    }
+
+    pub fn mid(&self) -> Option<Decimal> {
+        Some((self.best_bid()?.price + self.best_ask()?.price) / Decimal::TWO)
+    }
+
+    /// Top-of-book price weighted towards the side with less resting size,
+    /// i.e. where the next trade is more likely to move the price.
+    pub fn microprice(&self) -> Option<Decimal> {
+        let bid = self.best_bid()?;
+        let ask = self.best_ask()?;
+        let total = bid.size + ask.size;
+        if total.is_zero() {
+            return self.mid();
+        }
+        Some((bid.price * ask.size + ask.price * bid.size) / total)
+    }
+
+    pub fn spread(&self) -> Option<Decimal> {
+        Some(self.best_ask()?.price - self.best_bid()?.price)
+    }
+
+    pub fn market_data(&self) -> Option<MarketData> {
+        Some(MarketData {
+            symbol: self.symbol.clone(),
+            venue: self.venue.clone(),
+            price: self.mid()?,
+            bid: self.best_bid()?.price,
+            ask: self.best_ask()?.price,
+            timestamp: self.timestamp,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum Venue {
     Hyperliquid,
@@ -19,36 +84,93 @@ pub enum Venue {
     SolanaJupiter,
 }
 
+impl Venue {
+    /// Spot venues settle swaps into token balances and hold no positions.
+    pub fn is_spot(&self) -> bool {
+        matches!(self, Self::HyperEvm | Self::SolanaJupiter)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FundingRate {
     pub symbol: String,
     pub venue: Venue,
+    /// Rate paid once per funding interval.
     pub rate: Decimal,
     pub predicted_rate: Option<Decimal>,
+    /// Hours between funding payments on this venue and symbol.
+    pub interval_hours: u32,
+    pub next_funding_time: Option<DateTime<Utc>>,
+    pub timestamp: DateTime<Utc>,
+}
+
+pub const HOURS_PER_YEAR: u32 = 24 * 365;
+
+impl FundingRate {
+    /// Rate normalised to one hour so venues with different intervals compare directly.
+    pub fn hourly_rate(&self) -> Decimal {
+        self.rate / Decimal::from(self.interval_hours.max(1))
+    }
+
+    pub fn annualized_rate(&self) -> Decimal {
+        self.hourly_rate() * Decimal::from(HOURS_PER_YEAR)
+    }
+}
+
+/// A funding settlement on one venue, signed from our side: positive when received.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FundingPayment {
+    /// Venue-assigned id, used to avoid booking the same settlement twice.
+    pub id: String,
+    pub symbol: String,
+    pub venue: Venue,
+    pub amount: Decimal,
+    pub asset: String,
     pub timestamp: DateTime<Utc>,
 }
 
+/// A detected opportunity and what became of it.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArbitrageOpportunity {
+    /// Assigned by `OpportunityStore::record`, which also stamps `timestamp`.
+    pub id: u64,
     pub strategy: StrategyType,
     pub symbol: String,
     pub venue_a: Venue,
     pub venue_b: Venue,
+    /// Quotes on each venue: annualised funding rates for funding arb, and the
+    /// round trip's input and output amounts for Jupiter.
     pub price_a: Decimal,
     pub price_b: Decimal,
     pub spread_bps: i64,
+    /// In the quote asset, or the input token for Jupiter. Funding arb fills in
+    /// a day's funding on the executed notional.
     pub estimated_profit: Decimal,
+    pub status: OpportunityStatus,
+    /// Why the opportunity was skipped or failed.
+    pub reason: Option<String>,
     pub timestamp: DateTime<Utc>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpportunityStatus {
+    Detected,
+    SkippedByRisk,
+    DryRun,
+    Executed,
+    Failed,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq, Hash)]
 pub enum StrategyType {
+    #[default]
     FundingArb,
     HyperEvmSpot,
     SolanaJupiter,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Position {
     pub symbol: String,
     pub venue: Venue,
@@ -59,13 +181,139 @@ pub struct Position {
     pub timestamp: DateTime<Utc>,
 }
 
+impl Position {
+    /// `SharedState.positions` key; one net position per venue and symbol.
+    pub fn key(venue: &Venue, symbol: &str) -> String {
+        format!("{:?}:{}", venue, symbol)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum PositionSide {
     Long,
     Short,
 }
 
+/// A position we hold on record that the venue reports differently, or not at all.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PositionDiscrepancy {
+    pub venue: Venue,
+    pub symbol: String,
+    pub expected: Option<Position>,
+    pub reported: Option<Position>,
+    pub detected_at: DateTime<Utc>,
+}
+
+/// The surviving leg of a hedge whose other leg is gone.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OrphanedLeg {
+    pub hedge_id: String,
+    pub symbol: String,
+    pub venue: Venue,
+    pub side: PositionSide,
+    pub size: Decimal,
+    pub missing_venue: Venue,
+    pub detected_at: DateTime<Utc>,
+    pub flattened: bool,
+}
+
+/// Delta-neutral funding-arb pair: short where funding is high, long where it is low.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Hedge {
+    pub id: String,
+    pub symbol: String,
+    pub short_venue: Venue,
+    pub long_venue: Venue,
+    pub size: Decimal,
+    pub opened_at: DateTime<Utc>,
+    pub closed_at: Option<DateTime<Utc>>,
+    /// Realised funding on each leg, positive when received.
+    pub short_funding: Decimal,
+    pub long_funding: Decimal,
+    pub last_funding_at: Option<DateTime<Utc>>,
+    /// Settlement time of the last payment booked on each leg, where the
+    /// funding ledger resumes after a restart.
+    #[serde(default)]
+    pub short_funding_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub long_funding_at: Option<DateTime<Utc>>,
+    /// The strategy that opened the hedge and manages its exit.
+    #[serde(default)]
+    pub strategy: StrategyType,
+    /// Venues whose leg an unwind has already closed while the other is still open.
+    #[serde(default)]
+    pub closed_legs: Vec<Venue>,
+}
+
+impl Hedge {
+    pub fn new(symbol: &str, short_venue: Venue, long_venue: Venue, size: Decimal) -> Self {
+        let opened_at = Utc::now();
+        Self {
+            id: format!("{}-{}", symbol, opened_at.timestamp_millis()),
+            symbol: symbol.to_string(),
+            short_venue,
+            long_venue,
+            size,
+            opened_at,
+            closed_at: None,
+            short_funding: Decimal::ZERO,
+            long_funding: Decimal::ZERO,
+            last_funding_at: None,
+            short_funding_at: None,
+            long_funding_at: None,
+            strategy: StrategyType::FundingArb,
+            closed_legs: Vec::new(),
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: StrategyType) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Signed base size of this hedge's leg on `venue`, long positive.
+    pub fn leg_on(&self, venue: &Venue) -> Decimal {
+        if self.leg_closed(venue) {
+            Decimal::ZERO
+        } else if &self.short_venue == venue {
+            -self.size
+        } else if &self.long_venue == venue {
+            self.size
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.closed_at.is_none()
+    }
+
+    pub fn leg_closed(&self, venue: &Venue) -> bool {
+        self.closed_legs.contains(venue)
+    }
+
+    /// Whether the hedge held a leg on `venue` when funding settled at `at`.
+    pub fn was_open_at(&self, venue: &Venue, at: DateTime<Utc>) -> bool {
+        (&self.short_venue == venue || &self.long_venue == venue)
+            && self.opened_at <= at
+            && self.closed_at.is_none_or(|closed| at <= closed)
+    }
+
+    /// Settlement time of the last funding booked on the leg held on `venue`.
+    pub fn leg_funding_at(&self, venue: &Venue) -> Option<DateTime<Utc>> {
+        if &self.short_venue == venue {
+            self.short_funding_at
+        } else {
+            self.long_funding_at
+        }
+    }
+
+    pub fn funding_pnl(&self) -> Decimal {
+        self.short_funding + self.long_funding
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Trade {
     pub symbol: String,
     pub venue: Venue,
@@ -74,32 +322,386 @@ pub struct Trade {
     pub price: Decimal,
     pub timestamp: DateTime<Utc>,
     pub status: TradeStatus,
+    pub order_id: Option<String>,
+}
+
+impl Trade {
+    /// Key in `SharedState.trades`: the order id, or venue, symbol and time
+    /// for venues that assign none.
+    pub fn record_id(&self) -> String {
+        self.order_id.clone().unwrap_or_else(|| {
+            format!("{:?}-{}-{}", self.venue, self.symbol, self.timestamp.timestamp_millis())
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum TimeInForce {
+    #[default]
+    GoodTilCancelled,
+    ImmediateOrCancel,
+    PostOnly,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OrderOptions {
+    pub time_in_force: TimeInForce,
+    pub reduce_only: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub enum TradeStatus {
     Pending,
+    PartiallyFilled,
     Filled,
+    Cancelled,
     Rejected,
     Failed,
+    /// Filled in full after re-sending the unfilled remainder.
+    FilledOnRetry,
+    /// Filled in full by re-pricing the remainder further through the book.
+    Chased,
+    /// Filled, then closed again because the other leg could not be completed.
+    Unwound,
+}
+
+impl TradeStatus {
+    /// Whether the trade left an open position behind.
+    pub fn is_fill(&self) -> bool {
+        matches!(
+            self,
+            Self::PartiallyFilled | Self::Filled | Self::FilledOnRetry | Self::Chased
+        )
+    }
+
+    /// Whether the full requested size was filled.
+    pub fn is_complete(&self) -> bool {
+        matches!(self, Self::Filled | Self::FilledOnRetry | Self::Chased)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Balance {
+    pub venue: Venue,
+    pub asset: String,
+    pub free: Decimal,
+    pub total: Decimal,
+}
+
+/// Trading rules for one perpetual on one venue. Sizes are in contracts.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContractSpec {
+    pub symbol: String,
+    pub venue: Venue,
+    pub tick_size: Decimal,
+    pub lot_size: Decimal,
+    pub min_size: Decimal,
+    /// Smallest accepted order value in the quote asset.
+    pub min_notional: Decimal,
+    /// Base units per contract; 1 where quantities are quoted in the base asset.
+    pub contract_multiplier: Decimal,
+}
+
+impl ContractSpec {
+    pub fn round_size_down(&self, size: Decimal) -> Decimal {
+        round_to_step(size, self.lot_size, RoundingStrategy::ToZero)
+    }
+
+    /// Rounds a limit price to the tick, away from the touch so the order stays marketable.
+    pub fn round_price_marketable(&self, price: Decimal, side: &PositionSide) -> Decimal {
+        let strategy = match side {
+            PositionSide::Long => RoundingStrategy::AwayFromZero,
+            PositionSide::Short => RoundingStrategy::ToZero,
+        };
+        round_to_step(price, self.tick_size, strategy)
+    }
+}
+
+fn round_to_step(value: Decimal, step: Decimal, strategy: RoundingStrategy) -> Decimal {
+    if step.is_zero() {
+        return value;
+    }
+    ((value / step).round_dp_with_strategy(0, strategy) * step).normalize()
+}
+
+/// Cross-margin account health on one venue, in the settlement asset.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountMargin {
+    pub venue: Venue,
+    pub equity: Decimal,
+    pub initial_margin: Decimal,
+    pub maintenance_margin: Decimal,
+    /// Gross notional of open positions.
+    pub position_notional: Decimal,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl AccountMargin {
+    /// Equity not tied up as initial margin, free to open new positions.
+    pub fn available_margin(&self) -> Decimal {
+        (self.equity - self.initial_margin).max(Decimal::ZERO)
+    }
+
+    /// Maintenance margin over equity; the venue liquidates at 1.
+    pub fn margin_ratio(&self) -> Decimal {
+        if self.equity <= Decimal::ZERO {
+            return Decimal::ONE;
+        }
+        self.maintenance_margin / self.equity
+    }
+
+    pub fn leverage(&self) -> Decimal {
+        if self.equity <= Decimal::ZERO {
+            return Decimal::MAX;
+        }
+        self.position_notional / self.equity
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct SharedState {
     pub market_data: dashmap::DashMap<(String, Venue), MarketData>,
+    pub order_books: dashmap::DashMap<(String, Venue), OrderBook>,
     pub funding_rates: dashmap::DashMap<(String, Venue), FundingRate>,
+    /// Local position book, netted from our own fills and closes in venue
+    /// contracts. Only reconciliation replaces it with the venues' view.
     pub positions: dashmap::DashMap<String, Position>,
     pub trades: dashmap::DashMap<String, Trade>,
+    pub hedges: dashmap::DashMap<String, Hedge>,
+    /// Unresolved reconciliation findings, keyed like `positions`.
+    pub discrepancies: dashmap::DashMap<String, PositionDiscrepancy>,
+    /// Hedges left with one leg, keyed by hedge id.
+    pub orphans: dashmap::DashMap<String, OrphanedLeg>,
+    pub balances: dashmap::DashMap<(String, Venue), Balance>,
+    /// Listings loaded from the venues at startup; shared with the connectors.
+    pub instruments: Arc<InstrumentRegistry>,
+    pub opportunities: Arc<OpportunityStore>,
+    pub events: EventBus,
+    /// Bookings made per venue, so reconciliation can tell the book moved
+    /// while it was querying that venue.
+    book_sequence: dashmap::DashMap<Venue, u64>,
 }
 
 impl SharedState {
     pub fn new() -> Self {
         Self {
             market_data: dashmap::DashMap::new(),
+            order_books: dashmap::DashMap::new(),
             funding_rates: dashmap::DashMap::new(),
             positions: dashmap::DashMap::new(),
             trades: dashmap::DashMap::new(),
+            hedges: dashmap::DashMap::new(),
+            discrepancies: dashmap::DashMap::new(),
+            orphans: dashmap::DashMap::new(),
+            balances: dashmap::DashMap::new(),
+            instruments: Arc::new(InstrumentRegistry::new()),
+            opportunities: Arc::new(OpportunityStore::default()),
+            events: EventBus::default(),
+            book_sequence: dashmap::DashMap::new(),
+        }
+    }
+
+    pub fn update_market_data(&self, market: MarketData) {
+        self.market_data
+            .insert((market.symbol.clone(), market.venue.clone()), market.clone());
+        self.events.publish(Event::MarketUpdate(market));
+    }
+
+    pub fn update_funding_rate(&self, rate: FundingRate) {
+        self.funding_rates
+            .insert((rate.symbol.clone(), rate.venue.clone()), rate.clone());
+        self.events.publish(Event::FundingUpdate(rate));
+    }
+
+    /// Nets a fill into the position book and announces it. Trades that
+    /// filled nothing are ignored.
+    pub fn record_fill(&self, trade: Trade) {
+        if !trade.status.is_fill() {
+            return;
+        }
+        let event = Event::OrderFilled(trade);
+        self.book_position(&event);
+        self.events.publish(event);
+    }
+
+    /// Takes `size` off the `side` position in the book and announces the close.
+    pub fn record_position_closed(
+        &self,
+        venue: Venue,
+        symbol: String,
+        side: PositionSide,
+        size: Decimal,
+    ) {
+        let event = Event::PositionClosed {
+            venue,
+            symbol,
+            side,
+            size,
+        };
+        self.book_position(&event);
+        self.events.publish(event);
+    }
+
+    /// Signed base size that open hedges of strategies other than `strategy`
+    /// hold on `venue` in `symbol`, long positive. The venue nets them into
+    /// the same position as `strategy`'s own legs.
+    pub fn foreign_hedge_size(
+        &self,
+        strategy: &StrategyType,
+        venue: &Venue,
+        symbol: &str,
+    ) -> Decimal {
+        self.hedges
+            .iter()
+            .filter(|h| h.is_open() && &h.strategy != strategy && h.symbol == symbol)
+            .map(|h| h.leg_on(venue))
+            .sum()
+    }
+
+    /// Stores a newly opened hedge and announces it.
+    pub fn record_hedge(&self, hedge: Hedge) {
+        self.hedges.insert(hedge.id.clone(), hedge.clone());
+        self.events.publish(Event::HedgeUpdated(hedge));
+    }
+
+    /// Applies `update` to a known hedge and announces the result.
+    pub fn update_hedge(&self, id: &str, update: impl FnOnce(&mut Hedge)) {
+        let Some(mut hedge) = self.hedges.get_mut(id) else {
+            return;
+        };
+        update(&mut hedge);
+        let updated = hedge.clone();
+        drop(hedge);
+        self.events.publish(Event::HedgeUpdated(updated));
+    }
+
+    /// How many bookings the venue's positions have taken so far.
+    pub fn book_sequence(&self, venue: &Venue) -> u64 {
+        self.book_sequence.get(venue).map_or(0, |sequence| *sequence)
+    }
+
+    /// Swaps the venue's booked positions for `reported` and returns the ones
+    /// replaced, unless something was booked on the venue since `sequence`.
+    pub fn replace_positions(
+        &self,
+        venue: &Venue,
+        sequence: u64,
+        reported: Vec<Position>,
+    ) -> Option<Vec<Position>> {
+        let current = self.book_sequence.entry(venue.clone()).or_default();
+        if *current != sequence {
+            return None;
+        }
+        let mut replaced = Vec::new();
+        self.positions.retain(|_, p| {
+            if &p.venue != venue {
+                return true;
+            }
+            replaced.push(p.clone());
+            false
+        });
+        for position in &reported {
+            let key = Position::key(&position.venue, &position.symbol);
+            self.positions.insert(key, position.clone());
+        }
+        drop(current);
+        self.events.publish(Event::PositionsReplaced {
+            venue: venue.clone(),
+            positions: reported,
+        });
+        Some(replaced)
+    }
+
+    fn book_position(&self, event: &Event) {
+        let venue = match event {
+            Event::OrderFilled(trade) => &trade.venue,
+            Event::PositionClosed { venue, .. } => venue,
+            _ => return,
+        };
+        // Held while booking, so the venue's book cannot be replaced halfway through
+        let mut sequence = self.book_sequence.entry(venue.clone()).or_default();
+        *sequence += 1;
+
+        // Each position is read and written under its entry's lock
+        match event {
+            Event::OrderFilled(trade) => {
+                let signed = |side: &PositionSide, size: Decimal| match side {
+                    PositionSide::Long => size,
+                    PositionSide::Short => -size,
+                };
+                let key = Position::key(&trade.venue, &trade.symbol);
+                let mut held = match self.positions.entry(key) {
+                    Entry::Occupied(held) => held,
+                    Entry::Vacant(vacant) => {
+                        if !trade.size.is_zero() {
+                            vacant.insert(Position {
+                                symbol: trade.symbol.clone(),
+                                venue: trade.venue.clone(),
+                                side: trade.side.clone(),
+                                size: trade.size,
+                                entry_price: trade.price,
+                                leverage: Decimal::ONE,
+                                timestamp: trade.timestamp,
+                            });
+                        }
+                        return;
+                    }
+                };
+                let before = signed(&held.get().side, held.get().size);
+                let after = before + signed(&trade.side, trade.size);
+                if after.is_zero() {
+                    held.remove();
+                    return;
+                }
+
+                // Adding to a position averages the entry, reducing keeps it and
+                // flipping starts over at the fill price
+                let position = held.get_mut();
+                if after.is_sign_negative() != before.is_sign_negative() {
+                    position.entry_price = trade.price;
+                } else if after.abs() > before.abs() {
+                    position.entry_price = (position.size * position.entry_price
+                        + trade.size * trade.price)
+                        / after.abs();
+                }
+                position.side = if after.is_sign_negative() {
+                    PositionSide::Short
+                } else {
+                    PositionSide::Long
+                };
+                position.size = after.abs();
+                position.timestamp = trade.timestamp;
+            }
+            Event::PositionClosed {
+                venue,
+                symbol,
+                side,
+                size,
+            } => {
+                let key = Position::key(venue, symbol);
+                let Entry::Occupied(mut held) = self.positions.entry(key) else {
+                    return;
+                };
+                if held.get().side != *side {
+                    return;
+                }
+                if held.get().size > *size {
+                    held.get_mut().size -= *size;
+                } else {
+                    held.remove();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Stores the opportunity and announces it; returns its id.
+    pub fn record_opportunity(&self, opportunity: ArbitrageOpportunity) -> u64 {
+        let id = self.opportunities.record(opportunity);
+        if let Some(recorded) = self.opportunities.get(id) {
+            self.events.publish(Event::OpportunityDetected(recorded));
         }
+        id
     }
 }
 
@@ -0,0 +1,110 @@
+use arb_core::{AuditEntry, AuditFilter, AuditLog, ControlAction};
+use chrono::Utc;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+
+fn entry(actor: &str, action: ControlAction) -> AuditEntry {
+    AuditEntry {
+        id: 0,
+        at: Utc::now(),
+        actor: actor.to_string(),
+        action,
+        reason: "testing".to_string(),
+        succeeded: true,
+        detail: None,
+    }
+}
+
+fn pause(strategy: &str) -> ControlAction {
+    ControlAction::PauseStrategy {
+        strategy: strategy.to_string(),
+    }
+}
+
+fn all() -> AuditFilter {
+    AuditFilter {
+        limit: 100,
+        ..Default::default()
+    }
+}
+
+fn audit_path(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("arb-audit-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir.join("audit.jsonl")
+}
+
+#[test]
+fn queries_filter_by_actor_and_action_newest_first() {
+    let log = AuditLog::new(10);
+    log.record(entry("alice", ControlAction::TripKillSwitch)).unwrap();
+    log.record(entry("bob", pause("funding_arb"))).unwrap();
+    log.record(entry("alice", pause("hyperevm_spot"))).unwrap();
+
+    let alice = AuditFilter {
+        actor: Some("alice".to_string()),
+        ..all()
+    };
+    let ids: Vec<u64> = log.query(&alice).entries.iter().map(|e| e.id).collect();
+    assert_eq!(ids, vec![3, 1]);
+
+    let pauses = AuditFilter {
+        action: Some("pause_strategy".to_string()),
+        limit: 1,
+        ..all()
+    };
+    let page = log.query(&pauses);
+    assert_eq!(page.total, 2);
+    assert_eq!(page.entries[0].action, pause("hyperevm_spot"));
+}
+
+#[test]
+fn reopening_the_file_keeps_history_and_ids() {
+    let path = audit_path("reopen");
+    {
+        let log = AuditLog::open(&path, 10).unwrap();
+        log.record(entry("alice", ControlAction::TripKillSwitch)).unwrap();
+        log.record(entry("alice", ControlAction::RearmKillSwitch)).unwrap();
+    }
+    // A write torn by a crash is skipped rather than failing the load
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(b"{\"id\":3,\"act").unwrap();
+    drop(file);
+
+    let log = AuditLog::open(&path, 10).unwrap();
+    assert_eq!(log.len(), 2);
+    let next = log.record(entry("bob", ControlAction::FlattenAll)).unwrap();
+    assert_eq!(next.id, 3);
+
+    let latest = &log.query(&all()).entries[0];
+    assert_eq!((latest.actor.as_str(), latest.action.name()), ("bob", "flatten_all"));
+    drop(log);
+
+    // Bob's entry went onto a fresh line rather than onto the torn bytes
+    let log = AuditLog::open(&path, 10).unwrap();
+    assert_eq!(log.len(), 3);
+    let latest = &log.query(&all()).entries[0];
+    assert_eq!((latest.id, latest.actor.as_str()), (3, "bob"));
+    assert_eq!(log.record(entry("bob", ControlAction::RearmKillSwitch)).unwrap().id, 4);
+}
+
+#[test]
+fn entries_serialize_with_the_action_inline() {
+    let log = AuditLog::new(10);
+    let recorded = log
+        .record(entry(
+            "alice",
+            ControlAction::SetDryRun {
+                strategy: "funding_arb".to_string(),
+                dry_run: true,
+            },
+        ))
+        .unwrap();
+
+    let json = serde_json::to_value(&recorded).unwrap();
+    assert_eq!(json["action"], "set_dry_run");
+    assert_eq!(json["strategy"], "funding_arb");
+    assert_eq!(json["dry_run"], true);
+    assert_eq!(json["actor"], "alice");
+}
@@ -0,0 +1,16 @@
+use arb_core::config::RiskConfig;
+
+/// Limits loose enough that only the field a test overrides can bind.
+pub fn risk_config() -> RiskConfig {
+    RiskConfig {
+        max_notional_per_asset: 1_000_000.0,
+        max_open_positions_per_venue: 10,
+        max_leverage: 3.0,
+        kill_switch_daily_loss_threshold: -1000.0,
+        liquidation_buffer: 0.5,
+        margin_refresh_interval_ms: 30000,
+        daily_rollover_hour_utc: 0,
+        kill_switch_cooldown_secs: 3600,
+        kill_switch_manual_rearm: false,
+    }
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use arb_core::config::{ApiConfig, Config, OperatorConfig, RiskConfig};
+use common::risk_config;
+
+fn api(token: &str) -> ApiConfig {
+    ApiConfig {
+        operators: vec![OperatorConfig {
+            name: "ops".to_string(),
+            token: token.to_string(),
+        }],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn weak_operator_tokens_are_refused() {
+    for token in ["", "   ", "change-me", "CHANGE-ME", "short-but-random-7f3a"] {
+        assert!(api(token).validate().is_err(), "{:?} was accepted", token);
+    }
+}
+
+#[test]
+fn operator_tokens_are_trimmed_on_load() {
+    let example = include_str!("../../../config/example.toml");
+    let path = std::env::temp_dir().join(format!("arb-config-{}.toml", std::process::id()));
+    let placeholder = format!("{:>40}", "secret");
+    let operator =
+        |token: &str| format!("\n[[api.operators]]\nname = \"ops\"\ntoken = {:?}\n", token);
+
+    std::fs::write(&path, format!("{}{}", example, operator(&placeholder))).unwrap();
+    let config = Config::load(&path).unwrap();
+    assert_eq!(config.api.operators[0].token, "secret");
+    assert!(config.api.validate().is_err());
+
+    let token = "0f8e51c2b7a94d3e9a6c1b5f7d2e8a4c";
+    std::fs::write(&path, format!("{}{}", example, operator(&format!(" {}\n", token)))).unwrap();
+    assert_eq!(Config::load(&path).unwrap().api.operators[0].token, token);
+    let _ = std::fs::remove_file(&path);
+}
+
+#[test]
+fn generated_tokens_and_no_operators_are_accepted() {
+    assert!(api("0f8e51c2b7a94d3e9a6c1b5f7d2e8a4c").validate().is_ok());
+    assert!(ApiConfig::default().validate().is_ok());
+}
+
+fn risk() -> RiskConfig {
+    RiskConfig { max_notional_per_asset: 10_000.0, ..risk_config() }
+}
+
+#[test]
+fn non_finite_or_out_of_range_risk_limits_are_refused() {
+    assert!(risk().validate().is_ok());
+    for broken in [
+        RiskConfig { max_leverage: f64::NAN, ..risk() },
+        RiskConfig { max_leverage: 0.0, ..risk() },
+        RiskConfig { liquidation_buffer: f64::INFINITY, ..risk() },
+        RiskConfig { liquidation_buffer: 1.0, ..risk() },
+        RiskConfig { max_notional_per_asset: f64::NAN, ..risk() },
+        RiskConfig { kill_switch_daily_loss_threshold: f64::NEG_INFINITY, ..risk() },
+        RiskConfig { daily_rollover_hour_utc: 24, ..risk() },
+    ] {
+        assert!(broken.validate().is_err(), "{:?} was accepted", broken);
+    }
+}
@@ -0,0 +1,217 @@
+mod common;
+
+use arb_core::config::RiskConfig;
+use arb_core::types::{
+    FundingRate, Position, PositionSide, SharedState, Trade, TradeStatus, Venue,
+};
+use arb_core::{Event, EventBus, EventMetrics, KillSwitchReason, RiskEngine};
+use chrono::Utc;
+use common::risk_config;
+use rust_decimal::Decimal;
+
+fn config() -> RiskConfig {
+    RiskConfig { max_notional_per_asset: 1000.0, ..risk_config() }
+}
+
+fn funding(symbol: &str) -> FundingRate {
+    FundingRate {
+        symbol: symbol.to_string(),
+        venue: Venue::Hyperliquid,
+        rate: Decimal::new(1, 4),
+        predicted_rate: None,
+        interval_hours: 1,
+        next_funding_time: None,
+        timestamp: Utc::now(),
+    }
+}
+
+fn trade(notional: i64) -> Trade {
+    Trade {
+        symbol: "BTC".to_string(),
+        venue: Venue::Binance,
+        side: PositionSide::Long,
+        size: Decimal::from(notional) / Decimal::from(100),
+        price: Decimal::from(100),
+        timestamp: Utc::now(),
+        status: TradeStatus::Pending,
+        order_id: None,
+    }
+}
+
+#[tokio::test]
+async fn state_updates_reach_every_subscriber() {
+    let state = SharedState::new();
+    let mut persistence = state.events.subscribe("persistence");
+    let mut api = state.events.subscribe("api");
+
+    state.update_funding_rate(funding("BTC"));
+
+    assert!(state.funding_rates.contains_key(&("BTC".to_string(), Venue::Hyperliquid)));
+    for subscriber in [&mut persistence, &mut api] {
+        match subscriber.recv().await {
+            Some(Event::FundingUpdate(rate)) => assert_eq!(rate.symbol, "BTC"),
+            other => panic!("expected a funding update, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn lagging_subscriber_skips_the_oldest_and_counts_them() {
+    let bus = EventBus::new(2);
+    let mut slow = bus.subscribe("slow");
+
+    for symbol in ["A", "B", "C", "D", "E"] {
+        bus.publish(Event::FundingUpdate(funding(symbol)));
+    }
+
+    let received: Vec<String> = std::iter::from_fn(|| slow.try_recv())
+        .map(|event| match event {
+            Event::FundingUpdate(rate) => rate.symbol,
+            other => panic!("unexpected {:?}", other),
+        })
+        .collect();
+    assert_eq!(received, vec!["D", "E"]);
+    assert_eq!(slow.missed(), 3);
+}
+
+#[test]
+fn publishing_without_subscribers_is_a_no_op() {
+    let bus = EventBus::new(4);
+    bus.publish(Event::FundingUpdate(funding("BTC")));
+    assert_eq!(bus.subscriber_count(), 0);
+}
+
+#[test]
+fn risk_engine_announces_rejections_and_trips() {
+    let bus = EventBus::new(16);
+    let mut events = bus.subscribe("alerts");
+    let engine = RiskEngine::new(config()).with_events(bus.clone());
+
+    assert!(engine.check_trade(&trade(2000)).is_err());
+    engine.trip(KillSwitchReason::Manual("operator".to_string()));
+    // A second trip while tripped changes nothing and is not announced
+    engine.trip(KillSwitchReason::Manual("again".to_string()));
+
+    match events.try_recv() {
+        Some(Event::RiskRejected { notional, reason, .. }) => {
+            assert_eq!(notional, Decimal::from(2000));
+            assert!(reason.contains("notional"), "{}", reason);
+        }
+        other => panic!("expected a rejection, got {:?}", other),
+    }
+    match events.try_recv() {
+        Some(Event::KillSwitchTripped { reason, .. }) => {
+            assert_eq!(reason, KillSwitchReason::Manual("operator".to_string()));
+        }
+        other => panic!("expected a trip, got {:?}", other),
+    }
+    assert!(events.try_recv().is_none());
+}
+
+#[tokio::test]
+async fn metrics_count_events_by_kind() {
+    let bus = EventBus::new(16);
+    let metrics = EventMetrics::default();
+    let task = tokio::spawn({
+        let metrics = metrics.clone();
+        let events = bus.subscribe("metrics");
+        async move { metrics.run(events).await }
+    });
+
+    bus.publish(Event::FundingUpdate(funding("BTC")));
+    bus.publish(Event::FundingUpdate(funding("ETH")));
+    bus.publish(Event::OrderFilled(trade(100)));
+    drop(bus);
+    task.await.unwrap();
+
+    let counts = metrics.counts();
+    assert_eq!(counts.get("funding_update"), Some(&2));
+    assert_eq!(counts.get("order_filled"), Some(&1));
+    assert_eq!(metrics.missed(), 0);
+}
+
+fn fill(side: PositionSide, size: i64, price: i64) -> Trade {
+    Trade {
+        side,
+        size: Decimal::from(size),
+        price: Decimal::from(price),
+        status: TradeStatus::Filled,
+        ..trade(0)
+    }
+}
+
+#[test]
+fn fills_and_closes_net_into_the_position_book() {
+    let state = SharedState::new();
+    let mut events = state.events.subscribe("test");
+    let key = Position::key(&Venue::Binance, "BTC");
+
+    state.record_fill(fill(PositionSide::Long, 1, 100));
+    state.record_fill(fill(PositionSide::Long, 1, 200));
+    let held = state.positions.get(&key).unwrap().clone();
+    assert_eq!((held.side, held.size), (PositionSide::Long, Decimal::from(2)));
+    assert_eq!(held.entry_price, Decimal::from(150));
+
+    // Selling through the position flips it at the fill price
+    state.record_fill(fill(PositionSide::Short, 3, 120));
+    let held = state.positions.get(&key).unwrap().clone();
+    assert_eq!((held.side, held.size), (PositionSide::Short, Decimal::ONE));
+    assert_eq!(held.entry_price, Decimal::from(120));
+
+    // Unfilled orders leave the book alone
+    state.record_fill(Trade {
+        status: TradeStatus::Failed,
+        ..fill(PositionSide::Long, 5, 100)
+    });
+    state.record_position_closed(
+        Venue::Binance,
+        "BTC".to_string(),
+        PositionSide::Short,
+        Decimal::ONE,
+    );
+    assert!(state.positions.is_empty());
+
+    let kinds: Vec<&str> = std::iter::from_fn(|| events.try_recv()).map(|e| e.kind()).collect();
+    assert_eq!(kinds.last(), Some(&"position_closed"));
+    assert_eq!(kinds.iter().filter(|k| **k == "order_filled").count(), 3);
+}
+
+#[test]
+fn positions_are_not_replaced_over_a_newer_booking() {
+    let state = SharedState::new();
+    let key = Position::key(&Venue::Binance, "BTC");
+    state.record_fill(fill(PositionSide::Long, 1, 100));
+    let sequence = state.book_sequence(&Venue::Binance);
+
+    // A fill booked after the venue was queried must survive its stale answer
+    state.record_fill(fill(PositionSide::Long, 1, 100));
+    assert!(state.replace_positions(&Venue::Binance, sequence, Vec::new()).is_none());
+    assert_eq!(state.positions.get(&key).unwrap().size, Decimal::from(2));
+
+    let sequence = state.book_sequence(&Venue::Binance);
+    let replaced = state.replace_positions(&Venue::Binance, sequence, Vec::new()).unwrap();
+    assert_eq!(replaced.len(), 1);
+    assert!(state.positions.is_empty());
+}
+
+#[test]
+fn concurrent_fills_all_reach_the_position_book() {
+    let state = std::sync::Arc::new(SharedState::new());
+    let threads: Vec<_> = (0..8)
+        .map(|_| {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                for _ in 0..100 {
+                    state.record_fill(fill(PositionSide::Long, 1, 100));
+                }
+            })
+        })
+        .collect();
+    for thread in threads {
+        thread.join().unwrap();
+    }
+
+    let held = state.positions.get(&Position::key(&Venue::Binance, "BTC")).unwrap().clone();
+    assert_eq!((held.side, held.size), (PositionSide::Long, Decimal::from(800)));
+    assert_eq!(held.entry_price, Decimal::from(100));
+}
@@ -0,0 +1,91 @@
+use arb_core::instruments::{canonical_asset, Instrument, InstrumentRegistry};
+use arb_core::types::{ContractSpec, Venue};
+use rust_decimal::Decimal;
+
+fn instrument(asset: &str, venue: Venue, native_symbol: &str, multiplier: u64) -> Instrument {
+    Instrument {
+        asset: asset.to_string(),
+        venue: venue.clone(),
+        native_symbol: native_symbol.to_string(),
+        quote_asset: "USDT".to_string(),
+        spec: ContractSpec {
+            symbol: asset.to_string(),
+            venue,
+            tick_size: Decimal::new(1, 7),
+            lot_size: Decimal::ONE,
+            min_size: Decimal::ONE,
+            min_notional: Decimal::from(5),
+            contract_multiplier: Decimal::from(multiplier),
+        },
+    }
+}
+
+#[test]
+fn multiplier_prefixes_resolve_to_the_underlying_asset() {
+    let thousand = Decimal::from(1000);
+    assert_eq!(canonical_asset("1000PEPE"), ("PEPE".to_string(), thousand));
+    assert_eq!(canonical_asset("kPEPE"), ("PEPE".to_string(), thousand));
+    assert_eq!(canonical_asset("SHIB1000"), ("SHIB".to_string(), thousand));
+    assert_eq!(canonical_asset("10000LADYS"), ("LADYS".to_string(), Decimal::from(10_000)));
+    assert_eq!(canonical_asset("1MBABYDOGE"), ("BABYDOGE".to_string(), Decimal::from(1_000_000)));
+}
+
+#[test]
+fn plain_and_numeric_token_names_are_left_alone() {
+    assert_eq!(canonical_asset("BTC"), ("BTC".to_string(), Decimal::ONE));
+    assert_eq!(canonical_asset("1INCH"), ("1INCH".to_string(), Decimal::ONE));
+    assert_eq!(canonical_asset("1000"), ("1000".to_string(), Decimal::ONE));
+}
+
+#[test]
+fn registry_maps_assets_to_native_symbols_both_ways() {
+    let registry = InstrumentRegistry::new();
+    registry.load_venue(&Venue::Binance, vec![instrument("PEPE", Venue::Binance, "1000PEPEUSDT", 1000)]);
+    registry.load_venue(&Venue::Hyperliquid, vec![instrument("PEPE", Venue::Hyperliquid, "kPEPE", 1000)]);
+
+    assert_eq!(registry.native_symbol("PEPE", &Venue::Binance).as_deref(), Some("1000PEPEUSDT"));
+    assert_eq!(registry.native_symbol("PEPE", &Venue::Hyperliquid).as_deref(), Some("kPEPE"));
+    assert_eq!(registry.asset(&Venue::Hyperliquid, "kPEPE").as_deref(), Some("PEPE"));
+    assert_eq!(
+        registry.spec("PEPE", &Venue::Binance).unwrap().contract_multiplier,
+        Decimal::from(1000)
+    );
+    assert!(registry.native_symbol("PEPE", &Venue::Bybit).is_none());
+}
+
+#[test]
+fn common_assets_need_a_listing_on_every_venue() {
+    let registry = InstrumentRegistry::new();
+    registry.load_venue(
+        &Venue::Binance,
+        vec![
+            instrument("BTC", Venue::Binance, "BTCUSDT", 1),
+            instrument("PEPE", Venue::Binance, "1000PEPEUSDT", 1000),
+        ],
+    );
+    registry.load_venue(
+        &Venue::Hyperliquid,
+        vec![
+            instrument("PEPE", Venue::Hyperliquid, "kPEPE", 1000),
+            instrument("BTC", Venue::Hyperliquid, "BTC", 1),
+            instrument("HYPE", Venue::Hyperliquid, "HYPE", 1),
+        ],
+    );
+
+    assert_eq!(registry.common_assets(&[Venue::Hyperliquid, Venue::Binance]), vec!["BTC", "PEPE"]);
+    assert!(registry.common_assets(&[Venue::Hyperliquid, Venue::Bybit]).is_empty());
+}
+
+#[test]
+fn reloading_a_venue_replaces_its_listing() {
+    let registry = InstrumentRegistry::new();
+    registry.load_venue(&Venue::Bybit, vec![instrument("PEPE", Venue::Bybit, "1000PEPEUSDT", 1000)]);
+    registry.load_venue(&Venue::Binance, vec![instrument("BTC", Venue::Binance, "BTCUSDT", 1)]);
+
+    let loaded = registry.load_venue(&Venue::Bybit, vec![instrument("BTC", Venue::Bybit, "BTCUSDT", 1)]);
+
+    assert_eq!(loaded, 1);
+    assert_eq!(registry.len(), 2);
+    assert!(registry.asset(&Venue::Bybit, "1000PEPEUSDT").is_none());
+    assert!(registry.get("BTC", &Venue::Binance).is_some());
+}
@@ -0,0 +1,116 @@
+use arb_core::types::{ArbitrageOpportunity, OpportunityStatus, StrategyType, Venue};
+use arb_core::{OpportunityFilter, OpportunityStore};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::time::Duration;
+
+fn opportunity(strategy: StrategyType, symbol: &str) -> ArbitrageOpportunity {
+    ArbitrageOpportunity {
+        id: 0,
+        strategy,
+        symbol: symbol.to_string(),
+        venue_a: Venue::Hyperliquid,
+        venue_b: Venue::Binance,
+        price_a: Decimal::from(100),
+        price_b: Decimal::from(99),
+        spread_bps: 100,
+        estimated_profit: Decimal::ONE,
+        status: OpportunityStatus::Detected,
+        reason: None,
+        timestamp: Utc::now(),
+    }
+}
+
+fn all() -> OpportunityFilter {
+    OpportunityFilter {
+        limit: 100,
+        ..Default::default()
+    }
+}
+
+fn ids(store: &OpportunityStore, filter: &OpportunityFilter) -> Vec<u64> {
+    store.query(filter).opportunities.iter().map(|o| o.id).collect()
+}
+
+#[test]
+fn store_keeps_the_newest_up_to_capacity() {
+    let store = OpportunityStore::new(3);
+    for _ in 0..5 {
+        store.record(opportunity(StrategyType::FundingArb, "BTC"));
+    }
+
+    assert_eq!(store.len(), 3);
+    assert_eq!(ids(&store, &all()), vec![5, 4, 3]);
+    assert!(store.get(2).is_none());
+    assert!(!store.set_status(2, OpportunityStatus::Executed, None));
+}
+
+#[test]
+fn status_updates_land_on_the_recorded_opportunity() {
+    let store = OpportunityStore::new(10);
+    let first = store.record(opportunity(StrategyType::FundingArb, "BTC"));
+    let second = store.record(opportunity(StrategyType::FundingArb, "ETH"));
+
+    let reason = Some("Max notional per asset exceeded".to_string());
+    assert!(store.set_status(first, OpportunityStatus::SkippedByRisk, reason.clone()));
+
+    let skipped = store.get(first).unwrap();
+    assert_eq!((skipped.status, skipped.reason), (OpportunityStatus::SkippedByRisk, reason));
+    assert_eq!(store.get(second).unwrap().status, OpportunityStatus::Detected);
+}
+
+#[test]
+fn filters_combine_and_pages_count_every_match() {
+    let store = OpportunityStore::new(10);
+    for symbol in ["BTC", "ETH", "BTC", "BTC"] {
+        store.record(opportunity(StrategyType::FundingArb, symbol));
+    }
+    store.record(opportunity(StrategyType::HyperEvmSpot, "BTC-USDC"));
+    store.set_status(3, OpportunityStatus::DryRun, None);
+
+    let funding_btc = OpportunityFilter {
+        strategy: Some(StrategyType::FundingArb),
+        symbol: Some("BTC".to_string()),
+        ..all()
+    };
+    assert_eq!(ids(&store, &funding_btc), vec![4, 3, 1]);
+
+    let second_page = OpportunityFilter {
+        offset: 1,
+        limit: 1,
+        ..funding_btc.clone()
+    };
+    let page = store.query(&second_page);
+    assert_eq!(page.total, 3);
+    assert_eq!(page.opportunities.iter().map(|o| o.id).collect::<Vec<_>>(), vec![3]);
+
+    let dry_run = OpportunityFilter {
+        status: Some(OpportunityStatus::DryRun),
+        ..all()
+    };
+    assert_eq!(ids(&store, &dry_run), vec![3]);
+}
+
+#[test]
+fn time_window_is_inclusive_of_since_and_exclusive_of_until() {
+    let store = OpportunityStore::new(10);
+    for _ in 0..4 {
+        store.record(opportunity(StrategyType::SolanaJupiter, "SOL/USDC"));
+        std::thread::sleep(Duration::from_millis(2));
+    }
+    let at = |id| store.get(id).unwrap().timestamp;
+
+    let window = OpportunityFilter {
+        since: Some(at(2)),
+        until: Some(at(4)),
+        ..all()
+    };
+    assert_eq!(ids(&store, &window), vec![3, 2]);
+
+    let inverted = OpportunityFilter {
+        since: Some(at(4)),
+        until: Some(at(1)),
+        ..all()
+    };
+    assert!(store.query(&inverted).opportunities.is_empty());
+}
@@ -0,0 +1,204 @@
+mod common;
+
+use arb_core::types::{Hedge, Position, PositionSide, SharedState, Trade, TradeStatus, Venue};
+use arb_core::{
+    Event, EventSubscriber, JournalEntry, KillSwitchState, RiskEngine, StatePersister, StateSnapshot, StateStore,
+};
+use chrono::{Duration, Utc};
+use common::risk_config;
+use rust_decimal::Decimal;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+fn engine() -> RiskEngine {
+    RiskEngine::new(risk_config())
+}
+
+fn state_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("arb-state-{}-{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    dir
+}
+
+fn fill(venue: Venue, side: PositionSide, order_id: &str) -> Trade {
+    Trade {
+        symbol: "BTC".to_string(),
+        venue,
+        side,
+        size: Decimal::from(2),
+        price: Decimal::from(100),
+        timestamp: Utc::now(),
+        status: TradeStatus::Filled,
+        order_id: Some(order_id.to_string()),
+    }
+}
+
+fn populate(state: &SharedState, risk_engine: &RiskEngine) -> String {
+    state.record_fill(fill(Venue::Hyperliquid, PositionSide::Short, "41"));
+    state.record_fill(fill(Venue::Binance, PositionSide::Long, "42"));
+    let hedge = Hedge::new("BTC", Venue::Hyperliquid, Venue::Binance, Decimal::from(2));
+    let id = hedge.id.clone();
+    state.record_hedge(hedge);
+    risk_engine.update_daily_pnl(Decimal::from(-250));
+    id
+}
+
+fn persister(
+    dir: &PathBuf,
+    state: &Arc<SharedState>,
+    engine: &Arc<RiskEngine>,
+    compact_after_entries: usize,
+) -> StatePersister {
+    let store = StateStore::open(dir).unwrap();
+    StatePersister::new(store, state.clone(), engine.clone(), 1000, compact_after_entries, 24)
+}
+
+fn drain(events: &mut EventSubscriber) -> Vec<Event> {
+    std::iter::from_fn(|| events.try_recv()).collect()
+}
+
+#[tokio::test]
+async fn journaled_state_survives_a_restart() {
+    let dir = state_dir("restart");
+    let state = Arc::new(SharedState::new());
+    let risk_engine = Arc::new(engine());
+    let mut events = state.events.subscribe("test");
+    let hedge_id = populate(&state, &risk_engine);
+    let persister = persister(&dir, &state, &risk_engine, 1000);
+    assert!(persister.record(&drain(&mut events)).await.unwrap() > 0);
+    assert_eq!(persister.record(&drain(&mut events)).await.unwrap(), 0);
+
+    // A leg closes after the first pass
+    state.record_position_closed(
+        Venue::Binance,
+        "BTC".to_string(),
+        PositionSide::Long,
+        Decimal::from(2),
+    );
+    state.update_hedge(&hedge_id, |h| h.closed_legs.push(Venue::Binance));
+    assert_eq!(persister.record(&drain(&mut events)).await.unwrap(), 2);
+
+    let restored = StateStore::open(&dir).unwrap().load().unwrap();
+    let (fresh_state, fresh_engine) = (SharedState::new(), engine());
+    restored.restore_into(&fresh_state, &fresh_engine);
+
+    assert_eq!(fresh_state.positions.len(), 1);
+    assert!(fresh_state.positions.contains_key(&Position::key(&Venue::Hyperliquid, "BTC")));
+    assert_eq!(*fresh_state.hedges.get(&hedge_id).unwrap(), *state.hedges.get(&hedge_id).unwrap());
+    assert!(fresh_state.trades.contains_key("42"));
+    assert_eq!(fresh_engine.daily_pnl(), Decimal::from(-250));
+}
+
+#[tokio::test]
+async fn reconciled_positions_replace_the_venues_journaled_ones() {
+    let dir = state_dir("replace");
+    let state = Arc::new(SharedState::new());
+    let risk_engine = Arc::new(engine());
+    let mut events = state.events.subscribe("test");
+    populate(&state, &risk_engine);
+    let sequence = state.book_sequence(&Venue::Binance);
+    state.replace_positions(&Venue::Binance, sequence, Vec::new()).unwrap();
+    persister(&dir, &state, &risk_engine, 1000).record(&drain(&mut events)).await.unwrap();
+
+    let restored = StateStore::open(&dir).unwrap().load().unwrap();
+    assert_eq!(restored.positions.len(), 1);
+    assert!(restored.positions.contains_key(&Position::key(&Venue::Hyperliquid, "BTC")));
+}
+
+#[tokio::test]
+async fn compaction_folds_the_journal_into_the_snapshot() {
+    let dir = state_dir("compact");
+    let state = Arc::new(SharedState::new());
+    let risk_engine = Arc::new(engine());
+    let mut events = state.events.subscribe("test");
+    populate(&state, &risk_engine);
+
+    persister(&dir, &state, &risk_engine, 1).record(&drain(&mut events)).await.unwrap();
+
+    let mut store = StateStore::open(&dir).unwrap();
+    let restored = store.load().unwrap();
+    assert_eq!(store.journal_entries(), 0);
+    assert_eq!(restored, StateSnapshot::capture(&state, &risk_engine));
+}
+
+#[tokio::test]
+async fn snapshots_drop_hedges_and_trades_closed_past_retention() {
+    let dir = state_dir("prune");
+    let state = Arc::new(SharedState::new());
+    let risk_engine = Arc::new(engine());
+    let open_id = populate(&state, &risk_engine);
+    let long_ago = Utc::now() - Duration::hours(48);
+    let mut closed = Hedge::new("ETH", Venue::Hyperliquid, Venue::Binance, Decimal::ONE);
+    closed.id = "ETH-old".to_string();
+    closed.closed_at = Some(long_ago);
+    state.record_hedge(closed);
+    state.trades.insert(
+        "7".to_string(),
+        Trade {
+            timestamp: long_ago,
+            ..fill(Venue::Binance, PositionSide::Short, "7")
+        },
+    );
+    state.trades.insert(
+        "8".to_string(),
+        Trade {
+            timestamp: long_ago,
+            status: TradeStatus::Pending,
+            ..fill(Venue::Binance, PositionSide::Short, "8")
+        },
+    );
+    state.trades.insert("42".to_string(), fill(Venue::Binance, PositionSide::Long, "42"));
+
+    persister(&dir, &state, &risk_engine, 1000).snapshot().await.unwrap();
+
+    let restored = StateStore::open(&dir).unwrap().load().unwrap();
+    let hedges: Vec<&String> = restored.hedges.keys().collect();
+    assert_eq!(hedges, vec![&open_id]);
+    let mut trades: Vec<&str> = restored.trades.keys().map(String::as_str).collect();
+    trades.sort();
+    assert_eq!(trades, vec!["42", "8"]);
+    // Live state is left alone
+    assert!(state.hedges.contains_key("ETH-old"));
+    assert!(state.trades.contains_key("7"));
+}
+
+#[tokio::test]
+async fn a_torn_final_journal_line_is_ignored() {
+    let dir = state_dir("torn");
+    let state = Arc::new(SharedState::new());
+    let risk_engine = Arc::new(engine());
+    let mut events = state.events.subscribe("test");
+    populate(&state, &risk_engine);
+    persister(&dir, &state, &risk_engine, 1000).record(&drain(&mut events)).await.unwrap();
+
+    let mut journal = OpenOptions::new().append(true).open(dir.join("journal.jsonl")).unwrap();
+    journal.write_all(b"{\"kind\":\"position_remo").unwrap();
+
+    let mut store = StateStore::open(&dir).unwrap();
+    assert_eq!(store.load().unwrap().positions.len(), 2);
+
+    // The next run appends after the torn bytes and must still load
+    store
+        .append(&[JournalEntry::PositionRemoved {
+            key: Position::key(&Venue::Binance, "BTC"),
+        }])
+        .unwrap();
+    let restored = StateStore::open(&dir).unwrap().load().unwrap();
+    assert_eq!(restored.positions.len(), 1);
+}
+
+#[test]
+fn restoring_yesterdays_snapshot_rolls_the_day_over() {
+    let risk_engine = engine();
+    risk_engine.update_daily_pnl(Decimal::from(-1500));
+    let mut snapshot = risk_engine.snapshot();
+    snapshot.day_started -= Duration::days(1);
+
+    let restarted = engine();
+    restarted.restore(snapshot);
+
+    assert_eq!(restarted.daily_pnl(), Decimal::ZERO);
+    assert!(matches!(restarted.kill_switch_state(), KillSwitchState::CoolingDown { .. }));
+}
@@ -0,0 +1,317 @@
+mod common;
+
+use arb_core::config::RiskConfig;
+use arb_core::risk::RiskError;
+use arb_core::types::{AccountMargin, Position, PositionSide, Trade, TradeStatus, Venue};
+use arb_core::{KillSwitchReason, KillSwitchState, RiskEngine};
+use chrono::{Duration, Utc};
+use common::risk_config;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+
+fn engine() -> RiskEngine {
+    RiskEngine::new(risk_config())
+}
+
+/// An engine with room for exactly ten trades of 100 notional on one asset.
+fn tight_engine() -> RiskEngine {
+    RiskEngine::new(RiskConfig {
+        max_notional_per_asset: 1000.0,
+        max_open_positions_per_venue: 1000,
+        ..risk_config()
+    })
+}
+
+fn margin(equity: i64, maintenance_margin: i64, position_notional: i64) -> AccountMargin {
+    AccountMargin {
+        venue: Venue::Binance,
+        equity: Decimal::from(equity),
+        initial_margin: Decimal::ZERO,
+        maintenance_margin: Decimal::from(maintenance_margin),
+        position_notional: Decimal::from(position_notional),
+        timestamp: Utc::now(),
+    }
+}
+
+/// A trade worth `notional` at a price of 100.
+fn trade(notional: i64) -> Trade {
+    Trade {
+        symbol: "BTC".to_string(),
+        venue: Venue::Binance,
+        side: PositionSide::Long,
+        size: Decimal::from(notional) / Decimal::from(100),
+        price: Decimal::from(100),
+        timestamp: Utc::now(),
+        status: TradeStatus::Pending,
+        order_id: None,
+    }
+}
+
+#[test]
+fn venues_without_a_margin_snapshot_are_not_margin_checked() {
+    assert!(engine().check_trade(&trade(50_000)).is_ok());
+}
+
+#[test]
+fn rejects_trades_past_max_leverage() {
+    let engine = engine();
+    engine.update_margin(margin(1000, 10, 2000));
+
+    assert!(engine.check_trade(&trade(1000)).is_ok());
+    assert!(matches!(
+        engine.check_trade(&trade(1500)),
+        Err(RiskError::MaxLeverageExceeded)
+    ));
+}
+
+#[test]
+fn rejects_trades_that_would_eat_into_the_liquidation_buffer() {
+    let engine = engine();
+    // 40% maintenance rate on 1000 of positions: another 500 takes the ratio to 0.6
+    engine.update_margin(margin(1000, 400, 1000));
+
+    assert!(engine.check_trade(&trade(200)).is_ok());
+    assert!(matches!(
+        engine.check_trade(&trade(500)),
+        Err(RiskError::LiquidationBufferBreached)
+    ));
+}
+
+#[test]
+fn rejects_everything_once_equity_is_gone() {
+    let engine = engine();
+    engine.update_margin(margin(0, 0, 0));
+
+    assert!(matches!(
+        engine.check_trade(&trade(100)),
+        Err(RiskError::MaxLeverageExceeded)
+    ));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn concurrent_reservations_never_exceed_the_notional_limit() {
+    let engine = Arc::new(tight_engine());
+
+    let tasks: Vec<_> = (0..50)
+        .map(|_| {
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                let reservation = engine.reserve(&trade(100)).ok()?;
+                tokio::task::yield_now().await;
+                let mut filled = trade(100);
+                filled.status = TradeStatus::Filled;
+                reservation.commit(&filled);
+                Some(())
+            })
+        })
+        .collect();
+
+    let mut accepted = 0;
+    for task in tasks {
+        if task.await.unwrap().is_some() {
+            accepted += 1;
+        }
+    }
+    assert_eq!(accepted, 10);
+    assert_eq!(engine.exposure("BTC"), Decimal::from(1000));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn concurrent_reservations_never_exceed_max_leverage() {
+    let engine = Arc::new(RiskEngine::new(RiskConfig {
+        max_open_positions_per_venue: 1000,
+        ..risk_config()
+    }));
+    // 2000 of positions on 1000 equity leaves 1000 of room at 3x
+    engine.update_margin(margin(1000, 10, 2000));
+
+    let tasks: Vec<_> = (0..50)
+        .map(|_| {
+            let engine = engine.clone();
+            tokio::spawn(async move {
+                let reservation = engine.reserve(&trade(100)).ok()?;
+                tokio::task::yield_now().await;
+                let mut filled = trade(100);
+                filled.status = TradeStatus::Filled;
+                reservation.commit(&filled);
+                Some(())
+            })
+        })
+        .collect();
+
+    let mut accepted = 0;
+    for task in tasks {
+        if task.await.unwrap().is_some() {
+            accepted += 1;
+        }
+    }
+    assert_eq!(accepted, 10);
+    // Committed fills count until a snapshot taken after them arrives
+    assert!(matches!(
+        engine.check_trade(&trade(100)),
+        Err(RiskError::MaxLeverageExceeded)
+    ));
+    engine.update_margin(margin(1000, 10, 2000));
+    assert!(engine.check_trade(&trade(1000)).is_ok());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 8)]
+async fn reservations_block_checks_until_released() {
+    let engine = Arc::new(tight_engine());
+
+    let held: Vec<_> = (0..10).map(|_| engine.reserve(&trade(100)).unwrap()).collect();
+    let rejected = tokio::spawn({
+        let engine = engine.clone();
+        async move { engine.reserve(&trade(100)).is_err() }
+    });
+    assert!(rejected.await.unwrap());
+
+    drop(held);
+    assert_eq!(engine.exposure("BTC"), Decimal::ZERO);
+    assert!(engine.check_trade(&trade(1000)).is_ok());
+}
+
+#[test]
+fn commit_books_the_filled_size_and_unfilled_trades_release() {
+    let engine = tight_engine();
+
+    let mut partial = trade(40);
+    partial.status = TradeStatus::PartiallyFilled;
+    engine.reserve(&trade(100)).unwrap().commit(&partial);
+    assert_eq!(engine.exposure("BTC"), Decimal::from(40));
+
+    let mut rejected = trade(100);
+    rejected.status = TradeStatus::Rejected;
+    engine.reserve(&trade(100)).unwrap().commit(&rejected);
+    assert_eq!(engine.exposure("BTC"), Decimal::from(40));
+
+    engine.reserve(&trade(100)).unwrap().release();
+    assert_eq!(engine.exposure("BTC"), Decimal::from(40));
+}
+
+#[test]
+fn daily_loss_trips_immediately_and_blocks_trading() {
+    let engine = engine();
+    engine.update_daily_pnl(Decimal::from(-1500));
+
+    assert!(matches!(
+        engine.kill_switch_state(),
+        KillSwitchState::Tripped { reason: KillSwitchReason::DailyLoss { .. }, .. }
+    ));
+    assert!(matches!(
+        engine.check_trade(&trade(100)),
+        Err(RiskError::KillSwitchActive)
+    ));
+}
+
+#[test]
+fn rollover_resets_pnl_then_cooldown_rearms() {
+    let engine = engine();
+    engine.update_daily_pnl(Decimal::from(-1500));
+    let rollover = engine.next_rollover();
+
+    engine.tick(rollover - Duration::seconds(1));
+    assert!(matches!(engine.kill_switch_state(), KillSwitchState::Tripped { .. }));
+
+    engine.tick(rollover);
+    assert_eq!(engine.daily_pnl(), Decimal::ZERO);
+    assert_eq!(engine.next_rollover(), rollover + Duration::days(1));
+    assert!(matches!(engine.kill_switch_state(), KillSwitchState::CoolingDown { .. }));
+
+    engine.tick(rollover + Duration::seconds(3600));
+    assert_eq!(engine.kill_switch_state(), KillSwitchState::Armed);
+}
+
+#[test]
+fn manual_rearm_keeps_the_switch_tripped_across_rollovers() {
+    let engine = RiskEngine::new(RiskConfig {
+        kill_switch_manual_rearm: true,
+        ..risk_config()
+    });
+    engine.trip(KillSwitchReason::Manual("venue outage".to_string()));
+
+    engine.tick(engine.next_rollover() + Duration::days(2));
+    assert!(matches!(engine.kill_switch_state(), KillSwitchState::Tripped { .. }));
+
+    engine.rearm().unwrap();
+    assert!(engine.check_trade(&trade(100)).is_ok());
+}
+
+#[test]
+fn manual_trip_survives_rollover_even_with_automatic_rearm() {
+    let engine = engine();
+    engine.trip(KillSwitchReason::Manual("flatten all".to_string()));
+    let rollover = engine.next_rollover();
+
+    engine.tick(rollover);
+    engine.tick(rollover + Duration::days(2));
+    assert!(matches!(
+        engine.kill_switch_state(),
+        KillSwitchState::Tripped { reason: KillSwitchReason::Manual(_), .. }
+    ));
+    engine.reset_daily_pnl();
+    assert!(engine.is_kill_switch_active());
+
+    engine.rearm().unwrap();
+    assert!(engine.check_trade(&trade(100)).is_ok());
+}
+
+#[test]
+fn manual_trip_takes_over_a_daily_loss_trip() {
+    let engine = engine();
+    engine.update_daily_pnl(Decimal::from(-1500));
+    assert!(engine.trip(KillSwitchReason::Manual("operator".to_string())));
+    assert!(!engine.trip(KillSwitchReason::Manual("again".to_string())));
+
+    engine.tick(engine.next_rollover() + Duration::days(2));
+    assert!(matches!(
+        engine.kill_switch_state(),
+        KillSwitchState::Tripped { reason: KillSwitchReason::Manual(ref note), .. } if note == "operator"
+    ));
+}
+
+#[test]
+fn rearm_is_refused_while_the_daily_loss_stands() {
+    let engine = engine();
+    engine.update_daily_pnl(Decimal::from(-1500));
+
+    assert!(matches!(engine.rearm(), Err(RiskError::DailyLossThresholdExceeded)));
+    assert!(engine.is_kill_switch_active());
+}
+
+#[test]
+fn rollover_boundary_follows_the_configured_utc_hour() {
+    let engine = RiskEngine::new(RiskConfig {
+        daily_rollover_hour_utc: 8,
+        ..risk_config()
+    });
+    let next = engine.next_rollover();
+
+    assert_eq!(next.format("%H:%M:%S").to_string(), "08:00:00");
+    assert!(next > Utc::now() && next <= Utc::now() + Duration::days(1));
+}
+
+#[test]
+fn syncing_positions_keeps_in_flight_reservations() {
+    let engine = tight_engine();
+    let reservation = engine.reserve(&trade(300)).unwrap();
+    let mut filled = trade(100);
+    filled.status = TradeStatus::Filled;
+    engine.record_trade(&filled);
+
+    let held = Position {
+        symbol: "BTC".to_string(),
+        venue: Venue::Binance,
+        side: PositionSide::Long,
+        size: Decimal::from(2),
+        entry_price: Decimal::from(100),
+        leverage: Decimal::ONE,
+        timestamp: Utc::now(),
+    };
+    assert!(engine.sync_positions(std::slice::from_ref(&held)));
+    assert_eq!(engine.exposure("BTC"), Decimal::from(500));
+    assert!(!engine.sync_positions(&[held]));
+
+    drop(reservation);
+    assert_eq!(engine.exposure("BTC"), Decimal::from(200));
+}
@@ -1,37 +1,285 @@
-use arb_core::types::{FundingRate, Venue};
+use crate::signing::hmac_sha256_hex;
+use crate::venue::{request_not_sent, VenueConnector};
+use arb_core::instruments::{canonical_asset, Instrument, InstrumentRegistry};
+use arb_core::types::{
+    AccountMargin, Balance, BookLevel, ContractSpec, FundingPayment, FundingRate, MarketData,
+    OrderBook, OrderOptions, Position, PositionSide, TimeInForce, Trade, TradeStatus, Venue,
+};
 use anyhow::{Context, Result};
 use rust_decimal::prelude::*;
-use chrono::Utc;
-use reqwest::Client;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, Method};
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use parking_lot::RwLock;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 use tracing::warn;
 
+/// Binance error code returned when the request timestamp falls outside `recvWindow`.
+const TIMESTAMP_OUTSIDE_RECV_WINDOW: i64 = -1021;
+
+/// An error response from the Binance API.
+#[derive(Debug, thiserror::Error)]
+#[error("Binance API error {code}: {message}")]
+pub struct BinanceError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl BinanceError {
+    /// Whether Binance refused the request outright. Unknown and internal
+    /// errors, and -1006/-1007 whose documented outcome is unknown, do not count.
+    pub fn is_rejection(&self) -> bool {
+        !matches!(self.code, -1000 | -1001 | -1006 | -1007)
+    }
+}
+
+/// Interval for symbols absent from `fundingInfo`, which only lists adjusted ones.
+pub const DEFAULT_FUNDING_INTERVAL_HOURS: u32 = 8;
+const FUNDING_INFO_TTL: Duration = Duration::from_secs(3600);
+
+/// `fundingInfo` snapshot keyed by native symbol.
+struct FundingIntervals {
+    fetched_at: Instant,
+    hours: HashMap<String, u32>,
+}
+
 #[derive(Clone)]
 pub struct BinanceConnector {
     client: Client,
     api_key: String,
     api_secret: String,
     base_url: String,
+    recv_window_ms: u64,
+    time_offset_ms: Arc<AtomicI64>,
+    time_synced: Arc<AtomicBool>,
+    funding_intervals: Arc<RwLock<Option<FundingIntervals>>>,
+    instruments: Arc<InstrumentRegistry>,
 }
 
 impl BinanceConnector {
-    pub fn new(api_key: String, api_secret: String, base_url: String) -> Self {
+    pub fn new(api_key: String, api_secret: String, base_url: String, recv_window_ms: u64) -> Self {
         Self {
             client: Client::new(),
             api_key,
             api_secret,
             base_url,
+            recv_window_ms,
+            time_offset_ms: Arc::new(AtomicI64::new(0)),
+            time_synced: Arc::new(AtomicBool::new(false)),
+            funding_intervals: Arc::new(RwLock::new(None)),
+            instruments: Arc::new(InstrumentRegistry::new()),
         }
     }
 
-    pub async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
-        // Binance uses BTCUSDT format, convert if needed
-        let binance_symbol = if symbol.contains("-") {
-            symbol.replace("-", "")
-        } else {
-            symbol.to_string()
-        };
+    /// Resolves canonical asset ids through `instruments` once it is loaded.
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    fn native_symbol(&self, symbol: &str) -> String {
+        self.instruments
+            .native_symbol(symbol, &Venue::Binance)
+            .unwrap_or_else(|| native_symbol(symbol))
+    }
+
+    fn canonical_symbol(&self, native: &str) -> String {
+        self.instruments
+            .asset(&Venue::Binance, native)
+            .unwrap_or_else(|| canonical_symbol(native))
+    }
+
+    async fn exchange_info(&self) -> Result<BinanceExchangeInfo> {
+        let url = format!("{}/fapi/v1/exchangeInfo", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch Binance exchange info")?;
+        Ok(resp.json().await?)
+    }
+
+    /// Funding interval for `symbol`, refreshing the `fundingInfo` cache hourly.
+    pub async fn funding_interval_hours(&self, symbol: &str) -> Result<u32> {
+        let binance_symbol = self.native_symbol(symbol);
+        let cached = self.funding_intervals.read().as_ref().and_then(|intervals| {
+            (intervals.fetched_at.elapsed() < FUNDING_INFO_TTL)
+                .then(|| intervals.hours.get(&binance_symbol).copied())
+        });
+        if let Some(hours) = cached {
+            return Ok(hours.unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS));
+        }
+
+        let url = format!("{}/fapi/v1/fundingInfo", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch Binance funding info")?;
+        let info: Vec<BinanceFundingInfo> = resp.json().await?;
+
+        let hours: HashMap<String, u32> = info
+            .into_iter()
+            .map(|entry| (entry.symbol, entry.funding_interval_hours))
+            .collect();
+        let interval = hours
+            .get(&binance_symbol)
+            .copied()
+            .unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS);
+        *self.funding_intervals.write() = Some(FundingIntervals {
+            fetched_at: Instant::now(),
+            hours,
+        });
+        Ok(interval)
+    }
+
+    fn require_credentials(&self) -> Result<()> {
+        if self.api_key.is_empty() || self.api_secret.is_empty() {
+            anyhow::bail!("Binance API credentials not configured");
+        }
+        Ok(())
+    }
+
+    /// Measures the offset between the local clock and Binance server time,
+    /// which is applied to every signed request's `timestamp`.
+    pub async fn sync_time(&self) -> Result<i64> {
+        let url = format!("{}/fapi/v1/time", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .context("Failed to fetch Binance server time")?;
+
+        let data: BinanceServerTime = resp.json().await?;
+        let offset = data.server_time - Utc::now().timestamp_millis();
+        self.time_offset_ms.store(offset, Ordering::Relaxed);
+        self.time_synced.store(true, Ordering::Relaxed);
+        Ok(offset)
+    }
+
+    async fn signed_request<T: DeserializeOwned>(
+        &self,
+        method: Method,
+        path: &str,
+        params: &[(&str, String)],
+    ) -> Result<T> {
+        self.require_credentials()?;
+        if !self.time_synced.load(Ordering::Relaxed) {
+            self.sync_time().await?;
+        }
+
+        let mut resynced = false;
+        loop {
+            let timestamp = Utc::now().timestamp_millis() + self.time_offset_ms.load(Ordering::Relaxed);
+            let mut query: Vec<String> = params.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+            query.push(format!("recvWindow={}", self.recv_window_ms));
+            query.push(format!("timestamp={}", timestamp));
+            let query = query.join("&");
+            let signature = hmac_sha256_hex(&self.api_secret, &query);
+            let url = format!("{}{}?{}&signature={}", self.base_url, path, query, signature);
+
+            let resp = self
+                .client
+                .request(method.clone(), &url)
+                .header("X-MBX-APIKEY", &self.api_key)
+                .send()
+                .await
+                .with_context(|| format!("Failed to send Binance request {} {}", method, path))?;
+
+            let status = resp.status();
+            let body = resp.text().await?;
+            if status.is_success() {
+                return serde_json::from_str(&body)
+                    .with_context(|| format!("Failed to parse Binance response from {}", path));
+            }
+
+            let error: BinanceErrorBody = serde_json::from_str(&body)
+                .with_context(|| format!("Binance request {} failed with {}: {}", path, status, body))?;
+            if error.code == TIMESTAMP_OUTSIDE_RECV_WINDOW && !resynced {
+                warn!("Binance rejected timestamp, resyncing server time");
+                self.sync_time().await?;
+                resynced = true;
+                continue;
+            }
+            return Err(BinanceError {
+                code: error.code,
+                message: error.msg,
+            }
+            .into());
+        }
+    }
+}
+
+#[async_trait]
+impl VenueConnector for BinanceConnector {
+    fn venue(&self) -> Venue {
+        Venue::Binance
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let url = format!("{}/fapi/v1/ticker/bookTicker", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[("symbol", &self.native_symbol(symbol))])
+            .send()
+            .await
+            .context("Failed to fetch Binance book ticker")?;
+
+        let data: BinanceBookTicker = resp.json().await?;
+        let bid = Decimal::from_str(&data.bid_price).context("Failed to parse bid price")?;
+        let ask = Decimal::from_str(&data.ask_price).context("Failed to parse ask price")?;
+
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            venue: Venue::Binance,
+            price: (bid + ask) / Decimal::TWO,
+            bid,
+            ask,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_order_book(&self, symbol: &str, depth: usize) -> Result<OrderBook> {
+        // The depth endpoint only accepts a fixed set of limits
+        let limit = [5, 10, 20, 50, 100, 500, 1000]
+            .into_iter()
+            .find(|l| *l >= depth)
+            .unwrap_or(1000);
+
+        let url = format!("{}/fapi/v1/depth", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[("symbol", self.native_symbol(symbol)), ("limit", limit.to_string())])
+            .send()
+            .await
+            .context("Failed to fetch Binance order book")?;
+
+        let data: BinanceDepth = resp.json().await?;
+        Ok(OrderBook {
+            symbol: symbol.to_string(),
+            venue: Venue::Binance,
+            bids: parse_levels(&data.bids, depth)?,
+            asks: parse_levels(&data.asks, depth)?,
+            timestamp: data
+                .transaction_time
+                .and_then(DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
+        let binance_symbol = self.native_symbol(symbol);
 
         let url = format!("{}/fapi/v1/premiumIndex", self.base_url);
         let resp = self
@@ -44,20 +292,87 @@ impl BinanceConnector {
 
         let data: BinancePremiumIndex = resp.json().await?;
         
-        let rate = Decimal::from_str_exact(&data.lastFundingRate)
-            .or_else(|_| Decimal::from_str(&data.lastFundingRate))
+        let rate = Decimal::from_str_exact(&data.last_funding_rate)
+            .or_else(|_| Decimal::from_str(&data.last_funding_rate))
             .context("Failed to parse funding rate")?;
+        let interval_hours = self.funding_interval_hours(symbol).await?;
 
         Ok(FundingRate {
             symbol: symbol.to_string(),
             venue: Venue::Binance,
             rate,
             predicted_rate: None,
+            interval_hours,
+            next_funding_time: data
+                .next_funding_time
+                .filter(|&time| time > 0)
+                .and_then(DateTime::from_timestamp_millis),
             timestamp: Utc::now(),
         })
     }
 
-    pub async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
+    async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        let binance_symbol = self.native_symbol(symbol);
+        let market = self
+            .exchange_info()
+            .await?
+            .symbols
+            .into_iter()
+            .find(|s| s.symbol == binance_symbol)
+            .with_context(|| format!("Unknown Binance symbol {}", binance_symbol))?;
+        Ok(ContractSpec {
+            symbol: symbol.to_string(),
+            ..market.instrument()?.spec
+        })
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Instrument>> {
+        let info = self.exchange_info().await?;
+        Ok(info
+            .symbols
+            .iter()
+            .filter(|market| market.is_usdt_perpetual())
+            .filter_map(|market| match market.instrument() {
+                Ok(instrument) => Some(instrument),
+                Err(e) => {
+                    warn!("Skipping Binance symbol {}: {:#}", market.symbol, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn get_funding_payments(
+        &self,
+        symbol: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FundingPayment>> {
+        let params = [
+            ("symbol", self.native_symbol(symbol)),
+            ("incomeType", "FUNDING_FEE".to_string()),
+            ("startTime", since.timestamp_millis().to_string()),
+            ("limit", "1000".to_string()),
+        ];
+        let incomes: Vec<BinanceIncome> = self
+            .signed_request(Method::GET, "/fapi/v1/income", &params)
+            .await?;
+
+        incomes
+            .into_iter()
+            .map(|income| {
+                Ok(FundingPayment {
+                    id: income.tran_id.to_string(),
+                    symbol: symbol.to_string(),
+                    venue: Venue::Binance,
+                    amount: parse_decimal(&income.income, "funding income")?,
+                    asset: income.asset,
+                    timestamp: DateTime::from_timestamp_millis(income.time).unwrap_or_else(Utc::now),
+                })
+            })
+            .collect()
+    }
+
+    async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
         let url = format!("{}/fapi/v1/ticker/24hr", self.base_url);
         let resp = self
             .client
@@ -71,30 +386,434 @@ impl BinanceConnector {
         // Filter for USDT pairs and sort by volume
         tickers.retain(|t| t.symbol.ends_with("USDT"));
         tickers.sort_by(|a, b| {
-            b.quoteVolume
+            b.quote_volume
                 .parse::<f64>()
                 .unwrap_or(0.0)
-                .partial_cmp(&a.quoteVolume.parse::<f64>().unwrap_or(0.0))
+                .partial_cmp(&a.quote_volume.parse::<f64>().unwrap_or(0.0))
                 .unwrap()
         });
 
         Ok(tickers
             .into_iter()
             .take(limit)
-            .map(|t| t.symbol.replace("USDT", "-USDT"))
+            .map(|t| self.canonical_symbol(&t.symbol))
             .collect())
     }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+    ) -> Result<Trade> {
+        self.place_order_with_options(symbol, side, size, price, OrderOptions::default())
+            .await
+    }
+
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        let side_param = match side {
+            PositionSide::Long => "BUY",
+            PositionSide::Short => "SELL",
+        };
+        let mut params = vec![
+            ("symbol", self.native_symbol(symbol)),
+            ("side", side_param.to_string()),
+            ("quantity", size.normalize().to_string()),
+            ("newOrderRespType", "RESULT".to_string()),
+        ];
+        // A zero price means "take liquidity now"
+        if price.is_zero() {
+            if options.time_in_force == TimeInForce::PostOnly {
+                anyhow::bail!("Binance market orders cannot be post-only");
+            }
+            params.push(("type", "MARKET".to_string()));
+        } else {
+            let time_in_force = match options.time_in_force {
+                TimeInForce::GoodTilCancelled => "GTC",
+                TimeInForce::ImmediateOrCancel => "IOC",
+                TimeInForce::PostOnly => "GTX",
+            };
+            params.push(("type", "LIMIT".to_string()));
+            params.push(("timeInForce", time_in_force.to_string()));
+            params.push(("price", price.normalize().to_string()));
+        }
+        if options.reduce_only {
+            params.push(("reduceOnly", "true".to_string()));
+        }
+
+        let order: BinanceOrder = self
+            .signed_request(Method::POST, "/fapi/v1/order", &params)
+            .await?;
+        order.into_trade(symbol)
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()> {
+        let params = [
+            ("symbol", self.native_symbol(symbol)),
+            ("orderId", order_id.to_string()),
+        ];
+        let _: BinanceOrder = self
+            .signed_request(Method::DELETE, "/fapi/v1/order", &params)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<Trade> {
+        let params = [
+            ("symbol", self.native_symbol(symbol)),
+            ("orderId", order_id.to_string()),
+        ];
+        let order: BinanceOrder = self
+            .signed_request(Method::GET, "/fapi/v1/order", &params)
+            .await?;
+        order.into_trade(symbol)
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        let risks: Vec<BinancePositionRisk> = self
+            .signed_request(Method::GET, "/fapi/v2/positionRisk", &[])
+            .await?;
+
+        let mut positions = Vec::new();
+        for risk in risks {
+            let amount = parse_decimal(&risk.position_amt, "position amount")?;
+            if amount.is_zero() {
+                continue;
+            }
+            positions.push(Position {
+                symbol: self.canonical_symbol(&risk.symbol),
+                venue: Venue::Binance,
+                side: if amount.is_sign_negative() {
+                    PositionSide::Short
+                } else {
+                    PositionSide::Long
+                },
+                size: amount.abs(),
+                entry_price: parse_decimal(&risk.entry_price, "entry price")?,
+                leverage: parse_decimal(&risk.leverage, "leverage")?,
+                timestamp: risk
+                    .update_time
+                    .and_then(DateTime::from_timestamp_millis)
+                    .unwrap_or_else(Utc::now),
+            });
+        }
+        Ok(positions)
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let balances: Vec<BinanceBalance> = self
+            .signed_request(Method::GET, "/fapi/v2/balance", &[])
+            .await?;
+
+        balances
+            .into_iter()
+            .map(|b| {
+                Ok(Balance {
+                    venue: Venue::Binance,
+                    free: parse_decimal(&b.available_balance, "available balance")?,
+                    total: parse_decimal(&b.balance, "balance")?,
+                    asset: b.asset,
+                })
+            })
+            .collect()
+    }
+
+    async fn get_account_margin(&self) -> Result<AccountMargin> {
+        let account: BinanceAccount = self
+            .signed_request(Method::GET, "/fapi/v2/account", &[])
+            .await?;
+        Ok(AccountMargin {
+            venue: Venue::Binance,
+            equity: parse_decimal(&account.total_margin_balance, "margin balance")?,
+            initial_margin: parse_decimal(&account.total_initial_margin, "initial margin")?,
+            maintenance_margin: parse_decimal(&account.total_maint_margin, "maintenance margin")?,
+            position_notional: account
+                .positions
+                .iter()
+                .map(|p| parse_decimal(&p.notional, "position notional").map(|n| n.abs()))
+                .sum::<Result<Decimal>>()?,
+            timestamp: Utc::now(),
+        })
+    }
+
+    fn order_not_placed(&self, error: &anyhow::Error) -> bool {
+        let rejected = |cause: &(dyn std::error::Error + 'static)| {
+            cause.downcast_ref::<BinanceError>().is_some_and(BinanceError::is_rejection)
+        };
+        request_not_sent(error) || error.chain().any(rejected)
+    }
+}
+
+/// USDT perpetual for a symbol missing from the instrument registry; accepts
+/// both `BTC` and the older `BTC-USDT` form.
+pub(crate) fn native_symbol(symbol: &str) -> String {
+    let symbol = symbol.replace('-', "");
+    if symbol.ends_with("USDT") {
+        symbol
+    } else {
+        format!("{}USDT", symbol)
+    }
+}
+
+fn canonical_symbol(symbol: &str) -> String {
+    symbol.strip_suffix("USDT").unwrap_or(symbol).to_string()
+}
+
+fn parse_decimal(value: &str, field: &str) -> Result<Decimal> {
+    Decimal::from_str(value).with_context(|| format!("Failed to parse Binance {} {:?}", field, value))
+}
+
+fn parse_levels(levels: &[[String; 2]], depth: usize) -> Result<Vec<BookLevel>> {
+    levels
+        .iter()
+        .take(depth)
+        .map(|[price, size]| {
+            Ok(BookLevel {
+                price: parse_decimal(price, "level price")?,
+                size: parse_decimal(size, "level size")?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceDepth {
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+    #[serde(rename = "T", default)]
+    transaction_time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceServerTime {
+    #[serde(rename = "serverTime")]
+    server_time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceErrorBody {
+    code: i64,
+    msg: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceOrder {
+    #[serde(rename = "orderId")]
+    order_id: u64,
+    status: String,
+    side: String,
+    price: String,
+    #[serde(rename = "avgPrice", default)]
+    avg_price: Option<String>,
+    #[serde(rename = "origQty")]
+    orig_qty: String,
+    #[serde(rename = "executedQty")]
+    executed_qty: String,
+    #[serde(rename = "updateTime", default)]
+    update_time: Option<i64>,
+}
+
+impl BinanceOrder {
+    fn into_trade(self, symbol: &str) -> Result<Trade> {
+        let status = match self.status.as_str() {
+            "NEW" => TradeStatus::Pending,
+            "PARTIALLY_FILLED" => TradeStatus::PartiallyFilled,
+            "FILLED" => TradeStatus::Filled,
+            "CANCELED" => TradeStatus::Cancelled,
+            "REJECTED" | "EXPIRED" | "EXPIRED_IN_MATCH" => TradeStatus::Rejected,
+            other => anyhow::bail!("Unknown Binance order status {}", other),
+        };
+        let executed = parse_decimal(&self.executed_qty, "executed quantity")?;
+        // An IOC order that expires after a partial fill still holds that fill
+        let status = match status {
+            TradeStatus::Cancelled | TradeStatus::Rejected if !executed.is_zero() => {
+                TradeStatus::PartiallyFilled
+            }
+            status => status,
+        };
+        let size = if executed.is_zero() {
+            parse_decimal(&self.orig_qty, "order quantity")?
+        } else {
+            executed
+        };
+        let avg_price = match &self.avg_price {
+            Some(avg) => parse_decimal(avg, "average price")?,
+            None => Decimal::ZERO,
+        };
+        let price = if avg_price.is_zero() {
+            parse_decimal(&self.price, "price")?
+        } else {
+            avg_price
+        };
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: Venue::Binance,
+            side: if self.side == "SELL" {
+                PositionSide::Short
+            } else {
+                PositionSide::Long
+            },
+            size,
+            price,
+            timestamp: self
+                .update_time
+                .and_then(DateTime::from_timestamp_millis)
+                .unwrap_or_else(Utc::now),
+            status,
+            order_id: Some(self.order_id.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BinancePositionRisk {
+    symbol: String,
+    #[serde(rename = "positionAmt")]
+    position_amt: String,
+    #[serde(rename = "entryPrice")]
+    entry_price: String,
+    leverage: String,
+    #[serde(rename = "updateTime", default)]
+    update_time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceAccount {
+    total_margin_balance: String,
+    total_initial_margin: String,
+    total_maint_margin: String,
+    #[serde(default)]
+    positions: Vec<BinanceAccountPosition>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceAccountPosition {
+    notional: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceBalance {
+    asset: String,
+    balance: String,
+    #[serde(rename = "availableBalance")]
+    available_balance: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceBookTicker {
+    #[serde(rename = "bidPrice")]
+    bid_price: String,
+    #[serde(rename = "askPrice")]
+    ask_price: String,
 }
 
 #[derive(Debug, Deserialize)]
 struct BinancePremiumIndex {
     #[serde(rename = "lastFundingRate")]
-    lastFundingRate: String,
+    last_funding_rate: String,
+    #[serde(rename = "nextFundingTime")]
+    next_funding_time: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceExchangeInfo {
+    symbols: Vec<BinanceSymbolInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceSymbolInfo {
+    symbol: String,
+    base_asset: Option<String>,
+    quote_asset: Option<String>,
+    contract_type: Option<String>,
+    status: Option<String>,
+    filters: Vec<BinanceSymbolFilter>,
+}
+
+impl BinanceSymbolInfo {
+    /// Fields missing from the response don't exclude a symbol.
+    fn is_usdt_perpetual(&self) -> bool {
+        self.quote_asset.as_deref().is_none_or(|q| q == "USDT")
+            && self.contract_type.as_deref().is_none_or(|t| t == "PERPETUAL")
+            && self.status.as_deref().is_none_or(|s| s == "TRADING")
+    }
+
+    fn instrument(&self) -> Result<Instrument> {
+        let base = match &self.base_asset {
+            Some(base) => base.clone(),
+            None => canonical_symbol(&self.symbol),
+        };
+        let (asset, multiplier) = canonical_asset(&base);
+        let filter = |kind: &str| self.filters.iter().find(|f| f.filter_type == kind);
+        let value = |kind: &str, field: Option<&String>| -> Result<Decimal> {
+            let raw = field.with_context(|| format!("Binance {} missing {}", self.symbol, kind))?;
+            parse_decimal(raw, kind)
+        };
+
+        let price_filter = filter("PRICE_FILTER");
+        let lot_filter = filter("LOT_SIZE");
+        Ok(Instrument {
+            asset: asset.clone(),
+            venue: Venue::Binance,
+            native_symbol: self.symbol.clone(),
+            quote_asset: self.quote_asset.clone().unwrap_or_else(|| "USDT".to_string()),
+            spec: ContractSpec {
+                symbol: asset,
+                venue: Venue::Binance,
+                tick_size: value("PRICE_FILTER", price_filter.and_then(|f| f.tick_size.as_ref()))?,
+                lot_size: value("LOT_SIZE", lot_filter.and_then(|f| f.step_size.as_ref()))?,
+                min_size: value("LOT_SIZE", lot_filter.and_then(|f| f.min_qty.as_ref()))?,
+                min_notional: filter("MIN_NOTIONAL")
+                    .and_then(|f| f.notional.as_deref())
+                    .map(|n| parse_decimal(n, "MIN_NOTIONAL"))
+                    .transpose()?
+                    .unwrap_or(Decimal::ZERO),
+                contract_multiplier: multiplier,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BinanceSymbolFilter {
+    filter_type: String,
+    tick_size: Option<String>,
+    step_size: Option<String>,
+    min_qty: Option<String>,
+    notional: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceIncome {
+    income: String,
+    asset: String,
+    time: i64,
+    #[serde(rename = "tranId")]
+    tran_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct BinanceFundingInfo {
+    symbol: String,
+    #[serde(rename = "fundingIntervalHours")]
+    funding_interval_hours: u32,
 }
 
 #[derive(Debug, Deserialize)]
 struct BinanceTicker {
     symbol: String,
     #[serde(rename = "quoteVolume")]
-    quoteVolume: String,
+    quote_volume: String,
 }
@@ -1,67 +1,446 @@
-use arb_core::types::{FundingRate, Venue};
+use crate::signing::hmac_sha256_hex;
+use crate::venue::{request_not_sent, VenueConnector};
+use arb_core::instruments::{canonical_asset, Instrument, InstrumentRegistry};
+use arb_core::types::{
+    AccountMargin, Balance, BookLevel, ContractSpec, FundingPayment, FundingRate, MarketData,
+    OrderBook, OrderOptions, Position, PositionSide, TimeInForce, Trade, TradeStatus, Venue,
+};
 use anyhow::{Context, Result};
 use rust_decimal::prelude::*;
-use chrono::Utc;
-use reqwest::Client;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::{Client, RequestBuilder};
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::sync::Arc;
 use tracing::warn;
 
+/// Interval assumed when a ticker omits `fundingIntervalHour`.
+pub const DEFAULT_FUNDING_INTERVAL_HOURS: u32 = 8;
+
+const TRANSACTION_LOG_WINDOW: chrono::Duration = chrono::Duration::days(7);
+
 #[derive(Clone)]
 pub struct BybitConnector {
     client: Client,
     api_key: String,
     api_secret: String,
     base_url: String,
+    recv_window_ms: u64,
+    time_offset_ms: Arc<AtomicI64>,
+    time_synced: Arc<AtomicBool>,
+    instruments: Arc<InstrumentRegistry>,
+}
+
+/// Errors reported by Bybit through a non-zero `retCode`.
+#[derive(Debug, thiserror::Error)]
+pub enum BybitError {
+    #[error("Bybit request timestamp outside recv window: {0}")]
+    TimestampExpired(String),
+    #[error("Bybit API key is invalid: {0}")]
+    InvalidApiKey(String),
+    #[error("Bybit signature rejected: {0}")]
+    InvalidSignature(String),
+    #[error("Bybit API key lacks permission: {0}")]
+    PermissionDenied(String),
+    #[error("Bybit rate limit exceeded: {0}")]
+    RateLimited(String),
+    #[error("Bybit order not found: {0}")]
+    OrderNotFound(String),
+    #[error("Bybit insufficient balance: {0}")]
+    InsufficientBalance(String),
+    #[error("Bybit reduce-only order rejected: {0}")]
+    ReduceOnlyRejected(String),
+    #[error("Bybit API error {code}: {message}")]
+    Api { code: i64, message: String },
+}
+
+impl BybitError {
+    pub fn from_ret_code(code: i64, message: String) -> Self {
+        match code {
+            10002 => BybitError::TimestampExpired(message),
+            10003 => BybitError::InvalidApiKey(message),
+            10004 => BybitError::InvalidSignature(message),
+            10005 => BybitError::PermissionDenied(message),
+            10006 => BybitError::RateLimited(message),
+            110001 => BybitError::OrderNotFound(message),
+            110004 | 110007 | 110012 => BybitError::InsufficientBalance(message),
+            110017 => BybitError::ReduceOnlyRejected(message),
+            _ => BybitError::Api { code, message },
+        }
+    }
+
+    /// Whether Bybit refused the request outright. Codes we do not recognise
+    /// may mean the outcome is unknown.
+    pub fn is_rejection(&self) -> bool {
+        !matches!(self, BybitError::Api { .. })
+    }
 }
 
 impl BybitConnector {
-    pub fn new(api_key: String, api_secret: String, base_url: String) -> Self {
+    pub fn new(api_key: String, api_secret: String, base_url: String, recv_window_ms: u64) -> Self {
         Self {
             client: Client::new(),
             api_key,
             api_secret,
             base_url,
+            recv_window_ms,
+            time_offset_ms: Arc::new(AtomicI64::new(0)),
+            time_synced: Arc::new(AtomicBool::new(false)),
+            instruments: Arc::new(InstrumentRegistry::new()),
         }
     }
 
-    pub async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
-        // Bybit uses BTCUSDT format
-        let bybit_symbol = if symbol.contains("-") {
-            symbol.replace("-", "")
-        } else {
-            symbol.to_string()
-        };
+    /// Resolves canonical asset ids through `instruments` once it is loaded.
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    fn native_symbol(&self, symbol: &str) -> String {
+        self.instruments
+            .native_symbol(symbol, &Venue::Bybit)
+            .unwrap_or_else(|| native_symbol(symbol))
+    }
 
-        let url = format!("{}/v5/market/funding/history", self.base_url);
+    fn canonical_symbol(&self, native: &str) -> String {
+        self.instruments
+            .asset(&Venue::Bybit, native)
+            .unwrap_or_else(|| canonical_symbol(native))
+    }
+
+    /// Linear instruments, all pages unless `symbol` narrows the query.
+    async fn instruments_info(&self, symbol: Option<&str>) -> Result<Vec<BybitInstrument>> {
+        let url = format!("{}/v5/market/instruments-info", self.base_url);
+        let mut instruments = Vec::new();
+        let mut cursor = String::new();
+        loop {
+            let mut params = vec![("category", "linear"), ("limit", "1000")];
+            if let Some(symbol) = symbol {
+                params.push(("symbol", symbol));
+            }
+            if !cursor.is_empty() {
+                params.push(("cursor", &cursor));
+            }
+            let resp = self
+                .client
+                .get(&url)
+                .query(&params)
+                .send()
+                .await
+                .context("Failed to fetch Bybit instrument info")?;
+            let page: BybitInstrumentsResponse = resp.json().await?;
+            instruments.extend(page.result.list);
+
+            if page.result.next_page_cursor.is_empty() {
+                return Ok(instruments);
+            }
+            cursor = page.result.next_page_cursor;
+        }
+    }
+
+    fn require_credentials(&self) -> Result<()> {
+        if self.api_key.is_empty() || self.api_secret.is_empty() {
+            anyhow::bail!("Bybit API credentials not configured");
+        }
+        Ok(())
+    }
+
+    /// Measures the offset between the local clock and Bybit server time,
+    /// which is applied to every signed request's `X-BAPI-TIMESTAMP`.
+    pub async fn sync_time(&self) -> Result<i64> {
+        let url = format!("{}/v5/market/time", self.base_url);
         let resp = self
             .client
             .get(&url)
-            .query(&[("category", "linear"), ("symbol", &bybit_symbol), ("limit", "1")])
             .send()
             .await
-            .context("Failed to fetch Bybit funding rate")?;
+            .context("Failed to fetch Bybit server time")?;
 
-        let data: BybitFundingResponse = resp.json().await?;
-        
-        if data.result.list.is_empty() {
-            anyhow::bail!("No funding rate data for {}", symbol);
+        let data: BybitResponse<BybitServerTime> = resp.json().await?;
+        let server_ms = data
+            .result
+            .time_nano
+            .parse::<i64>()
+            .context("Failed to parse Bybit server time")?
+            / 1_000_000;
+        let offset = server_ms - Utc::now().timestamp_millis();
+        self.time_offset_ms.store(offset, Ordering::Relaxed);
+        self.time_synced.store(true, Ordering::Relaxed);
+        Ok(offset)
+    }
+
+    async fn signed_get<T: DeserializeOwned>(&self, path: &str, params: &[(&str, String)]) -> Result<T> {
+        let query = params
+            .iter()
+            .map(|(k, v)| format!("{}={}", k, v))
+            .collect::<Vec<_>>()
+            .join("&");
+        let url = format!("{}{}?{}", self.base_url, path, query);
+        self.send_signed(path, &query, || self.client.get(&url)).await
+    }
+
+    async fn signed_post<T: DeserializeOwned>(&self, path: &str, body: serde_json::Value) -> Result<T> {
+        let body = body.to_string();
+        let url = format!("{}{}", self.base_url, path);
+        self.send_signed(path, &body, || {
+            self.client
+                .post(&url)
+                .header("Content-Type", "application/json")
+                .body(body.clone())
+        })
+        .await
+    }
+
+    /// Signs `timestamp + api_key + recv_window + payload`, where the payload is
+    /// the query string for GET requests and the JSON body for POST requests.
+    async fn send_signed<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        payload: &str,
+        build: impl Fn() -> RequestBuilder,
+    ) -> Result<T> {
+        self.require_credentials()?;
+        if !self.time_synced.load(Ordering::Relaxed) {
+            self.sync_time().await?;
+        }
+
+        let mut resynced = false;
+        loop {
+            let timestamp = Utc::now().timestamp_millis() + self.time_offset_ms.load(Ordering::Relaxed);
+            let signature = hmac_sha256_hex(
+                &self.api_secret,
+                &format!("{}{}{}{}", timestamp, self.api_key, self.recv_window_ms, payload),
+            );
+
+            let resp = build()
+                .header("X-BAPI-API-KEY", &self.api_key)
+                .header("X-BAPI-SIGN", signature)
+                .header("X-BAPI-TIMESTAMP", timestamp.to_string())
+                .header("X-BAPI-RECV-WINDOW", self.recv_window_ms.to_string())
+                .send()
+                .await
+                .with_context(|| format!("Failed to send Bybit request {}", path))?;
+
+            let data: BybitResponse<serde_json::Value> = resp
+                .json()
+                .await
+                .with_context(|| format!("Failed to parse Bybit response from {}", path))?;
+
+            if data.ret_code == 0 {
+                return serde_json::from_value(data.result)
+                    .with_context(|| format!("Failed to parse Bybit result from {}", path));
+            }
+
+            let error = BybitError::from_ret_code(data.ret_code, data.ret_msg);
+            if matches!(error, BybitError::TimestampExpired(_)) && !resynced {
+                warn!("Bybit rejected timestamp, resyncing server time");
+                self.sync_time().await?;
+                resynced = true;
+                continue;
+            }
+            return Err(error.into());
         }
+    }
+}
+
+#[async_trait]
+impl VenueConnector for BybitConnector {
+    fn venue(&self) -> Venue {
+        Venue::Bybit
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let url = format!("{}/v5/market/tickers", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[("category", "linear"), ("symbol", &self.native_symbol(symbol))])
+            .send()
+            .await
+            .context("Failed to fetch Bybit ticker")?;
+
+        let data: BybitBookTickerResponse = resp.json().await?;
+        let ticker = data
+            .result
+            .list
+            .first()
+            .with_context(|| format!("No ticker data for {}", symbol))?;
+        let bid = Decimal::from_str(&ticker.bid1_price).context("Failed to parse bid price")?;
+        let ask = Decimal::from_str(&ticker.ask1_price).context("Failed to parse ask price")?;
+
+        Ok(MarketData {
+            symbol: symbol.to_string(),
+            venue: Venue::Bybit,
+            price: (bid + ask) / Decimal::TWO,
+            bid,
+            ask,
+            timestamp: Utc::now(),
+        })
+    }
 
-        let rate = Decimal::from_str_exact(&data.result.list[0].fundingRate)
-            .or_else(|_| Decimal::from_str(&data.result.list[0].fundingRate))
+    async fn get_order_book(&self, symbol: &str, depth: usize) -> Result<OrderBook> {
+        let url = format!("{}/v5/market/orderbook", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[
+                ("category", "linear".to_string()),
+                ("symbol", self.native_symbol(symbol)),
+                ("limit", depth.clamp(1, 500).to_string()),
+            ])
+            .send()
+            .await
+            .context("Failed to fetch Bybit order book")?;
+
+        let data: BybitResponse<BybitOrderBook> = resp.json().await?;
+        if data.ret_code != 0 {
+            return Err(BybitError::from_ret_code(data.ret_code, data.ret_msg).into());
+        }
+        Ok(OrderBook {
+            symbol: symbol.to_string(),
+            venue: Venue::Bybit,
+            bids: parse_levels(&data.result.bids, depth)?,
+            asks: parse_levels(&data.result.asks, depth)?,
+            timestamp: DateTime::from_timestamp_millis(data.result.ts).unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
+        let bybit_symbol = self.native_symbol(symbol);
+
+        let url = format!("{}/v5/market/tickers", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[("category", "linear"), ("symbol", &bybit_symbol)])
+            .send()
+            .await
+            .context("Failed to fetch Bybit funding rate")?;
+
+        let data: BybitTickersResponse = resp.json().await?;
+        let ticker = data
+            .result
+            .list
+            .into_iter()
+            .find(|t| t.symbol == bybit_symbol)
+            .with_context(|| format!("No funding rate data for {}", symbol))?;
+        let funding_rate = ticker
+            .funding_rate
+            .filter(|r| !r.is_empty())
+            .with_context(|| format!("No funding rate data for {}", symbol))?;
+
+        let rate = Decimal::from_str_exact(&funding_rate)
+            .or_else(|_| Decimal::from_str(&funding_rate))
             .context("Failed to parse funding rate")?;
+        let interval_hours = ticker
+            .funding_interval_hour
+            .and_then(|h| h.parse().ok())
+            .unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS);
+        let next_funding_time = ticker
+            .next_funding_time
+            .and_then(|t| t.parse::<i64>().ok())
+            .filter(|&t| t > 0)
+            .and_then(DateTime::from_timestamp_millis);
 
         Ok(FundingRate {
             symbol: symbol.to_string(),
             venue: Venue::Bybit,
             rate,
             predicted_rate: None,
+            interval_hours,
+            next_funding_time,
             timestamp: Utc::now(),
         })
     }
 
-    pub async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
+    async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        let bybit_symbol = self.native_symbol(symbol);
+        let instrument = self
+            .instruments_info(Some(&bybit_symbol))
+            .await?
+            .into_iter()
+            .find(|i| i.symbol == bybit_symbol)
+            .with_context(|| format!("Unknown Bybit symbol {}", bybit_symbol))?;
+        Ok(ContractSpec {
+            symbol: symbol.to_string(),
+            ..instrument.instrument()?.spec
+        })
+    }
+
+    async fn get_instruments(&self) -> Result<Vec<Instrument>> {
+        Ok(self
+            .instruments_info(None)
+            .await?
+            .iter()
+            .filter(|instrument| instrument.is_usdt_perpetual())
+            .filter_map(|instrument| match instrument.instrument() {
+                Ok(instrument) => Some(instrument),
+                Err(e) => {
+                    warn!("Skipping Bybit symbol {}: {:#}", instrument.symbol, e);
+                    None
+                }
+            })
+            .collect())
+    }
+
+    async fn get_funding_payments(
+        &self,
+        symbol: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FundingPayment>> {
+        let bybit_symbol = self.native_symbol(symbol);
+        let now = Utc::now();
+        let mut payments = Vec::new();
+
+        // The transaction log only serves seven days per query
+        let mut window_start = since;
+        while window_start < now {
+            let window_end = (window_start + TRANSACTION_LOG_WINDOW).min(now);
+            let mut cursor = String::new();
+            loop {
+                let mut params = vec![
+                    ("accountType", "UNIFIED".to_string()),
+                    ("category", "linear".to_string()),
+                    ("type", "SETTLEMENT".to_string()),
+                    ("startTime", window_start.timestamp_millis().to_string()),
+                    ("endTime", window_end.timestamp_millis().to_string()),
+                    ("limit", "50".to_string()),
+                ];
+                if !cursor.is_empty() {
+                    params.push(("cursor", cursor.clone()));
+                }
+                let page: BybitTransactionLog = self
+                    .signed_get("/v5/account/transaction-log", &params)
+                    .await?;
+
+                for entry in page.list.into_iter().filter(|e| e.symbol == bybit_symbol) {
+                    // Settlement rows carry the funding fee in `change`, signed from our side
+                    payments.push(FundingPayment {
+                        id: entry.id,
+                        symbol: symbol.to_string(),
+                        venue: Venue::Bybit,
+                        amount: parse_decimal(&entry.change, "funding change")?,
+                        asset: entry.currency,
+                        timestamp: parse_timestamp(&entry.transaction_time),
+                    });
+                }
+
+                if page.next_page_cursor.is_empty() {
+                    break;
+                }
+                cursor = page.next_page_cursor;
+            }
+            window_start = window_end;
+        }
+
+        payments.sort_by_key(|p| p.timestamp);
+        Ok(payments)
+    }
+
+    async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
         let url = format!("{}/v5/market/tickers", self.base_url);
         let resp = self
             .client
@@ -74,6 +453,7 @@ impl BybitConnector {
         let data: BybitTickersResponse = resp.json().await?;
         
         let mut tickers = data.result.list;
+        tickers.retain(|t| t.symbol.ends_with("USDT"));
         tickers.sort_by(|a, b| {
             b.turnover24h
                 .parse::<f64>()
@@ -85,25 +465,494 @@ impl BybitConnector {
         Ok(tickers
             .into_iter()
             .take(limit)
-            .map(|t| t.symbol.replace("USDT", "-USDT"))
+            .map(|t| self.canonical_symbol(&t.symbol))
             .collect())
     }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+    ) -> Result<Trade> {
+        self.place_order_with_options(symbol, side, size, price, OrderOptions::default())
+            .await
+    }
+
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        let mut body = serde_json::json!({
+            "category": "linear",
+            "symbol": self.native_symbol(symbol),
+            "side": side_param(&side),
+            "qty": size.normalize().to_string(),
+        });
+        // A zero price means "take liquidity now"
+        if price.is_zero() {
+            if options.time_in_force == TimeInForce::PostOnly {
+                anyhow::bail!("Bybit market orders cannot be post-only");
+            }
+            body["orderType"] = "Market".into();
+        } else {
+            body["orderType"] = "Limit".into();
+            body["timeInForce"] = match options.time_in_force {
+                TimeInForce::GoodTilCancelled => "GTC",
+                TimeInForce::ImmediateOrCancel => "IOC",
+                TimeInForce::PostOnly => "PostOnly",
+            }
+            .into();
+            body["price"] = price.normalize().to_string().into();
+        }
+        if options.reduce_only {
+            body["reduceOnly"] = true.into();
+        }
+
+        let created: BybitOrderCreated = self.signed_post("/v5/order/create", body).await?;
+
+        // The create endpoint only acknowledges the order, fetch its actual state
+        match self.get_order(symbol, &created.order_id).await {
+            Ok(trade) => Ok(trade),
+            Err(e) => {
+                warn!("Failed to query Bybit order {} after placement: {}", created.order_id, e);
+                Ok(Trade {
+                    symbol: symbol.to_string(),
+                    venue: Venue::Bybit,
+                    side,
+                    size,
+                    price,
+                    timestamp: Utc::now(),
+                    status: TradeStatus::Pending,
+                    order_id: Some(created.order_id),
+                })
+            }
+        }
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()> {
+        let body = serde_json::json!({
+            "category": "linear",
+            "symbol": self.native_symbol(symbol),
+            "orderId": order_id,
+        });
+        let _: BybitOrderCreated = self.signed_post("/v5/order/cancel", body).await?;
+        Ok(())
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<Trade> {
+        let params = [
+            ("category", "linear".to_string()),
+            ("symbol", self.native_symbol(symbol)),
+            ("orderId", order_id.to_string()),
+        ];
+        let data: BybitList<BybitOrder> = self.signed_get("/v5/order/realtime", &params).await?;
+        let order = data
+            .list
+            .into_iter()
+            .next()
+            .ok_or_else(|| BybitError::OrderNotFound(order_id.to_string()))?;
+        order.into_trade(symbol)
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        let params = [
+            ("category", "linear".to_string()),
+            ("settleCoin", "USDT".to_string()),
+        ];
+        let data: BybitList<BybitPosition> = self.signed_get("/v5/position/list", &params).await?;
+
+        let mut positions = Vec::new();
+        for pos in data.list {
+            let size = parse_decimal(&pos.size, "position size")?;
+            if size.is_zero() {
+                continue;
+            }
+            positions.push(Position {
+                symbol: self.canonical_symbol(&pos.symbol),
+                venue: Venue::Bybit,
+                side: if pos.side == "Sell" {
+                    PositionSide::Short
+                } else {
+                    PositionSide::Long
+                },
+                size,
+                entry_price: parse_decimal(&pos.avg_price, "entry price")?,
+                leverage: parse_decimal(&pos.leverage, "leverage")?,
+                timestamp: parse_timestamp(&pos.updated_time),
+            });
+        }
+        Ok(positions)
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let params = [("accountType", "UNIFIED".to_string())];
+        let data: BybitList<BybitWallet> = self
+            .signed_get("/v5/account/wallet-balance", &params)
+            .await?;
+
+        let mut balances = Vec::new();
+        for coin in data.list.into_iter().flat_map(|w| w.coin) {
+            let total = parse_decimal(&coin.wallet_balance, "wallet balance")?;
+            // Unified accounts report an empty string when nothing is withdrawable
+            let free = if coin.available_to_withdraw.is_empty() {
+                Decimal::ZERO
+            } else {
+                parse_decimal(&coin.available_to_withdraw, "available balance")?
+            };
+            balances.push(Balance {
+                venue: Venue::Bybit,
+                asset: coin.coin,
+                free,
+                total,
+            });
+        }
+        Ok(balances)
+    }
+
+    async fn get_account_margin(&self) -> Result<AccountMargin> {
+        let params = [("accountType", "UNIFIED".to_string())];
+        let data: BybitList<BybitWallet> = self
+            .signed_get("/v5/account/wallet-balance", &params)
+            .await?;
+        let wallet = data
+            .list
+            .into_iter()
+            .next()
+            .context("Bybit returned no unified wallet")?;
+
+        let params = [
+            ("category", "linear".to_string()),
+            ("settleCoin", "USDT".to_string()),
+        ];
+        let positions: BybitList<BybitPosition> =
+            self.signed_get("/v5/position/list", &params).await?;
+        let position_notional = positions
+            .list
+            .iter()
+            .filter(|p| !p.position_value.is_empty())
+            .map(|p| parse_decimal(&p.position_value, "position value"))
+            .sum::<Result<Decimal>>()?;
+
+        Ok(AccountMargin {
+            venue: Venue::Bybit,
+            equity: parse_decimal(&wallet.total_equity, "total equity")?,
+            initial_margin: parse_decimal(&wallet.total_initial_margin, "initial margin")?,
+            maintenance_margin: parse_decimal(&wallet.total_maintenance_margin, "maintenance margin")?,
+            position_notional,
+            timestamp: Utc::now(),
+        })
+    }
+
+    fn order_not_placed(&self, error: &anyhow::Error) -> bool {
+        let rejected = |cause: &(dyn std::error::Error + 'static)| {
+            cause.downcast_ref::<BybitError>().is_some_and(BybitError::is_rejection)
+        };
+        request_not_sent(error) || error.chain().any(rejected)
+    }
+}
+
+/// USDT perpetual for a symbol missing from the instrument registry; accepts
+/// both `BTC` and the older `BTC-USDT` form.
+pub(crate) fn native_symbol(symbol: &str) -> String {
+    let symbol = symbol.replace('-', "");
+    if symbol.ends_with("USDT") {
+        symbol
+    } else {
+        format!("{}USDT", symbol)
+    }
+}
+
+fn canonical_symbol(symbol: &str) -> String {
+    symbol.strip_suffix("USDT").unwrap_or(symbol).to_string()
+}
+
+fn side_param(side: &PositionSide) -> &'static str {
+    match side {
+        PositionSide::Long => "Buy",
+        PositionSide::Short => "Sell",
+    }
+}
+
+fn parse_decimal(value: &str, field: &str) -> Result<Decimal> {
+    Decimal::from_str(value).with_context(|| format!("Failed to parse Bybit {} {:?}", field, value))
+}
+
+fn parse_timestamp(millis: &str) -> DateTime<Utc> {
+    millis
+        .parse::<i64>()
+        .ok()
+        .and_then(DateTime::from_timestamp_millis)
+        .unwrap_or_else(Utc::now)
+}
+
+fn parse_levels(levels: &[[String; 2]], depth: usize) -> Result<Vec<BookLevel>> {
+    levels
+        .iter()
+        .take(depth)
+        .map(|[price, size]| {
+            Ok(BookLevel {
+                price: parse_decimal(price, "level price")?,
+                size: parse_decimal(size, "level size")?,
+            })
+        })
+        .collect()
 }
 
 #[derive(Debug, Deserialize)]
-struct BybitFundingResponse {
-    result: BybitFundingResult,
+struct BybitOrderBook {
+    #[serde(rename = "b")]
+    bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    asks: Vec<[String; 2]>,
+    ts: i64,
 }
 
 #[derive(Debug, Deserialize)]
-struct BybitFundingResult {
-    list: Vec<BybitFundingItem>,
+struct BybitResponse<T> {
+    #[serde(rename = "retCode")]
+    ret_code: i64,
+    #[serde(rename = "retMsg")]
+    ret_msg: String,
+    result: T,
 }
 
 #[derive(Debug, Deserialize)]
-struct BybitFundingItem {
-    #[serde(rename = "fundingRate")]
-    fundingRate: String,
+struct BybitList<T> {
+    list: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitInstrumentsResponse {
+    result: BybitInstrumentPage,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitInstrumentPage {
+    list: Vec<BybitInstrument>,
+    #[serde(rename = "nextPageCursor", default)]
+    next_page_cursor: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitInstrument {
+    symbol: String,
+    base_coin: Option<String>,
+    quote_coin: Option<String>,
+    contract_type: Option<String>,
+    status: Option<String>,
+    price_filter: BybitPriceFilter,
+    lot_size_filter: BybitLotSizeFilter,
+}
+
+impl BybitInstrument {
+    /// Fields missing from the response don't exclude a symbol.
+    fn is_usdt_perpetual(&self) -> bool {
+        self.quote_coin.as_deref().is_none_or(|q| q == "USDT")
+            && self.contract_type.as_deref().is_none_or(|t| t == "LinearPerpetual")
+            && self.status.as_deref().is_none_or(|s| s == "Trading")
+    }
+
+    fn instrument(&self) -> Result<Instrument> {
+        let base = match &self.base_coin {
+            Some(base) => base.clone(),
+            None => canonical_symbol(&self.symbol),
+        };
+        let (asset, multiplier) = canonical_asset(&base);
+        let lot = &self.lot_size_filter;
+        Ok(Instrument {
+            asset: asset.clone(),
+            venue: Venue::Bybit,
+            native_symbol: self.symbol.clone(),
+            quote_asset: self.quote_coin.clone().unwrap_or_else(|| "USDT".to_string()),
+            spec: ContractSpec {
+                symbol: asset,
+                venue: Venue::Bybit,
+                tick_size: parse_decimal(&self.price_filter.tick_size, "tick size")?,
+                lot_size: parse_decimal(&lot.qty_step, "qty step")?,
+                min_size: parse_decimal(&lot.min_order_qty, "min order qty")?,
+                min_notional: match lot.min_notional_value.as_deref() {
+                    Some(value) if !value.is_empty() => parse_decimal(value, "min notional")?,
+                    _ => Decimal::ZERO,
+                },
+                contract_multiplier: multiplier,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitPriceFilter {
+    tick_size: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitLotSizeFilter {
+    qty_step: String,
+    min_order_qty: String,
+    min_notional_value: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitTransactionLog {
+    list: Vec<BybitTransaction>,
+    #[serde(rename = "nextPageCursor", default)]
+    next_page_cursor: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitTransaction {
+    id: String,
+    symbol: String,
+    currency: String,
+    change: String,
+    #[serde(rename = "transactionTime")]
+    transaction_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitServerTime {
+    #[serde(rename = "timeNano")]
+    time_nano: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitOrderCreated {
+    #[serde(rename = "orderId")]
+    order_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitOrder {
+    #[serde(rename = "orderId")]
+    order_id: String,
+    side: String,
+    #[serde(rename = "orderStatus")]
+    order_status: String,
+    price: String,
+    #[serde(rename = "avgPrice", default)]
+    avg_price: String,
+    qty: String,
+    #[serde(rename = "cumExecQty", default)]
+    cum_exec_qty: String,
+    #[serde(rename = "updatedTime", default)]
+    updated_time: String,
+}
+
+impl BybitOrder {
+    fn into_trade(self, symbol: &str) -> Result<Trade> {
+        let status = match self.order_status.as_str() {
+            "New" | "Created" | "Untriggered" | "Triggered" => TradeStatus::Pending,
+            "PartiallyFilled" => TradeStatus::PartiallyFilled,
+            "Filled" => TradeStatus::Filled,
+            "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => TradeStatus::Cancelled,
+            "Rejected" => TradeStatus::Rejected,
+            other => anyhow::bail!("Unknown Bybit order status {}", other),
+        };
+        let executed = if self.cum_exec_qty.is_empty() {
+            Decimal::ZERO
+        } else {
+            parse_decimal(&self.cum_exec_qty, "executed quantity")?
+        };
+        // An IOC order cancelled after a partial fill still holds that fill
+        let status = match status {
+            TradeStatus::Cancelled | TradeStatus::Rejected if !executed.is_zero() => {
+                TradeStatus::PartiallyFilled
+            }
+            status => status,
+        };
+        let size = if executed.is_zero() {
+            parse_decimal(&self.qty, "order quantity")?
+        } else {
+            executed
+        };
+        let avg_price = if self.avg_price.is_empty() {
+            Decimal::ZERO
+        } else {
+            parse_decimal(&self.avg_price, "average price")?
+        };
+        let price = if avg_price.is_zero() {
+            parse_decimal(&self.price, "price")?
+        } else {
+            avg_price
+        };
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: Venue::Bybit,
+            side: if self.side == "Sell" {
+                PositionSide::Short
+            } else {
+                PositionSide::Long
+            },
+            size,
+            price,
+            timestamp: parse_timestamp(&self.updated_time),
+            status,
+            order_id: Some(self.order_id),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitPosition {
+    symbol: String,
+    side: String,
+    size: String,
+    #[serde(rename = "avgPrice")]
+    avg_price: String,
+    leverage: String,
+    #[serde(rename = "positionValue", default)]
+    position_value: String,
+    #[serde(rename = "updatedTime", default)]
+    updated_time: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BybitWallet {
+    #[serde(default)]
+    total_equity: String,
+    #[serde(default)]
+    total_initial_margin: String,
+    #[serde(default)]
+    total_maintenance_margin: String,
+    coin: Vec<BybitCoinBalance>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitCoinBalance {
+    coin: String,
+    #[serde(rename = "walletBalance")]
+    wallet_balance: String,
+    #[serde(rename = "availableToWithdraw", default)]
+    available_to_withdraw: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitBookTickerResponse {
+    result: BybitBookTickerResult,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitBookTickerResult {
+    list: Vec<BybitBookTicker>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BybitBookTicker {
+    #[serde(rename = "bid1Price")]
+    bid1_price: String,
+    #[serde(rename = "ask1Price")]
+    ask1_price: String,
 }
 
 #[derive(Debug, Deserialize)]
@@ -121,4 +970,10 @@ struct BybitTicker {
     symbol: String,
     #[serde(rename = "turnover24h")]
     turnover24h: String,
+    #[serde(rename = "fundingRate")]
+    funding_rate: Option<String>,
+    #[serde(rename = "nextFundingTime")]
+    next_funding_time: Option<String>,
+    #[serde(rename = "fundingIntervalHour")]
+    funding_interval_hour: Option<String>,
 }
@@ -1,38 +1,865 @@
-use arb_core::types::{MarketData, Venue};
-use anyhow::Result;
+use crate::signing::{keccak256, EcdsaSignature, EvmWallet};
+use crate::venue::{unsupported, VenueConnector};
+use arb_core::config::{DexKind, HyperEvmConfig, TokenConfig};
+use arb_core::types::{
+    Balance, MarketData, OrderOptions, Position, PositionSide, TimeInForce, Trade, TradeStatus,
+    Venue,
+};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
 use chrono::Utc;
+use parking_lot::RwLock;
+use reqwest::Client;
+use rust_decimal::prelude::*;
 use rust_decimal::Decimal;
-use tracing::warn;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::info;
+
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Executable quote for swapping a fixed input amount.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapQuote {
+    pub token_in: String,
+    pub token_out: String,
+    pub amount_in: Decimal,
+    pub amount_out: Decimal,
+    pub amount_in_raw: u128,
+    pub amount_out_raw: u128,
+}
+
+impl SwapQuote {
+    /// Output tokens received per input token.
+    pub fn price(&self) -> Decimal {
+        if self.amount_in.is_zero() {
+            Decimal::ZERO
+        } else {
+            self.amount_out / self.amount_in
+        }
+    }
+}
+
+/// Outcome of a mined swap transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapFill {
+    pub tx_hash: String,
+    pub amount_in: Decimal,
+    /// Output tokens transferred to the wallet, zero if the swap reverted.
+    pub amount_out: Decimal,
+    pub success: bool,
+}
 
 #[derive(Clone)]
 pub struct HyperEvmConnector {
+    client: Client,
     rpc_url: String,
     dex_router_address: Option<String>,
+    dex_kind: DexKind,
+    quoter_address: Option<String>,
+    fee_tier: u32,
+    quote_size: Decimal,
+    tokens: HashMap<String, TokenConfig>,
+    /// Token decimals keyed by lowercase address, seeded from the config.
+    decimals: Arc<RwLock<HashMap<String, u8>>>,
+    request_id: Arc<AtomicU64>,
+    wallet: Option<EvmWallet>,
+    chain_id: u64,
+    max_slippage_bps: u64,
+    swap_deadline: Duration,
+    receipt_timeout: Duration,
+    /// Next nonce to use, fetched lazily and refetched after a failed send.
+    /// Held across signing and broadcast so transactions go out in order.
+    nonce: Arc<Mutex<Option<u64>>>,
 }
 
 impl HyperEvmConnector {
-    pub fn new(rpc_url: String, dex_router_address: Option<String>) -> Result<Self> {
+    /// An empty `private_key` yields a quote-only connector.
+    pub fn new(config: HyperEvmConfig) -> Result<Self> {
+        let non_empty = |s: String| if s.is_empty() { None } else { Some(s) };
+        let wallet = if config.private_key.is_empty() {
+            None
+        } else {
+            Some(EvmWallet::from_hex(&config.private_key).context("Invalid HyperEVM private key")?)
+        };
+        if config.max_slippage_bps >= 10_000 {
+            anyhow::bail!("HyperEVM max_slippage_bps must be below 10000");
+        }
+        let quote_size = Decimal::from_f64(config.quote_size)
+            .filter(|size| size.is_sign_positive() && !size.is_zero())
+            .context("HyperEVM quote_size must be positive")?;
+
+        let mut tokens = HashMap::new();
+        let mut decimals = HashMap::new();
+        for token in config.tokens {
+            parse_address(&token.address)
+                .with_context(|| format!("Invalid address for HyperEVM token {}", token.symbol))?;
+            if let Some(d) = token.decimals {
+                decimals.insert(token.address.to_lowercase(), d);
+            }
+            tokens.insert(token.symbol.to_uppercase(), token);
+        }
+
         Ok(Self {
-            rpc_url,
-            dex_router_address,
+            client: Client::new(),
+            rpc_url: config.rpc_url,
+            dex_router_address: non_empty(config.dex_router_address),
+            dex_kind: config.dex_kind,
+            quoter_address: non_empty(config.quoter_address),
+            fee_tier: config.fee_tier,
+            quote_size,
+            tokens,
+            decimals: Arc::new(RwLock::new(decimals)),
+            request_id: Arc::new(AtomicU64::new(1)),
+            wallet,
+            chain_id: config.chain_id,
+            max_slippage_bps: config.max_slippage_bps,
+            swap_deadline: Duration::from_secs(config.swap_deadline_secs),
+            receipt_timeout: Duration::from_millis(config.receipt_timeout_ms),
+            nonce: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Base-token amount used for quotes, and the natural trade size.
+    pub fn quote_size(&self) -> Decimal {
+        self.quote_size
+    }
+
+    fn wallet(&self) -> Result<&EvmWallet> {
+        self.wallet
+            .as_ref()
+            .context("HyperEVM private key not configured")
+    }
+
+    fn router(&self) -> Result<&str> {
+        self.dex_router_address
+            .as_deref()
+            .context("HyperEVM DEX router address not configured")
+    }
+
+    async fn rpc<T: serde::de::DeserializeOwned>(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<T> {
+        let payload = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": self.request_id.fetch_add(1, Ordering::Relaxed),
+            "method": method,
+            "params": params,
+        });
+        let resp: RpcResponse<T> = self
+            .client
+            .post(&self.rpc_url)
+            .json(&payload)
+            .send()
+            .await
+            .with_context(|| format!("Failed to send {} to HyperEVM RPC", method))?
+            .json()
+            .await
+            .with_context(|| format!("Invalid {} response from HyperEVM RPC", method))?;
+
+        if let Some(error) = resp.error {
+            anyhow::bail!("HyperEVM RPC error {}: {}", error.code, error.message);
+        }
+        resp.result
+            .with_context(|| format!("HyperEVM RPC returned no result for {}", method))
+    }
+
+    async fn eth_call(&self, to: &str, data: &[u8]) -> Result<Vec<u8>> {
+        let result: String = self
+            .rpc(
+                "eth_call",
+                serde_json::json!([{ "to": to, "data": format!("0x{}", hex::encode(data)) }, "latest"]),
+            )
+            .await?;
+        hex::decode(result.trim_start_matches("0x")).context("Invalid eth_call result")
+    }
+
+    fn token(&self, symbol: &str) -> Result<&TokenConfig> {
+        self.tokens
+            .get(&symbol.to_uppercase())
+            .with_context(|| format!("Unknown HyperEVM token {}", symbol))
+    }
+
+    async fn token_decimals(&self, token: &TokenConfig) -> Result<u8> {
+        let key = token.address.to_lowercase();
+        if let Some(d) = self.decimals.read().get(&key) {
+            return Ok(*d);
+        }
+        let output = self.eth_call(&token.address, &selector("decimals()")).await?;
+        let d = u8::try_from(decode_uint(&output, 0)?)
+            .with_context(|| format!("Unexpected decimals for {}", token.symbol))?;
+        self.decimals.write().insert(key, d);
+        Ok(d)
+    }
+
+    /// Quotes swapping `amount_in` of `token_in` for `token_out` on the
+    /// configured DEX.
+    pub async fn quote(&self, token_in: &str, token_out: &str, amount_in: Decimal) -> Result<SwapQuote> {
+        let router = self.router()?;
+        let input = self.token(token_in)?;
+        let output = self.token(token_out)?;
+        let in_decimals = self.token_decimals(input).await?;
+        let out_decimals = self.token_decimals(output).await?;
+
+        let amount_in_raw = to_raw(amount_in, in_decimals)?;
+        if amount_in_raw == 0 {
+            anyhow::bail!("Quote amount {} {} rounds to zero", amount_in, token_in);
+        }
+        let token_in_address = parse_address(&input.address)?;
+        let token_out_address = parse_address(&output.address)?;
+
+        let amount_out_raw = match self.dex_kind {
+            DexKind::V2 => {
+                let data = encode_get_amounts_out(amount_in_raw, &[token_in_address, token_out_address]);
+                let result = self.eth_call(router, &data).await.context("getAmountsOut failed")?;
+                decode_get_amounts_out(&result)?
+            }
+            DexKind::V3 => {
+                let quoter = self
+                    .quoter_address
+                    .as_deref()
+                    .context("HyperEVM V3 quoter address not configured")?;
+                let data = encode_quote_exact_input_single(
+                    token_in_address,
+                    token_out_address,
+                    amount_in_raw,
+                    self.fee_tier,
+                );
+                let result = self
+                    .eth_call(quoter, &data)
+                    .await
+                    .context("quoteExactInputSingle failed")?;
+                decode_uint(&result, 0)?
+            }
+        };
+
+        Ok(SwapQuote {
+            token_in: input.symbol.clone(),
+            token_out: output.symbol.clone(),
+            amount_in: from_raw(amount_in_raw, in_decimals)?,
+            amount_out: from_raw(amount_out_raw, out_decimals)?,
+            amount_in_raw,
+            amount_out_raw,
         })
     }
 
+    /// Executable bid/ask for `quote_size` of the base token of a `BASE-QUOTE`
+    /// pair: the bid sells the base, the ask spends the bid proceeds buying it
+    /// back.
     pub async fn get_spot_price(&self, token_pair: &str) -> Result<MarketData> {
-        // In a real implementation, this would query the DEX router contract
-        // For now, we'll simulate by querying a simple price oracle or AMM
-        warn!("HyperEVM spot price fetching not fully implemented - simulating");
-        
-        // This would typically involve:
-        // 1. Parse token pair (e.g., "WETH/USDC")
-        // 2. Query router contract for quote
-        // 3. Return MarketData
-        
+        let (base, quote) = token_pair
+            .split_once(['-', '/'])
+            .with_context(|| format!("Invalid HyperEVM pair {}, expected BASE-QUOTE", token_pair))?;
+
+        let sell = self.quote(base, quote, self.quote_size).await?;
+        let bid = sell.price();
+        if sell.amount_out.is_zero() {
+            anyhow::bail!("No liquidity quoting {}", token_pair);
+        }
+        let buy = self.quote(quote, base, sell.amount_out).await?;
+        if buy.amount_out.is_zero() {
+            anyhow::bail!("No liquidity quoting {}", token_pair);
+        }
+        let ask = buy.amount_in / buy.amount_out;
+
         Ok(MarketData {
             symbol: token_pair.to_string(),
             venue: Venue::HyperEvm,
-            price: Decimal::from(2000u64), // Placeholder - would query actual DEX price
+            price: (bid + ask) / Decimal::TWO,
+            bid,
+            ask,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn quantity(&self, method: &str, params: serde_json::Value) -> Result<u128> {
+        let value: String = self.rpc(method, params).await?;
+        parse_quantity(&value)
+    }
+
+    /// Signs and broadcasts an EIP-1559 call to `to`, returning the hash.
+    async fn send_transaction(&self, to: &str, data: Vec<u8>) -> Result<String> {
+        let wallet = self.wallet()?;
+        let from = wallet.address_hex();
+        let call = serde_json::json!({ "from": from, "to": to, "data": format!("0x{}", hex::encode(&data)) });
+
+        let gas = self.quantity("eth_estimateGas", serde_json::json!([call])).await?;
+        let priority_fee = self.quantity("eth_maxPriorityFeePerGas", serde_json::json!([])).await?;
+        let block: BlockHeader = self
+            .rpc("eth_getBlockByNumber", serde_json::json!(["latest", false]))
+            .await?;
+        let base_fee = parse_quantity(&block.base_fee_per_gas)?;
+
+        let mut nonce = self.nonce.lock().await;
+        let next = match *nonce {
+            Some(n) => n,
+            None => {
+                let count = self
+                    .quantity("eth_getTransactionCount", serde_json::json!([from, "pending"]))
+                    .await?;
+                u64::try_from(count)?
+            }
+        };
+
+        let tx = Eip1559Transaction {
+            chain_id: self.chain_id,
+            nonce: next,
+            max_priority_fee_per_gas: priority_fee,
+            // Headroom for the base fee doubling before inclusion
+            max_fee_per_gas: base_fee * 2 + priority_fee,
+            gas_limit: u64::try_from(gas * 12 / 10)?,
+            to: parse_address(to)?,
+            value: 0,
+            data,
+        };
+        let raw = tx.encode_signed(&wallet.sign_hash(&tx.signing_hash()));
+
+        match self
+            .rpc::<String>("eth_sendRawTransaction", serde_json::json!([format!("0x{}", hex::encode(raw))]))
+            .await
+        {
+            Ok(hash) => {
+                *nonce = Some(next + 1);
+                Ok(hash)
+            }
+            Err(e) => {
+                // The node may or may not have accepted the nonce, resync on next send
+                *nonce = None;
+                Err(e)
+            }
+        }
+    }
+
+    async fn wait_for_receipt(&self, tx_hash: &str) -> Result<Receipt> {
+        let started = Instant::now();
+        loop {
+            let receipt: Option<Receipt> = self
+                .rpc("eth_getTransactionReceipt", serde_json::json!([tx_hash]))
+                .await?;
+            if let Some(receipt) = receipt {
+                return Ok(receipt);
+            }
+            if started.elapsed() > self.receipt_timeout {
+                anyhow::bail!("Timed out waiting for receipt of {}", tx_hash);
+            }
+            sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Approves the router for unlimited spending of `token` if the current
+    /// allowance does not cover `amount_raw`.
+    async fn ensure_allowance(&self, token: &TokenConfig, spender: &str, amount_raw: u128) -> Result<()> {
+        let owner = self.wallet()?.address();
+        let spender_address = parse_address(spender)?;
+
+        let mut data = selector("allowance(address,address)").to_vec();
+        data.extend_from_slice(&encode_address(owner));
+        data.extend_from_slice(&encode_address(spender_address));
+        let output = self.eth_call(&token.address, &data).await?;
+        // Allowances above 128 bits are effectively unlimited
+        let allowance = decode_uint_saturating(&output, 0)
+            .with_context(|| format!("Failed to read {} allowance", token.symbol))?;
+        if allowance >= amount_raw {
+            return Ok(());
+        }
+
+        info!("Approving HyperEVM router to spend {}", token.symbol);
+        let mut data = selector("approve(address,uint256)").to_vec();
+        data.extend_from_slice(&encode_address(spender_address));
+        data.extend_from_slice(&[0xff; 32]);
+        let tx_hash = self.send_transaction(&token.address, data).await?;
+        let receipt = self.wait_for_receipt(&tx_hash).await?;
+        if !receipt.succeeded() {
+            anyhow::bail!("Approval of {} reverted ({})", token.symbol, tx_hash);
+        }
+        Ok(())
+    }
+
+    /// Swaps exactly `amount_in` of `token_in` through the router. The
+    /// minimum output is the quote less `max_slippage_bps`, raised to
+    /// `min_amount_out` when given.
+    pub async fn swap(
+        &self,
+        token_in: &str,
+        token_out: &str,
+        amount_in: Decimal,
+        min_amount_out: Option<Decimal>,
+    ) -> Result<SwapFill> {
+        let recipient = self.wallet()?.address();
+        let router = self.router()?.to_string();
+        let quote = self.quote(token_in, token_out, amount_in).await?;
+        let input = self.token(token_in)?.clone();
+        let output = self.token(token_out)?.clone();
+        let out_decimals = self.token_decimals(&output).await?;
+
+        let mut amount_out_min = apply_slippage(quote.amount_out_raw, self.max_slippage_bps);
+        if let Some(limit) = min_amount_out {
+            amount_out_min = amount_out_min.max(to_raw(limit, out_decimals)?);
+        }
+        if amount_out_min > quote.amount_out_raw {
+            anyhow::bail!(
+                "Quoted {} {} is below the required minimum {}",
+                quote.amount_out,
+                output.symbol,
+                from_raw(amount_out_min, out_decimals)?
+            );
+        }
+
+        self.ensure_allowance(&input, &router, quote.amount_in_raw).await?;
+
+        let token_in_address = parse_address(&input.address)?;
+        let token_out_address = parse_address(&output.address)?;
+        let data = match self.dex_kind {
+            DexKind::V2 => {
+                let deadline = (Utc::now().timestamp() as u64 + self.swap_deadline.as_secs()) as u128;
+                encode_swap_exact_tokens_for_tokens(
+                    quote.amount_in_raw,
+                    amount_out_min,
+                    &[token_in_address, token_out_address],
+                    recipient,
+                    deadline,
+                )
+            }
+            DexKind::V3 => encode_exact_input_single(
+                token_in_address,
+                token_out_address,
+                self.fee_tier,
+                recipient,
+                quote.amount_in_raw,
+                amount_out_min,
+            ),
+        };
+
+        let tx_hash = self.send_transaction(&router, data).await?;
+        info!(
+            "Sent HyperEVM swap {} {} -> {} (min {}): {}",
+            quote.amount_in, input.symbol, output.symbol, amount_out_min, tx_hash
+        );
+        let receipt = self.wait_for_receipt(&tx_hash).await?;
+
+        let amount_out_raw = if receipt.succeeded() {
+            receipt.transferred(token_out_address, recipient)?
+        } else {
+            0
+        };
+        Ok(SwapFill {
+            tx_hash,
+            amount_in: quote.amount_in,
+            amount_out: from_raw(amount_out_raw, out_decimals)?,
+            success: receipt.succeeded(),
+        })
+    }
+}
+
+#[async_trait]
+impl VenueConnector for HyperEvmConnector {
+    fn venue(&self) -> Venue {
+        Venue::HyperEvm
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        self.get_spot_price(symbol).await
+    }
+
+    /// Swaps `size` of the pair's base token: a short sells it for the quote
+    /// token, a long spends `size * price` of the quote token on it (the
+    /// current ask when `price` is zero). A non-zero `price` also bounds the
+    /// minimum output.
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+    ) -> Result<Trade> {
+        let (base, quote) = symbol
+            .split_once(['-', '/'])
+            .with_context(|| format!("Invalid HyperEVM pair {}, expected BASE-QUOTE", symbol))?;
+        let limit = (!price.is_zero()).then_some(price);
+
+        let (fill, base_amount, quote_amount) = match side {
+            PositionSide::Short => {
+                let fill = self.swap(base, quote, size, limit.map(|p| size * p)).await?;
+                (fill.clone(), fill.amount_in, fill.amount_out)
+            }
+            PositionSide::Long => {
+                let price = match limit {
+                    Some(p) => p,
+                    None => self.get_spot_price(symbol).await?.ask,
+                };
+                let fill = self.swap(quote, base, size * price, limit.map(|_| size)).await?;
+                (fill.clone(), fill.amount_out, fill.amount_in)
+            }
+        };
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: Venue::HyperEvm,
+            side,
+            size: base_amount,
+            price: if base_amount.is_zero() {
+                Decimal::ZERO
+            } else {
+                quote_amount / base_amount
+            },
             timestamp: Utc::now(),
+            status: if fill.success {
+                TradeStatus::Filled
+            } else {
+                TradeStatus::Failed
+            },
+            order_id: Some(fill.tx_hash),
         })
     }
+
+    /// Swaps fill in full or revert, so every order already behaves as
+    /// immediate-or-cancel; reduce-only has no meaning for a spot balance.
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        if options.time_in_force == TimeInForce::PostOnly {
+            return Err(unsupported(Venue::HyperEvm, "post-only orders"));
+        }
+        self.place_order(symbol, side, size, price).await
+    }
+
+    async fn cancel_order(&self, _symbol: &str, _order_id: &str) -> Result<()> {
+        // Swaps are atomic, there is nothing resting to cancel
+        Err(unsupported(Venue::HyperEvm, "order cancellation"))
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        // Spot swaps settle immediately, there are no open positions to report
+        Ok(Vec::new())
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Err(unsupported(Venue::HyperEvm, "balance queries"))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<RpcError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RpcError {
+    code: i64,
+    message: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct BlockHeader {
+    #[serde(rename = "baseFeePerGas")]
+    base_fee_per_gas: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Receipt {
+    status: String,
+    #[serde(default)]
+    logs: Vec<Log>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Log {
+    address: String,
+    topics: Vec<String>,
+    data: String,
+}
+
+impl Receipt {
+    fn succeeded(&self) -> bool {
+        self.status == "0x1"
+    }
+
+    /// Sum of ERC-20 `Transfer`s of `token` to `recipient` in this receipt.
+    fn transferred(&self, token: [u8; 20], recipient: [u8; 20]) -> Result<u128> {
+        let transfer_topic = format!("0x{}", hex::encode(keccak256(b"Transfer(address,address,uint256)")));
+        let recipient_topic = format!("0x{}", hex::encode(encode_address(recipient)));
+        let mut total = 0u128;
+        for log in &self.logs {
+            if parse_address(&log.address)? != token
+                || log.topics.len() != 3
+                || !log.topics[0].eq_ignore_ascii_case(&transfer_topic)
+                || !log.topics[2].eq_ignore_ascii_case(&recipient_topic)
+            {
+                continue;
+            }
+            let data = hex::decode(log.data.trim_start_matches("0x")).context("Invalid log data")?;
+            total += decode_uint(&data, 0)?;
+        }
+        Ok(total)
+    }
+}
+
+/// Type-2 (EIP-1559) transaction with an empty access list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub max_priority_fee_per_gas: u128,
+    pub max_fee_per_gas: u128,
+    pub gas_limit: u64,
+    pub to: [u8; 20],
+    pub value: u128,
+    pub data: Vec<u8>,
+}
+
+impl Eip1559Transaction {
+    fn rlp_fields(&self) -> Vec<Vec<u8>> {
+        vec![
+            rlp_bytes(&trim_int(self.chain_id as u128)),
+            rlp_bytes(&trim_int(self.nonce as u128)),
+            rlp_bytes(&trim_int(self.max_priority_fee_per_gas)),
+            rlp_bytes(&trim_int(self.max_fee_per_gas)),
+            rlp_bytes(&trim_int(self.gas_limit as u128)),
+            rlp_bytes(&self.to),
+            rlp_bytes(&trim_int(self.value)),
+            rlp_bytes(&self.data),
+            rlp_list(&[]),
+        ]
+    }
+
+    /// `keccak(0x02 || rlp([chainId, nonce, ..., accessList]))`
+    pub fn signing_hash(&self) -> [u8; 32] {
+        let mut payload = vec![0x02];
+        payload.extend(rlp_list(&self.rlp_fields()));
+        keccak256(&payload)
+    }
+
+    /// Raw transaction bytes for `eth_sendRawTransaction`.
+    pub fn encode_signed(&self, signature: &EcdsaSignature) -> Vec<u8> {
+        let mut fields = self.rlp_fields();
+        fields.push(rlp_bytes(&trim_int(signature.y_parity() as u128)));
+        fields.push(rlp_bytes(trim_leading_zeros(&signature.r)));
+        fields.push(rlp_bytes(trim_leading_zeros(&signature.s)));
+        let mut raw = vec![0x02];
+        raw.extend(rlp_list(&fields));
+        raw
+    }
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+fn trim_int(value: u128) -> Vec<u8> {
+    trim_leading_zeros(&value.to_be_bytes()).to_vec()
+}
+
+fn rlp_length_prefix(len: usize, short_offset: u8) -> Vec<u8> {
+    if len <= 55 {
+        vec![short_offset + len as u8]
+    } else {
+        let len_bytes = trim_int(len as u128);
+        let mut prefix = vec![short_offset + 55 + len_bytes.len() as u8];
+        prefix.extend(len_bytes);
+        prefix
+    }
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut out = rlp_length_prefix(bytes.len(), 0x80);
+    out.extend_from_slice(bytes);
+    out
+}
+
+/// Wraps already-encoded items in a list header.
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload: Vec<u8> = items.concat();
+    let mut out = rlp_length_prefix(payload.len(), 0xc0);
+    out.extend(payload);
+    out
+}
+
+/// Parses a `0x`-prefixed JSON-RPC quantity.
+pub fn parse_quantity(value: &str) -> Result<u128> {
+    let digits = value.trim_start_matches("0x");
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u128::from_str_radix(digits, 16).with_context(|| format!("Invalid quantity {}", value))
+}
+
+/// `amount * (1 - bps / 10000)`, rounded down.
+pub fn apply_slippage(amount: u128, bps: u64) -> u128 {
+    let keep = 10_000u128.saturating_sub(bps as u128);
+    amount / 10_000 * keep + amount % 10_000 * keep / 10_000
+}
+
+/// First four bytes of the keccak hash of a function signature.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+pub fn parse_address(address: &str) -> Result<[u8; 20]> {
+    let bytes = hex::decode(address.trim_start_matches("0x"))
+        .with_context(|| format!("Invalid address {}", address))?;
+    bytes
+        .try_into()
+        .map_err(|_| anyhow::anyhow!("Address {} is not 20 bytes", address))
+}
+
+pub fn encode_uint(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+pub fn encode_address(address: [u8; 20]) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&address);
+    word
+}
+
+/// Reads the `index`-th 32-byte word as an unsigned integer.
+pub fn decode_uint(data: &[u8], index: usize) -> Result<u128> {
+    let word = abi_word(data, index)?;
+    if word[..16].iter().any(|b| *b != 0) {
+        anyhow::bail!("ABI value does not fit in 128 bits");
+    }
+    Ok(u128::from_be_bytes(word[16..].try_into()?))
+}
+
+/// Like [`decode_uint`], but values wider than 128 bits read as `u128::MAX`.
+pub fn decode_uint_saturating(data: &[u8], index: usize) -> Result<u128> {
+    if abi_word(data, index)?[..16].iter().any(|b| *b != 0) {
+        return Ok(u128::MAX);
+    }
+    decode_uint(data, index)
+}
+
+fn abi_word(data: &[u8], index: usize) -> Result<&[u8]> {
+    data.get(index * 32..(index + 1) * 32)
+        .with_context(|| format!("ABI output too short for word {}", index))
+}
+
+/// `getAmountsOut(uint256 amountIn, address[] path)`
+pub fn encode_get_amounts_out(amount_in: u128, path: &[[u8; 20]]) -> Vec<u8> {
+    let mut data = selector("getAmountsOut(uint256,address[])").to_vec();
+    data.extend_from_slice(&encode_uint(amount_in));
+    data.extend_from_slice(&encode_uint(64));
+    data.extend_from_slice(&encode_uint(path.len() as u128));
+    for address in path {
+        data.extend_from_slice(&encode_address(*address));
+    }
+    data
+}
+
+/// Last element of the `uint256[] amounts` returned by `getAmountsOut`.
+pub fn decode_get_amounts_out(data: &[u8]) -> Result<u128> {
+    let offset = usize::try_from(decode_uint(data, 0)?)?;
+    if offset % 32 != 0 {
+        anyhow::bail!("Misaligned getAmountsOut output");
+    }
+    let start = offset / 32;
+    let len = usize::try_from(decode_uint(data, start)?)?;
+    if len == 0 {
+        anyhow::bail!("getAmountsOut returned no amounts");
+    }
+    decode_uint(data, start + len)
+}
+
+/// QuoterV2 `quoteExactInputSingle((tokenIn, tokenOut, amountIn, fee,
+/// sqrtPriceLimitX96))` with no price limit. `amountOut` is the first word of
+/// the output.
+pub fn encode_quote_exact_input_single(
+    token_in: [u8; 20],
+    token_out: [u8; 20],
+    amount_in: u128,
+    fee: u32,
+) -> Vec<u8> {
+    let mut data = selector("quoteExactInputSingle((address,address,uint256,uint24,uint160))").to_vec();
+    data.extend_from_slice(&encode_address(token_in));
+    data.extend_from_slice(&encode_address(token_out));
+    data.extend_from_slice(&encode_uint(amount_in));
+    data.extend_from_slice(&encode_uint(fee as u128));
+    data.extend_from_slice(&encode_uint(0));
+    data
+}
+
+/// V2 `swapExactTokensForTokens(amountIn, amountOutMin, path, to, deadline)`
+pub fn encode_swap_exact_tokens_for_tokens(
+    amount_in: u128,
+    amount_out_min: u128,
+    path: &[[u8; 20]],
+    to: [u8; 20],
+    deadline: u128,
+) -> Vec<u8> {
+    let mut data = selector("swapExactTokensForTokens(uint256,uint256,address[],address,uint256)").to_vec();
+    data.extend_from_slice(&encode_uint(amount_in));
+    data.extend_from_slice(&encode_uint(amount_out_min));
+    data.extend_from_slice(&encode_uint(160));
+    data.extend_from_slice(&encode_address(to));
+    data.extend_from_slice(&encode_uint(deadline));
+    data.extend_from_slice(&encode_uint(path.len() as u128));
+    for address in path {
+        data.extend_from_slice(&encode_address(*address));
+    }
+    data
+}
+
+/// SwapRouter02 `exactInputSingle((tokenIn, tokenOut, fee, recipient,
+/// amountIn, amountOutMinimum, sqrtPriceLimitX96))` with no price limit.
+pub fn encode_exact_input_single(
+    token_in: [u8; 20],
+    token_out: [u8; 20],
+    fee: u32,
+    recipient: [u8; 20],
+    amount_in: u128,
+    amount_out_min: u128,
+) -> Vec<u8> {
+    let mut data =
+        selector("exactInputSingle((address,address,uint24,address,uint256,uint256,uint160))").to_vec();
+    data.extend_from_slice(&encode_address(token_in));
+    data.extend_from_slice(&encode_address(token_out));
+    data.extend_from_slice(&encode_uint(fee as u128));
+    data.extend_from_slice(&encode_address(recipient));
+    data.extend_from_slice(&encode_uint(amount_in));
+    data.extend_from_slice(&encode_uint(amount_out_min));
+    data.extend_from_slice(&encode_uint(0));
+    data
+}
+
+fn pow10(decimals: u8) -> Result<Decimal> {
+    10i128
+        .checked_pow(decimals as u32)
+        .and_then(|p| Decimal::try_from_i128_with_scale(p, 0).ok())
+        .with_context(|| format!("Unsupported token decimals {}", decimals))
+}
+
+/// Converts a token amount to its integer on-chain representation, truncating
+/// anything below the token's precision.
+pub fn to_raw(amount: Decimal, decimals: u8) -> Result<u128> {
+    (amount * pow10(decimals)?)
+        .trunc()
+        .to_u128()
+        .with_context(|| format!("Amount {} out of range", amount))
+}
+
+pub fn from_raw(raw: u128, decimals: u8) -> Result<Decimal> {
+    let raw = i128::try_from(raw)?;
+    Decimal::try_from_i128_with_scale(raw, decimals as u32)
+        .map(|d| d.normalize())
+        .with_context(|| format!("Raw amount {} out of range", raw))
 }
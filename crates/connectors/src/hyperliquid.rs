@@ -1,68 +1,227 @@
-use arb_core::types::{FundingRate, MarketData, Position, PositionSide, Trade, TradeStatus, Venue};
+use crate::signing::{keccak256, EcdsaSignature, EvmWallet};
+use crate::venue::VenueConnector;
+use arb_core::instruments::{canonical_asset, Instrument, InstrumentRegistry};
+use arb_core::types::{
+    AccountMargin, Balance, BookLevel, ContractSpec, FundingPayment, FundingRate, MarketData,
+    OrderBook, OrderOptions, Position, PositionSide, TimeInForce, Trade, TradeStatus, Venue,
+};
 use anyhow::{Context, Result};
 use rust_decimal::prelude::*;
 use async_trait::async_trait;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
 use reqwest::Client;
 use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use tracing::{error, info, warn};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use tracing::warn;
+
+/// Exchange-wide EIP-712 domain used for L1 action signing.
+const EIP712_DOMAIN_NAME: &str = "Exchange";
+const EIP712_DOMAIN_VERSION: &str = "1";
+const EIP712_CHAIN_ID: u64 = 1337;
+
+/// Price offset applied to the mid when a zero price requests a market order,
+/// which Hyperliquid only supports as an aggressive IOC limit.
+const MARKET_ORDER_SLIPPAGE_BPS: u64 = 500;
+
+/// Orders below this value in USDC are rejected.
+const MIN_ORDER_VALUE_USD: u64 = 10;
+
+/// `userFunding` returns at most this many rows per request.
+const USER_FUNDING_PAGE_SIZE: usize = 500;
+
+/// Hyperliquid pays funding every hour, on the hour.
+pub const FUNDING_INTERVAL_HOURS: u32 = 1;
+
+/// Next top of the hour after `now`, when Hyperliquid settles funding.
+pub fn next_funding_time(now: DateTime<Utc>) -> DateTime<Utc> {
+    let hour_start = now.timestamp() - now.timestamp().rem_euclid(3600);
+    DateTime::from_timestamp(hour_start + 3600, 0).unwrap_or(now)
+}
 
 #[derive(Clone)]
 pub struct HyperliquidConnector {
     client: Client,
     api_url: String,
-    private_key: String,
+    wallet: Option<EvmWallet>,
+    is_mainnet: bool,
+    assets: Arc<RwLock<HashMap<String, AssetInfo>>>,
+    last_nonce: Arc<AtomicU64>,
+    instruments: Arc<InstrumentRegistry>,
+}
+
+/// Perpetual asset metadata from the `meta` info endpoint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssetInfo {
+    pub index: u32,
+    pub sz_decimals: u32,
 }
 
 impl HyperliquidConnector {
-    pub fn new(api_url: String, private_key: String) -> Self {
-        Self {
+    /// An empty `private_key` yields a read-only connector.
+    pub fn new(api_url: String, private_key: String) -> Result<Self> {
+        let wallet = if private_key.is_empty() {
+            None
+        } else {
+            Some(EvmWallet::from_hex(&private_key).context("Invalid Hyperliquid private key")?)
+        };
+
+        Ok(Self {
             client: Client::new(),
+            is_mainnet: !api_url.contains("testnet"),
             api_url,
-            private_key,
-        }
+            wallet,
+            assets: Arc::new(RwLock::new(HashMap::new())),
+            last_nonce: Arc::new(AtomicU64::new(0)),
+            instruments: Arc::new(InstrumentRegistry::new()),
+        })
     }
 
-    pub async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
-        let url = format!("{}/info", self.api_url);
-        let payload = serde_json::json!({
-            "type": "l2Book",
-            "coin": symbol
-        });
+    /// Resolves canonical asset ids through `instruments` once it is loaded.
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    /// Coin name for a canonical asset id, e.g. `kPEPE` for `PEPE`.
+    fn coin(&self, symbol: &str) -> String {
+        self.instruments
+            .native_symbol(symbol, &Venue::Hyperliquid)
+            .unwrap_or_else(|| symbol.to_string())
+    }
+
+    fn canonical_symbol(&self, coin: &str) -> String {
+        self.instruments
+            .asset(&Venue::Hyperliquid, coin)
+            .unwrap_or_else(|| coin.to_string())
+    }
+
+    fn wallet(&self) -> Result<&EvmWallet> {
+        self.wallet
+            .as_ref()
+            .context("Hyperliquid private key not configured")
+    }
 
+    async fn info<T: DeserializeOwned>(&self, payload: serde_json::Value) -> Result<T> {
+        let url = format!("{}/info", self.api_url);
         let resp = self
             .client
             .post(&url)
             .json(&payload)
             .send()
             .await
-            .context("Failed to fetch Hyperliquid market data")?;
+            .with_context(|| format!("Failed to query Hyperliquid info {}", payload["type"]))?;
+        Ok(resp.json().await?)
+    }
 
-        let data: HyperliquidL2Book = resp.json().await?;
-        
-        let mid_price = if !data.levels.is_empty() {
-            let best_bid = data.levels[0].px.parse::<f64>()?;
-            let best_ask = data.levels[0].sz.parse::<f64>()?;
-            Decimal::try_from((best_bid + best_ask) / 2.0)
-                .unwrap_or(Decimal::ZERO)
-        } else {
-            Decimal::ZERO
-        };
+    /// Hyperliquid's own prediction for the coin's next funding, if it publishes one.
+    async fn predicted_funding(&self, coin: &str) -> Result<Option<Decimal>> {
+        let predictions: PredictedFundings =
+            self.info(serde_json::json!({ "type": "predictedFundings" })).await?;
+        let prediction = predictions
+            .into_iter()
+            .find(|(name, _)| name == coin)
+            .and_then(|(_, venues)| venues.into_iter().find(|(venue, _)| venue == "HlPerp"))
+            .and_then(|(_, prediction)| prediction);
+        prediction
+            .map(|p| {
+                Decimal::from_str(&p.funding_rate).context("Failed to parse predicted funding")
+            })
+            .transpose()
+    }
 
-        Ok(MarketData {
-            symbol: symbol.to_string(),
-            venue: Venue::Hyperliquid,
-            price: mid_price,
-            timestamp: Utc::now(),
-        })
+    /// Size-weighted price of the order's fills since it was placed, if any.
+    async fn average_fill_price(
+        &self,
+        user: &str,
+        oid: u64,
+        since: i64,
+    ) -> Result<Option<Decimal>> {
+        let payload =
+            serde_json::json!({ "type": "userFillsByTime", "user": user, "startTime": since });
+        let fills: Vec<UserFill> = self
+            .info(payload)
+            .await
+            .context("Failed to fetch Hyperliquid fills")?;
+        let mut size = Decimal::ZERO;
+        let mut notional = Decimal::ZERO;
+        for fill in fills.iter().filter(|f| f.oid == oid) {
+            let fill_size = Decimal::from_str(&fill.sz).context("Failed to parse fill size")?;
+            let fill_price = Decimal::from_str(&fill.px).context("Failed to parse fill price")?;
+            size += fill_size;
+            notional += fill_size * fill_price;
+        }
+        Ok((!size.is_zero()).then(|| notional / size))
     }
 
-    pub async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
-        let url = format!("{}/info", self.api_url);
+    /// Reloads the perpetual universe; asset indices are positions in `meta.universe`.
+    pub async fn refresh_meta(&self) -> Result<()> {
+        let meta: HyperliquidUniverse = self.info(serde_json::json!({ "type": "meta" })).await?;
+        let assets = meta
+            .universe
+            .into_iter()
+            .enumerate()
+            .map(|(index, asset)| {
+                (
+                    asset.name,
+                    AssetInfo {
+                        index: index as u32,
+                        sz_decimals: asset.sz_decimals,
+                    },
+                )
+            })
+            .collect();
+        *self.assets.write() = assets;
+        Ok(())
+    }
+
+    pub async fn asset_info(&self, coin: &str) -> Result<AssetInfo> {
+        if let Some(info) = self.assets.read().get(coin) {
+            return Ok(*info);
+        }
+        self.refresh_meta().await?;
+        self.assets
+            .read()
+            .get(coin)
+            .copied()
+            .with_context(|| format!("Unknown Hyperliquid asset {}", coin))
+    }
+
+    /// Millisecond timestamps, bumped when needed so nonces stay strictly increasing.
+    fn next_nonce(&self) -> u64 {
+        let now = Utc::now().timestamp_millis() as u64;
+        let mut last = self.last_nonce.load(Ordering::Relaxed);
+        loop {
+            let next = now.max(last + 1);
+            match self
+                .last_nonce
+                .compare_exchange(last, next, Ordering::SeqCst, Ordering::Relaxed)
+            {
+                Ok(_) => return next,
+                Err(current) => last = current,
+            }
+        }
+    }
+
+    async fn post_action<T: Serialize>(&self, action: &T) -> Result<serde_json::Value> {
+        let wallet = self.wallet()?;
+        let nonce = self.next_nonce();
+        let signature = sign_l1_action(wallet, action, None, nonce, self.is_mainnet)?;
+
+        let url = format!("{}/exchange", self.api_url);
         let payload = serde_json::json!({
-            "type": "metaAndAssetCtxs"
+            "action": action,
+            "nonce": nonce,
+            "signature": {
+                "r": format!("0x{}", hex::encode(signature.r)),
+                "s": format!("0x{}", hex::encode(signature.s)),
+                "v": signature.v,
+            },
+            "vaultAddress": null,
         });
 
         let resp = self
@@ -71,105 +230,744 @@ impl HyperliquidConnector {
             .json(&payload)
             .send()
             .await
+            .context("Failed to send Hyperliquid exchange request")?;
+
+        let data: ExchangeResponse = resp.json().await?;
+        if data.status != "ok" {
+            anyhow::bail!("Hyperliquid exchange error: {}", data.response);
+        }
+        Ok(data.response)
+    }
+}
+
+/// Hash of an L1 action: `keccak(msgpack(action) || nonce_be || vault_flag [|| vault])`.
+pub fn action_hash<T: Serialize>(action: &T, vault_address: Option<[u8; 20]>, nonce: u64) -> Result<[u8; 32]> {
+    let mut data = rmp_serde::to_vec_named(action).context("Failed to msgpack Hyperliquid action")?;
+    data.extend_from_slice(&nonce.to_be_bytes());
+    match vault_address {
+        Some(vault) => {
+            data.push(1);
+            data.extend_from_slice(&vault);
+        }
+        None => data.push(0),
+    }
+    Ok(keccak256(&data))
+}
+
+/// EIP-712 digest of the phantom `Agent(string source,bytes32 connectionId)` that
+/// Hyperliquid verifies for L1 actions.
+pub fn phantom_agent_hash(connection_id: &[u8; 32], is_mainnet: bool) -> [u8; 32] {
+    let source = if is_mainnet { "a" } else { "b" };
+
+    let mut domain = Vec::with_capacity(5 * 32);
+    domain.extend_from_slice(&keccak256(
+        b"EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)",
+    ));
+    domain.extend_from_slice(&keccak256(EIP712_DOMAIN_NAME.as_bytes()));
+    domain.extend_from_slice(&keccak256(EIP712_DOMAIN_VERSION.as_bytes()));
+    domain.extend_from_slice(&[0u8; 24]);
+    domain.extend_from_slice(&EIP712_CHAIN_ID.to_be_bytes());
+    domain.extend_from_slice(&[0u8; 32]);
+    let domain_separator = keccak256(&domain);
+
+    let mut agent = Vec::with_capacity(3 * 32);
+    agent.extend_from_slice(&keccak256(b"Agent(string source,bytes32 connectionId)"));
+    agent.extend_from_slice(&keccak256(source.as_bytes()));
+    agent.extend_from_slice(connection_id);
+    let struct_hash = keccak256(&agent);
+
+    let mut digest = Vec::with_capacity(2 + 2 * 32);
+    digest.extend_from_slice(&[0x19, 0x01]);
+    digest.extend_from_slice(&domain_separator);
+    digest.extend_from_slice(&struct_hash);
+    keccak256(&digest)
+}
+
+pub fn sign_l1_action<T: Serialize>(
+    wallet: &EvmWallet,
+    action: &T,
+    vault_address: Option<[u8; 20]>,
+    nonce: u64,
+    is_mainnet: bool,
+) -> Result<EcdsaSignature> {
+    let hash = action_hash(action, vault_address, nonce)?;
+    Ok(wallet.sign_hash(&phantom_agent_hash(&hash, is_mainnet)))
+}
+
+/// Formats a price or size the way Hyperliquid hashes it: no trailing zeros.
+pub fn to_wire(value: Decimal) -> String {
+    let normalized = value.normalize();
+    if normalized.is_zero() {
+        "0".to_string()
+    } else {
+        normalized.to_string()
+    }
+}
+
+/// Prices are limited to 5 significant figures and `6 - szDecimals` decimals.
+pub fn round_price(price: Decimal, sz_decimals: u32) -> Decimal {
+    price
+        .round_sf(5)
+        .unwrap_or(price)
+        .round_dp(6u32.saturating_sub(sz_decimals))
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderWire {
+    pub a: u32,
+    pub b: bool,
+    pub p: String,
+    pub s: String,
+    pub r: bool,
+    pub t: OrderTypeWire,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderTypeWire {
+    pub limit: LimitOrderWire,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LimitOrderWire {
+    pub tif: String,
+}
+
+impl OrderTypeWire {
+    pub fn limit(time_in_force: TimeInForce) -> Self {
+        let tif = match time_in_force {
+            TimeInForce::GoodTilCancelled => "Gtc",
+            TimeInForce::ImmediateOrCancel => "Ioc",
+            TimeInForce::PostOnly => "Alo",
+        };
+        Self {
+            limit: LimitOrderWire { tif: tif.to_string() },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OrderAction {
+    #[serde(rename = "type")]
+    pub action_type: String,
+    pub orders: Vec<OrderWire>,
+    pub grouping: String,
+}
+
+impl OrderAction {
+    pub fn new(orders: Vec<OrderWire>) -> Self {
+        Self {
+            action_type: "order".to_string(),
+            orders,
+            grouping: "na".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CancelAction {
+    #[serde(rename = "type")]
+    action_type: String,
+    cancels: Vec<CancelWire>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CancelWire {
+    a: u32,
+    o: u64,
+}
+
+#[async_trait]
+impl VenueConnector for HyperliquidConnector {
+    fn venue(&self) -> Venue {
+        Venue::Hyperliquid
+    }
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        self.get_order_book(symbol, 1)
+            .await?
+            .market_data()
+            .with_context(|| format!("Hyperliquid book for {} is one-sided", symbol))
+    }
+
+    async fn get_order_book(&self, symbol: &str, depth: usize) -> Result<OrderBook> {
+        let data: HyperliquidL2Book = self
+            .info(serde_json::json!({ "type": "l2Book", "coin": self.coin(symbol) }))
+            .await
+            .context("Failed to fetch Hyperliquid market data")?;
+
+        // `levels` is `[bids, asks]`, each sorted best first
+        let [bids, asks] = data.levels;
+        Ok(OrderBook {
+            symbol: symbol.to_string(),
+            venue: Venue::Hyperliquid,
+            bids: parse_levels(bids, depth)?,
+            asks: parse_levels(asks, depth)?,
+            timestamp: DateTime::from_timestamp_millis(data.time).unwrap_or_else(Utc::now),
+        })
+    }
+
+    async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
+        let (meta, ctxs): MetaAndAssetCtxs = self
+            .info(serde_json::json!({ "type": "metaAndAssetCtxs" }))
+            .await
             .context("Failed to fetch Hyperliquid funding rate")?;
 
-        let data: HyperliquidMeta = resp.json().await?;
-        
-        // Find the asset in the response
-        let asset_ctx = data
-            .asset_ctxs
+        // Contexts are positional, matching `meta.universe`
+        let coin = self.coin(symbol);
+        let asset_ctx = meta
+            .universe
             .iter()
-            .find(|a| a.name == symbol)
+            .zip(&ctxs)
+            .find(|(asset, _)| asset.name == coin)
+            .map(|(_, ctx)| ctx)
             .context(format!("Symbol {} not found", symbol))?;
 
         let rate = Decimal::from_str_exact(&asset_ctx.funding)
             .or_else(|_| Decimal::from_str(&asset_ctx.funding))
             .context("Failed to parse funding rate")?;
-        let predicted = asset_ctx.predicted_funding.and_then(|p| {
-            Decimal::from_str_exact(&p).or_else(|_| Decimal::from_str(&p)).ok()
-        });
 
+        // The prediction only feeds exit checks, so the current rate is still worth returning
+        let predicted_rate = match self.predicted_funding(&coin).await {
+            Ok(predicted) => predicted,
+            Err(e) => {
+                warn!("Failed to fetch Hyperliquid predicted funding for {}: {:#}", coin, e);
+                None
+            }
+        };
+
+        let now = Utc::now();
         Ok(FundingRate {
             symbol: symbol.to_string(),
             venue: Venue::Hyperliquid,
             rate,
-            predicted_rate: predicted,
-            timestamp: Utc::now(),
+            predicted_rate,
+            interval_hours: FUNDING_INTERVAL_HOURS,
+            next_funding_time: Some(next_funding_time(now)),
+            timestamp: now,
         })
     }
 
-    pub async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
-        let url = format!("{}/info", self.api_url);
-        let payload = serde_json::json!({
-            "type": "metaAndAssetCtxs"
-        });
+    async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        let coin = self.coin(symbol);
+        let asset = self.asset_info(&coin).await?;
+        Ok(ContractSpec {
+            symbol: symbol.to_string(),
+            ..contract_spec(&coin, asset.sz_decimals)
+        })
+    }
 
-        let resp = self
-            .client
-            .post(&url)
-            .json(&payload)
-            .send()
+    async fn get_instruments(&self) -> Result<Vec<Instrument>> {
+        let meta: HyperliquidUniverse = self.info(serde_json::json!({ "type": "meta" })).await?;
+        Ok(meta
+            .universe
+            .into_iter()
+            .filter(|asset| !asset.is_delisted)
+            .map(|asset| {
+                let spec = contract_spec(&asset.name, asset.sz_decimals);
+                Instrument {
+                    asset: spec.symbol.clone(),
+                    venue: Venue::Hyperliquid,
+                    native_symbol: asset.name,
+                    quote_asset: "USDC".to_string(),
+                    spec,
+                }
+            })
+            .collect())
+    }
+
+    async fn get_funding_payments(
+        &self,
+        symbol: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FundingPayment>> {
+        let user = self.wallet()?.address_hex();
+        let coin = self.coin(symbol);
+        let mut payments = Vec::new();
+        let mut start_time = since.timestamp_millis();
+
+        loop {
+            let page: Vec<UserFundingEntry> = self
+                .info(serde_json::json!({ "type": "userFunding", "user": user, "startTime": start_time }))
+                .await?;
+            let Some(last_time) = page.last().map(|entry| entry.time) else {
+                break;
+            };
+            let full_page = page.len() >= USER_FUNDING_PAGE_SIZE;
+
+            for entry in page.into_iter().filter(|e| e.delta.coin == coin) {
+                payments.push(FundingPayment {
+                    // Funding rows share a zero hash, so key on time and coin instead
+                    id: format!("{}-{}", entry.time, entry.delta.coin),
+                    symbol: symbol.to_string(),
+                    venue: Venue::Hyperliquid,
+                    amount: Decimal::from_str(&entry.delta.usdc).context("Failed to parse funding amount")?,
+                    asset: "USDC".to_string(),
+                    timestamp: DateTime::from_timestamp_millis(entry.time).unwrap_or_else(Utc::now),
+                });
+            }
+
+            if !full_page {
+                break;
+            }
+            start_time = last_time + 1;
+        }
+        Ok(payments)
+    }
+
+    async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
+        let (meta, ctxs): MetaAndAssetCtxs = self
+            .info(serde_json::json!({ "type": "metaAndAssetCtxs" }))
             .await
             .context("Failed to fetch Hyperliquid symbols")?;
 
-        let data: HyperliquidMeta = resp.json().await?;
-        
-        let mut symbols: Vec<(String, f64)> = data
-            .asset_ctxs
-            .iter()
-            .map(|a| (a.name.clone(), a.volume_24h.unwrap_or(0.0)))
+        let mut symbols: Vec<(String, f64)> = meta
+            .universe
+            .into_iter()
+            .zip(&ctxs)
+            .map(|(asset, ctx)| {
+                let volume = ctx
+                    .day_ntl_vlm
+                    .as_deref()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(0.0);
+                (asset.name, volume)
+            })
             .collect();
         
         symbols.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap());
         
-        Ok(symbols.into_iter().take(limit).map(|(s, _)| s).collect())
+        Ok(symbols
+            .into_iter()
+            .take(limit)
+            .map(|(coin, _)| self.canonical_symbol(&coin))
+            .collect())
     }
 
-    pub async fn place_order(
+    async fn place_order(
         &self,
         symbol: &str,
         side: PositionSide,
         size: Decimal,
         price: Decimal,
     ) -> Result<Trade> {
-        // In a real implementation, this would sign and submit orders via Hyperliquid API
-        // For now, we'll simulate the order placement
-        warn!("Hyperliquid order placement not fully implemented - simulating");
-        
-        Ok(Trade {
+        let options = if price.is_zero() {
+            OrderOptions {
+                time_in_force: TimeInForce::ImmediateOrCancel,
+                reduce_only: false,
+            }
+        } else {
+            OrderOptions::default()
+        };
+        self.place_order_with_options(symbol, side, size, price, options)
+            .await
+    }
+
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        self.wallet()?;
+        let asset = self.asset_info(&self.coin(symbol)).await?;
+        let is_buy = side == PositionSide::Long;
+
+        // A zero price means "take liquidity now": cross the mid by the slippage allowance
+        let limit_price = if price.is_zero() {
+            let mid = self.get_market_data(symbol).await?.price;
+            let slippage = Decimal::new(MARKET_ORDER_SLIPPAGE_BPS as i64, 4);
+            if is_buy {
+                mid * (Decimal::ONE + slippage)
+            } else {
+                mid * (Decimal::ONE - slippage)
+            }
+        } else {
+            price
+        };
+        let limit_price = round_price(limit_price, asset.sz_decimals);
+        let size = size.round_dp_with_strategy(asset.sz_decimals, RoundingStrategy::ToZero);
+        if size.is_zero() {
+            anyhow::bail!("Order size for {} rounds to zero at {} decimals", symbol, asset.sz_decimals);
+        }
+
+        let action = OrderAction::new(vec![OrderWire {
+            a: asset.index,
+            b: is_buy,
+            p: to_wire(limit_price),
+            s: to_wire(size),
+            r: options.reduce_only,
+            t: OrderTypeWire::limit(options.time_in_force),
+        }]);
+
+        let response = self.post_action(&action).await?;
+        let statuses: OrderResponseData = serde_json::from_value(response["data"].clone())
+            .context("Failed to parse Hyperliquid order statuses")?;
+        let status = statuses
+            .statuses
+            .into_iter()
+            .next()
+            .context("Hyperliquid returned no order status")?;
+
+        let mut trade = Trade {
             symbol: symbol.to_string(),
             venue: Venue::Hyperliquid,
             side,
             size,
-            price,
+            price: limit_price,
             timestamp: Utc::now(),
             status: TradeStatus::Pending,
+            order_id: None,
+        };
+        match status {
+            OrderStatusWire::Resting { oid } => {
+                trade.order_id = Some(oid.to_string());
+            }
+            OrderStatusWire::Filled { total_sz, avg_px, oid } => {
+                let filled = Decimal::from_str(&total_sz).context("Failed to parse filled size")?;
+                trade.status = if filled < size {
+                    TradeStatus::PartiallyFilled
+                } else {
+                    TradeStatus::Filled
+                };
+                trade.size = filled;
+                trade.price = Decimal::from_str(&avg_px).context("Failed to parse fill price")?;
+                trade.order_id = Some(oid.to_string());
+            }
+            OrderStatusWire::Error(message) => {
+                warn!("Hyperliquid rejected {} order: {}", symbol, message);
+                trade.status = TradeStatus::Rejected;
+            }
+        }
+        Ok(trade)
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()> {
+        let asset = self.asset_info(&self.coin(symbol)).await?;
+        let action = CancelAction {
+            action_type: "cancel".to_string(),
+            cancels: vec![CancelWire {
+                a: asset.index,
+                o: order_id.parse().context("Hyperliquid order ids are numeric")?,
+            }],
+        };
+
+        let response = self.post_action(&action).await?;
+        match response["data"]["statuses"].get(0) {
+            Some(serde_json::Value::String(s)) if s == "success" => Ok(()),
+            Some(other) => anyhow::bail!("Hyperliquid cancel of {} failed: {}", order_id, other),
+            None => anyhow::bail!("Hyperliquid returned no cancel status"),
+        }
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<Trade> {
+        let user = self.wallet()?.address_hex();
+        let oid: u64 = order_id.parse().context("Hyperliquid order ids are numeric")?;
+        let data: OrderStatusResponse = self
+            .info(serde_json::json!({ "type": "orderStatus", "user": user, "oid": oid }))
+            .await?;
+        let entry = data
+            .order
+            .with_context(|| format!("Hyperliquid order {} not found ({})", order_id, data.status))?;
+
+        let order = entry.order;
+        let orig_size = Decimal::from_str(&order.orig_sz).context("Failed to parse order size")?;
+        let remaining = Decimal::from_str(&order.sz).context("Failed to parse remaining size")?;
+        let filled = orig_size - remaining;
+        let status = match entry.status.as_str() {
+            "open" if filled.is_zero() => TradeStatus::Pending,
+            "open" => TradeStatus::PartiallyFilled,
+            "filled" => TradeStatus::Filled,
+            "rejected" => TradeStatus::Rejected,
+            // Cancels come in several flavours, e.g. `marginCanceled`
+            _ if filled.is_zero() => TradeStatus::Cancelled,
+            _ => TradeStatus::PartiallyFilled,
+        };
+
+        // The order only carries its limit; what filled is priced by its fills
+        let limit_px = Decimal::from_str(&order.limit_px).context("Failed to parse order price")?;
+        let price = if filled.is_zero() {
+            limit_px
+        } else {
+            match self.average_fill_price(&user, order.oid, order.timestamp).await? {
+                Some(price) => price,
+                None => {
+                    warn!("No fills found yet for Hyperliquid order {}, using its limit", oid);
+                    limit_px
+                }
+            }
+        };
+
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: Venue::Hyperliquid,
+            side: if order.side == "B" {
+                PositionSide::Long
+            } else {
+                PositionSide::Short
+            },
+            size: if status == TradeStatus::Filled || filled.is_zero() {
+                orig_size
+            } else {
+                filled
+            },
+            price,
+            timestamp: DateTime::from_timestamp_millis(order.timestamp).unwrap_or_else(Utc::now),
+            status,
+            order_id: Some(order.oid.to_string()),
+        })
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        let state = self.clearinghouse_state().await?;
+
+        let mut positions = Vec::new();
+        for entry in state.asset_positions {
+            let pos = entry.position;
+            let signed_size = Decimal::from_str(&pos.szi).context("Failed to parse position size")?;
+            if signed_size.is_zero() {
+                continue;
+            }
+            positions.push(Position {
+                symbol: self.canonical_symbol(&pos.coin),
+                venue: Venue::Hyperliquid,
+                side: if signed_size.is_sign_negative() {
+                    PositionSide::Short
+                } else {
+                    PositionSide::Long
+                },
+                size: signed_size.abs(),
+                entry_price: pos
+                    .entry_px
+                    .as_deref()
+                    .map(Decimal::from_str)
+                    .transpose()
+                    .context("Failed to parse entry price")?
+                    .unwrap_or(Decimal::ZERO),
+                leverage: Decimal::from(pos.leverage.value),
+                timestamp: Utc::now(),
+            });
+        }
+        Ok(positions)
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        let state = self.clearinghouse_state().await?;
+        Ok(vec![Balance {
+            venue: Venue::Hyperliquid,
+            asset: "USDC".to_string(),
+            free: Decimal::from_str(&state.withdrawable).context("Failed to parse withdrawable")?,
+            total: Decimal::from_str(&state.margin_summary.account_value)
+                .context("Failed to parse account value")?,
+        }])
+    }
+
+    async fn get_account_margin(&self) -> Result<AccountMargin> {
+        let state = self.clearinghouse_state().await?;
+        let parse = |value: &str, field: &str| {
+            Decimal::from_str(value).with_context(|| format!("Failed to parse {}", field))
+        };
+        Ok(AccountMargin {
+            venue: Venue::Hyperliquid,
+            equity: parse(&state.margin_summary.account_value, "account value")?,
+            initial_margin: parse(&state.margin_summary.total_margin_used, "margin used")?,
+            maintenance_margin: parse(&state.cross_maintenance_margin_used, "maintenance margin")?,
+            position_notional: parse(&state.margin_summary.total_ntl_pos, "position notional")?,
+            timestamp: Utc::now(),
         })
     }
 }
 
+impl HyperliquidConnector {
+    async fn clearinghouse_state(&self) -> Result<ClearinghouseState> {
+        let user = self.wallet()?.address_hex();
+        self.info(serde_json::json!({ "type": "clearinghouseState", "user": user }))
+            .await
+    }
+}
+
+/// Rules for `coin`, filed under its canonical asset id.
+fn contract_spec(coin: &str, sz_decimals: u32) -> ContractSpec {
+    let (asset, multiplier) = canonical_asset(coin);
+    let lot_size = Decimal::new(1, sz_decimals);
+    ContractSpec {
+        symbol: asset,
+        venue: Venue::Hyperliquid,
+        // The decimal limit; the five significant figure limit is applied when orders are sent
+        tick_size: Decimal::new(1, 6u32.saturating_sub(sz_decimals)),
+        lot_size,
+        min_size: lot_size,
+        min_notional: Decimal::from(MIN_ORDER_VALUE_USD),
+        contract_multiplier: multiplier,
+    }
+}
+
+pub(crate) fn parse_levels(levels: Vec<Level>, depth: usize) -> Result<Vec<BookLevel>> {
+    levels
+        .into_iter()
+        .take(depth)
+        .map(|level| {
+            Ok(BookLevel {
+                price: Decimal::from_str(&level.px).context("Failed to parse level price")?,
+                size: Decimal::from_str(&level.sz).context("Failed to parse level size")?,
+            })
+        })
+        .collect()
+}
+
 #[derive(Debug, Deserialize)]
 struct HyperliquidL2Book {
-    levels: Vec<Level>,
+    time: i64,
+    levels: [Vec<Level>; 2],
 }
 
 #[derive(Debug, Deserialize)]
-struct Level {
+pub(crate) struct Level {
     px: String,
     sz: String,
 }
 
 #[derive(Debug, Deserialize)]
-struct HyperliquidMeta {
-    asset_ctxs: Vec<AssetCtx>,
+struct UserFundingEntry {
+    time: i64,
+    delta: FundingDelta,
+}
+
+#[derive(Debug, Deserialize)]
+struct FundingDelta {
+    coin: String,
+    usdc: String,
+}
+
+/// `predictedFundings` responds with `[coin, [[venue, prediction | null], ...]]` pairs.
+type PredictedFundings = Vec<(String, Vec<(String, Option<PredictedFunding>)>)>;
+
+#[derive(Debug, Deserialize)]
+struct PredictedFunding {
+    #[serde(rename = "fundingRate")]
+    funding_rate: String,
 }
 
+/// `metaAndAssetCtxs` responds with `[meta, assetCtxs]`.
+type MetaAndAssetCtxs = (HyperliquidUniverse, Vec<AssetCtx>);
+
 #[derive(Debug, Deserialize)]
 struct AssetCtx {
-    name: String,
     funding: String,
-    #[serde(rename = "predictedFunding")]
-    predicted_funding: Option<String>,
-    #[serde(rename = "volume24h")]
-    volume_24h: Option<f64>,
+    #[serde(rename = "dayNtlVlm")]
+    day_ntl_vlm: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HyperliquidUniverse {
+    universe: Vec<UniverseAsset>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UniverseAsset {
+    name: String,
+    #[serde(rename = "szDecimals")]
+    sz_decimals: u32,
+    #[serde(rename = "isDelisted", default)]
+    is_delisted: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct ExchangeResponse {
+    status: String,
+    response: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderResponseData {
+    statuses: Vec<OrderStatusWire>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum OrderStatusWire {
+    Resting {
+        oid: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    Filled {
+        total_sz: String,
+        avg_px: String,
+        oid: u64,
+    },
+    Error(String),
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderStatusResponse {
+    status: String,
+    order: Option<OrderStatusEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OrderStatusEntry {
+    order: OpenOrder,
+    status: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct OpenOrder {
+    oid: u64,
+    side: String,
+    limit_px: String,
+    sz: String,
+    orig_sz: String,
+    timestamp: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct UserFill {
+    oid: u64,
+    px: String,
+    sz: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ClearinghouseState {
+    asset_positions: Vec<AssetPosition>,
+    margin_summary: MarginSummary,
+    #[serde(default = "zero")]
+    cross_maintenance_margin_used: String,
+    withdrawable: String,
+}
+
+fn zero() -> String {
+    "0".to_string()
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetPosition {
+    position: PerpPosition,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct PerpPosition {
+    coin: String,
+    szi: String,
+    entry_px: Option<String>,
+    leverage: PositionLeverage,
+}
+
+#[derive(Debug, Deserialize)]
+struct PositionLeverage {
+    value: u32,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct MarginSummary {
+    account_value: String,
+    #[serde(default = "zero")]
+    total_margin_used: String,
+    #[serde(default = "zero")]
+    total_ntl_pos: String,
 }
@@ -1,57 +1,116 @@
-use arb_core::types::{MarketData, Venue};
+use crate::venue::{unsupported, VenueConnector};
+use arb_core::config::SolanaConfig;
+use arb_core::types::{Balance, MarketData, Position, PositionSide, Trade, Venue};
 use anyhow::{Context, Result};
+use async_trait::async_trait;
+use base64::Engine;
 use chrono::Utc;
 use reqwest::Client;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::{
-    commitment_config::CommitmentConfig,
-    pubkey::Pubkey,
-    signature::Keypair,
-    transaction::Transaction,
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{
+    RpcSendTransactionConfig, RpcSimulateTransactionConfig, RpcTransactionConfig,
 };
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{UiTransactionEncoding, UiTransactionTokenBalance};
+use std::collections::BTreeMap;
 use std::str::FromStr;
 use std::sync::Arc;
-use tracing::warn;
+use std::time::{Duration, Instant};
+use tokio::time::sleep;
+use tracing::info;
 
+/// Mint label used for native lamport balance changes in `SwapExecution`.
+pub const NATIVE_SOL: &str = "SOL";
+
+const CONFIRM_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Net change of one of the wallet's balances caused by a transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BalanceDelta {
+    /// Token mint, or `NATIVE_SOL` for lamports (net of fees).
+    pub mint: String,
+    /// Change in UI units (decimals applied).
+    pub change: Decimal,
+}
+
+/// A confirmed swap transaction.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapExecution {
+    pub signature: String,
+    pub slot: u64,
+    pub fee_lamports: u64,
+    pub balance_deltas: Vec<BalanceDelta>,
+}
+
+impl SwapExecution {
+    pub fn delta(&self, mint: &str) -> Decimal {
+        self.balance_deltas
+            .iter()
+            .find(|d| d.mint == mint)
+            .map(|d| d.change)
+            .unwrap_or(Decimal::ZERO)
+    }
+}
+
+#[derive(Clone)]
 pub struct JupiterConnector {
     client: Client,
     rpc_client: Arc<RpcClient>,
     jupiter_api_url: String,
-    _keypair: Option<Keypair>, // Not used yet, but kept for future implementation
-}
-
-impl Clone for JupiterConnector {
-    fn clone(&self) -> Self {
-        Self {
-            client: self.client.clone(),
-            rpc_client: Arc::clone(&self.rpc_client),
-            jupiter_api_url: self.jupiter_api_url.clone(),
-            _keypair: None, // Keypair doesn't implement Clone, set to None on clone
-        }
-    }
+    keypair: Option<Arc<Keypair>>,
+    skip_preflight: bool,
+    max_retries: usize,
+    confirm_timeout: Duration,
 }
 
 impl JupiterConnector {
-    pub fn new(rpc_url: String, jupiter_api_url: String, private_key: Option<String>) -> Result<Self> {
+    /// An empty `private_key` yields a quote-only connector.
+    pub fn new(config: SolanaConfig) -> Result<Self> {
         let rpc_client = Arc::new(RpcClient::new_with_commitment(
-            rpc_url.clone(),
+            config.rpc_url,
             CommitmentConfig::confirmed(),
         ));
-
-        // In production, properly parse the private key from string
-        // For now, we'll leave it as None since Keypair doesn't implement Clone
-        let _keypair: Option<Keypair> = None; // TODO: Parse private key if provided
+        let keypair = if config.private_key.is_empty() {
+            None
+        } else {
+            Some(Arc::new(
+                parse_keypair(&config.private_key).context("Invalid Solana private key")?,
+            ))
+        };
 
         Ok(Self {
             client: Client::new(),
             rpc_client,
-            jupiter_api_url,
-            _keypair,
+            jupiter_api_url: config.jupiter_api_url,
+            keypair,
+            skip_preflight: config.skip_preflight,
+            max_retries: config.max_retries,
+            confirm_timeout: Duration::from_millis(config.confirm_timeout_ms),
         })
     }
 
+    fn keypair(&self) -> Result<&Keypair> {
+        self.keypair
+            .as_deref()
+            .context("Solana private key not configured")
+    }
+
+    pub fn public_key(&self) -> Result<Pubkey> {
+        Ok(self.keypair()?.pubkey())
+    }
+
     pub async fn get_quote(
         &self,
         input_mint: &str,
@@ -104,15 +163,270 @@ impl JupiterConnector {
         Ok(swap)
     }
 
-    pub async fn execute_swap(&self, swap_response: &JupiterSwapResponse) -> Result<String> {
-        // In a real implementation, this would:
-        // 1. Deserialize the transaction from swap_response
-        // 2. Sign it with the keypair
-        // 3. Send it to the Solana RPC
-        // 4. Return the transaction signature
-        
-        warn!("Jupiter swap execution not fully implemented - simulating");
-        Ok("simulated_tx_signature".to_string())
+    pub async fn get_swap_instruction_set(&self, quote: &JupiterQuote) -> Result<JupiterSwapInstructions> {
+        let url = format!("{}/swap-instructions", self.jupiter_api_url);
+        let payload = serde_json::json!({
+            "quoteResponse": quote,
+            "userPublicKey": self.public_key()?.to_string(),
+            "wrapAndUnwrapSol": true,
+        });
+
+        let resp = self
+            .client
+            .post(&url)
+            .json(&payload)
+            .send()
+            .await
+            .context("Failed to get Jupiter swap instructions")?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let body = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Jupiter swap-instructions failed ({}): {}", status, body);
+        }
+        Ok(resp.json().await?)
+    }
+
+    async fn lookup_tables(&self, addresses: &[String]) -> Result<Vec<AddressLookupTableAccount>> {
+        let mut tables = Vec::new();
+        for address in addresses {
+            let key = Pubkey::from_str(address)
+                .with_context(|| format!("Invalid lookup table address {}", address))?;
+            let data = self
+                .rpc_client
+                .get_account_data(&key)
+                .await
+                .with_context(|| format!("Failed to fetch lookup table {}", address))?;
+            let table = AddressLookupTable::deserialize(&data)
+                .map_err(|e| anyhow::anyhow!("Invalid lookup table {}: {}", address, e))?;
+            tables.push(AddressLookupTableAccount {
+                key,
+                addresses: table.addresses.to_vec(),
+            });
+        }
+        Ok(tables)
+    }
+
+    /// Composes both legs into one signed v0 transaction: a single compute
+    /// budget, then each leg's setup, swap and cleanup in order. The legs'
+    /// lookup tables are merged so the transaction fits in a packet.
+    pub async fn build_round_trip(
+        &self,
+        forward: &JupiterSwapInstructions,
+        reverse: &JupiterSwapInstructions,
+    ) -> Result<VersionedTransaction> {
+        let keypair = self.keypair()?;
+
+        let mut budget = ComputeBudget::default();
+        let mut legs = Vec::new();
+        for leg in [forward, reverse] {
+            for instruction in &leg.compute_budget_instructions {
+                budget.add(&instruction.to_instruction()?);
+            }
+            for instruction in &leg.setup_instructions {
+                legs.push(instruction.to_instruction()?);
+            }
+            legs.push(leg.swap_instruction.to_instruction()?);
+            if let Some(cleanup) = &leg.cleanup_instruction {
+                legs.push(cleanup.to_instruction()?);
+            }
+        }
+        let mut instructions = budget.instructions();
+        instructions.extend(legs);
+
+        let mut table_addresses: Vec<String> = Vec::new();
+        for address in forward
+            .address_lookup_table_addresses
+            .iter()
+            .chain(&reverse.address_lookup_table_addresses)
+        {
+            if !table_addresses.contains(address) {
+                table_addresses.push(address.clone());
+            }
+        }
+        let tables = self.lookup_tables(&table_addresses).await?;
+
+        let blockhash = self
+            .rpc_client
+            .get_latest_blockhash()
+            .await
+            .context("Failed to fetch latest blockhash")?;
+        let message = v0::Message::try_compile(&keypair.pubkey(), &instructions, &tables, blockhash)
+            .context("Failed to compile round-trip transaction")?;
+        let transaction = VersionedTransaction::try_new(VersionedMessage::V0(message), &[keypair])
+            .context("Failed to sign round-trip transaction")?;
+
+        let size = bincode::serialized_size(&transaction)? as usize;
+        if size > PACKET_DATA_SIZE {
+            anyhow::bail!("Round-trip transaction is {} bytes, limit is {}", size, PACKET_DATA_SIZE);
+        }
+        Ok(transaction)
+    }
+
+    /// Runs the transaction through `simulateTransaction`, failing with the
+    /// program logs if it would revert. Returns the compute units consumed.
+    pub async fn simulate(&self, transaction: &VersionedTransaction) -> Result<u64> {
+        let result = self
+            .rpc_client
+            .simulate_transaction_with_config(
+                transaction,
+                RpcSimulateTransactionConfig {
+                    sig_verify: true,
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    ..Default::default()
+                },
+            )
+            .await
+            .context("Failed to simulate transaction")?
+            .value;
+        if let Some(err) = result.err {
+            anyhow::bail!(
+                "Simulation failed: {} (logs: {})",
+                err,
+                result.logs.unwrap_or_default().join(" | ")
+            );
+        }
+        Ok(result.units_consumed.unwrap_or_default())
+    }
+
+    /// Executes `forward` then `reverse` atomically in one transaction,
+    /// simulating first. The reverse quote's minimum output is the guard that
+    /// makes the whole transaction revert if the round trip comes up short.
+    pub async fn execute_round_trip(&self, forward: &JupiterQuote, reverse: &JupiterQuote) -> Result<SwapExecution> {
+        if forward.output_mint != reverse.input_mint || reverse.output_mint != forward.input_mint {
+            anyhow::bail!("Quotes do not form a round trip");
+        }
+        let (forward_leg, reverse_leg) = tokio::try_join!(
+            self.get_swap_instruction_set(forward),
+            self.get_swap_instruction_set(reverse),
+        )?;
+        let transaction = self.build_round_trip(&forward_leg, &reverse_leg).await?;
+        let units = self.simulate(&transaction).await?;
+        info!("Round-trip simulation succeeded using {} compute units", units);
+        self.send_and_confirm(&transaction).await
+    }
+
+    /// Signs the swap transaction returned by Jupiter, sends it and waits for
+    /// confirmation.
+    pub async fn execute_swap(&self, swap_response: &JupiterSwapResponse) -> Result<SwapExecution> {
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&swap_response.swap_transaction)
+            .context("Swap transaction is not valid base64")?;
+        let unsigned: VersionedTransaction =
+            bincode::deserialize(&bytes).context("Failed to deserialize swap transaction")?;
+        let transaction = VersionedTransaction::try_new(unsigned.message, &[self.keypair()?])
+            .context("Failed to sign swap transaction")?;
+        self.send_and_confirm(&transaction).await
+    }
+
+    pub(crate) async fn send_and_confirm(&self, transaction: &VersionedTransaction) -> Result<SwapExecution> {
+        let config = RpcSendTransactionConfig {
+            skip_preflight: self.skip_preflight,
+            preflight_commitment: Some(CommitmentLevel::Confirmed),
+            encoding: Some(UiTransactionEncoding::Base64),
+            max_retries: Some(self.max_retries),
+            min_context_slot: None,
+        };
+        let signature = self
+            .rpc_client
+            .send_transaction_with_config(transaction, config)
+            .await
+            .context("Failed to send Solana transaction")?;
+        info!("Sent Solana transaction {}", signature);
+
+        self.wait_for_confirmation(&signature).await?;
+        self.transaction_deltas(&signature, transaction).await
+    }
+
+    async fn wait_for_confirmation(&self, signature: &Signature) -> Result<()> {
+        let started = Instant::now();
+        loop {
+            let statuses = self
+                .rpc_client
+                .get_signature_statuses(&[*signature])
+                .await
+                .context("Failed to fetch signature status")?;
+            if let Some(Some(status)) = statuses.value.first() {
+                if let Some(err) = &status.err {
+                    anyhow::bail!("Solana transaction {} failed: {}", signature, err);
+                }
+                if status.satisfies_commitment(CommitmentConfig::confirmed()) {
+                    return Ok(());
+                }
+            }
+            if started.elapsed() > self.confirm_timeout {
+                anyhow::bail!("Solana transaction {} not confirmed within {:?}", signature, self.confirm_timeout);
+            }
+            sleep(CONFIRM_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Reads the confirmed transaction back and diffs the wallet's lamport and
+    /// token balances.
+    async fn transaction_deltas(
+        &self,
+        signature: &Signature,
+        transaction: &VersionedTransaction,
+    ) -> Result<SwapExecution> {
+        let owner = self.public_key()?;
+        let confirmed = self
+            .rpc_client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Base64),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await
+            .context("Failed to fetch confirmed transaction")?;
+        let meta = confirmed
+            .transaction
+            .meta
+            .context("Confirmed transaction has no status meta")?;
+
+        let mut balance_deltas = Vec::new();
+        if let Some(index) = transaction
+            .message
+            .static_account_keys()
+            .iter()
+            .position(|key| *key == owner)
+        {
+            let pre = meta.pre_balances.get(index).copied().unwrap_or_default();
+            let post = meta.post_balances.get(index).copied().unwrap_or_default();
+            let change = Decimal::from(post) - Decimal::from(pre);
+            if !change.is_zero() {
+                balance_deltas.push(BalanceDelta {
+                    mint: NATIVE_SOL.to_string(),
+                    change: change / Decimal::from(1_000_000_000u64),
+                });
+            }
+        }
+
+        // Token balances are keyed by mint; an account missing on one side had a zero balance
+        let owner = owner.to_string();
+        let mut tokens: BTreeMap<String, Decimal> = BTreeMap::new();
+        for (balances, sign) in [(meta.pre_token_balances, Decimal::NEGATIVE_ONE), (meta.post_token_balances, Decimal::ONE)] {
+            if let OptionSerializer::Some(balances) = balances {
+                for balance in balances.iter().filter(|b| is_owned_by(b, &owner)) {
+                    *tokens.entry(balance.mint.clone()).or_default() += token_amount(balance)? * sign;
+                }
+            }
+        }
+        balance_deltas.extend(
+            tokens
+                .into_iter()
+                .filter(|(_, change)| !change.is_zero())
+                .map(|(mint, change)| BalanceDelta { mint, change }),
+        );
+
+        Ok(SwapExecution {
+            signature: signature.to_string(),
+            slot: confirmed.slot,
+            fee_lamports: meta.fee,
+            balance_deltas,
+        })
     }
 
     pub fn get_spot_price_from_quote(&self, quote: &JupiterQuote) -> MarketData {
@@ -127,27 +441,216 @@ impl JupiterConnector {
             symbol: format!("{}/{}", quote.input_mint, quote.output_mint),
             venue: Venue::SolanaJupiter,
             price,
+            bid: price,
+            ask: price,
             timestamp: Utc::now(),
         }
     }
 }
 
+#[async_trait]
+impl VenueConnector for JupiterConnector {
+    fn venue(&self) -> Venue {
+        Venue::SolanaJupiter
+    }
+
+    /// Symbols are `inputMint/outputMint` pairs, priced from a quote of
+    /// `MARKET_DATA_QUOTE_AMOUNT` base units of the input mint.
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData> {
+        let (input_mint, output_mint) = symbol
+            .split_once('/')
+            .with_context(|| format!("Invalid Jupiter pair {}, expected inputMint/outputMint", symbol))?;
+        let quote = self
+            .get_quote(input_mint, output_mint, MARKET_DATA_QUOTE_AMOUNT, MARKET_DATA_SLIPPAGE_BPS)
+            .await?;
+        Ok(self.get_spot_price_from_quote(&quote))
+    }
+
+    async fn place_order(
+        &self,
+        _symbol: &str,
+        _side: PositionSide,
+        _size: Decimal,
+        _price: Decimal,
+    ) -> Result<Trade> {
+        Err(unsupported(Venue::SolanaJupiter, "order placement"))
+    }
+
+    async fn cancel_order(&self, _symbol: &str, _order_id: &str) -> Result<()> {
+        Err(unsupported(Venue::SolanaJupiter, "order cancellation"))
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        // Swaps settle atomically, there are no open positions to report
+        Ok(Vec::new())
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Err(unsupported(Venue::SolanaJupiter, "balance queries"))
+    }
+}
+
+const MARKET_DATA_QUOTE_AMOUNT: u64 = 1_000_000;
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const MARKET_DATA_SLIPPAGE_BPS: u64 = 50;
+
+/// Quote as returned by `/quote`. Fields the connector does not interpret
+/// (`routePlan`, ...) are kept in `extra` so the quote can be passed back to
+/// `/swap` and `/swap-instructions` unchanged.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JupiterQuote {
     #[serde(rename = "inputMint")]
     pub input_mint: String,
     #[serde(rename = "outputMint")]
     pub output_mint: String,
-    #[serde(rename = "inAmount")]
+    #[serde(rename = "inAmount", with = "string_amount")]
     pub in_amount: u64,
-    #[serde(rename = "outAmount")]
+    #[serde(rename = "outAmount", with = "string_amount")]
     pub out_amount: u64,
-    #[serde(rename = "otherAmountThreshold")]
+    /// Minimum output (ExactIn) enforced on-chain by the swap instruction.
+    #[serde(rename = "otherAmountThreshold", with = "string_amount")]
     pub other_amount_threshold: u64,
     #[serde(rename = "swapMode")]
     pub swap_mode: String,
-    #[serde(rename = "priceImpactPct")]
-    pub price_impact_pct: Option<f64>,
+    #[serde(rename = "slippageBps", default)]
+    pub slippage_bps: u64,
+    #[serde(rename = "priceImpactPct", default, skip_serializing_if = "Option::is_none")]
+    pub price_impact_pct: Option<serde_json::Value>,
+    #[serde(flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl JupiterQuote {
+    /// Tightens the on-chain minimum output to at least `min_out` by lowering
+    /// the slippage tolerance. Fails if the quote itself is below `min_out`.
+    pub fn with_min_output(&self, min_out: u64) -> Result<JupiterQuote> {
+        if self.out_amount < min_out {
+            anyhow::bail!("Quoted output {} is below the required {}", self.out_amount, min_out);
+        }
+        let headroom = (self.out_amount - min_out) as u128 * 10_000 / self.out_amount as u128;
+        let slippage_bps = self.slippage_bps.min(headroom as u64);
+        let mut quote = self.clone();
+        quote.slippage_bps = slippage_bps;
+        quote.other_amount_threshold =
+            (self.out_amount as u128 * (10_000 - slippage_bps) as u128 / 10_000) as u64;
+        Ok(quote)
+    }
+}
+
+/// Jupiter v6 encodes amounts as decimal strings; numbers are accepted too.
+mod string_amount {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&value.to_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Amount {
+            String(String),
+            Number(u64),
+        }
+        match Amount::deserialize(deserializer)? {
+            Amount::String(s) => s.parse().map_err(de::Error::custom),
+            Amount::Number(n) => Ok(n),
+        }
+    }
+}
+
+/// `/swap-instructions` response: the instructions of one swap, to be
+/// composed into a transaction by the caller.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterSwapInstructions {
+    #[serde(default)]
+    pub compute_budget_instructions: Vec<JupiterInstruction>,
+    #[serde(default)]
+    pub setup_instructions: Vec<JupiterInstruction>,
+    pub swap_instruction: JupiterInstruction,
+    #[serde(default)]
+    pub cleanup_instruction: Option<JupiterInstruction>,
+    #[serde(default)]
+    pub address_lookup_table_addresses: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterInstruction {
+    pub program_id: String,
+    pub accounts: Vec<JupiterAccountMeta>,
+    /// Base64 instruction data.
+    pub data: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JupiterAccountMeta {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+impl JupiterInstruction {
+    pub fn to_instruction(&self) -> Result<Instruction> {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|a| {
+                Ok(AccountMeta {
+                    pubkey: Pubkey::from_str(&a.pubkey)
+                        .with_context(|| format!("Invalid account {}", a.pubkey))?,
+                    is_signer: a.is_signer,
+                    is_writable: a.is_writable,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Instruction {
+            program_id: Pubkey::from_str(&self.program_id)
+                .with_context(|| format!("Invalid program id {}", self.program_id))?,
+            accounts,
+            data: base64::engine::general_purpose::STANDARD
+                .decode(&self.data)
+                .context("Instruction data is not valid base64")?,
+        })
+    }
+}
+
+/// Compute budget accumulated across legs: limits add up, the price is the
+/// highest requested.
+#[derive(Debug, Default)]
+struct ComputeBudget {
+    unit_limit: u32,
+    unit_price: u64,
+}
+
+impl ComputeBudget {
+    /// SetComputeUnitLimit / SetComputeUnitPrice are borsh enums: a one-byte
+    /// tag followed by a little-endian value.
+    fn add(&mut self, instruction: &Instruction) {
+        match instruction.data.split_first() {
+            Some((2, limit)) if limit.len() == 4 => {
+                self.unit_limit += u32::from_le_bytes(limit.try_into().unwrap_or_default());
+            }
+            Some((3, price)) if price.len() == 8 => {
+                self.unit_price = self
+                    .unit_price
+                    .max(u64::from_le_bytes(price.try_into().unwrap_or_default()));
+            }
+            _ => {}
+        }
+    }
+
+    fn instructions(&self) -> Vec<Instruction> {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(
+            self.unit_limit.clamp(1, MAX_COMPUTE_UNIT_LIMIT),
+        )];
+        if self.unit_price > 0 {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(self.unit_price));
+        }
+        instructions
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,3 +658,33 @@ pub struct JupiterSwapResponse {
     #[serde(rename = "swapTransaction")]
     pub swap_transaction: String,
 }
+
+/// Parses a keypair from a base58 string (as exported by wallets) or a JSON
+/// byte array (as written by `solana-keygen`).
+pub fn parse_keypair(private_key: &str) -> Result<Keypair> {
+    let private_key = private_key.trim();
+    let bytes = if private_key.starts_with('[') {
+        serde_json::from_str::<Vec<u8>>(private_key).context("Keypair is not a JSON byte array")?
+    } else {
+        solana_sdk::bs58::decode(private_key)
+            .into_vec()
+            .context("Keypair is not valid base58")?
+    };
+    if bytes.len() != 64 {
+        anyhow::bail!("Keypair must be 64 bytes, got {}", bytes.len());
+    }
+    Keypair::from_bytes(&bytes).map_err(|e| anyhow::anyhow!("Invalid keypair: {}", e))
+}
+
+fn is_owned_by(balance: &UiTransactionTokenBalance, owner: &str) -> bool {
+    matches!(&balance.owner, OptionSerializer::Some(o) if o == owner)
+}
+
+fn token_amount(balance: &UiTransactionTokenBalance) -> Result<Decimal> {
+    let mut amount = Decimal::from_str(&balance.ui_token_amount.amount)
+        .with_context(|| format!("Invalid token amount for {}", balance.mint))?;
+    amount
+        .set_scale(balance.ui_token_amount.decimals as u32)
+        .with_context(|| format!("Unsupported decimals for {}", balance.mint))?;
+    Ok(amount.normalize())
+}
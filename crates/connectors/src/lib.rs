@@ -3,9 +3,15 @@ pub mod bybit;
 pub mod hyperevm;
 pub mod hyperliquid;
 pub mod jupiter;
+pub mod registry;
+pub mod signing;
+pub mod streaming;
+pub mod venue;
 
-pub use binance::BinanceConnector;
-pub use bybit::BybitConnector;
+pub use binance::{BinanceConnector, BinanceError};
+pub use bybit::{BybitConnector, BybitError};
 pub use hyperevm::HyperEvmConnector;
 pub use hyperliquid::HyperliquidConnector;
 pub use jupiter::JupiterConnector;
+pub use registry::ConnectorRegistry;
+pub use venue::{request_not_sent, VenueConnector};
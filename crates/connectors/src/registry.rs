@@ -0,0 +1,56 @@
+use crate::venue::VenueConnector;
+use arb_core::instruments::InstrumentRegistry;
+use arb_core::types::Venue;
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Connectors keyed by venue, shared between strategies.
+#[derive(Clone, Default)]
+pub struct ConnectorRegistry {
+    connectors: HashMap<Venue, Arc<dyn VenueConnector>>,
+}
+
+impl ConnectorRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, connector: Arc<dyn VenueConnector>) {
+        self.connectors.insert(connector.venue(), connector);
+    }
+
+    pub fn get(&self, venue: &Venue) -> Result<Arc<dyn VenueConnector>> {
+        self.connectors
+            .get(venue)
+            .cloned()
+            .with_context(|| format!("No connector registered for {:?}", venue))
+    }
+
+    pub fn contains(&self, venue: &Venue) -> bool {
+        self.connectors.contains_key(venue)
+    }
+
+    pub fn venues(&self) -> Vec<Venue> {
+        self.connectors.keys().cloned().collect()
+    }
+
+    /// Loads each of `venues`' listings into `instruments`. A venue that fails
+    /// keeps its previous listing, and symbols it lacks use the fallback mapping.
+    pub async fn load_instruments(&self, venues: &[Venue], instruments: &InstrumentRegistry) {
+        for venue in venues {
+            let listing = match self.get(venue) {
+                Ok(connector) => connector.get_instruments().await,
+                Err(e) => Err(e),
+            };
+            match listing {
+                Ok(listing) => {
+                    let loaded = instruments.load_venue(venue, listing);
+                    info!("Loaded {} {:?} instruments", loaded, venue);
+                }
+                Err(e) => warn!("Failed to load {:?} instruments: {:#}", venue, e),
+            }
+        }
+    }
+}
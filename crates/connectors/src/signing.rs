@@ -0,0 +1,78 @@
+use anyhow::{Context, Result};
+use hmac::{Hmac, Mac};
+use libsecp256k1::{Message, PublicKey, SecretKey};
+use sha2::Sha256;
+use sha3::{Digest, Keccak256};
+
+/// Hex-encoded HMAC-SHA256 of `payload`, as used by Binance and Bybit request signing.
+pub fn hmac_sha256_hex(secret: &str, payload: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// Recoverable secp256k1 signature with an Ethereum-style `v` (27 or 28).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EcdsaSignature {
+    pub r: [u8; 32],
+    pub s: [u8; 32],
+    pub v: u8,
+}
+
+impl EcdsaSignature {
+    /// Recovery id without the legacy 27 offset, as used by typed EVM transactions.
+    pub fn y_parity(&self) -> u8 {
+        self.v - 27
+    }
+}
+
+/// secp256k1 key used to sign EVM-style payloads (Hyperliquid actions, HyperEVM transactions).
+#[derive(Clone)]
+pub struct EvmWallet {
+    secret: SecretKey,
+    address: [u8; 20],
+}
+
+impl EvmWallet {
+    pub fn from_hex(private_key: &str) -> Result<Self> {
+        let bytes = hex::decode(private_key.trim().trim_start_matches("0x"))
+            .context("Private key is not valid hex")?;
+        let secret = SecretKey::parse_slice(&bytes)
+            .map_err(|e| anyhow::anyhow!("Invalid secp256k1 private key: {:?}", e))?;
+
+        // The address is the last 20 bytes of keccak(uncompressed pubkey without the 0x04 prefix)
+        let public = PublicKey::from_secret_key(&secret).serialize();
+        let hash = keccak256(&public[1..]);
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&hash[12..]);
+
+        Ok(Self { secret, address })
+    }
+
+    pub fn address(&self) -> [u8; 20] {
+        self.address
+    }
+
+    pub fn address_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.address))
+    }
+
+    pub fn sign_hash(&self, hash: &[u8; 32]) -> EcdsaSignature {
+        let (signature, recovery_id) = libsecp256k1::sign(&Message::parse(hash), &self.secret);
+        let bytes = signature.serialize();
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&bytes[..32]);
+        s.copy_from_slice(&bytes[32..]);
+        EcdsaSignature {
+            r,
+            s,
+            v: 27 + recovery_id.serialize(),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use super::{publish_book, FeedAction, LocalBook, StreamFeed};
+use crate::binance::{native_symbol, DEFAULT_FUNDING_INTERVAL_HOURS};
+use arb_core::instruments::InstrumentRegistry;
+use arb_core::types::{FundingRate, SharedState, Venue};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use reqwest::Client;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use tracing::warn;
+
+/// `markPrice@1s` and diff `depth@100ms` streams. The local book is seeded from
+/// a REST snapshot, with the events received meanwhile replayed over it, and
+/// re-seeded whenever an event's `pu` does not match the previous event's `u`.
+pub struct BinanceFeed {
+    ws_url: String,
+    rest_url: String,
+    client: Client,
+    /// Canonical asset ids, as used in `SharedState`.
+    symbols: Vec<String>,
+    instruments: Arc<InstrumentRegistry>,
+    /// Native symbol (`BTCUSDT`) to canonical id, resolved on every connect.
+    natives: HashMap<String, String>,
+    books: HashMap<String, SymbolBook>,
+}
+
+#[derive(Default)]
+struct SymbolBook {
+    book: LocalBook,
+    /// Final update id the book is current to; `None` until seeded.
+    last_update_id: Option<u64>,
+    /// Set once an event has been applied over the snapshot, from when on
+    /// every event must continue from the previous one.
+    live: bool,
+    /// Events received since the last gap, replayed over the next snapshot.
+    buffered: Vec<DepthUpdate>,
+}
+
+impl BinanceFeed {
+    pub fn new(ws_url: String, rest_url: String, symbols: Vec<String>) -> Self {
+        Self {
+            ws_url,
+            rest_url,
+            client: Client::new(),
+            symbols,
+            instruments: Arc::new(InstrumentRegistry::new()),
+            natives: HashMap::new(),
+            books: HashMap::new(),
+        }
+    }
+
+    /// Resolves canonical asset ids through `instruments` once it is loaded.
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    async fn fetch_snapshot(&self, native: &str) -> Result<DepthSnapshot> {
+        let url = format!("{}/fapi/v1/depth", self.rest_url);
+        let resp = self
+            .client
+            .get(&url)
+            .query(&[("symbol", native), ("limit", "1000")])
+            .send()
+            .await
+            .context("Failed to fetch Binance depth snapshot")?;
+        Ok(resp.json().await?)
+    }
+
+    async fn handle_depth(&mut self, update: DepthUpdate, state: &SharedState) -> Result<()> {
+        let Some(symbol) = self.natives.get(&update.symbol).cloned() else {
+            return Ok(());
+        };
+        let native = update.symbol.clone();
+
+        let entry = self.books.entry(native.clone()).or_default();
+        let pending = if entry.last_update_id.is_some() {
+            vec![update]
+        } else {
+            // One snapshot per gap, with the stream buffered until it arrives
+            entry.buffered.push(update);
+            let snapshot = self.fetch_snapshot(&native).await?;
+            let entry = self.books.entry(native.clone()).or_default();
+            if entry.buffered[0].first_update_id > snapshot.last_update_id {
+                // Snapshot is older than the stream, take a new one on the next event
+                return Ok(());
+            }
+            entry.book.clear();
+            entry.book.apply(&snapshot.bids, &snapshot.asks)?;
+            entry.last_update_id = Some(snapshot.last_update_id);
+            entry.live = false;
+            std::mem::take(&mut entry.buffered)
+        };
+
+        let entry = self.books.entry(native).or_default();
+        let mut applied = None;
+        let mut pending = pending.into_iter();
+        while let Some(update) = pending.next() {
+            let Some(last) = entry.last_update_id else {
+                break;
+            };
+            if entry.live {
+                if update.prev_final_update_id != last {
+                    warn!(
+                        "Binance depth gap for {}: expected pu {}, got {}",
+                        update.symbol, last, update.prev_final_update_id
+                    );
+                    entry.last_update_id = None;
+                    entry.live = false;
+                    entry.buffered.push(update);
+                    entry.buffered.extend(pending);
+                    break;
+                }
+            } else if update.final_update_id < last {
+                // Already contained in the snapshot
+                continue;
+            }
+            entry.book.apply(&update.bids, &update.asks)?;
+            entry.last_update_id = Some(update.final_update_id);
+            entry.live = true;
+            applied = Some(update.transaction_time);
+        }
+
+        if let Some(time) = applied {
+            let timestamp = DateTime::from_timestamp_millis(time).unwrap_or_else(Utc::now);
+            publish_book(state, entry.book.snapshot(&symbol, Venue::Binance, timestamp));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl StreamFeed for BinanceFeed {
+    fn venue(&self) -> Venue {
+        Venue::Binance
+    }
+
+    fn url(&self) -> String {
+        format!("{}/stream", self.ws_url)
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        let params: Vec<String> = self
+            .natives
+            .keys()
+            .flat_map(|native| {
+                let lower = native.to_lowercase();
+                [format!("{}@markPrice@1s", lower), format!("{}@depth@100ms", lower)]
+            })
+            .collect();
+        vec![serde_json::json!({ "method": "SUBSCRIBE", "params": params, "id": 1 }).to_string()]
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+        self.natives = self
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let native = self
+                    .instruments
+                    .native_symbol(symbol, &Venue::Binance)
+                    .unwrap_or_else(|| native_symbol(symbol));
+                (native, symbol.clone())
+            })
+            .collect();
+    }
+
+    async fn handle_message(&mut self, text: &str, state: &SharedState) -> Result<FeedAction> {
+        let message: StreamMessage = serde_json::from_str(text).context("Invalid Binance message")?;
+        // Subscription acknowledgements carry no stream
+        let Some(data) = message.data else {
+            return Ok(FeedAction::Continue);
+        };
+
+        match data.get("e").and_then(|e| e.as_str()) {
+            Some("depthUpdate") => {
+                self.handle_depth(serde_json::from_value(data)?, state).await?;
+            }
+            Some("markPriceUpdate") => {
+                let update: MarkPriceUpdate = serde_json::from_value(data)?;
+                if let Some(symbol) = self.natives.get(&update.symbol) {
+                    let rate = Decimal::from_str(&update.funding_rate).context("Failed to parse funding rate")?;
+                    // The stream omits the interval; keep whatever REST last reported
+                    let key = (symbol.clone(), Venue::Binance);
+                    let interval_hours = state
+                        .funding_rates
+                        .get(&key)
+                        .map(|existing| existing.interval_hours)
+                        .unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS);
+                    state.update_funding_rate(FundingRate {
+                        symbol: symbol.clone(),
+                        venue: Venue::Binance,
+                        rate,
+                        predicted_rate: None,
+                        interval_hours,
+                        next_funding_time: update
+                            .next_funding_time
+                            .filter(|&time| time > 0)
+                            .and_then(DateTime::from_timestamp_millis),
+                        timestamp: DateTime::from_timestamp_millis(update.event_time)
+                            .unwrap_or_else(Utc::now),
+                    });
+                }
+            }
+            _ => {}
+        }
+        Ok(FeedAction::Continue)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DepthSnapshot {
+    #[serde(rename = "lastUpdateId")]
+    last_update_id: u64,
+    bids: Vec<[String; 2]>,
+    asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DepthUpdate {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "T")]
+    transaction_time: i64,
+    #[serde(rename = "U")]
+    first_update_id: u64,
+    #[serde(rename = "u")]
+    final_update_id: u64,
+    #[serde(rename = "pu")]
+    prev_final_update_id: u64,
+    #[serde(rename = "b")]
+    bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    asks: Vec<[String; 2]>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarkPriceUpdate {
+    #[serde(rename = "E")]
+    event_time: i64,
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "r")]
+    funding_rate: String,
+    #[serde(rename = "T", default)]
+    next_funding_time: Option<i64>,
+}
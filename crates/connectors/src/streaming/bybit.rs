@@ -0,0 +1,208 @@
+use super::{publish_book, FeedAction, LocalBook, StreamFeed};
+use crate::bybit::{native_symbol, DEFAULT_FUNDING_INTERVAL_HOURS};
+use arb_core::instruments::InstrumentRegistry;
+use arb_core::types::{FundingRate, SharedState, Venue};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Bybit accepts at most this many topics per subscribe request.
+const MAX_ARGS_PER_SUBSCRIBE: usize = 10;
+
+/// `tickers` and `orderbook.50` subscriptions. Book deltas must carry
+/// consecutive update ids; a gap forces a reconnect, which makes Bybit send a
+/// fresh snapshot.
+pub struct BybitFeed {
+    ws_url: String,
+    /// Canonical asset ids, as used in `SharedState`.
+    symbols: Vec<String>,
+    instruments: Arc<InstrumentRegistry>,
+    /// Native symbol (`BTCUSDT`) to canonical id, resolved on every connect.
+    natives: HashMap<String, String>,
+    books: HashMap<String, SymbolBook>,
+}
+
+#[derive(Default)]
+struct SymbolBook {
+    book: LocalBook,
+    last_update_id: Option<u64>,
+}
+
+impl BybitFeed {
+    pub fn new(ws_url: String, symbols: Vec<String>) -> Self {
+        Self {
+            ws_url,
+            symbols,
+            instruments: Arc::new(InstrumentRegistry::new()),
+            natives: HashMap::new(),
+            books: HashMap::new(),
+        }
+    }
+
+    /// Resolves canonical asset ids through `instruments` once it is loaded.
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+
+    fn handle_book(&mut self, kind: &str, update: BookUpdate, timestamp: i64, state: &SharedState) -> Result<FeedAction> {
+        let Some(symbol) = self.natives.get(&update.symbol).cloned() else {
+            return Ok(FeedAction::Continue);
+        };
+        let entry = self.books.entry(update.symbol.clone()).or_default();
+
+        if kind == "snapshot" {
+            entry.book.clear();
+        } else {
+            match entry.last_update_id {
+                Some(last) if update.update_id != last + 1 => {
+                    return Ok(FeedAction::Reconnect(format!(
+                        "{} book gap: expected update {}, got {}",
+                        update.symbol,
+                        last + 1,
+                        update.update_id
+                    )));
+                }
+                Some(_) => {}
+                // Deltas before the first snapshot cannot be applied
+                None => return Ok(FeedAction::Continue),
+            }
+        }
+
+        entry.book.apply(&update.bids, &update.asks)?;
+        entry.last_update_id = Some(update.update_id);
+
+        let timestamp = DateTime::from_timestamp_millis(timestamp).unwrap_or_else(Utc::now);
+        publish_book(state, entry.book.snapshot(&symbol, Venue::Bybit, timestamp));
+        Ok(FeedAction::Continue)
+    }
+}
+
+#[async_trait]
+impl StreamFeed for BybitFeed {
+    fn venue(&self) -> Venue {
+        Venue::Bybit
+    }
+
+    fn url(&self) -> String {
+        self.ws_url.clone()
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        let topics: Vec<String> = self
+            .natives
+            .keys()
+            .flat_map(|native| [format!("tickers.{}", native), format!("orderbook.50.{}", native)])
+            .collect();
+        topics
+            .chunks(MAX_ARGS_PER_SUBSCRIBE)
+            .map(|args| serde_json::json!({ "op": "subscribe", "args": args }).to_string())
+            .collect()
+    }
+
+    fn ping_message(&self) -> Option<String> {
+        Some(serde_json::json!({ "op": "ping" }).to_string())
+    }
+
+    fn reset(&mut self) {
+        self.books.clear();
+        self.natives = self
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let native = self
+                    .instruments
+                    .native_symbol(symbol, &Venue::Bybit)
+                    .unwrap_or_else(|| native_symbol(symbol));
+                (native, symbol.clone())
+            })
+            .collect();
+    }
+
+    async fn handle_message(&mut self, text: &str, state: &SharedState) -> Result<FeedAction> {
+        let message: TopicMessage = serde_json::from_str(text).context("Invalid Bybit message")?;
+        // Pongs and subscription acknowledgements carry no topic
+        let Some(topic) = message.topic else {
+            return Ok(FeedAction::Continue);
+        };
+
+        if topic.starts_with("orderbook.") {
+            let update: BookUpdate = serde_json::from_value(message.data)?;
+            return self.handle_book(&message.kind, update, message.ts, state);
+        }
+
+        if topic.starts_with("tickers.") {
+            let update: TickerUpdate = serde_json::from_value(message.data)?;
+            // Deltas only include fields that changed
+            if let (Some(symbol), Some(funding)) = (self.natives.get(&update.symbol), update.funding_rate) {
+                let rate = Decimal::from_str(&funding).context("Failed to parse funding rate")?;
+                let key = (symbol.clone(), Venue::Bybit);
+                let previous = state
+                    .funding_rates
+                    .get(&key)
+                    .map(|existing| (existing.interval_hours, existing.next_funding_time));
+                let interval_hours = update
+                    .funding_interval_hour
+                    .and_then(|h| h.parse().ok())
+                    .or(previous.map(|(hours, _)| hours))
+                    .unwrap_or(DEFAULT_FUNDING_INTERVAL_HOURS);
+                let next_funding_time = update
+                    .next_funding_time
+                    .and_then(|t| t.parse::<i64>().ok())
+                    .filter(|&t| t > 0)
+                    .and_then(DateTime::from_timestamp_millis)
+                    .or(previous.and_then(|(_, time)| time));
+                state.update_funding_rate(FundingRate {
+                    symbol: symbol.clone(),
+                    venue: Venue::Bybit,
+                    rate,
+                    predicted_rate: None,
+                    interval_hours,
+                    next_funding_time,
+                    timestamp: DateTime::from_timestamp_millis(message.ts).unwrap_or_else(Utc::now),
+                });
+            }
+        }
+        Ok(FeedAction::Continue)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TopicMessage {
+    #[serde(default)]
+    topic: Option<String>,
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    ts: i64,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct BookUpdate {
+    #[serde(rename = "s")]
+    symbol: String,
+    #[serde(rename = "b")]
+    bids: Vec<[String; 2]>,
+    #[serde(rename = "a")]
+    asks: Vec<[String; 2]>,
+    #[serde(rename = "u")]
+    update_id: u64,
+}
+
+#[derive(Debug, Deserialize)]
+struct TickerUpdate {
+    symbol: String,
+    #[serde(rename = "fundingRate", default)]
+    funding_rate: Option<String>,
+    #[serde(rename = "nextFundingTime", default)]
+    next_funding_time: Option<String>,
+    #[serde(rename = "fundingIntervalHour", default)]
+    funding_interval_hour: Option<String>,
+}
@@ -0,0 +1,152 @@
+use super::{publish_book, FeedAction, StreamFeed, STREAM_BOOK_DEPTH};
+use crate::hyperliquid::{next_funding_time, parse_levels, Level, FUNDING_INTERVAL_HOURS};
+use arb_core::instruments::InstrumentRegistry;
+use arb_core::types::{FundingRate, OrderBook, SharedState, Venue};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// `l2Book` and `activeAssetCtx` subscriptions. Hyperliquid pushes full book
+/// snapshots, so there is no sequence to track.
+pub struct HyperliquidFeed {
+    ws_url: String,
+    /// Canonical asset ids, as used in `SharedState`.
+    symbols: Vec<String>,
+    instruments: Arc<InstrumentRegistry>,
+    /// Coin name (`kPEPE`) to canonical id, resolved on every connect.
+    coins: HashMap<String, String>,
+}
+
+impl HyperliquidFeed {
+    pub fn new(ws_url: String, symbols: Vec<String>) -> Self {
+        Self {
+            ws_url,
+            symbols,
+            instruments: Arc::new(InstrumentRegistry::new()),
+            coins: HashMap::new(),
+        }
+    }
+
+    /// Resolves canonical asset ids through `instruments` once it is loaded.
+    pub fn with_instruments(mut self, instruments: Arc<InstrumentRegistry>) -> Self {
+        self.instruments = instruments;
+        self
+    }
+}
+
+#[async_trait]
+impl StreamFeed for HyperliquidFeed {
+    fn venue(&self) -> Venue {
+        Venue::Hyperliquid
+    }
+
+    fn url(&self) -> String {
+        self.ws_url.clone()
+    }
+
+    fn subscriptions(&self) -> Vec<String> {
+        self.coins
+            .keys()
+            .flat_map(|coin| {
+                ["l2Book", "activeAssetCtx"].map(|channel| {
+                    serde_json::json!({
+                        "method": "subscribe",
+                        "subscription": { "type": channel, "coin": coin },
+                    })
+                    .to_string()
+                })
+            })
+            .collect()
+    }
+
+    fn ping_message(&self) -> Option<String> {
+        Some(serde_json::json!({ "method": "ping" }).to_string())
+    }
+
+    fn reset(&mut self) {
+        self.coins = self
+            .symbols
+            .iter()
+            .map(|symbol| {
+                let coin = self
+                    .instruments
+                    .native_symbol(symbol, &Venue::Hyperliquid)
+                    .unwrap_or_else(|| symbol.clone());
+                (coin, symbol.clone())
+            })
+            .collect();
+    }
+
+    async fn handle_message(&mut self, text: &str, state: &SharedState) -> Result<FeedAction> {
+        let message: ChannelMessage = serde_json::from_str(text).context("Invalid Hyperliquid message")?;
+        match message.channel.as_str() {
+            "l2Book" => {
+                let data: BookUpdate = serde_json::from_value(message.data)?;
+                let Some(symbol) = self.coins.get(&data.coin) else {
+                    return Ok(FeedAction::Continue);
+                };
+                let [bids, asks] = data.levels;
+                publish_book(
+                    state,
+                    OrderBook {
+                        symbol: symbol.clone(),
+                        venue: Venue::Hyperliquid,
+                        bids: parse_levels(bids, STREAM_BOOK_DEPTH)?,
+                        asks: parse_levels(asks, STREAM_BOOK_DEPTH)?,
+                        timestamp: DateTime::from_timestamp_millis(data.time).unwrap_or_else(Utc::now),
+                    },
+                );
+            }
+            "activeAssetCtx" => {
+                let data: AssetCtxUpdate = serde_json::from_value(message.data)?;
+                let Some(symbol) = self.coins.get(&data.coin) else {
+                    return Ok(FeedAction::Continue);
+                };
+                let rate = Decimal::from_str(&data.ctx.funding).context("Failed to parse funding rate")?;
+                let now = Utc::now();
+                state.update_funding_rate(FundingRate {
+                    symbol: symbol.clone(),
+                    venue: Venue::Hyperliquid,
+                    rate,
+                    predicted_rate: None,
+                    interval_hours: FUNDING_INTERVAL_HOURS,
+                    next_funding_time: Some(next_funding_time(now)),
+                    timestamp: now,
+                });
+            }
+            // subscriptionResponse, pong, ...
+            _ => {}
+        }
+        Ok(FeedAction::Continue)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ChannelMessage {
+    channel: String,
+    #[serde(default)]
+    data: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct BookUpdate {
+    coin: String,
+    time: i64,
+    levels: [Vec<Level>; 2],
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetCtxUpdate {
+    coin: String,
+    ctx: AssetCtx,
+}
+
+#[derive(Debug, Deserialize)]
+struct AssetCtx {
+    funding: String,
+}
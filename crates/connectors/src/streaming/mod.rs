@@ -0,0 +1,225 @@
+pub mod binance;
+pub mod bybit;
+pub mod hyperliquid;
+
+pub use binance::BinanceFeed;
+pub use bybit::BybitFeed;
+pub use hyperliquid::HyperliquidFeed;
+
+use arb_core::config::StreamingConfig;
+use arb_core::types::{BookLevel, OrderBook, SharedState, Venue};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use futures::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::time::{interval, sleep};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info, warn};
+
+/// Depth kept in `SharedState.order_books` for streamed books.
+pub const STREAM_BOOK_DEPTH: usize = 50;
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// What the session loop should do after a feed has handled a message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FeedAction {
+    Continue,
+    /// Drop the connection and resubscribe, e.g. after a sequence gap that
+    /// can only be repaired by a fresh snapshot.
+    Reconnect(String),
+}
+
+/// Venue-specific half of a market-data stream: what to subscribe to and how
+/// to turn messages into `SharedState` updates.
+#[async_trait]
+pub trait StreamFeed: Send {
+    fn venue(&self) -> Venue;
+
+    fn url(&self) -> String;
+
+    fn subscriptions(&self) -> Vec<String>;
+
+    /// Application-level ping sent every heartbeat interval, for venues that
+    /// expect one on top of WebSocket ping frames.
+    fn ping_message(&self) -> Option<String> {
+        None
+    }
+
+    /// Called before every (re)connect so sequence tracking starts over.
+    fn reset(&mut self);
+
+    async fn handle_message(&mut self, text: &str, state: &SharedState) -> Result<FeedAction>;
+}
+
+#[derive(Debug, Clone)]
+pub struct StreamSettings {
+    pub heartbeat_interval: Duration,
+    pub stale_timeout: Duration,
+    pub max_backoff: Duration,
+}
+
+impl From<&StreamingConfig> for StreamSettings {
+    fn from(config: &StreamingConfig) -> Self {
+        Self {
+            heartbeat_interval: Duration::from_millis(config.heartbeat_interval_ms),
+            stale_timeout: Duration::from_millis(config.stale_timeout_ms),
+            max_backoff: Duration::from_millis(config.reconnect_backoff_max_ms),
+        }
+    }
+}
+
+/// Keeps `feed` connected forever, reconnecting with exponential backoff.
+pub async fn run_feed<F: StreamFeed>(mut feed: F, state: Arc<SharedState>, settings: StreamSettings) {
+    let mut backoff = INITIAL_BACKOFF;
+    loop {
+        feed.reset();
+        match run_session(&mut feed, &state, &settings).await {
+            Ok(received) => {
+                info!("{:?} stream disconnected, reconnecting", feed.venue());
+                if received {
+                    backoff = INITIAL_BACKOFF;
+                }
+            }
+            Err(e) => warn!("{:?} stream error: {:#}", feed.venue(), e),
+        }
+        sleep(backoff).await;
+        backoff = (backoff * 2).min(settings.max_backoff);
+    }
+}
+
+/// Runs one connection until it closes, goes stale or the feed asks for a
+/// reconnect. Returns whether any message was received.
+async fn run_session<F: StreamFeed>(
+    feed: &mut F,
+    state: &SharedState,
+    settings: &StreamSettings,
+) -> Result<bool> {
+    let url = feed.url();
+    let (socket, _) = connect_async(url.as_str())
+        .await
+        .with_context(|| format!("Failed to connect to {}", url))?;
+    let (mut write, mut read) = socket.split();
+    info!("{:?} stream connected to {}", feed.venue(), url);
+
+    for subscription in feed.subscriptions() {
+        write.send(Message::Text(subscription)).await?;
+    }
+
+    let mut heartbeat = interval(settings.heartbeat_interval);
+    heartbeat.tick().await;
+    let mut last_message = Instant::now();
+    let mut received = false;
+
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => {
+                if last_message.elapsed() > settings.stale_timeout {
+                    anyhow::bail!("no message for {:?}", last_message.elapsed());
+                }
+                if let Some(ping) = feed.ping_message() {
+                    write.send(Message::Text(ping)).await?;
+                }
+            }
+            message = read.next() => {
+                let message = match message {
+                    Some(message) => message?,
+                    None => return Ok(received),
+                };
+                last_message = Instant::now();
+                match message {
+                    Message::Text(text) => {
+                        received = true;
+                        if let FeedAction::Reconnect(reason) = feed.handle_message(&text, state).await? {
+                            warn!("{:?} stream resyncing: {}", feed.venue(), reason);
+                            return Ok(received);
+                        }
+                    }
+                    Message::Ping(payload) => write.send(Message::Pong(payload)).await?,
+                    Message::Close(_) => return Ok(received),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+/// Incrementally maintained book for feeds that publish diffs.
+#[derive(Debug, Default)]
+pub(crate) struct LocalBook {
+    bids: BTreeMap<Reverse<Decimal>, Decimal>,
+    asks: BTreeMap<Decimal, Decimal>,
+}
+
+impl LocalBook {
+    pub(crate) fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+    }
+
+    /// Applies `[price, size]` updates; a zero size removes the level.
+    pub(crate) fn apply(&mut self, bids: &[[String; 2]], asks: &[[String; 2]]) -> Result<()> {
+        for [price, size] in bids {
+            let (price, size) = parse_level(price, size)?;
+            if size.is_zero() {
+                self.bids.remove(&Reverse(price));
+            } else {
+                self.bids.insert(Reverse(price), size);
+            }
+        }
+        for [price, size] in asks {
+            let (price, size) = parse_level(price, size)?;
+            if size.is_zero() {
+                self.asks.remove(&price);
+            } else {
+                self.asks.insert(price, size);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn snapshot(&self, symbol: &str, venue: Venue, timestamp: DateTime<Utc>) -> OrderBook {
+        OrderBook {
+            symbol: symbol.to_string(),
+            venue,
+            bids: self
+                .bids
+                .iter()
+                .take(STREAM_BOOK_DEPTH)
+                .map(|(Reverse(price), size)| BookLevel { price: *price, size: *size })
+                .collect(),
+            asks: self
+                .asks
+                .iter()
+                .take(STREAM_BOOK_DEPTH)
+                .map(|(price, size)| BookLevel { price: *price, size: *size })
+                .collect(),
+            timestamp,
+        }
+    }
+}
+
+fn parse_level(price: &str, size: &str) -> Result<(Decimal, Decimal)> {
+    Ok((
+        Decimal::from_str(price).with_context(|| format!("Invalid level price {:?}", price))?,
+        Decimal::from_str(size).with_context(|| format!("Invalid level size {:?}", size))?,
+    ))
+}
+
+/// Stores a book snapshot and the market data derived from it, and publishes
+/// the market update.
+pub(crate) fn publish_book(state: &SharedState, book: OrderBook) {
+    if let Some(market) = book.market_data() {
+        state.update_market_data(market);
+    }
+    state
+        .order_books
+        .insert((book.symbol.clone(), book.venue.clone()), book);
+}
@@ -0,0 +1,114 @@
+use arb_core::instruments::Instrument;
+use arb_core::types::{
+    AccountMargin, Balance, ContractSpec, FundingPayment, FundingRate, MarketData, OrderBook,
+    OrderOptions, Position, PositionSide, Trade, Venue,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+
+/// Common interface implemented by every venue connector so strategies can
+/// route market data and orders without knowing which exchange they talk to.
+#[async_trait]
+pub trait VenueConnector: Send + Sync {
+    fn venue(&self) -> Venue;
+
+    async fn get_market_data(&self, symbol: &str) -> Result<MarketData>;
+
+    async fn get_order_book(&self, symbol: &str, depth: usize) -> Result<OrderBook> {
+        let _ = (symbol, depth);
+        Err(unsupported(self.venue(), "order books"))
+    }
+
+    async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
+        let _ = symbol;
+        Err(unsupported(self.venue(), "funding rates"))
+    }
+
+    async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        let _ = symbol;
+        Err(unsupported(self.venue(), "contract specs"))
+    }
+
+    /// Every perpetual the venue lists, keyed by canonical asset id.
+    async fn get_instruments(&self) -> Result<Vec<Instrument>> {
+        Err(unsupported(self.venue(), "instrument listings"))
+    }
+
+    /// Funding settled on our account for `symbol` at or after `since`, oldest first.
+    async fn get_funding_payments(
+        &self,
+        symbol: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FundingPayment>> {
+        let _ = (symbol, since);
+        Err(unsupported(self.venue(), "funding history"))
+    }
+
+    async fn get_top_symbols_by_volume(&self, limit: usize) -> Result<Vec<String>> {
+        let _ = limit;
+        Err(unsupported(self.venue(), "symbol discovery"))
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+    ) -> Result<Trade>;
+
+    /// Places an order with an explicit time-in-force and reduce-only flag.
+    /// Venues that only support plain orders reject anything but the defaults.
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        if options != OrderOptions::default() {
+            return Err(unsupported(self.venue(), "order options"));
+        }
+        self.place_order(symbol, side, size, price).await
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: &str) -> Result<()>;
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<Trade> {
+        let _ = (symbol, order_id);
+        Err(unsupported(self.venue(), "order queries"))
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>>;
+
+    async fn get_balances(&self) -> Result<Vec<Balance>>;
+
+    async fn get_account_margin(&self) -> Result<AccountMargin> {
+        Err(unsupported(self.venue(), "margin queries"))
+    }
+
+    /// Whether `error`, returned when placing an order, means the order was
+    /// certainly not placed and may be sent again. Venues that report their
+    /// rejections as `TradeStatus::Rejected` need nothing beyond the default.
+    fn order_not_placed(&self, error: &anyhow::Error) -> bool {
+        request_not_sent(error)
+    }
+}
+
+pub(crate) fn unsupported(venue: Venue, operation: &str) -> anyhow::Error {
+    anyhow::anyhow!("{} not supported on {:?}", operation, venue)
+}
+
+/// Whether a failed request never left: the connection was never made or the
+/// request never built. Timeouts, dropped connections and unreadable responses
+/// may have reached the venue and do not qualify.
+pub fn request_not_sent(error: &anyhow::Error) -> bool {
+    error.chain().any(|cause| {
+        cause
+            .downcast_ref::<reqwest::Error>()
+            .is_some_and(|e| e.is_connect() || e.is_builder())
+    })
+}
@@ -0,0 +1,369 @@
+use arb_core::types::{PositionSide, TradeStatus, Venue};
+use arb_core::InstrumentRegistry;
+use connectors::signing::hmac_sha256_hex;
+use connectors::{BinanceConnector, BinanceError, ConnectorRegistry, VenueConnector};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::Arc;
+use wiremock::matchers::{header, method, path, query_param};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+const API_KEY: &str = "test-key";
+const API_SECRET: &str = "test-secret";
+
+/// Accepts only requests whose `signature` matches the HMAC of the rest of the query.
+struct ValidSignature;
+
+impl wiremock::Match for ValidSignature {
+    fn matches(&self, request: &Request) -> bool {
+        let Some(query) = request.url.query() else {
+            return false;
+        };
+        let Some((payload, signature)) = query.rsplit_once("&signature=") else {
+            return false;
+        };
+        hmac_sha256_hex(API_SECRET, payload) == signature
+    }
+}
+
+async fn connector(server: &MockServer) -> BinanceConnector {
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/time"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "serverTime": chrono::Utc::now().timestamp_millis()
+        })))
+        .mount(server)
+        .await;
+
+    BinanceConnector::new(API_KEY.to_string(), API_SECRET.to_string(), server.uri(), 5000)
+}
+
+#[test]
+fn signature_matches_binance_documentation_vector() {
+    let secret = "NhqPtmdSJYdKjVHjA7PZj4Mge3R5YNiP1e3UZjInClVN65XAbvqqM6A7H5fATj0j";
+    let query = "symbol=LTCBTC&side=BUY&type=LIMIT&timeInForce=GTC&quantity=1&price=0.1&recvWindow=5000&timestamp=1499827319559";
+    assert_eq!(
+        hmac_sha256_hex(secret, query),
+        "c8db56825ae71d6d79447849e617115f4a920fa2acdcab2b053c4b2838bd6b71"
+    );
+}
+
+#[tokio::test]
+async fn place_market_order_returns_filled_trade() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/fapi/v1/order"))
+        .and(header("X-MBX-APIKEY", API_KEY))
+        .and(query_param("symbol", "BTCUSDT"))
+        .and(query_param("side", "SELL"))
+        .and(query_param("type", "MARKET"))
+        .and(query_param("quantity", "0.01"))
+        .and(query_param("recvWindow", "5000"))
+        .and(ValidSignature)
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "orderId": 42,
+            "symbol": "BTCUSDT",
+            "status": "FILLED",
+            "side": "SELL",
+            "price": "0",
+            "avgPrice": "65000.10",
+            "origQty": "0.01",
+            "executedQty": "0.01",
+            "updateTime": 1700000000000i64
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let trade = binance
+        .place_order("BTC-USDT", PositionSide::Short, Decimal::from_str("0.01").unwrap(), Decimal::ZERO)
+        .await
+        .unwrap();
+
+    assert_eq!(trade.status, TradeStatus::Filled);
+    assert_eq!(trade.side, PositionSide::Short);
+    assert_eq!(trade.price, Decimal::from_str("65000.10").unwrap());
+    assert_eq!(trade.order_id.as_deref(), Some("42"));
+}
+
+#[tokio::test]
+async fn api_errors_are_surfaced() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("DELETE"))
+        .and(path("/fapi/v1/order"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "code": -2011,
+            "msg": "Unknown order sent."
+        })))
+        .mount(&server)
+        .await;
+
+    let err = binance.cancel_order("BTC-USDT", "7").await.unwrap_err();
+    assert!(err.to_string().contains("-2011"), "{}", err);
+    assert_eq!(err.downcast_ref::<BinanceError>().map(|e| e.code), Some(-2011));
+}
+
+#[tokio::test]
+async fn only_definite_rejections_count_as_unplaced_orders() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+    let responses = [(-2019, "Margin is insufficient."), (-1007, "Timeout waiting for response.")];
+    for (code, msg) in responses {
+        Mock::given(method("POST"))
+            .and(path("/fapi/v1/order"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "code": code,
+                "msg": msg
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+    }
+    let size = Decimal::ONE;
+    let place = || binance.place_order("BTC-USDT", PositionSide::Long, size, Decimal::ZERO);
+
+    assert!(binance.order_not_placed(&place().await.unwrap_err()));
+    // Binance documents -1007 as an unknown outcome
+    assert!(!binance.order_not_placed(&place().await.unwrap_err()));
+}
+
+#[tokio::test]
+async fn timestamp_rejection_resyncs_and_retries() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/order"))
+        .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+            "code": -1021,
+            "msg": "Timestamp for this request is outside of the recvWindow."
+        })))
+        .up_to_n_times(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/order"))
+        .and(query_param("orderId", "9"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "orderId": 9,
+            "symbol": "ETHUSDT",
+            "status": "PARTIALLY_FILLED",
+            "side": "BUY",
+            "price": "3000",
+            "avgPrice": "0",
+            "origQty": "2",
+            "executedQty": "0.5"
+        })))
+        .mount(&server)
+        .await;
+
+    let trade = binance.get_order("ETH-USDT", "9").await.unwrap();
+    assert_eq!(trade.status, TradeStatus::PartiallyFilled);
+    assert_eq!(trade.size, Decimal::from_str("0.5").unwrap());
+    assert_eq!(trade.price, Decimal::from(3000));
+}
+
+#[tokio::test]
+async fn position_risk_skips_flat_symbols() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/fapi/v2/positionRisk"))
+        .and(ValidSignature)
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"symbol": "BTCUSDT", "positionAmt": "-0.250", "entryPrice": "64000.0", "leverage": "5"},
+            {"symbol": "ETHUSDT", "positionAmt": "0.000", "entryPrice": "0.0", "leverage": "10"}
+        ])))
+        .mount(&server)
+        .await;
+
+    let positions = binance.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].symbol, "BTC");
+    assert_eq!(positions[0].side, PositionSide::Short);
+    assert_eq!(positions[0].size, Decimal::from_str("0.25").unwrap());
+}
+
+#[tokio::test]
+async fn funding_rate_carries_interval_from_funding_info() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    for (symbol, rate) in [("BTCUSDT", "0.00010000"), ("ETHUSDT", "0.00020000")] {
+        Mock::given(method("GET"))
+            .and(path("/fapi/v1/premiumIndex"))
+            .and(query_param("symbol", symbol))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "symbol": symbol,
+                "lastFundingRate": rate,
+                "nextFundingTime": 1700006400000i64
+            })))
+            .mount(&server)
+            .await;
+    }
+    // Only symbols with a non-default interval are listed; the list is cached
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/fundingInfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"symbol": "BTCUSDT", "adjustedFundingRateCap": "0.02", "fundingIntervalHours": 4}
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let btc = binance.get_funding_rate("BTC-USDT").await.unwrap();
+    assert_eq!(btc.interval_hours, 4);
+    assert_eq!(btc.next_funding_time.unwrap().timestamp_millis(), 1700006400000);
+    assert_eq!(btc.hourly_rate(), Decimal::from_str("0.000025").unwrap());
+    assert_eq!(btc.annualized_rate(), Decimal::from_str("0.219").unwrap());
+
+    let eth = binance.get_funding_rate("ETH-USDT").await.unwrap();
+    assert_eq!(eth.interval_hours, 8);
+    assert_eq!(eth.annualized_rate(), btc.annualized_rate());
+}
+
+#[tokio::test]
+async fn funding_payments_read_income_history() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/income"))
+        .and(query_param("symbol", "BTCUSDT"))
+        .and(query_param("incomeType", "FUNDING_FEE"))
+        .and(query_param("startTime", "1700000000000"))
+        .and(ValidSignature)
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"symbol": "BTCUSDT", "incomeType": "FUNDING_FEE", "income": "-0.01250000", "asset": "USDT",
+             "info": "FUNDING_FEE", "time": 1700006400000i64, "tranId": 9689322392u64, "tradeId": ""}
+        ])))
+        .mount(&server)
+        .await;
+
+    let since = chrono::DateTime::from_timestamp_millis(1700000000000).unwrap();
+    let payments = binance.get_funding_payments("BTC-USDT", since).await.unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].id, "9689322392");
+    assert_eq!(payments[0].amount, Decimal::from_str("-0.0125").unwrap());
+    assert_eq!(payments[0].asset, "USDT");
+    assert_eq!(payments[0].timestamp.timestamp_millis(), 1700006400000);
+}
+
+#[tokio::test]
+async fn contract_spec_reads_exchange_filters() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/exchangeInfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "symbols": [{
+                "symbol": "ETHUSDT",
+                "quoteAsset": "USDT",
+                "filters": [
+                    {"filterType": "PRICE_FILTER", "minPrice": "39.86", "maxPrice": "306177", "tickSize": "0.01"},
+                    {"filterType": "LOT_SIZE", "maxQty": "10000", "minQty": "0.001", "stepSize": "0.001"},
+                    {"filterType": "MIN_NOTIONAL", "notional": "20"}
+                ]
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let spec = binance.get_contract_spec("ETH-USDT").await.unwrap();
+    assert_eq!(spec.tick_size, Decimal::from_str("0.01").unwrap());
+    assert_eq!(spec.lot_size, Decimal::from_str("0.001").unwrap());
+    assert_eq!(spec.min_size, Decimal::from_str("0.001").unwrap());
+    assert_eq!(spec.min_notional, Decimal::from(20));
+    assert_eq!(
+        spec.round_size_down(Decimal::from_str("1.23456").unwrap()),
+        Decimal::from_str("1.234").unwrap()
+    );
+}
+
+fn exchange_filters(tick: &str, step: &str) -> serde_json::Value {
+    serde_json::json!([
+        {"filterType": "PRICE_FILTER", "tickSize": tick},
+        {"filterType": "LOT_SIZE", "minQty": step, "stepSize": step},
+        {"filterType": "MIN_NOTIONAL", "notional": "5"}
+    ])
+}
+
+#[tokio::test]
+async fn instruments_map_thousand_unit_contracts_to_the_asset() {
+    let server = MockServer::start().await;
+    let binance = connector(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/exchangeInfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "symbols": [
+                {"symbol": "1000PEPEUSDT", "baseAsset": "1000PEPE", "quoteAsset": "USDT",
+                 "contractType": "PERPETUAL", "status": "TRADING",
+                 "filters": exchange_filters("0.0000001", "1")},
+                {"symbol": "BTCUSDC", "baseAsset": "BTC", "quoteAsset": "USDC",
+                 "contractType": "PERPETUAL", "status": "TRADING",
+                 "filters": exchange_filters("0.1", "0.001")},
+                {"symbol": "BTCUSDT_250926", "baseAsset": "BTC", "quoteAsset": "USDT",
+                 "contractType": "CURRENT_QUARTER", "status": "TRADING",
+                 "filters": exchange_filters("0.1", "0.001")}
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let instruments = binance.get_instruments().await.unwrap();
+    assert_eq!(instruments.len(), 1);
+    let pepe = &instruments[0];
+    assert_eq!((pepe.asset.as_str(), pepe.native_symbol.as_str()), ("PEPE", "1000PEPEUSDT"));
+    assert_eq!(pepe.spec.symbol, "PEPE");
+    assert_eq!(pepe.spec.contract_multiplier, Decimal::from(1000));
+    assert_eq!(pepe.spec.tick_size, Decimal::from_str("0.0000001").unwrap());
+}
+
+#[tokio::test]
+async fn loaded_instruments_route_canonical_symbols() {
+    let server = MockServer::start().await;
+    let instruments = Arc::new(InstrumentRegistry::new());
+    let binance = connector(&server).await.with_instruments(instruments.clone());
+
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/exchangeInfo"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "symbols": [{"symbol": "1000PEPEUSDT", "baseAsset": "1000PEPE", "quoteAsset": "USDT",
+                         "filters": exchange_filters("0.0000001", "1")}]
+        })))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fapi/v2/positionRisk"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"symbol": "1000PEPEUSDT", "positionAmt": "500", "entryPrice": "0.012", "leverage": "3"}
+        ])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/depth"))
+        .and(query_param("symbol", "1000PEPEUSDT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "bids": [["0.0120000", "1000"]],
+            "asks": [["0.0120010", "800"]]
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut registry = ConnectorRegistry::new();
+    registry.register(Arc::new(binance.clone()));
+    registry.load_instruments(&[Venue::Binance], &instruments).await;
+
+    let book = binance.get_order_book("PEPE", 5).await.unwrap();
+    assert_eq!(book.symbol, "PEPE");
+    let positions = binance.get_positions().await.unwrap();
+    assert_eq!(positions[0].symbol, "PEPE");
+}
@@ -0,0 +1,219 @@
+use arb_core::types::{PositionSide, TradeStatus};
+use connectors::signing::hmac_sha256_hex;
+use connectors::{request_not_sent, BybitConnector, BybitError, VenueConnector};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+const API_KEY: &str = "test-key";
+const API_SECRET: &str = "test-secret";
+
+/// Accepts only requests whose `X-BAPI-SIGN` matches the v5 signing payload.
+struct ValidSignature;
+
+impl wiremock::Match for ValidSignature {
+    fn matches(&self, request: &Request) -> bool {
+        let header = |name: &str| {
+            request
+                .headers
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string()
+        };
+        let payload = if request.method.as_str() == "GET" {
+            request.url.query().unwrap_or_default().to_string()
+        } else {
+            String::from_utf8_lossy(&request.body).to_string()
+        };
+        let expected = hmac_sha256_hex(
+            API_SECRET,
+            &format!(
+                "{}{}{}{}",
+                header("X-BAPI-TIMESTAMP"),
+                API_KEY,
+                header("X-BAPI-RECV-WINDOW"),
+                payload
+            ),
+        );
+        header("X-BAPI-SIGN") == expected
+    }
+}
+
+fn ok(result: serde_json::Value) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_json(serde_json::json!({
+        "retCode": 0,
+        "retMsg": "OK",
+        "result": result
+    }))
+}
+
+async fn connector(server: &MockServer) -> BybitConnector {
+    let nanos = chrono::Utc::now().timestamp_nanos_opt().unwrap();
+    Mock::given(method("GET"))
+        .and(path("/v5/market/time"))
+        .respond_with(ok(serde_json::json!({
+            "timeSecond": (nanos / 1_000_000_000).to_string(),
+            "timeNano": nanos.to_string()
+        })))
+        .mount(server)
+        .await;
+
+    BybitConnector::new(API_KEY.to_string(), API_SECRET.to_string(), server.uri(), 5000)
+}
+
+#[tokio::test]
+async fn place_order_creates_then_reports_fill() {
+    let server = MockServer::start().await;
+    let bybit = connector(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/v5/order/create"))
+        .and(header("X-BAPI-API-KEY", API_KEY))
+        .and(body_partial_json(serde_json::json!({
+            "category": "linear",
+            "symbol": "BTCUSDT",
+            "side": "Buy",
+            "orderType": "Limit",
+            "qty": "0.5",
+            "price": "64000"
+        })))
+        .and(ValidSignature)
+        .respond_with(ok(serde_json::json!({"orderId": "abc-1", "orderLinkId": ""})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/v5/order/realtime"))
+        .and(query_param("orderId", "abc-1"))
+        .and(ValidSignature)
+        .respond_with(ok(serde_json::json!({
+            "list": [{
+                "orderId": "abc-1",
+                "symbol": "BTCUSDT",
+                "side": "Buy",
+                "orderStatus": "Filled",
+                "price": "64000",
+                "avgPrice": "63990.5",
+                "qty": "0.5",
+                "cumExecQty": "0.5",
+                "updatedTime": "1700000000000"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let trade = bybit
+        .place_order("BTC-USDT", PositionSide::Long, Decimal::from_str("0.5").unwrap(), Decimal::from(64000))
+        .await
+        .unwrap();
+
+    assert_eq!(trade.status, TradeStatus::Filled);
+    assert_eq!(trade.price, Decimal::from_str("63990.5").unwrap());
+    assert_eq!(trade.order_id.as_deref(), Some("abc-1"));
+}
+
+#[tokio::test]
+async fn ret_codes_map_to_typed_errors() {
+    let server = MockServer::start().await;
+    let bybit = connector(&server).await;
+
+    Mock::given(method("POST"))
+        .and(path("/v5/order/cancel"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "retCode": 110001,
+            "retMsg": "order not exists or too late to cancel",
+            "result": {}
+        })))
+        .mount(&server)
+        .await;
+
+    let err = bybit.cancel_order("BTC-USDT", "missing").await.unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<BybitError>(),
+        Some(BybitError::OrderNotFound(_))
+    ));
+}
+
+#[tokio::test]
+async fn only_known_rejections_count_as_unplaced_orders() {
+    let server = MockServer::start().await;
+    let bybit = connector(&server).await;
+    let responses = [(110007, "ab not enough for new order"), (10016, "Server Error")];
+    for (code, message) in responses {
+        Mock::given(method("POST"))
+            .and(path("/v5/order/create"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "retCode": code,
+                "retMsg": message,
+                "result": {}
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+    }
+    let size = Decimal::ONE;
+    let place = || bybit.place_order("BTC-USDT", PositionSide::Long, size, Decimal::ZERO);
+
+    assert!(bybit.order_not_placed(&place().await.unwrap_err()));
+    // An unrecognised code may mean the venue lost track of the request
+    assert!(!bybit.order_not_placed(&place().await.unwrap_err()));
+
+    // Nothing listens on port 1, so the request never left
+    let refused = reqwest::get("http://127.0.0.1:1").await.unwrap_err();
+    assert!(request_not_sent(&anyhow::Error::new(refused).context("Failed to send order")));
+    assert!(!bybit.order_not_placed(&anyhow::anyhow!("Failed to parse order response")));
+}
+
+#[tokio::test]
+async fn position_list_reports_open_positions() {
+    let server = MockServer::start().await;
+    let bybit = connector(&server).await;
+
+    Mock::given(method("GET"))
+        .and(path("/v5/position/list"))
+        .and(query_param("category", "linear"))
+        .and(ValidSignature)
+        .respond_with(ok(serde_json::json!({
+            "list": [
+                {"symbol": "ETHUSDT", "side": "Sell", "size": "3", "avgPrice": "3100.5", "leverage": "4", "updatedTime": "1700000000000"},
+                {"symbol": "SOLUSDT", "side": "", "size": "0", "avgPrice": "0", "leverage": "10", "updatedTime": ""}
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let positions = bybit.get_positions().await.unwrap();
+    assert_eq!(positions.len(), 1);
+    assert_eq!(positions[0].symbol, "ETH");
+    assert_eq!(positions[0].side, PositionSide::Short);
+    assert_eq!(positions[0].leverage, Decimal::from(4));
+}
+
+#[tokio::test]
+async fn funding_rate_reads_ticker_interval() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/v5/market/tickers"))
+        .and(query_param("symbol", "SOLUSDT"))
+        .respond_with(ok(serde_json::json!({
+            "category": "linear",
+            "list": [{
+                "symbol": "SOLUSDT",
+                "turnover24h": "1000000",
+                "fundingRate": "0.0001",
+                "nextFundingTime": "1700003600000",
+                "fundingIntervalHour": "1"
+            }]
+        })))
+        .mount(&server)
+        .await;
+
+    let bybit = connector(&server).await;
+    let funding = bybit.get_funding_rate("SOL-USDT").await.unwrap();
+    assert_eq!(funding.rate, Decimal::from_str("0.0001").unwrap());
+    assert_eq!(funding.interval_hours, 1);
+    assert_eq!(funding.next_funding_time.unwrap().timestamp_millis(), 1700003600000);
+    assert_eq!(funding.annualized_rate(), Decimal::from_str("0.876").unwrap());
+}
@@ -0,0 +1,362 @@
+use arb_core::config::{DexKind, HyperEvmConfig, TokenConfig};
+use arb_core::types::{PositionSide, TradeStatus};
+use connectors::hyperevm::{
+    decode_uint_saturating, parse_address, selector, Eip1559Transaction,
+};
+use connectors::signing::{keccak256, EvmWallet};
+use connectors::{HyperEvmConnector, VenueConnector};
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::Arc;
+use wiremock::matchers::{body_partial_json, method};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const ROUTER: &str = "0x1111111111111111111111111111111111111111";
+const QUOTER: &str = "0x2222222222222222222222222222222222222222";
+const WETH: &str = "0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+const USDC: &str = "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb";
+const TEST_KEY: &str = "0x0123456789012345678901234567890123456789012345678901234567890123";
+
+/// Constant-price pool behind a router/quoter: 1 WETH (18 decimals) = 3000
+/// USDC (6 decimals), minus a 0.3% fee on the input.
+struct Pool;
+
+impl Pool {
+    fn amount_out(token_in: &[u8], amount_in: u128) -> u128 {
+        let after_fee = amount_in * 997 / 1000;
+        if token_in == parse_address(WETH).unwrap() {
+            after_fee * 3000 / 1_000_000_000_000
+        } else {
+            after_fee * 1_000_000_000_000 / 3000
+        }
+    }
+}
+
+fn word(data: &[u8], index: usize) -> &[u8] {
+    &data[4 + index * 32..4 + (index + 1) * 32]
+}
+
+fn uint(value: u128) -> String {
+    format!("{:064x}", value)
+}
+
+impl Respond for Pool {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let call = &body["params"][0];
+        let data = hex::decode(call["data"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        let amount = |index: usize| u128::from_be_bytes(word(&data, index)[16..].try_into().unwrap());
+
+        let result = if data[..4] == selector("decimals()") {
+            let to = call["to"].as_str().unwrap();
+            uint(if to == WETH { 18 } else { 6 })
+        } else if data[..4] == selector("allowance(address,address)") {
+            uint(0)
+        } else if data[..4] == selector("getAmountsOut(uint256,address[])") {
+            assert_eq!(call["to"], ROUTER);
+            assert_eq!(amount(2), 2, "path length");
+            let amount_in = amount(0);
+            let out = Pool::amount_out(&word(&data, 3)[12..], amount_in);
+            format!("{}{}{}{}", uint(32), uint(2), uint(amount_in), uint(out))
+        } else if data[..4] == selector("quoteExactInputSingle((address,address,uint256,uint24,uint160))") {
+            assert_eq!(call["to"], QUOTER);
+            assert_eq!(amount(3), 500, "fee tier");
+            let out = Pool::amount_out(&word(&data, 0)[12..], amount(2));
+            format!("{}{}{}{}", uint(out), uint(0), uint(1), uint(90000))
+        } else {
+            panic!("unexpected call {}", hex::encode(&data[..4]));
+        };
+
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "result": format!("0x{}", result)
+        }))
+    }
+}
+
+/// Token list with decimals either configured or left to be read on-chain.
+fn config(rpc_url: String, dex_kind: DexKind, configured_decimals: bool) -> HyperEvmConfig {
+    let decimals = |d: u8| configured_decimals.then_some(d);
+    HyperEvmConfig {
+        rpc_url,
+        dex_router_address: ROUTER.to_string(),
+        dex_kind,
+        quoter_address: QUOTER.to_string(),
+        fee_tier: 500,
+        quote_size: 2.0,
+        tokens: vec![
+            TokenConfig { symbol: "ETH".to_string(), address: WETH.to_string(), decimals: decimals(18) },
+            TokenConfig { symbol: "USDC".to_string(), address: USDC.to_string(), decimals: decimals(6) },
+        ],
+        private_key: TEST_KEY.to_string(),
+        chain_id: 999,
+        max_slippage_bps: 50,
+        swap_deadline_secs: 60,
+        receipt_timeout_ms: 5000,
+    }
+}
+
+#[tokio::test]
+async fn v2_router_quotes_exact_input() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({"method": "eth_call"})))
+        .respond_with(Pool)
+        .mount(&server)
+        .await;
+
+    let evm = HyperEvmConnector::new(config(server.uri(), DexKind::V2, true)).unwrap();
+    let quote = evm.quote("ETH", "USDC", Decimal::from_str("1.5").unwrap()).await.unwrap();
+    assert_eq!(quote.amount_in_raw, 1_500_000_000_000_000_000);
+    assert_eq!(quote.amount_out_raw, 4_486_500_000);
+    assert_eq!(quote.amount_out, Decimal::from_str("4486.5").unwrap());
+    assert_eq!(quote.price(), Decimal::from(2991));
+}
+
+#[tokio::test]
+async fn v3_quoter_reads_decimals_on_chain_and_brackets_mid() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(body_partial_json(serde_json::json!({"method": "eth_call"})))
+        .respond_with(Pool)
+        .mount(&server)
+        .await;
+
+    let evm = HyperEvmConnector::new(config(server.uri(), DexKind::V3, false)).unwrap();
+    let market = evm.get_market_data("ETH-USDC").await.unwrap();
+
+    assert_eq!(market.bid, Decimal::from(2991));
+    assert!(market.ask > Decimal::from(3000) && market.ask < Decimal::from(3019));
+    assert!(market.bid < market.price && market.price < market.ask);
+
+    // Two decimals() lookups, then cached for the second quote
+    let calls = server.received_requests().await.unwrap();
+    let decimals_calls = calls
+        .iter()
+        .filter(|r| String::from_utf8_lossy(&r.body).contains(&hex::encode(selector("decimals()"))))
+        .count();
+    assert_eq!(decimals_calls, 2);
+}
+
+#[tokio::test]
+async fn rpc_errors_are_surfaced() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "error": {"code": 3, "message": "execution reverted: INSUFFICIENT_LIQUIDITY"}
+        })))
+        .mount(&server)
+        .await;
+
+    let evm = HyperEvmConnector::new(config(server.uri(), DexKind::V2, true)).unwrap();
+    let err = evm.quote("ETH", "USDC", Decimal::ONE).await.unwrap_err();
+    assert!(format!("{:#}", err).contains("INSUFFICIENT_LIQUIDITY"), "{:#}", err);
+
+    let err = evm.quote("ETH", "DOGE", Decimal::ONE).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown HyperEVM token DOGE"));
+}
+
+#[test]
+fn eip1559_signature_recovers_to_wallet() {
+    let wallet = EvmWallet::from_hex(TEST_KEY).unwrap();
+    let tx = Eip1559Transaction {
+        chain_id: 999,
+        nonce: 7,
+        max_priority_fee_per_gas: 1_000_000_000,
+        max_fee_per_gas: 3_000_000_000,
+        gas_limit: 210_000,
+        to: parse_address(ROUTER).unwrap(),
+        value: 0,
+        data: vec![0xde, 0xad, 0xbe, 0xef],
+    };
+    let signature = wallet.sign_hash(&tx.signing_hash());
+
+    let message = libsecp256k1::Message::parse(&tx.signing_hash());
+    let mut rs = [0u8; 64];
+    rs[..32].copy_from_slice(&signature.r);
+    rs[32..].copy_from_slice(&signature.s);
+    let public = libsecp256k1::recover(
+        &message,
+        &libsecp256k1::Signature::parse_standard(&rs).unwrap(),
+        &libsecp256k1::RecoveryId::parse(signature.y_parity()).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(&keccak256(&public.serialize()[1..])[12..], wallet.address());
+
+    let raw = tx.encode_signed(&signature);
+    assert_eq!(raw[0], 0x02);
+    assert!(raw.ends_with(&signature.s[signature.s.iter().position(|b| *b != 0).unwrap()..]));
+}
+
+/// JSON-RPC node that answers quotes from `Pool`, accepts any transaction and
+/// reports every receipt as a successful swap paying out 2990 USDC.
+#[derive(Clone, Default)]
+struct Node {
+    calls: Arc<Mutex<Vec<serde_json::Value>>>,
+}
+
+impl Node {
+    fn calls(&self, method: &str) -> Vec<serde_json::Value> {
+        self.calls
+            .lock()
+            .iter()
+            .filter(|c| c["method"] == method)
+            .map(|c| c["params"].clone())
+            .collect()
+    }
+}
+
+impl Respond for Node {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        self.calls.lock().push(body.clone());
+        let wallet = EvmWallet::from_hex(TEST_KEY).unwrap();
+
+        let result = match body["method"].as_str().unwrap() {
+            "eth_call" => return Pool.respond(request),
+            "eth_estimateGas" => json_quantity(100_000),
+            "eth_maxPriorityFeePerGas" => json_quantity(1_000_000_000),
+            "eth_getBlockByNumber" => serde_json::json!({"baseFeePerGas": "0x3b9aca00"}),
+            "eth_getTransactionCount" => json_quantity(5),
+            "eth_sendRawTransaction" => {
+                let raw = hex::decode(body["params"][0].as_str().unwrap().trim_start_matches("0x")).unwrap();
+                serde_json::json!(format!("0x{}", hex::encode(keccak256(&raw))))
+            }
+            "eth_getTransactionReceipt" => serde_json::json!({
+                "status": "0x1",
+                "logs": [{
+                    "address": USDC,
+                    "topics": [
+                        format!("0x{}", hex::encode(keccak256(b"Transfer(address,address,uint256)"))),
+                        format!("0x{}", uint(0)),
+                        format!("0x000000000000000000000000{}", hex::encode(wallet.address())),
+                    ],
+                    "data": format!("0x{}", uint(2_990_000_000)),
+                }]
+            }),
+            other => panic!("unexpected method {}", other),
+        };
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "result": result
+        }))
+    }
+}
+
+fn json_quantity(value: u128) -> serde_json::Value {
+    serde_json::json!(format!("0x{:x}", value))
+}
+
+/// Nonce of a signed type-2 transaction: the second field after the list header.
+fn raw_nonce(raw: &str) -> u8 {
+    let raw = hex::decode(raw.trim_start_matches("0x")).unwrap();
+    assert_eq!(raw[0], 0x02);
+    let header = 1 + 1 + (raw[1] as usize).saturating_sub(0xf7);
+    // chain id 999 encodes as 0x82 0x03 0xe7
+    assert_eq!(&raw[header..header + 3], &[0x82, 0x03, 0xe7]);
+    raw[header + 3]
+}
+
+#[tokio::test]
+async fn sell_approves_router_then_swaps_with_min_output() {
+    let server = MockServer::start().await;
+    let node = Node::default();
+    Mock::given(method("POST")).respond_with(node.clone()).mount(&server).await;
+
+    let evm = HyperEvmConnector::new(config(server.uri(), DexKind::V2, true)).unwrap();
+    let trade = evm
+        .place_order("ETH-USDC", PositionSide::Short, Decimal::ONE, Decimal::ZERO)
+        .await
+        .unwrap();
+
+    assert_eq!(trade.status, TradeStatus::Filled);
+    assert_eq!(trade.size, Decimal::ONE);
+    assert_eq!(trade.price, Decimal::from(2990));
+    assert!(trade.order_id.unwrap().starts_with("0x"));
+
+    // Approval then swap, nonces fetched once and incremented locally
+    let sent = node.calls("eth_sendRawTransaction");
+    assert_eq!(sent.len(), 2);
+    assert_eq!(node.calls("eth_getTransactionCount").len(), 1);
+    assert_eq!(raw_nonce(sent[0][0].as_str().unwrap()), 5);
+    assert_eq!(raw_nonce(sent[1][0].as_str().unwrap()), 6);
+
+    let estimates = node.calls("eth_estimateGas");
+    assert_eq!(estimates[0][0]["to"], WETH);
+    let swap = hex::decode(estimates[1][0]["data"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+    assert_eq!(
+        swap[..4],
+        selector("swapExactTokensForTokens(uint256,uint256,address[],address,uint256)")
+    );
+    // Quoted 2991 USDC less 50 bps
+    assert_eq!(u128::from_be_bytes(word(&swap, 1)[16..].try_into().unwrap()), 2_976_045_000);
+}
+
+#[tokio::test]
+async fn limit_price_above_quote_is_refused_before_sending() {
+    let server = MockServer::start().await;
+    let node = Node::default();
+    Mock::given(method("POST")).respond_with(node.clone()).mount(&server).await;
+
+    let evm = HyperEvmConnector::new(config(server.uri(), DexKind::V2, true)).unwrap();
+    let err = evm
+        .place_order("ETH-USDC", PositionSide::Short, Decimal::ONE, Decimal::from(3100))
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("below the required minimum"), "{}", err);
+    assert!(node.calls("eth_sendRawTransaction").is_empty());
+}
+
+#[test]
+fn saturating_decode_caps_wide_values_but_not_short_output() {
+    assert_eq!(decode_uint_saturating(&[0xff; 32], 0).unwrap(), u128::MAX);
+    let mut seven = [0; 32];
+    seven[31] = 7;
+    assert_eq!(decode_uint_saturating(&seven, 0).unwrap(), 7);
+    assert!(decode_uint_saturating(&[], 0).is_err());
+    assert!(decode_uint_saturating(&seven[..31], 0).is_err());
+}
+
+/// A token address with no contract behind it: `allowance` returns no data.
+struct NoAllowance(Node);
+
+impl Respond for NoAllowance {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let data = body["params"][0]["data"].as_str().unwrap_or_default();
+        let allowance = hex::encode(selector("allowance(address,address)"));
+        if body["method"] == "eth_call" && data.trim_start_matches("0x").starts_with(&allowance) {
+            return ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": body["id"],
+                "result": "0x"
+            }));
+        }
+        self.0.respond(request)
+    }
+}
+
+#[tokio::test]
+async fn unreadable_allowance_fails_instead_of_skipping_approval() {
+    let server = MockServer::start().await;
+    let node = Node::default();
+    Mock::given(method("POST"))
+        .respond_with(NoAllowance(node.clone()))
+        .mount(&server)
+        .await;
+
+    let evm = HyperEvmConnector::new(config(server.uri(), DexKind::V2, true)).unwrap();
+    let err = evm
+        .place_order("ETH-USDC", PositionSide::Short, Decimal::ONE, Decimal::ZERO)
+        .await
+        .unwrap_err();
+
+    assert!(format!("{:#}", err).contains("allowance"), "{:#}", err);
+    assert!(node.calls("eth_sendRawTransaction").is_empty());
+}
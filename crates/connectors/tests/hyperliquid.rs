@@ -0,0 +1,358 @@
+use arb_core::types::{OrderOptions, PositionSide, TimeInForce, TradeStatus};
+use connectors::hyperliquid::{
+    action_hash, phantom_agent_hash, round_price, sign_l1_action, to_wire, OrderAction,
+    OrderTypeWire, OrderWire,
+};
+use connectors::signing::EvmWallet;
+use connectors::{HyperliquidConnector, VenueConnector};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use wiremock::matchers::{body_partial_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const TEST_KEY: &str = "0x0123456789012345678901234567890123456789012345678901234567890123";
+
+fn order(asset: u32, price: &str, size: &str, tif: TimeInForce) -> OrderAction {
+    OrderAction::new(vec![OrderWire {
+        a: asset,
+        b: true,
+        p: to_wire(Decimal::from_str(price).unwrap()),
+        s: to_wire(Decimal::from_str(size).unwrap()),
+        r: false,
+        t: OrderTypeWire::limit(tif),
+    }])
+}
+
+#[test]
+fn wallet_derives_ethereum_address() {
+    let wallet =
+        EvmWallet::from_hex("0x4c0883a69102937d6231471b5dbb6204fe5129617082792ae468d01a3f362318").unwrap();
+    assert_eq!(wallet.address_hex(), "0x2c7536e3605d9c16a7a3d7b1898e529396a65c23");
+}
+
+#[test]
+fn phantom_agent_matches_production_vector() {
+    let action = order(4, "1670.1", "0.0147", TimeInForce::ImmediateOrCancel);
+    let hash = action_hash(&action, None, 1677777606040).unwrap();
+    assert_eq!(
+        hex::encode(hash),
+        "0fcbeda5ae3c4950a548021552a4fea2226858c4453571bf3f24ba017eac2908"
+    );
+}
+
+#[test]
+fn l1_order_signature_matches_sdk_vectors() {
+    let wallet = EvmWallet::from_hex(TEST_KEY).unwrap();
+    let action = order(1, "100", "100", TimeInForce::GoodTilCancelled);
+
+    let mainnet = sign_l1_action(&wallet, &action, None, 0, true).unwrap();
+    assert_eq!(
+        hex::encode(mainnet.r),
+        "d65369825a9df5d80099e513cce430311d7d26ddf477f5b3a33d2806b100d78e"
+    );
+    assert_eq!(
+        hex::encode(mainnet.s),
+        "2b54116ff64054968aa237c20ca9ff68000f977c93289157748a3162b6ea940e"
+    );
+    assert_eq!(mainnet.v, 28);
+
+    let testnet = sign_l1_action(&wallet, &action, None, 0, false).unwrap();
+    assert_eq!(
+        hex::encode(testnet.r),
+        "82b2ba28e76b3d761093aaded1b1cdad4960b3af30212b343fb2e6cdfa4e3d54"
+    );
+    assert_eq!(
+        hex::encode(testnet.s),
+        "6b53878fc99d26047f4d7e8c90eb98955a109f44209163f52d8dc4278cbbd9f5"
+    );
+    assert_eq!(testnet.v, 27);
+}
+
+#[test]
+fn mainnet_and_testnet_sources_differ() {
+    let id = [7u8; 32];
+    assert_ne!(phantom_agent_hash(&id, true), phantom_agent_hash(&id, false));
+}
+
+#[test]
+fn prices_round_to_five_significant_figures() {
+    assert_eq!(round_price(Decimal::from_str("1670.123").unwrap(), 4), Decimal::from_str("1670.1").unwrap());
+    assert_eq!(round_price(Decimal::from_str("0.0123456").unwrap(), 0), Decimal::from_str("0.012346").unwrap());
+    assert_eq!(to_wire(Decimal::from_str("100.000").unwrap()), "100");
+}
+
+async fn mount_meta(server: &MockServer) {
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "meta"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "universe": [
+                {"name": "BTC", "szDecimals": 5, "maxLeverage": 50},
+                {"name": "ETH", "szDecimals": 4, "maxLeverage": 50}
+            ]
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn place_order_parses_fill_status() {
+    let server = MockServer::start().await;
+    mount_meta(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/exchange"))
+        .and(body_partial_json(serde_json::json!({
+            "action": {
+                "type": "order",
+                "orders": [{"a": 1, "b": false, "p": "3000.5", "s": "0.25", "r": true, "t": {"limit": {"tif": "Ioc"}}}],
+                "grouping": "na"
+            }
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "ok",
+            "response": {"type": "order", "data": {"statuses": [
+                {"filled": {"totalSz": "0.2", "avgPx": "3000.4", "oid": 991}}
+            ]}}
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hl = HyperliquidConnector::new(server.uri(), TEST_KEY.to_string()).unwrap();
+    let trade = hl
+        .place_order_with_options(
+            "ETH",
+            PositionSide::Short,
+            Decimal::from_str("0.25").unwrap(),
+            Decimal::from_str("3000.5").unwrap(),
+            OrderOptions {
+                time_in_force: TimeInForce::ImmediateOrCancel,
+                reduce_only: true,
+            },
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(trade.status, TradeStatus::PartiallyFilled);
+    assert_eq!(trade.size, Decimal::from_str("0.2").unwrap());
+    assert_eq!(trade.order_id.as_deref(), Some("991"));
+}
+
+#[tokio::test]
+async fn place_order_reports_rejections() {
+    let server = MockServer::start().await;
+    mount_meta(&server).await;
+    Mock::given(method("POST"))
+        .and(path("/exchange"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "ok",
+            "response": {"type": "order", "data": {"statuses": [
+                {"error": "Insufficient margin to place order."}
+            ]}}
+        })))
+        .mount(&server)
+        .await;
+
+    let hl = HyperliquidConnector::new(server.uri(), TEST_KEY.to_string()).unwrap();
+    let trade = hl
+        .place_order("BTC", PositionSide::Long, Decimal::from_str("0.01").unwrap(), Decimal::from(60000))
+        .await
+        .unwrap();
+
+    assert_eq!(trade.status, TradeStatus::Rejected);
+    assert!(trade.order_id.is_none());
+}
+
+#[tokio::test]
+async fn l2_book_parses_bids_and_asks() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "l2Book", "coin": "BTC"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "coin": "BTC",
+            "time": 1700000000000i64,
+            "levels": [
+                [{"px": "64000", "sz": "3", "n": 4}, {"px": "63999", "sz": "1", "n": 1}],
+                [{"px": "64002", "sz": "1", "n": 2}, {"px": "64003", "sz": "5", "n": 3}]
+            ]
+        })))
+        .mount(&server)
+        .await;
+
+    let hl = HyperliquidConnector::new(server.uri(), String::new()).unwrap();
+    let book = hl.get_order_book("BTC", 10).await.unwrap();
+    assert_eq!(book.bids.len(), 2);
+    assert_eq!(book.best_bid().unwrap().price, Decimal::from(64000));
+    assert_eq!(book.best_ask().unwrap().price, Decimal::from(64002));
+    assert_eq!(book.mid(), Some(Decimal::from(64001)));
+    // Thin ask side pulls the microprice towards the ask
+    assert_eq!(book.microprice(), Some(Decimal::from_str("64001.5").unwrap()));
+
+    let market = hl.get_market_data("BTC").await.unwrap();
+    assert_eq!(market.bid, Decimal::from(64000));
+    assert_eq!(market.ask, Decimal::from(64002));
+    assert_eq!(market.price, Decimal::from(64001));
+}
+
+#[tokio::test]
+async fn meta_and_asset_ctxs_pair_contexts_with_universe() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "metaAndAssetCtxs"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {
+                "universe": [
+                    {"name": "BTC", "szDecimals": 5, "maxLeverage": 50},
+                    {"name": "ETH", "szDecimals": 4, "maxLeverage": 50}
+                ]
+            },
+            [
+                {"funding": "0.0000125", "openInterest": "100", "dayNtlVlm": "1500000.5", "markPx": "64000"},
+                {"funding": "-0.00002", "openInterest": "900", "dayNtlVlm": "2500000.0", "markPx": "3000"}
+            ]
+        ])))
+        .mount(&server)
+        .await;
+
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "predictedFundings"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            ["BTC", [
+                ["HlPerp", {"fundingRate": "0.0000125", "nextFundingTime": 1733961600000u64}]
+            ]],
+            ["ETH", [
+                ["BinPerp", {"fundingRate": "0.0001", "nextFundingTime": 1733961600000u64}],
+                ["HlPerp", {"fundingRate": "0.00001", "nextFundingTime": 1733961600000u64}],
+                ["BybitPerp", null]
+            ]]
+        ])))
+        .mount(&server)
+        .await;
+
+    let hl = HyperliquidConnector::new(server.uri(), String::new()).unwrap();
+    let funding = hl.get_funding_rate("ETH").await.unwrap();
+    assert_eq!(funding.rate, Decimal::from_str("-0.00002").unwrap());
+    assert_eq!(funding.predicted_rate, Some(Decimal::from_str("0.00001").unwrap()));
+    assert_eq!(funding.interval_hours, 1);
+    assert_eq!(funding.annualized_rate(), Decimal::from_str("-0.1752").unwrap());
+    let next = funding.next_funding_time.unwrap();
+    assert_eq!(next.timestamp() % 3600, 0);
+    assert!(next > funding.timestamp);
+
+    let top = hl.get_top_symbols_by_volume(2).await.unwrap();
+    assert_eq!(top, vec!["ETH".to_string(), "BTC".to_string()]);
+}
+
+#[tokio::test]
+async fn user_funding_filters_by_coin() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "userFunding", "startTime": 1700000000000i64})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"time": 1700003600000i64, "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+             "delta": {"type": "funding", "coin": "ETH", "usdc": "-3.625312", "szi": "49.1477", "fundingRate": "0.0000417"}},
+            {"time": 1700003600000i64, "hash": "0x0000000000000000000000000000000000000000000000000000000000000000",
+             "delta": {"type": "funding", "coin": "BTC", "usdc": "1.5", "szi": "-0.5", "fundingRate": "0.0000125"}}
+        ])))
+        .mount(&server)
+        .await;
+
+    let hl = HyperliquidConnector::new(server.uri(), TEST_KEY.to_string()).unwrap();
+    let since = chrono::DateTime::from_timestamp_millis(1700000000000).unwrap();
+    let payments = hl.get_funding_payments("ETH", since).await.unwrap();
+    assert_eq!(payments.len(), 1);
+    assert_eq!(payments[0].amount, Decimal::from_str("-3.625312").unwrap());
+    assert_eq!(payments[0].asset, "USDC");
+    assert_eq!(payments[0].id, "1700003600000-ETH");
+}
+
+#[tokio::test]
+async fn instruments_resolve_k_prefixed_coins() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "meta"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "universe": [
+                {"name": "BTC", "szDecimals": 5, "maxLeverage": 50},
+                {"name": "kPEPE", "szDecimals": 0, "maxLeverage": 10},
+                {"name": "FTT", "szDecimals": 1, "maxLeverage": 3, "isDelisted": true}
+            ]
+        })))
+        .mount(&server)
+        .await;
+    let hyperliquid = HyperliquidConnector::new(server.uri(), String::new()).unwrap();
+
+    let instruments = hyperliquid.get_instruments().await.unwrap();
+
+    let names: Vec<(&str, &str)> = instruments
+        .iter()
+        .map(|i| (i.asset.as_str(), i.native_symbol.as_str()))
+        .collect();
+    assert_eq!(names, vec![("BTC", "BTC"), ("PEPE", "kPEPE")]);
+    assert_eq!(instruments[1].spec.contract_multiplier, Decimal::from(1000));
+    assert_eq!(instruments[1].quote_asset, "USDC");
+}
+
+async fn mount_order_status(server: &MockServer, status: &str, remaining: &str) {
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({"type": "orderStatus", "oid": 991})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "status": "order",
+            "order": {
+                "order": {
+                    "coin": "ETH", "side": "A", "limitPx": "2990.0", "sz": remaining,
+                    "oid": 991, "timestamp": 1700000000000i64, "origSz": "1.0"
+                },
+                "status": status,
+                "statusTimestamp": 1700000001000i64
+            }
+        })))
+        .mount(server)
+        .await;
+}
+
+#[tokio::test]
+async fn cancelled_order_with_fills_reports_them_at_their_average_price() {
+    let server = MockServer::start().await;
+    mount_order_status(&server, "canceled", "0.4").await;
+    Mock::given(method("POST"))
+        .and(path("/info"))
+        .and(body_partial_json(serde_json::json!({
+            "type": "userFillsByTime",
+            "startTime": 1700000000000i64
+        })))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+            {"coin": "ETH", "px": "3000.0", "sz": "0.2", "side": "A", "oid": 991},
+            {"coin": "ETH", "px": "3003.0", "sz": "0.4", "side": "A", "oid": 991},
+            {"coin": "ETH", "px": "2500.0", "sz": "5.0", "side": "B", "oid": 992}
+        ])))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let hl = HyperliquidConnector::new(server.uri(), TEST_KEY.to_string()).unwrap();
+    let trade = hl.get_order("ETH", "991").await.unwrap();
+
+    assert_eq!(trade.status, TradeStatus::PartiallyFilled);
+    assert_eq!(trade.size, Decimal::from_str("0.6").unwrap());
+    assert_eq!(trade.price, Decimal::from(3002));
+    assert_eq!(trade.side, PositionSide::Short);
+}
+
+#[tokio::test]
+async fn cancelled_order_without_fills_is_cancelled() {
+    let server = MockServer::start().await;
+    mount_order_status(&server, "marginCanceled", "1.0").await;
+
+    let hl = HyperliquidConnector::new(server.uri(), TEST_KEY.to_string()).unwrap();
+    let trade = hl.get_order("ETH", "991").await.unwrap();
+
+    assert_eq!(trade.status, TradeStatus::Cancelled);
+    assert_eq!(trade.price, Decimal::from(2990));
+}
@@ -0,0 +1,403 @@
+use arb_core::config::SolanaConfig;
+use base64::Engine;
+use connectors::jupiter::{parse_keypair, JupiterSwapResponse, NATIVE_SOL};
+use connectors::JupiterConnector;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use connectors::jupiter::JupiterQuote;
+use solana_sdk::address_lookup_table::state::{AddressLookupTable, LookupTableMeta};
+use solana_sdk::compute_budget::{self, ComputeBudgetInstruction};
+use solana_sdk::hash::Hash;
+use std::borrow::Cow;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::VersionedTransaction;
+use std::str::FromStr;
+use std::sync::Arc;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, Respond, ResponseTemplate};
+
+const USDC: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+fn config(rpc_url: String, private_key: String) -> SolanaConfig {
+    SolanaConfig {
+        rpc_url,
+        private_key,
+        jupiter_api_url: "http://127.0.0.1:1".to_string(),
+        skip_preflight: true,
+        max_retries: 2,
+        confirm_timeout_ms: 5000,
+    }
+}
+
+/// Unsigned v0 transaction paying from `payer`, as Jupiter's `/swap` returns it.
+fn unsigned_swap(payer: &Pubkey) -> JupiterSwapResponse {
+    let instruction = Instruction::new_with_bytes(
+        Pubkey::new_unique(),
+        &[1, 2, 3],
+        vec![AccountMeta::new(*payer, true), AccountMeta::new(Pubkey::new_unique(), false)],
+    );
+    let message = v0::Message::try_compile(payer, &[instruction], &[], Hash::new_unique()).unwrap();
+    let transaction = VersionedTransaction {
+        signatures: vec![Signature::default()],
+        message: VersionedMessage::V0(message),
+    };
+    JupiterSwapResponse {
+        swap_transaction: base64::engine::general_purpose::STANDARD
+            .encode(bincode::serialize(&transaction).unwrap()),
+    }
+}
+
+#[test]
+fn keypairs_parse_from_base58_and_json_array() {
+    let keypair = Keypair::new();
+    let from_base58 = parse_keypair(&keypair.to_base58_string()).unwrap();
+    let from_json = parse_keypair(&serde_json::to_string(&keypair.to_bytes().to_vec()).unwrap()).unwrap();
+
+    assert_eq!(from_base58.pubkey(), keypair.pubkey());
+    assert_eq!(from_json.pubkey(), keypair.pubkey());
+    assert!(parse_keypair("[1, 2, 3]").is_err());
+    assert!(parse_keypair("not-base58-0OIl").is_err());
+}
+
+#[test]
+fn cloned_connector_keeps_keypair() {
+    let keypair = Keypair::new();
+    let jupiter = JupiterConnector::new(config("http://127.0.0.1:1".to_string(), keypair.to_base58_string())).unwrap();
+    assert_eq!(jupiter.clone().public_key().unwrap(), keypair.pubkey());
+}
+
+/// Solana RPC stand-in: verifies and accepts sent transactions, reports them
+/// unconfirmed on the first status poll, then serves their balance changes.
+#[derive(Clone)]
+struct Rpc {
+    owner: Pubkey,
+    sent: Arc<Mutex<Option<String>>>,
+    status_polls: Arc<Mutex<usize>>,
+    methods: Arc<Mutex<Vec<String>>>,
+    lookup_table: Option<Vec<u8>>,
+    simulation_error: bool,
+}
+
+impl Rpc {
+    fn new(owner: Pubkey) -> Self {
+        Self {
+            owner,
+            sent: Arc::default(),
+            status_polls: Arc::default(),
+            methods: Arc::default(),
+            lookup_table: None,
+            simulation_error: false,
+        }
+    }
+
+    fn sent_transaction(&self) -> VersionedTransaction {
+        let sent = self.sent.lock().clone().expect("no transaction sent");
+        bincode::deserialize(&base64::engine::general_purpose::STANDARD.decode(sent).unwrap()).unwrap()
+    }
+}
+
+impl Respond for Rpc {
+    fn respond(&self, request: &Request) -> ResponseTemplate {
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let params = &body["params"];
+        let context = serde_json::json!({"slot": 250});
+        let method = body["method"].as_str().unwrap();
+        self.methods.lock().push(method.to_string());
+
+        let result = match method {
+            "getVersion" => serde_json::json!({"solana-core": "1.18.26", "feature-set": 1}),
+            "getLatestBlockhash" => serde_json::json!({"context": context, "value": {
+                "blockhash": Hash::new_unique().to_string(),
+                "lastValidBlockHeight": 300
+            }}),
+            "getAccountInfo" => {
+                let data = self.lookup_table.clone().expect("unexpected account lookup");
+                serde_json::json!({"context": context, "value": {
+                    "data": [base64::engine::general_purpose::STANDARD.encode(&data), "base64"],
+                    "executable": false,
+                    "lamports": 1_000_000,
+                    "owner": "AddressLookupTab1e1111111111111111111111111",
+                    "rentEpoch": 0,
+                    "space": data.len()
+                }})
+            }
+            "simulateTransaction" => {
+                let err = if self.simulation_error {
+                    serde_json::json!({"InstructionError": [3, {"Custom": 6001}]})
+                } else {
+                    serde_json::Value::Null
+                };
+                serde_json::json!({"context": context, "value": {
+                    "err": err,
+                    "logs": ["Program log: SlippageToleranceExceeded"],
+                    "accounts": null,
+                    "unitsConsumed": 180000,
+                    "returnData": null
+                }})
+            }
+            "sendTransaction" => {
+                assert_eq!(params[1]["skipPreflight"], true);
+                assert_eq!(params[1]["maxRetries"], 2);
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(params[0].as_str().unwrap())
+                    .unwrap();
+                let tx: VersionedTransaction = bincode::deserialize(&bytes).unwrap();
+                assert!(tx.verify_with_results().iter().all(|ok| *ok), "bad signature");
+                assert_eq!(tx.message.static_account_keys()[0], self.owner);
+                *self.sent.lock() = Some(params[0].as_str().unwrap().to_string());
+                serde_json::json!(tx.signatures[0].to_string())
+            }
+            "getSignatureStatuses" => {
+                let mut polls = self.status_polls.lock();
+                *polls += 1;
+                if *polls == 1 {
+                    serde_json::json!({"context": context, "value": [null]})
+                } else {
+                    serde_json::json!({"context": context, "value": [{
+                        "slot": 250,
+                        "confirmations": 1,
+                        "err": null,
+                        "status": {"Ok": null},
+                        "confirmationStatus": "confirmed"
+                    }]})
+                }
+            }
+            "getTransaction" => {
+                let token = |mint: &str, amount: &str, decimals: u8| {
+                    serde_json::json!({
+                        "accountIndex": 1,
+                        "mint": mint,
+                        "owner": self.owner.to_string(),
+                        "programId": "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA",
+                        "uiTokenAmount": {"amount": amount, "decimals": decimals, "uiAmount": null, "uiAmountString": "0"}
+                    })
+                };
+                serde_json::json!({
+                    "slot": 250,
+                    "blockTime": null,
+                    "version": 0,
+                    "transaction": [self.sent.lock().clone().unwrap(), "base64"],
+                    "meta": {
+                        "err": null,
+                        "status": {"Ok": null},
+                        "fee": 5000,
+                        "preBalances": [2_000_000_000u64, 0, 1],
+                        "postBalances": [1_499_995_000u64, 0, 1],
+                        "preTokenBalances": [token(USDC, "1000000", 6)],
+                        "postTokenBalances": [token(USDC, "76500000", 6)]
+                    }
+                })
+            }
+            other => panic!("unexpected method {}", other),
+        };
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": body["id"],
+            "result": result
+        }))
+    }
+}
+
+#[tokio::test]
+async fn execute_swap_signs_sends_and_reports_balance_deltas() {
+    let server = MockServer::start().await;
+    let keypair = Keypair::new();
+    let rpc = Rpc::new(keypair.pubkey());
+    Mock::given(method("POST")).respond_with(rpc.clone()).mount(&server).await;
+
+    let jupiter = JupiterConnector::new(config(server.uri(), keypair.to_base58_string())).unwrap();
+    let execution = jupiter.execute_swap(&unsigned_swap(&keypair.pubkey())).await.unwrap();
+
+    assert_eq!(*rpc.status_polls.lock(), 2);
+    assert_eq!(execution.slot, 250);
+    assert_eq!(execution.fee_lamports, 5000);
+    assert_eq!(execution.delta(NATIVE_SOL), Decimal::from_str("-0.500005").unwrap());
+    assert_eq!(execution.delta(USDC), Decimal::from_str("75.5").unwrap());
+    assert_eq!(
+        execution.signature,
+        Signature::from_str(&execution.signature).unwrap().to_string()
+    );
+}
+
+#[tokio::test]
+async fn execute_swap_requires_a_keypair() {
+    let jupiter = JupiterConnector::new(config("http://127.0.0.1:1".to_string(), String::new())).unwrap();
+    let err = jupiter.execute_swap(&unsigned_swap(&Pubkey::new_unique())).await.unwrap_err();
+    assert!(err.to_string().contains("private key not configured"), "{}", err);
+}
+
+#[test]
+fn quotes_round_trip_and_tighten_min_output() {
+    let quote: JupiterQuote = serde_json::from_value(serde_json::json!({
+        "inputMint": USDC,
+        "inAmount": "1000000000",
+        "outputMint": "So11111111111111111111111111111111111111112",
+        "outAmount": "1010000000",
+        "otherAmountThreshold": "1004950000",
+        "swapMode": "ExactIn",
+        "slippageBps": 50,
+        "priceImpactPct": "0.0001",
+        "routePlan": [{"swapInfo": {"ammKey": "pool"}, "percent": 100}]
+    }))
+    .unwrap();
+    assert_eq!(quote.out_amount, 1_010_000_000);
+
+    // Only 1% above the minimum: slippage drops from 50 bps to the headroom
+    let guarded = quote.with_min_output(1_008_000_000).unwrap();
+    assert_eq!(guarded.slippage_bps, 19);
+    assert_eq!(guarded.other_amount_threshold, 1_008_081_000);
+    assert!(guarded.other_amount_threshold >= 1_008_000_000);
+    assert!(quote.with_min_output(1_020_000_000).is_err());
+
+    let json = serde_json::to_value(&guarded).unwrap();
+    assert_eq!(json["otherAmountThreshold"], "1008081000");
+    assert_eq!(json["routePlan"][0]["percent"], 100);
+}
+
+fn instruction(program: Pubkey, accounts: &[Pubkey], data: &[u8]) -> serde_json::Value {
+    serde_json::json!({
+        "programId": program.to_string(),
+        "accounts": accounts.iter().map(|a| serde_json::json!({
+            "pubkey": a.to_string(), "isSigner": false, "isWritable": true
+        })).collect::<Vec<_>>(),
+        "data": base64::engine::general_purpose::STANDARD.encode(data),
+    })
+}
+
+fn budget(instruction: Instruction) -> serde_json::Value {
+    serde_json::json!({
+        "programId": instruction.program_id.to_string(),
+        "accounts": [],
+        "data": base64::engine::general_purpose::STANDARD.encode(&instruction.data),
+    })
+}
+
+struct RoundTrip {
+    /// Kept alive for the test; dropping it verifies the `/swap-instructions` expectations.
+    _server: MockServer,
+    rpc: Rpc,
+    jupiter: JupiterConnector,
+    swap_program: Pubkey,
+    pool_accounts: Vec<Pubkey>,
+}
+
+/// Jupiter API and Solana RPC on one stand-in server; both legs route through
+/// the same pool accounts, which live in one lookup table.
+async fn round_trip(simulation_error: bool) -> RoundTrip {
+    let server = MockServer::start().await;
+    let keypair = Keypair::new();
+    let swap_program = Pubkey::new_unique();
+    let pool_accounts: Vec<Pubkey> = (0..24).map(|_| Pubkey::new_unique()).collect();
+    let table_key = Pubkey::new_unique();
+
+    let table = AddressLookupTable {
+        meta: LookupTableMeta::default(),
+        addresses: Cow::Owned(pool_accounts.clone()),
+    };
+    let mut rpc = Rpc::new(keypair.pubkey());
+    rpc.lookup_table = Some(table.serialize_for_tests().unwrap());
+    rpc.simulation_error = simulation_error;
+
+    for (input, limit, price, tag) in [(USDC, 200_000, 1_000, 1u8), ("So11111111111111111111111111111111111111112", 300_000, 5_000, 2u8)] {
+        Mock::given(method("POST"))
+            .and(path("/swap-instructions"))
+            .and(wiremock::matchers::body_partial_json(serde_json::json!({
+                "quoteResponse": {"inputMint": input},
+                "userPublicKey": keypair.pubkey().to_string()
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "computeBudgetInstructions": [
+                    budget(ComputeBudgetInstruction::set_compute_unit_limit(limit)),
+                    budget(ComputeBudgetInstruction::set_compute_unit_price(price)),
+                ],
+                "setupInstructions": [instruction(Pubkey::new_unique(), &[Pubkey::new_unique()], &[tag, 0])],
+                "swapInstruction": instruction(swap_program, &pool_accounts, &[tag, 1]),
+                "cleanupInstruction": null,
+                "addressLookupTableAddresses": [table_key.to_string()]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+    }
+    Mock::given(method("POST")).and(path("/")).respond_with(rpc.clone()).mount(&server).await;
+
+    let mut solana = config(server.uri(), keypair.to_base58_string());
+    solana.jupiter_api_url = server.uri();
+    RoundTrip {
+        jupiter: JupiterConnector::new(solana).unwrap(),
+        _server: server,
+        rpc,
+        swap_program,
+        pool_accounts,
+    }
+}
+
+fn quote(input: &str, output: &str) -> JupiterQuote {
+    serde_json::from_value(serde_json::json!({
+        "inputMint": input,
+        "inAmount": "1000000",
+        "outputMint": output,
+        "outAmount": "1000000",
+        "otherAmountThreshold": "1000000",
+        "swapMode": "ExactIn",
+        "slippageBps": 0,
+        "routePlan": []
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn round_trip_merges_both_legs_into_one_simulated_transaction() {
+    let setup = round_trip(false).await;
+    let sol = "So11111111111111111111111111111111111111112";
+    let execution = setup
+        .jupiter
+        .execute_round_trip(&quote(USDC, sol), &quote(sol, USDC))
+        .await
+        .unwrap();
+    assert_eq!(execution.delta(USDC), Decimal::from_str("75.5").unwrap());
+
+    let methods = setup.rpc.methods.lock().clone();
+    let simulated = methods.iter().position(|m| m == "simulateTransaction").unwrap();
+    let sent = methods.iter().position(|m| m == "sendTransaction").unwrap();
+    assert!(simulated < sent);
+
+    let tx = setup.rpc.sent_transaction();
+    let VersionedMessage::V0(message) = &tx.message else {
+        panic!("expected a v0 message");
+    };
+    assert_eq!(message.address_table_lookups.len(), 1);
+    assert!(setup.pool_accounts.iter().all(|a| !message.account_keys.contains(a)));
+
+    let programs: Vec<(Pubkey, Vec<u8>)> = message
+        .instructions
+        .iter()
+        .map(|ix| (message.account_keys[ix.program_id_index as usize], ix.data.clone()))
+        .collect();
+    // One merged compute budget: limits summed, highest price kept
+    assert_eq!(programs[0].0, compute_budget::id());
+    assert_eq!(programs[0].1, ComputeBudgetInstruction::set_compute_unit_limit(500_000).data);
+    assert_eq!(programs[1].1, ComputeBudgetInstruction::set_compute_unit_price(5_000).data);
+    assert_eq!(programs.iter().filter(|(p, _)| *p == compute_budget::id()).count(), 2);
+    // Forward setup and swap, then reverse setup and swap
+    let data: Vec<Vec<u8>> = programs[2..].iter().map(|(_, d)| d.clone()).collect();
+    assert_eq!(data, vec![vec![1, 0], vec![1, 1], vec![2, 0], vec![2, 1]]);
+    assert_eq!(programs[3].0, setup.swap_program);
+}
+
+#[tokio::test]
+async fn failed_simulation_is_never_sent() {
+    let setup = round_trip(true).await;
+    let sol = "So11111111111111111111111111111111111111112";
+    let err = setup
+        .jupiter
+        .execute_round_trip(&quote(USDC, sol), &quote(sol, USDC))
+        .await
+        .unwrap_err();
+
+    assert!(err.to_string().contains("SlippageToleranceExceeded"), "{}", err);
+    assert!(!setup.rpc.methods.lock().iter().any(|m| m == "sendTransaction"));
+}
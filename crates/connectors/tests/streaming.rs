@@ -0,0 +1,281 @@
+use arb_core::instruments::Instrument;
+use arb_core::types::{ContractSpec, SharedState, Venue};
+use connectors::streaming::{run_feed, BinanceFeed, BybitFeed, HyperliquidFeed, StreamSettings};
+use futures::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn settings() -> StreamSettings {
+    StreamSettings {
+        heartbeat_interval: Duration::from_secs(1),
+        stale_timeout: Duration::from_secs(10),
+        max_backoff: Duration::from_secs(1),
+    }
+}
+
+/// Local WebSocket server. Connection `n` replays `scripts[n]` (the last script
+/// for any later connection) once the client has sent its first subscription.
+async fn stand_in(scripts: Vec<Vec<Value>>) -> (String, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}", listener.local_addr().unwrap());
+    let connections = Arc::new(AtomicUsize::new(0));
+    let counter = connections.clone();
+
+    tokio::spawn(async move {
+        while let Ok((stream, _)) = listener.accept().await {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            let script = scripts[n.min(scripts.len() - 1)].clone();
+            tokio::spawn(async move {
+                let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+                if socket.next().await.is_none() {
+                    return;
+                }
+                for message in script {
+                    if socket.send(Message::Text(message.to_string())).await.is_err() {
+                        return;
+                    }
+                }
+                while let Some(Ok(_)) = socket.next().await {}
+            });
+        }
+    });
+
+    (url, connections)
+}
+
+async fn eventually(condition: impl Fn() -> bool) {
+    for _ in 0..250 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    panic!("condition not met within 5s");
+}
+
+fn best_bid(state: &SharedState, symbol: &str, venue: Venue) -> Option<Decimal> {
+    state
+        .order_books
+        .get(&(symbol.to_string(), venue))
+        .and_then(|book| book.best_bid().map(|level| level.price))
+}
+
+#[tokio::test]
+async fn hyperliquid_feed_writes_books_and_funding() {
+    let (url, _) = stand_in(vec![vec![
+        json!({"channel": "subscriptionResponse", "data": {"method": "subscribe"}}),
+        json!({"channel": "l2Book", "data": {
+            "coin": "ETH",
+            "time": 1700000000000i64,
+            "levels": [
+                [{"px": "3000", "sz": "2", "n": 1}],
+                [{"px": "3001", "sz": "1", "n": 1}]
+            ]
+        }}),
+        json!({"channel": "activeAssetCtx", "data": {
+            "coin": "ETH",
+            "ctx": {"funding": "0.0000125", "openInterest": "1000", "markPx": "3000.5"}
+        }}),
+    ]])
+    .await;
+
+    let state = Arc::new(SharedState::new());
+    let feed = HyperliquidFeed::new(url, vec!["ETH".to_string()]);
+    let task = tokio::spawn(run_feed(feed, state.clone(), settings()));
+
+    eventually(|| state.funding_rates.contains_key(&("ETH".to_string(), Venue::Hyperliquid))).await;
+    let market = state.market_data.get(&("ETH".to_string(), Venue::Hyperliquid)).unwrap().clone();
+    assert_eq!(market.price, Decimal::from_str_exact("3000.5").unwrap());
+    let funding = state.funding_rates.get(&("ETH".to_string(), Venue::Hyperliquid)).unwrap().clone();
+    assert_eq!(funding.rate, Decimal::from_str_exact("0.0000125").unwrap());
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn hyperliquid_feed_keys_listed_coins_by_asset() {
+    let (url, _) = stand_in(vec![vec![
+        json!({"channel": "l2Book", "data": {
+            "coin": "kPEPE",
+            "time": 1700000000000i64,
+            "levels": [
+                [{"px": "0.012", "sz": "2", "n": 1}],
+                [{"px": "0.013", "sz": "1", "n": 1}]
+            ]
+        }}),
+        json!({"channel": "activeAssetCtx", "data": {
+            "coin": "kPEPE",
+            "ctx": {"funding": "0.0000125", "openInterest": "1000", "markPx": "0.0125"}
+        }}),
+    ]])
+    .await;
+
+    let state = Arc::new(SharedState::new());
+    state.instruments.load_venue(
+        &Venue::Hyperliquid,
+        vec![Instrument {
+            asset: "PEPE".to_string(),
+            venue: Venue::Hyperliquid,
+            native_symbol: "kPEPE".to_string(),
+            quote_asset: "USDC".to_string(),
+            spec: ContractSpec {
+                symbol: "PEPE".to_string(),
+                venue: Venue::Hyperliquid,
+                tick_size: Decimal::new(1, 6),
+                lot_size: Decimal::ONE,
+                min_size: Decimal::ONE,
+                min_notional: Decimal::from(10),
+                contract_multiplier: Decimal::from(1000),
+            },
+        }],
+    );
+    let feed = HyperliquidFeed::new(url, vec!["PEPE".to_string()])
+        .with_instruments(state.instruments.clone());
+    let task = tokio::spawn(run_feed(feed, state.clone(), settings()));
+
+    let key = ("PEPE".to_string(), Venue::Hyperliquid);
+    eventually(|| state.funding_rates.contains_key(&key)).await;
+    assert_eq!(best_bid(&state, "PEPE", Venue::Hyperliquid), Some(Decimal::new(12, 3)));
+    assert!(!state.order_books.contains_key(&("kPEPE".to_string(), Venue::Hyperliquid)));
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn bybit_sequence_gap_reconnects_for_a_fresh_snapshot() {
+    let book = |kind: &str, u: u64, bid: &str| {
+        json!({
+            "topic": "orderbook.50.BTCUSDT",
+            "type": kind,
+            "ts": 1700000000000i64,
+            "data": {"s": "BTCUSDT", "b": [[bid, "1"]], "a": [["70000", "1"]], "u": u, "seq": u}
+        })
+    };
+    let (url, connections) = stand_in(vec![
+        vec![book("snapshot", 1, "64000"), book("delta", 3, "64001")],
+        vec![
+            book("snapshot", 10, "65000"),
+            json!({
+                "topic": "tickers.BTCUSDT",
+                "type": "snapshot",
+                "ts": 1700000000000i64,
+                "data": {"symbol": "BTCUSDT", "fundingRate": "0.0001"}
+            }),
+        ],
+    ])
+    .await;
+
+    let state = Arc::new(SharedState::new());
+    let feed = BybitFeed::new(url, vec!["BTC-USDT".to_string()]);
+    let task = tokio::spawn(run_feed(feed, state.clone(), settings()));
+
+    eventually(|| best_bid(&state, "BTC-USDT", Venue::Bybit) == Some(Decimal::from(65000))).await;
+    assert_eq!(connections.load(Ordering::SeqCst), 2);
+    eventually(|| state.funding_rates.contains_key(&("BTC-USDT".to_string(), Venue::Bybit))).await;
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn binance_pu_gap_refetches_depth_snapshot() {
+    let rest = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/depth"))
+        .and(query_param("symbol", "BTCUSDT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "lastUpdateId": 5,
+            "bids": [["64000", "1"]],
+            "asks": [["64010", "1"]]
+        })))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&rest)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/depth"))
+        .and(query_param("symbol", "BTCUSDT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "lastUpdateId": 11,
+            "bids": [["65000", "1"]],
+            "asks": [["65010", "1"]]
+        })))
+        .expect(1)
+        .mount(&rest)
+        .await;
+
+    let depth = |first: u64, last: u64, prev: u64, bid: &str| {
+        json!({"stream": "btcusdt@depth@100ms", "data": {
+            "e": "depthUpdate", "E": 1700000000000i64, "T": 1700000000000i64, "s": "BTCUSDT",
+            "U": first, "u": last, "pu": prev,
+            "b": [[bid, "2"]], "a": []
+        }})
+    };
+    let (url, _) = stand_in(vec![vec![
+        json!({"result": null, "id": 1}),
+        depth(5, 7, 4, "64001"),
+        // pu should be 7: the book is dropped and re-seeded on the next event
+        depth(10, 10, 9, "64002"),
+        depth(11, 12, 10, "65001"),
+    ]])
+    .await;
+
+    let state = Arc::new(SharedState::new());
+    let feed = BinanceFeed::new(url, rest.uri(), vec!["BTC-USDT".to_string()]);
+    let task = tokio::spawn(run_feed(feed, state.clone(), settings()));
+
+    eventually(|| best_bid(&state, "BTC-USDT", Venue::Binance) == Some(Decimal::from(65001))).await;
+    let book = state.order_books.get(&("BTC-USDT".to_string(), Venue::Binance)).unwrap().clone();
+    assert!(book.bids.iter().all(|level| level.price != Decimal::from(64002)));
+
+    task.abort();
+}
+
+#[tokio::test]
+async fn binance_events_over_a_snapshot_need_no_refetch() {
+    let rest = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/fapi/v1/depth"))
+        .and(query_param("symbol", "BTCUSDT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "lastUpdateId": 5,
+            "bids": [["64000", "1"]],
+            "asks": [["64010", "1"]]
+        })))
+        .expect(1)
+        .mount(&rest)
+        .await;
+
+    let depth = |first: u64, last: u64, prev: u64, bid: &str| {
+        json!({"stream": "btcusdt@depth@100ms", "data": {
+            "e": "depthUpdate", "E": 1700000000000i64, "T": 1700000000000i64, "s": "BTCUSDT",
+            "U": first, "u": last, "pu": prev,
+            "b": [[bid, "2"]], "a": []
+        }})
+    };
+    let (url, _) = stand_in(vec![vec![
+        json!({"result": null, "id": 1}),
+        // Contained in the snapshot
+        depth(3, 4, 2, "63999"),
+        // Straddles the snapshot, so its pu is not checked
+        depth(4, 6, 4, "64001"),
+        depth(7, 8, 6, "64002"),
+    ]])
+    .await;
+
+    let state = Arc::new(SharedState::new());
+    let feed = BinanceFeed::new(url, rest.uri(), vec!["BTC-USDT".to_string()]);
+    let task = tokio::spawn(run_feed(feed, state.clone(), settings()));
+
+    eventually(|| best_bid(&state, "BTC-USDT", Venue::Binance) == Some(Decimal::from(64002))).await;
+    let book = state.order_books.get(&("BTC-USDT".to_string(), Venue::Binance)).unwrap().clone();
+    assert!(book.bids.iter().all(|level| level.price != Decimal::from(63999)));
+
+    task.abort();
+}
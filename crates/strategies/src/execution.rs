@@ -0,0 +1,397 @@
+use arb_core::config::{ExecutionConfig, LegMode};
+use arb_core::types::{
+    ContractSpec, OrderOptions, PositionSide, SharedState, TimeInForce, Trade, TradeStatus, Venue,
+};
+use arb_core::{Event, RiskEngine};
+use anyhow::{Context, Result};
+use chrono::Utc;
+use connectors::ConnectorRegistry;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// How many times an acknowledged order is queried for its final state.
+const SETTLE_POLLS: u32 = 3;
+
+/// Reduce-only orders sent to take a position off before the rest is left.
+const REDUCE_ATTEMPTS: u32 = 3;
+
+/// One side of a two-legged execution.
+#[derive(Debug, Clone)]
+pub struct LegOrder {
+    pub venue: Venue,
+    pub symbol: String,
+    pub side: PositionSide,
+    /// Order quantity in the venue's contracts.
+    pub contracts: Decimal,
+    /// Base-asset quantity the leg adds, booked against risk limits.
+    pub base_size: Decimal,
+    /// Marketable limit price.
+    pub price: Decimal,
+    /// Used to keep chase prices on the venue's tick grid.
+    pub spec: Option<ContractSpec>,
+}
+
+/// Final state of both legs. Each trade carries the leg's outcome as its status.
+#[derive(Debug, Clone)]
+pub struct PairExecution {
+    pub first: Trade,
+    pub second: Trade,
+}
+
+impl PairExecution {
+    pub fn is_hedged(&self) -> bool {
+        self.first.status.is_complete() && self.second.status.is_complete()
+    }
+}
+
+/// Executes paired legs so that a failure on one side never leaves the other
+/// naked: both legs clear risk before anything is sent, and a leg that cannot
+/// be completed gets the filled side unwound.
+pub struct ExecutionCoordinator {
+    connectors: Arc<ConnectorRegistry>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+    config: ExecutionConfig,
+}
+
+impl ExecutionCoordinator {
+    pub fn new(
+        connectors: Arc<ConnectorRegistry>,
+        state: Arc<SharedState>,
+        risk_engine: Arc<RiskEngine>,
+        config: ExecutionConfig,
+    ) -> Self {
+        Self {
+            connectors,
+            state,
+            risk_engine,
+            config,
+        }
+    }
+
+    /// Runs both legs under the configured leg mode. Fails, before sending
+    /// anything, only if either leg is refused by the risk engine. In an
+    /// unhedged execution a leg an unwind could not close keeps its fill
+    /// status and size still open, and is booked, so the caller can track it.
+    pub async fn execute_pair(&self, first: &LegOrder, second: &LegOrder) -> Result<PairExecution> {
+        let first_reservation = self
+            .risk_engine
+            .reserve(&provisional(first))
+            .with_context(|| format!("Pre-trade risk check failed for {:?} leg", first.venue))?;
+        let second_reservation = self
+            .risk_engine
+            .reserve(&provisional(second))
+            .with_context(|| format!("Pre-trade risk check failed for {:?} leg", second.venue))?;
+
+        let (mut first_trade, mut second_trade) = match self.config.leg_mode {
+            LegMode::Concurrent => tokio::join!(self.fill_leg(first), self.fill_leg(second)),
+            LegMode::Sequential => {
+                let first_trade = self.fill_leg(first).await;
+                if first_trade.status.is_complete() {
+                    (first_trade, self.fill_leg(second).await)
+                } else {
+                    (first_trade, not_sent(second))
+                }
+            }
+        };
+
+        if !(first_trade.status.is_complete() && second_trade.status.is_complete()) {
+            for (order, trade) in [(first, &mut first_trade), (second, &mut second_trade)] {
+                if let Err(e) = self.unwind(order, trade).await {
+                    error!(
+                        "Failed to unwind {:?} {} leg, {} left open: {:#}",
+                        order.venue, order.symbol, trade.size, e
+                    );
+                }
+            }
+        }
+
+        first_reservation.commit(&first_trade);
+        second_reservation.commit(&second_trade);
+        for trade in [&first_trade, &second_trade] {
+            self.record(trade);
+        }
+
+        Ok(PairExecution {
+            first: first_trade,
+            second: second_trade,
+        })
+    }
+
+    /// Places the leg, re-sending any unfilled remainder `max_retries` times
+    /// and then once more at the chase price. Returns the combined fill.
+    /// A failed request is only sent again when it cannot have been placed,
+    /// and an order acknowledged without a final state only once it is settled;
+    /// otherwise the leg stops and reconciliation picks up whatever it did.
+    async fn fill_leg(&self, order: &LegOrder) -> Trade {
+        let chase_attempts = if self.config.chase_slippage_bps > 0 { 1 } else { 0 };
+        let attempts = 1 + self.config.max_retries + chase_attempts;
+        let mut filled = Decimal::ZERO;
+        let mut cost = Decimal::ZERO;
+        let mut order_id = None;
+        let mut last_status = TradeStatus::Failed;
+
+        for attempt in 0..attempts {
+            let chasing = chase_attempts > 0 && attempt + 1 == attempts;
+            if attempt > 0 {
+                sleep(Duration::from_millis(self.config.retry_delay_ms)).await;
+            }
+            let price = if chasing { self.chase_price(order) } else { order.price };
+            let remaining = order.contracts - filled;
+
+            let placed = self.place(order, order.side.clone(), remaining, price, false).await;
+            // An acknowledged order may still fill, so it is settled before anything is resent
+            let placed = match placed {
+                Ok(pending) if pending.status == TradeStatus::Pending => {
+                    self.settle(order, pending).await
+                }
+                placed => placed,
+            };
+            match placed {
+                Ok(trade) => {
+                    if trade.status.is_fill() {
+                        filled += trade.size;
+                        cost += trade.size * trade.price;
+                    }
+                    order_id = trade.order_id.or(order_id);
+                    last_status = trade.status;
+                }
+                Err(e) if self.order_not_placed(order, &e) => {
+                    warn!(
+                        "{:?} {} leg attempt {} failed: {:#}",
+                        order.venue,
+                        order.symbol,
+                        attempt + 1,
+                        e
+                    );
+                    last_status = TradeStatus::Failed;
+                }
+                Err(e) => {
+                    // The order may be live or filled; sending it again could double the leg
+                    error!(
+                        "{:?} {} leg attempt {} has an unknown outcome, not resending: {:#}",
+                        order.venue,
+                        order.symbol,
+                        attempt + 1,
+                        e
+                    );
+                    last_status = TradeStatus::Failed;
+                    break;
+                }
+            }
+
+            if filled >= order.contracts {
+                last_status = match attempt {
+                    0 => TradeStatus::Filled,
+                    _ if chasing => TradeStatus::Chased,
+                    _ => TradeStatus::FilledOnRetry,
+                };
+                break;
+            }
+        }
+
+        if !last_status.is_complete() && !filled.is_zero() {
+            last_status = TradeStatus::PartiallyFilled;
+        }
+        Trade {
+            symbol: order.symbol.clone(),
+            venue: order.venue.clone(),
+            side: order.side.clone(),
+            size: if filled.is_zero() { order.contracts } else { filled },
+            price: if filled.is_zero() { order.price } else { cost / filled },
+            timestamp: Utc::now(),
+            status: last_status,
+            order_id,
+        }
+    }
+
+    /// Cancels what is left of an order the venue acknowledged without a final
+    /// state and waits for it to report what filled. Fails if that cannot be
+    /// confirmed, since the order may still be live.
+    async fn settle(&self, order: &LegOrder, trade: Trade) -> Result<Trade> {
+        let order_id = trade
+            .order_id
+            .with_context(|| format!("{:?} acknowledged an order without an id", order.venue))?;
+        let connector = self.connectors.get(&order.venue)?;
+        if let Err(e) = connector.cancel_order(&order.symbol, &order_id).await {
+            // Usually the order has already finished
+            warn!("Failed to cancel {:?} order {}: {:#}", order.venue, order_id, e);
+        }
+        for poll in 0..SETTLE_POLLS {
+            if poll > 0 {
+                sleep(Duration::from_millis(self.config.retry_delay_ms)).await;
+            }
+            match connector.get_order(&order.symbol, &order_id).await {
+                Ok(settled) if settled.status != TradeStatus::Pending => return Ok(settled),
+                Ok(_) => {}
+                Err(e) => warn!("Failed to query {:?} order {}: {:#}", order.venue, order_id, e),
+            }
+        }
+        anyhow::bail!("{:?} order {} did not settle", order.venue, order_id)
+    }
+
+    /// Closes whatever `trade` filled with a reduce-only market order. If it
+    /// only partly closes, `trade` is left with the quantity still open.
+    async fn unwind(&self, order: &LegOrder, trade: &mut Trade) -> Result<()> {
+        if !trade.status.is_fill() {
+            return Ok(());
+        }
+
+        let close_side = match order.side {
+            PositionSide::Long => PositionSide::Short,
+            PositionSide::Short => PositionSide::Long,
+        };
+        let close = self.place(order, close_side, trade.size, Decimal::ZERO, true).await?;
+        if close.status.is_fill() && close.size < trade.size {
+            trade.size -= close.size;
+            trade.status = TradeStatus::PartiallyFilled;
+        }
+        if close.status != TradeStatus::Filled {
+            anyhow::bail!("Unwind order on {:?} ended {:?}", order.venue, close.status);
+        }
+        info!("Unwound {} {} on {:?}", trade.size, order.symbol, order.venue);
+        trade.status = TradeStatus::Unwound;
+        Ok(())
+    }
+
+    async fn place(
+        &self,
+        order: &LegOrder,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        reduce_only: bool,
+    ) -> Result<Trade> {
+        let options = OrderOptions {
+            time_in_force: TimeInForce::ImmediateOrCancel,
+            reduce_only,
+        };
+        self.state.events.publish(Event::OrderSubmitted {
+            venue: order.venue.clone(),
+            symbol: order.symbol.clone(),
+            side: side.clone(),
+            size,
+            price,
+            reduce_only,
+        });
+        self.connectors
+            .get(&order.venue)?
+            .place_order_with_options(&order.symbol, side, size, price, options)
+            .await
+    }
+
+    /// Whether the leg's venue is sure a failed order was never placed.
+    fn order_not_placed(&self, order: &LegOrder, error: &anyhow::Error) -> bool {
+        self.connectors.get(&order.venue).is_ok_and(|c| c.order_not_placed(error))
+    }
+
+    fn chase_price(&self, order: &LegOrder) -> Decimal {
+        let band = Decimal::from(self.config.chase_slippage_bps) / Decimal::from(10_000);
+        let price = match order.side {
+            PositionSide::Long => order.price * (Decimal::ONE + band),
+            PositionSide::Short => order.price * (Decimal::ONE - band),
+        };
+        match &order.spec {
+            Some(spec) => spec.round_price_marketable(price, &order.side),
+            None => price,
+        }
+    }
+
+    fn record(&self, trade: &Trade) {
+        self.state.trades.insert(trade.record_id(), trade.clone());
+        if trade.status.is_fill() {
+            self.state.record_fill(trade.clone());
+        }
+    }
+}
+
+/// The trade a leg would book if it filled as ordered, priced per base unit.
+fn provisional(order: &LegOrder) -> Trade {
+    let multiplier = order.spec.as_ref().map_or(Decimal::ONE, |s| s.contract_multiplier);
+    Trade {
+        symbol: order.symbol.clone(),
+        venue: order.venue.clone(),
+        side: order.side.clone(),
+        size: order.base_size,
+        price: order.price / multiplier,
+        timestamp: Utc::now(),
+        status: TradeStatus::Pending,
+        order_id: None,
+    }
+}
+
+/// A second leg held back because the first never completed.
+fn not_sent(order: &LegOrder) -> Trade {
+    Trade {
+        status: TradeStatus::Cancelled,
+        size: order.contracts,
+        ..provisional(order)
+    }
+}
+
+/// Takes `size` contracts off a position with reduce-only IOC market orders,
+/// re-sending only the unfilled remainder. The trade carries the combined
+/// fill at its average price: `Filled` once all of `size` is off, otherwise
+/// `PartiallyFilled` so what did fill can still be booked. Fails only when
+/// nothing filled.
+pub(crate) async fn reduce_position(
+    connectors: &ConnectorRegistry,
+    venue: &Venue,
+    symbol: &str,
+    side: PositionSide,
+    size: Decimal,
+) -> Result<Trade> {
+    let connector = connectors.get(venue)?;
+    let options = OrderOptions {
+        time_in_force: TimeInForce::ImmediateOrCancel,
+        reduce_only: true,
+    };
+    let mut filled = Decimal::ZERO;
+    let mut cost = Decimal::ZERO;
+    let mut order_id = None;
+    let mut last_status = TradeStatus::Failed;
+    for _ in 0..REDUCE_ATTEMPTS {
+        let remaining = size - filled;
+        if remaining <= Decimal::ZERO {
+            break;
+        }
+        let trade = match connector
+            .place_order_with_options(symbol, side.clone(), remaining, Decimal::ZERO, options)
+            .await
+        {
+            Ok(trade) => trade,
+            Err(e) if filled.is_zero() => return Err(e),
+            Err(e) => {
+                warn!("Reduce-only order on {:?} for {} failed: {:#}", venue, symbol, e);
+                break;
+            }
+        };
+        if trade.status.is_fill() {
+            filled += trade.size;
+            cost += trade.size * trade.price;
+        }
+        order_id = trade.order_id.or(order_id);
+        last_status = trade.status;
+    }
+
+    if filled.is_zero() {
+        anyhow::bail!("Reduce-only order on {:?} for {} ended {:?}", venue, symbol, last_status);
+    }
+    Ok(Trade {
+        symbol: symbol.to_string(),
+        venue: venue.clone(),
+        side,
+        size: filled,
+        price: cost / filled,
+        timestamp: Utc::now(),
+        status: if filled >= size {
+            TradeStatus::Filled
+        } else {
+            TradeStatus::PartiallyFilled
+        },
+        order_id,
+    })
+}
@@ -0,0 +1,175 @@
+use crate::execution::reduce_position;
+use arb_core::types::{Position, PositionSide, SharedState, TradeStatus, Venue};
+use arb_core::RiskEngine;
+use anyhow::{Context, Result};
+use chrono::Utc;
+use connectors::ConnectorRegistry;
+use serde::Serialize;
+use std::collections::HashSet;
+use tracing::{error, info, warn};
+
+/// What a flatten-all pass closed and what it could not.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct FlattenReport {
+    pub closed: Vec<Position>,
+    /// One message per venue or position that could not be closed.
+    pub failures: Vec<String>,
+    pub dry_run: bool,
+}
+
+impl FlattenReport {
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Closes every open position on `venues` with reduce-only market orders,
+/// fetched live rather than from `state` so nothing unrecorded survives.
+/// Hedges whose legs all closed are marked closed. Spot legs hold tokens
+/// rather than positions, so hedges with one stay open and are reported as
+/// failures. The caller should trip the kill switch first, or strategies may
+/// open new positions straight away.
+pub async fn flatten_all(
+    connectors: &ConnectorRegistry,
+    venues: &[Venue],
+    state: &SharedState,
+    risk_engine: &RiskEngine,
+    dry_run: bool,
+) -> FlattenReport {
+    let mut report = FlattenReport {
+        dry_run,
+        ..Default::default()
+    };
+    // (venue, symbol) pairs still holding something afterwards
+    let mut remaining = HashSet::new();
+
+    for venue in venues {
+        let positions = match fetch_positions(connectors, venue).await {
+            Ok(positions) => positions,
+            Err(e) => {
+                error!("Flatten: {:#}", e);
+                report.failures.push(format!("{:#}", e));
+                remaining.extend(open_hedge_legs(state, venue));
+                continue;
+            }
+        };
+
+        for position in positions.into_iter().filter(|p| !p.size.is_zero()) {
+            if dry_run {
+                info!(
+                    "DRY RUN: Would close {:?} {} {} on {:?}",
+                    position.side, position.size, position.symbol, venue
+                );
+                report.closed.push(position);
+                continue;
+            }
+            match close(connectors, state, risk_engine, &position).await {
+                Ok(()) => report.closed.push(position),
+                Err(e) => {
+                    let message = format!("{:?} {}: {:#}", venue, position.symbol, e);
+                    error!("Flatten: {}", message);
+                    report.failures.push(message);
+                    remaining.insert((venue.clone(), position.symbol.clone()));
+                }
+            }
+        }
+    }
+
+    for hedge in state.hedges.iter().filter(|h| h.is_open()) {
+        let legs = [&hedge.short_venue, &hedge.long_venue];
+        if let Some(spot) = legs.into_iter().find(|v| v.is_spot()) {
+            let message = format!("hedge {} still holds its {:?} spot leg", hedge.id, spot);
+            error!("Flatten: {}", message);
+            report.failures.push(message);
+            remaining.insert((spot.clone(), hedge.symbol.clone()));
+        }
+    }
+
+    if !dry_run {
+        let now = Utc::now();
+        let closed: Vec<String> = state
+            .hedges
+            .iter()
+            .filter(|h| {
+                let short = (h.short_venue.clone(), h.symbol.clone());
+                let long = (h.long_venue.clone(), h.symbol.clone());
+                h.is_open() && !remaining.contains(&short) && !remaining.contains(&long)
+            })
+            .map(|h| h.id.clone())
+            .collect();
+        // Through `update_hedge` so the closes are announced and journaled
+        for id in closed {
+            state.update_hedge(&id, |h| h.closed_at = Some(now));
+        }
+    }
+
+    if report.is_complete() {
+        info!("Flattened {} position(s)", report.closed.len());
+    } else {
+        warn!(
+            "Flattened {} position(s), {} failure(s)",
+            report.closed.len(),
+            report.failures.len()
+        );
+    }
+    report
+}
+
+async fn fetch_positions(connectors: &ConnectorRegistry, venue: &Venue) -> Result<Vec<Position>> {
+    connectors
+        .get(venue)?
+        .get_positions()
+        .await
+        .with_context(|| format!("Failed to fetch {:?} positions", venue))
+}
+
+async fn close(
+    connectors: &ConnectorRegistry,
+    state: &SharedState,
+    risk_engine: &RiskEngine,
+    position: &Position,
+) -> Result<()> {
+    let close_side = match position.side {
+        PositionSide::Long => PositionSide::Short,
+        PositionSide::Short => PositionSide::Long,
+    };
+    let trade = reduce_position(
+        connectors,
+        &position.venue,
+        &position.symbol,
+        close_side,
+        position.size,
+    )
+    .await?;
+    if trade.status != TradeStatus::Filled {
+        // Book what filled before reporting the rest as still open
+        risk_engine.record_position_reduced(&position.symbol, trade.size * trade.price);
+        state.record_position_closed(
+            position.venue.clone(),
+            position.symbol.clone(),
+            position.side.clone(),
+            trade.size,
+        );
+        anyhow::bail!("Closing order only filled {} of {}", trade.size, position.size);
+    }
+
+    let notional = trade.size * trade.price;
+    risk_engine.record_position_closed(&position.venue, &position.symbol, notional);
+    state.record_position_closed(
+        position.venue.clone(),
+        position.symbol.clone(),
+        position.side.clone(),
+        trade.size,
+    );
+    Ok(())
+}
+
+/// Legs of open hedges on `venue`, whose state is unknown if it cannot be queried.
+fn open_hedge_legs(state: &SharedState, venue: &Venue) -> Vec<(Venue, String)> {
+    state
+        .hedges
+        .iter()
+        .filter(|h| h.is_open() && (&h.short_venue == venue || &h.long_venue == venue))
+        .map(|h| (venue.clone(), h.symbol.clone()))
+        .collect()
+}
@@ -1,95 +1,114 @@
-use arb_core::types::{ArbitrageOpportunity, FundingRate, PositionSide, SharedState, StrategyType, Trade, TradeStatus, Venue};
+use crate::execution::{reduce_position, ExecutionCoordinator, LegOrder};
+use crate::sizing::{
+    contract_spec, own_leg_size, size_hedge, HedgeSize, LegMarket, SizingLimits,
+};
+use crate::strategy::{Strategy, StrategyHealth};
+use arb_core::config::FundingArbConfig;
+use arb_core::risk::RiskError;
+use arb_core::types::{
+    ArbitrageOpportunity, ContractSpec, FundingRate, Hedge, OpportunityStatus, Position,
+    PositionSide, SharedState, StrategyType, Trade, TradeStatus, Venue,
+};
 use arb_core::RiskEngine;
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::Utc;
-use connectors::{BinanceConnector, BybitConnector, HyperliquidConnector};
+use connectors::ConnectorRegistry;
+use parking_lot::RwLock;
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
+
+/// Levels per side fetched when sizing a new hedge.
+const SIZING_BOOK_DEPTH: usize = 20;
+
+/// Why an open hedge is being unwound.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExitReason {
+    SpreadCompressed(Decimal),
+    PredictedFlip(Decimal),
+    MaxHoldingTime,
+    MarginBreach(Venue),
+    /// An earlier close unwound one leg but not the other.
+    UnfinishedClose,
+}
 
 pub struct FundingArbStrategy {
-    hyperliquid: HyperliquidConnector,
-    binance: BinanceConnector,
-    bybit: BybitConnector,
+    connectors: Arc<ConnectorRegistry>,
+    venues: Vec<Venue>,
     state: Arc<SharedState>,
     risk_engine: Arc<RiskEngine>,
-    min_annualized_spread: f64,
+    config: FundingArbConfig,
+    execution: ExecutionCoordinator,
     check_interval: Duration,
     dry_run: bool,
+    /// Found by `init`.
+    symbols: Vec<String>,
+    /// The venues' latest view of the legs, keyed like `SharedState.positions`.
+    venue_positions: RwLock<HashMap<String, Position>>,
 }
 
 impl FundingArbStrategy {
     pub fn new(
-        hyperliquid: HyperliquidConnector,
-        binance: BinanceConnector,
-        bybit: BybitConnector,
+        connectors: Arc<ConnectorRegistry>,
+        venues: Vec<Venue>,
         state: Arc<SharedState>,
         risk_engine: Arc<RiskEngine>,
-        min_annualized_spread: f64,
-        check_interval_ms: u64,
+        config: FundingArbConfig,
         dry_run: bool,
     ) -> Self {
+        let execution = ExecutionCoordinator::new(
+            connectors.clone(),
+            state.clone(),
+            risk_engine.clone(),
+            config.execution.clone(),
+        );
         Self {
-            hyperliquid,
-            binance,
-            bybit,
+            connectors,
+            venues,
             state,
             risk_engine,
-            min_annualized_spread,
-            check_interval: Duration::from_millis(check_interval_ms),
+            execution,
+            check_interval: Duration::from_millis(config.check_interval_ms),
+            config,
             dry_run,
+            symbols: Vec::new(),
+            venue_positions: RwLock::new(HashMap::new()),
         }
     }
 
-    pub async fn run_loop(&self) {
-        info!("Starting funding arbitrage loop");
-        
-        // Discover top symbols
-        let symbols = match self.discover_symbols().await {
-            Ok(syms) => syms,
-            Err(e) => {
-                error!("Failed to discover symbols: {}", e);
-                return;
-            }
-        };
-
-        info!("Monitoring {} symbols for funding arbitrage", symbols.len());
-
-        loop {
-            if self.risk_engine.is_kill_switch_active() {
-                warn!("Kill switch active, pausing funding arb loop");
-                sleep(Duration::from_secs(10)).await;
-                continue;
-            }
-
-            for symbol in &symbols {
-                if let Err(e) = self.check_opportunity(symbol).await {
-                    error!("Error checking opportunity for {}: {}", symbol, e);
-                }
-            }
-
-            sleep(self.check_interval).await;
+    async fn discover_symbols(&self) -> Result<Vec<String>> {
+        let mut per_venue = Vec::with_capacity(self.venues.len());
+        for venue in &self.venues {
+            let connector = self.connectors.get(venue)?;
+            per_venue.push(connector.get_top_symbols_by_volume(10).await?);
         }
-    }
 
-    async fn discover_symbols(&self) -> Result<Vec<String>> {
-        let hl_symbols = self.hyperliquid.get_top_symbols_by_volume(10).await?;
-        let binance_symbols = self.binance.get_top_symbols_by_volume(10).await?;
-        let bybit_symbols = self.bybit.get_top_symbols_by_volume(10).await?;
+        let Some((primary, others)) = per_venue.split_first() else {
+            anyhow::bail!("No venues configured for funding arbitrage");
+        };
 
-        // Find intersection of symbols across all venues
-        let mut common: Vec<String> = hl_symbols
+        // Symbols are canonical asset ids, so once the instrument registry is
+        // loaded a listing on every venue is enough; otherwise intersect the lists
+        let listed = self.state.instruments.common_assets(&self.venues);
+        let mut common: Vec<String> = primary
             .iter()
-            .filter(|s| binance_symbols.contains(s) && bybit_symbols.contains(s))
+            .filter(|s| {
+                if listed.is_empty() {
+                    others.iter().all(|symbols| symbols.contains(s))
+                } else {
+                    listed.contains(s)
+                }
+            })
             .cloned()
             .collect();
 
         if common.is_empty() {
-            // Fallback: use Hyperliquid symbols
-            common = hl_symbols;
+            // Fallback: use the first venue's symbols
+            common = primary.clone();
         }
 
         Ok(common.into_iter().take(10).collect())
@@ -97,138 +116,601 @@ impl FundingArbStrategy {
 
     async fn check_opportunity(&self, symbol: &str) -> Result<()> {
         // Fetch funding rates from all venues
-        let hl_funding = self.hyperliquid.get_funding_rate(symbol).await?;
-        let binance_funding = self.binance.get_funding_rate(symbol).await?;
-        let bybit_funding = self.bybit.get_funding_rate(symbol).await?;
-
-        // Update state
-        self.state.funding_rates.insert(
-            (symbol.to_string(), Venue::Hyperliquid),
-            hl_funding.clone(),
-        );
-        self.state.funding_rates.insert(
-            (symbol.to_string(), Venue::Binance),
-            binance_funding.clone(),
-        );
-        self.state.funding_rates.insert(
-            (symbol.to_string(), Venue::Bybit),
-            bybit_funding.clone(),
-        );
-
-        // Find the highest and lowest funding rates
-        let venues = vec![
-            ("Hyperliquid", hl_funding.rate, Venue::Hyperliquid),
-            ("Binance", binance_funding.rate, Venue::Binance),
-            ("Bybit", bybit_funding.rate, Venue::Bybit),
-        ];
-
-        let (high_venue_name, high_rate, high_venue) = venues
-            .iter()
-            .max_by(|a, b| a.1.cmp(&b.1))
-            .unwrap();
+        let mut rates = Vec::with_capacity(self.venues.len());
+        for venue in &self.venues {
+            let funding = self.connectors.get(venue)?.get_funding_rate(symbol).await?;
+            self.state.update_funding_rate(funding.clone());
+            rates.push(funding);
+        }
 
-        let (low_venue_name, low_rate, low_venue) = venues
-            .iter()
-            .min_by(|a, b| a.1.cmp(&b.1))
-            .unwrap();
+        // One hedge per symbol at a time; manage_hedges looks after it until it closes
+        let open = |h: &Hedge| h.is_open() && h.strategy == StrategyType::FundingArb;
+        if self.state.hedges.iter().any(|h| open(&h) && h.symbol == symbol) {
+            return Ok(());
+        }
 
-        // Calculate annualized spread
-        let spread = high_rate - low_rate;
-        let annualized_spread = spread * Decimal::from(365 * 3); // Funding is typically every 8 hours
+        // Venues pay on different intervals, so rank on the annualised rate
+        let (Some(high), Some(low)) = (
+            rates.iter().max_by_key(|r| r.annualized_rate()),
+            rates.iter().min_by_key(|r| r.annualized_rate()),
+        ) else {
+            return Ok(());
+        };
+        let (high_venue, low_venue) = (&high.venue, &low.venue);
 
+        let annualized_spread = high.annualized_rate() - low.annualized_rate();
         let annualized_f64 = annualized_spread.to_f64().unwrap_or(0.0);
-        if annualized_f64 < self.min_annualized_spread {
+        if annualized_f64 < self.config.min_annualized_spread {
             return Ok(());
         }
 
         info!(
-            "Funding arb opportunity found: {} - {}: {:.4}% vs {}: {:.4}% (annualized: {:.2}%)",
+            "Funding arb opportunity found: {} - {:?}: {:.4}%/{}h vs {:?}: {:.4}%/{}h (annualized: {:.2}%)",
             symbol,
-            high_venue_name,
-            high_rate.to_f64().unwrap_or(0.0) * 100.0,
-            low_venue_name,
-            low_rate.to_f64().unwrap_or(0.0) * 100.0,
+            high_venue,
+            high.rate.to_f64().unwrap_or(0.0) * 100.0,
+            high.interval_hours,
+            low_venue,
+            low.rate.to_f64().unwrap_or(0.0) * 100.0,
+            low.interval_hours,
             annualized_f64 * 100.0
         );
 
+        let opportunity = self.state.record_opportunity(ArbitrageOpportunity {
+            id: 0,
+            strategy: StrategyType::FundingArb,
+            symbol: symbol.to_string(),
+            venue_a: high_venue.clone(),
+            venue_b: low_venue.clone(),
+            price_a: high.annualized_rate(),
+            price_b: low.annualized_rate(),
+            spread_bps: (annualized_spread * Decimal::from(10_000)).to_i64().unwrap_or(0),
+            estimated_profit: Decimal::ZERO,
+            status: OpportunityStatus::Detected,
+            reason: None,
+            timestamp: Utc::now(),
+        });
+
         if self.dry_run {
             info!("DRY RUN: Would execute funding arbitrage");
+            self.state
+                .opportunities
+                .set_status(opportunity, OpportunityStatus::DryRun, None);
             return Ok(());
         }
 
         // Execute: short on high-funding venue, long on low-funding venue
-        self.execute_funding_arb(symbol, high_venue, low_venue, spread).await?;
-
-        Ok(())
+        let result = self
+            .execute_funding_arb(symbol, high_venue, low_venue, annualized_spread, opportunity)
+            .await;
+        if let Err(e) = &result {
+            let reason = Some(format!("{:#}", e));
+            self.state
+                .opportunities
+                .set_status(opportunity, OpportunityStatus::Failed, reason);
+        }
+        result
     }
 
+    /// Opens the hedge and settles `opportunity` with the outcome; errors are
+    /// left for the caller to record.
     async fn execute_funding_arb(
         &self,
         symbol: &str,
         high_venue: &Venue,
         low_venue: &Venue,
-        spread: Decimal,
+        annualized_spread: Decimal,
+        opportunity: u64,
     ) -> Result<()> {
-        // Determine position size (simplified - in production, use risk engine limits)
-        let position_size = Decimal::from(1000u64); // Placeholder
-
-        // Place orders
-        let short_trade = match high_venue {
-            Venue::Hyperliquid => {
-                self.hyperliquid
-                    .place_order(symbol, PositionSide::Short, position_size, Decimal::ZERO)
-                    .await?
-            }
-            Venue::Binance => {
-                // Binance connector would need order placement method
-                warn!("Binance order placement not implemented");
-                return Ok(());
-            }
-            Venue::Bybit => {
-                // Bybit connector would need order placement method
-                warn!("Bybit order placement not implemented");
+        let (size, short_spec, long_spec) = self.size_hedge(symbol, high_venue, low_venue).await?;
+        info!(
+            "Sized {} hedge at {} ({} notional): short {} @ {}, long {} @ {}",
+            symbol,
+            size.base_size,
+            size.notional,
+            size.short_contracts,
+            size.short_price,
+            size.long_contracts,
+            size.long_price
+        );
+
+        // Marketable limits at the depth the size relies on
+        let short = LegOrder {
+            venue: high_venue.clone(),
+            symbol: symbol.to_string(),
+            side: PositionSide::Short,
+            contracts: size.short_contracts,
+            base_size: size.base_size,
+            price: size.short_price,
+            spec: Some(short_spec),
+        };
+        let long = LegOrder {
+            venue: low_venue.clone(),
+            symbol: symbol.to_string(),
+            side: PositionSide::Long,
+            contracts: size.long_contracts,
+            base_size: size.base_size,
+            price: size.long_price,
+            spec: Some(long_spec),
+        };
+        let execution = match self.execution.execute_pair(&short, &long).await {
+            Ok(execution) => execution,
+            Err(e) if e.downcast_ref::<RiskError>().is_some() => {
+                warn!("Risk check failed for {} hedge: {:#}", symbol, e);
+                let reason = Some(format!("{:#}", e));
+                self.state
+                    .opportunities
+                    .set_status(opportunity, OpportunityStatus::SkippedByRisk, reason);
                 return Ok(());
             }
-            _ => return Err(anyhow::anyhow!("Unsupported venue for short")),
+            Err(e) => return Err(e),
         };
 
-        let long_trade = match low_venue {
-            Venue::Hyperliquid => {
-                self.hyperliquid
-                    .place_order(symbol, PositionSide::Long, position_size, Decimal::ZERO)
-                    .await?
+        if !execution.is_hedged() {
+            let reason = format!(
+                "short {:?}, long {:?}",
+                execution.first.status, execution.second.status
+            );
+            warn!("Funding arbitrage on {} not established: {}", symbol, reason);
+            self.state
+                .opportunities
+                .set_status(opportunity, OpportunityStatus::Failed, Some(reason));
+            // A leg that could not be unwound is tracked as a hedge, which the
+            // reconciler reports as orphaned and flattens once its grace period is up
+            let left_open = [(&execution.first, &short), (&execution.second, &long)]
+                .into_iter()
+                .filter(|(trade, _)| trade.status.is_fill())
+                .map(|(trade, order)| {
+                    let spec = order.spec.as_ref();
+                    trade.size * spec.map_or(Decimal::ONE, |s| s.contract_multiplier)
+                })
+                .max();
+            if let Some(size) = left_open {
+                error!("{} {} left open after a failed unwind", size, symbol);
+                let hedge = Hedge::new(symbol, high_venue.clone(), low_venue.clone(), size);
+                self.state.record_hedge(hedge);
             }
-            Venue::Binance => {
-                warn!("Binance order placement not implemented");
-                return Ok(());
+            return Ok(());
+        }
+
+        let hedge = Hedge::new(symbol, high_venue.clone(), low_venue.clone(), size.base_size);
+        self.state.record_hedge(hedge);
+        self.state.opportunities.update(opportunity, |o| {
+            o.status = OpportunityStatus::Executed;
+            o.estimated_profit = size.notional * annualized_spread / Decimal::from(365);
+        });
+        info!(
+            "Executed funding arbitrage: Short {} on {:?} ({:?}), Long {} on {:?} ({:?})",
+            symbol,
+            high_venue,
+            execution.first.status,
+            symbol,
+            low_venue,
+            execution.second.status
+        );
+
+        Ok(())
+    }
+
+    /// Sizes a new hedge from both venues' specs, books and free margin.
+    async fn size_hedge(
+        &self,
+        symbol: &str,
+        short_venue: &Venue,
+        long_venue: &Venue,
+    ) -> Result<(HedgeSize, ContractSpec, ContractSpec)> {
+        let short = self.connectors.get(short_venue)?;
+        let long = self.connectors.get(long_venue)?;
+        let (short_spec, long_spec, short_book, long_book, short_margin, long_margin) = tokio::try_join!(
+            short.get_contract_spec(symbol),
+            long.get_contract_spec(symbol),
+            short.get_order_book(symbol, SIZING_BOOK_DEPTH),
+            long.get_order_book(symbol, SIZING_BOOK_DEPTH),
+            short.get_account_margin(),
+            long.get_account_margin(),
+        )?;
+        let (short_available, long_available) =
+            (short_margin.available_margin(), long_margin.available_margin());
+        self.risk_engine.update_margin(short_margin);
+        self.risk_engine.update_margin(long_margin);
+
+        // Both legs are booked against the asset, so each gets half the headroom
+        let risk = self.risk_engine.config();
+        let headroom = Decimal::from_f64(risk.max_notional_per_asset).unwrap_or(Decimal::ZERO)
+            - self.risk_engine.exposure(symbol);
+        let max_notional = headroom / Decimal::TWO;
+        let size = size_hedge(
+            &LegMarket {
+                spec: &short_spec,
+                book: &short_book,
+                available_margin: short_available,
+            },
+            &LegMarket {
+                spec: &long_spec,
+                book: &long_book,
+                available_margin: long_available,
+            },
+            &SizingLimits {
+                max_notional,
+                max_leverage: Decimal::from_f64(risk.max_leverage).unwrap_or(Decimal::ONE),
+                max_slippage_bps: Decimal::from(self.config.max_depth_slippage_bps),
+            },
+        )?;
+        Ok((size, short_spec, long_spec))
+    }
+
+    /// Checks every open hedge against the exit rules, unwinding or re-hedging as needed.
+    pub async fn manage_hedges(&self) -> Result<()> {
+        let open: Vec<Hedge> = self
+            .state
+            .hedges
+            .iter()
+            .filter(|h| h.is_open() && h.strategy == StrategyType::FundingArb)
+            .map(|h| h.value().clone())
+            .collect();
+        if open.is_empty() {
+            return Ok(());
+        }
+
+        let legs: HashSet<Venue> = open
+            .iter()
+            .flat_map(|h| [h.short_venue.clone(), h.long_venue.clone()])
+            .collect();
+        let refreshed = self.refresh_positions(legs).await;
+
+        for hedge in open {
+            // Without fresh positions on both legs a missing leg cannot be told apart from a failed query
+            if !(refreshed.contains(&hedge.short_venue) && refreshed.contains(&hedge.long_venue)) {
+                continue;
             }
-            Venue::Bybit => {
-                warn!("Bybit order placement not implemented");
-                return Ok(());
+            // A hedge with one leg gone is an orphan, which the reconciler reports and
+            // flattens only when configured to, unless it is our own unfinished close
+            let (short_open, long_open) = match self.leg_sizes(&hedge).await {
+                Ok(sizes) => sizes,
+                Err(e) => {
+                    error!("Error managing hedge {}: {}", hedge.id, e);
+                    continue;
+                }
+            };
+            let closing = !hedge.closed_legs.is_empty();
+            if short_open.is_zero() != long_open.is_zero() && !closing {
+                debug!("Hedge {} has lost a leg, leaving it to reconciliation", hedge.id);
+                continue;
             }
-            _ => return Err(anyhow::anyhow!("Unsupported venue for long")),
+            // A close that already took one leg off finishes regardless of the exit rules
+            if closing {
+                if let Err(e) = self.close_hedge(&hedge, ExitReason::UnfinishedClose).await {
+                    error!("Error managing hedge {}: {}", hedge.id, e);
+                }
+                continue;
+            }
+            let result = match self.exit_reason(&hedge).await {
+                Ok(Some(reason)) => self.close_hedge(&hedge, reason).await,
+                Ok(None) => self.rebalance(&hedge).await,
+                Err(e) => Err(e),
+            };
+            if let Err(e) = result {
+                error!("Error managing hedge {}: {}", hedge.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Replaces the strategy's view of each venue's positions with what the
+    /// venue reports, returning the venues that answered. The local position
+    /// book is left to reconciliation.
+    async fn refresh_positions(&self, venues: HashSet<Venue>) -> HashSet<Venue> {
+        let mut refreshed = HashSet::new();
+        for venue in venues {
+            let positions = match self.connectors.get(&venue) {
+                Ok(connector) => connector.get_positions().await,
+                Err(e) => Err(e),
+            };
+            match positions {
+                Ok(positions) => {
+                    let mut held = self.venue_positions.write();
+                    held.retain(|_, p| p.venue != venue);
+                    for position in positions {
+                        held.insert(Position::key(&position.venue, &position.symbol), position);
+                    }
+                    refreshed.insert(venue);
+                }
+                Err(e) => warn!("Failed to refresh {:?} positions: {}", venue, e),
+            }
+        }
+        refreshed
+    }
+
+    async fn leg_specs(&self, hedge: &Hedge) -> Result<(ContractSpec, ContractSpec)> {
+        let connectors = &self.connectors;
+        tokio::try_join!(
+            contract_spec(connectors, &self.state, &hedge.short_venue, &hedge.symbol),
+            contract_spec(connectors, &self.state, &hedge.long_venue, &hedge.symbol),
+        )
+    }
+
+    /// Open size of the short and long legs in each venue's contracts, leaving
+    /// out what other strategies' hedges hold in the same positions.
+    async fn leg_sizes(&self, hedge: &Hedge) -> Result<(Decimal, Decimal)> {
+        let held = |venue: &Venue| {
+            self.venue_positions.read().get(&Position::key(venue, &hedge.symbol)).cloned()
         };
+        let (short, long) = (held(&hedge.short_venue), held(&hedge.long_venue));
+        let (connectors, state) = (&self.connectors, &self.state);
+        tokio::try_join!(
+            own_leg_size(
+                connectors,
+                state,
+                hedge,
+                &hedge.short_venue,
+                PositionSide::Short,
+                short.as_ref()
+            ),
+            own_leg_size(
+                connectors,
+                state,
+                hedge,
+                &hedge.long_venue,
+                PositionSide::Long,
+                long.as_ref()
+            ),
+        )
+    }
+
+    async fn exit_reason(&self, hedge: &Hedge) -> Result<Option<ExitReason>> {
+        for venue in [&hedge.short_venue, &hedge.long_venue] {
+            let margin = match self.connectors.get(venue)?.get_account_margin().await {
+                Ok(margin) => margin,
+                Err(e) => {
+                    warn!("Failed to fetch {:?} margin: {}", venue, e);
+                    continue;
+                }
+            };
+            let breached =
+                margin.margin_ratio().to_f64().unwrap_or(1.0) >= self.config.max_margin_ratio;
+            self.risk_engine.update_margin(margin);
+            if breached {
+                return Ok(Some(ExitReason::MarginBreach(venue.clone())));
+            }
+        }
+
+        if let Some(hours) = self.config.max_holding_hours {
+            if Utc::now() - hedge.opened_at >= chrono::Duration::hours(hours as i64) {
+                return Ok(Some(ExitReason::MaxHoldingTime));
+            }
+        }
+
+        let short = self.connectors.get(&hedge.short_venue)?.get_funding_rate(&hedge.symbol).await?;
+        let long = self.connectors.get(&hedge.long_venue)?.get_funding_rate(&hedge.symbol).await?;
+
+        // Shorting the high-funding leg earns (short - long); a negative value pays it
+        let spread = short.annualized_rate() - long.annualized_rate();
+        if spread.to_f64().unwrap_or(0.0) < self.config.exit_annualized_spread {
+            return Ok(Some(ExitReason::SpreadCompressed(spread)));
+        }
+
+        if self.config.exit_on_predicted_flip
+            && (short.predicted_rate.is_some() || long.predicted_rate.is_some())
+        {
+            let predicted = predicted(&short).annualized_rate() - predicted(&long).annualized_rate();
+            if predicted.is_sign_negative() {
+                return Ok(Some(ExitReason::PredictedFlip(predicted)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Reduce-only unwind of whichever legs are still open.
+    async fn close_hedge(&self, hedge: &Hedge, reason: ExitReason) -> Result<()> {
+        info!("Closing hedge {} on {}: {:?}", hedge.id, hedge.symbol, reason);
+        if self.dry_run {
+            info!("DRY RUN: Would unwind hedge {}", hedge.id);
+            return Ok(());
+        }
+
+        // Legs are held in each venue's contracts, the hedge size in the base asset
+        let (short_spec, long_spec) = self.leg_specs(hedge).await?;
+        let (short_open, long_open) = self.leg_sizes(hedge).await?;
+        let short_close =
+            short_spec.round_size_down(short_open.min(hedge.size / short_spec.contract_multiplier));
+        let long_close =
+            long_spec.round_size_down(long_open.min(hedge.size / long_spec.contract_multiplier));
+        let (short_unwind, long_unwind) = tokio::join!(
+            self.reduce_leg(hedge, &hedge.short_venue, PositionSide::Long, short_close),
+            self.reduce_leg(hedge, &hedge.long_venue, PositionSide::Short, long_close),
+        );
+
+        // Each fill is booked at once, so a failure on the other leg cannot lose it
+        let mut gone = Vec::new();
+        let mut failure = None;
+        let legs = [
+            (&hedge.short_venue, PositionSide::Short, short_unwind),
+            (&hedge.long_venue, PositionSide::Long, long_unwind),
+        ];
+        for (venue, side, unwind) in legs {
+            match unwind {
+                Ok(Some(trade)) if trade.status == TradeStatus::Filled => {
+                    self.book_close(hedge, venue, side, Some(&trade));
+                    // On the hedge, so the close is still finished after a restart
+                    self.state.update_hedge(&hedge.id, |h| h.closed_legs.push(venue.clone()));
+                }
+                Ok(Some(trade)) => {
+                    // What filled comes off the leg; the next pass closes the rest
+                    self.risk_engine.record_position_reduced(&hedge.symbol, trade.size * trade.price);
+                    self.state.record_position_closed(
+                        venue.clone(),
+                        hedge.symbol.clone(),
+                        side,
+                        trade.size,
+                    );
+                    failure = Some(anyhow::anyhow!(
+                        "{:?} leg of {} only partially closed ({})",
+                        venue,
+                        hedge.symbol,
+                        trade.size
+                    ));
+                }
+                Ok(None) => gone.push((venue, side)),
+                Err(e) => failure = Some(e),
+            }
+        }
+        if let Some(e) = failure {
+            // The hedge stays open; the next pass only unwinds what is left
+            return Err(e.context(format!("Failed to fully unwind hedge {}", hedge.id)));
+        }
+
+        // Legs that were already gone still release their position slot, unless an
+        // earlier pass closed and booked them
+        for (venue, side) in gone {
+            if !hedge.leg_closed(venue) {
+                self.book_close(hedge, venue, side, None);
+            }
+        }
+
+        self.state.update_hedge(&hedge.id, |h| h.closed_at = Some(Utc::now()));
+        info!("Closed hedge {} ({:?})", hedge.id, reason);
+        Ok(())
+    }
+
+    /// Releases a closed leg from the risk engine and the position book.
+    fn book_close(&self, hedge: &Hedge, venue: &Venue, side: PositionSide, trade: Option<&Trade>) {
+        let (size, notional) =
+            trade.map(|t| (t.size, t.size * t.price)).unwrap_or((Decimal::ZERO, Decimal::ZERO));
+        self.risk_engine.record_position_closed(venue, &hedge.symbol, notional);
+        self.state.record_position_closed(venue.clone(), hedge.symbol.clone(), side, size);
+    }
+
+    /// Trims the larger leg back to the smaller one when fills or liquidations
+    /// have left the hedge out of delta neutrality.
+    async fn rebalance(&self, hedge: &Hedge) -> Result<()> {
+        let (short_open, long_open) = self.leg_sizes(hedge).await?;
 
-        // Check risk before executing
-        if let Err(e) = self.risk_engine.check_trade(&short_trade) {
-            warn!("Risk check failed for short trade: {}", e);
+        if short_open.is_zero() && long_open.is_zero() {
+            warn!("Both legs of hedge {} are gone, marking it closed", hedge.id);
+            self.state.update_hedge(&hedge.id, |h| h.closed_at = Some(Utc::now()));
+            return Ok(());
+        }
+        if short_open.is_zero() || long_open.is_zero() {
+            // An orphan, or a close still under way, is not trimmed
             return Ok(());
         }
 
-        if let Err(e) = self.risk_engine.check_trade(&long_trade) {
-            warn!("Risk check failed for long trade: {}", e);
+        // Compare the legs in the base asset
+        let (short_spec, long_spec) = self.leg_specs(hedge).await?;
+        let short = short_open * short_spec.contract_multiplier;
+        let long = long_open * long_spec.contract_multiplier;
+        let drift = (short - long).abs();
+        let tolerance = Decimal::from_f64(self.config.rebalance_tolerance).unwrap_or(Decimal::ZERO);
+        if drift <= short.max(long) * tolerance {
             return Ok(());
         }
 
-        // Record trades
-        self.risk_engine.record_trade(&short_trade);
-        self.risk_engine.record_trade(&long_trade);
+        let (venue, side, spec) = if short > long {
+            (&hedge.short_venue, PositionSide::Long, &short_spec)
+        } else {
+            (&hedge.long_venue, PositionSide::Short, &long_spec)
+        };
+        let contracts = spec.round_size_down(drift / spec.contract_multiplier);
+        if contracts.is_zero() {
+            // Less than a lot apart, so there is nothing to trim
+            return Ok(());
+        }
 
         info!(
-            "Executed funding arbitrage: Short {} on {:?}, Long {} on {:?}",
-            symbol, high_venue, symbol, low_venue
+            "Re-hedging {}: short {} on {:?} vs long {} on {:?}",
+            hedge.id, short, hedge.short_venue, long, hedge.long_venue
         );
+        if self.dry_run {
+            info!("DRY RUN: Would trim hedge {} by {}", hedge.id, drift);
+            return Ok(());
+        }
+
+        // A failed trim leaves the hedge as it was; a partial one only takes off what filled
+        let Some(trade) = self.reduce_leg(hedge, venue, side, contracts).await? else {
+            return Ok(());
+        };
+        let trimmed = trade.size * spec.contract_multiplier;
+        self.risk_engine.record_position_reduced(&hedge.symbol, trade.size * trade.price);
+        self.state.record_fill(trade);
+        self.state.update_hedge(&hedge.id, |h| h.size = short.max(long) - trimmed);
+        Ok(())
+    }
+
+    /// Takes `size` off one leg with reduce-only IOC market orders; the
+    /// trade may be partially filled.
+    async fn reduce_leg(
+        &self,
+        hedge: &Hedge,
+        venue: &Venue,
+        side: PositionSide,
+        size: Decimal,
+    ) -> Result<Option<Trade>> {
+        if size.is_zero() {
+            return Ok(None);
+        }
+        reduce_position(&self.connectors, venue, &hedge.symbol, side, size)
+            .await
+            .map(Some)
+    }
+}
+
+#[async_trait]
+impl Strategy for FundingArbStrategy {
+    fn name(&self) -> &str {
+        "funding_arb"
+    }
+
+    fn tick_interval(&self) -> Duration {
+        self.check_interval
+    }
+
+    async fn init(&mut self) -> Result<()> {
+        self.symbols = self.discover_symbols().await?;
+        info!("Monitoring {} symbols for funding arbitrage", self.symbols.len());
+        Ok(())
+    }
+
+    async fn on_tick(&mut self) -> Result<()> {
+        // Exits reduce risk, so they keep running while the kill switch is active
+        if let Err(e) = self.manage_hedges().await {
+            error!("Error managing open hedges: {}", e);
+        }
 
+        if self.risk_engine.is_kill_switch_active() {
+            warn!("Kill switch active, skipping funding arb entries");
+            return Ok(());
+        }
+
+        for symbol in &self.symbols {
+            if let Err(e) = self.check_opportunity(symbol).await {
+                error!("Error checking opportunity for {}: {}", symbol, e);
+            }
+        }
         Ok(())
     }
+
+    fn health(&self) -> StrategyHealth {
+        if self.symbols.is_empty() {
+            StrategyHealth::Degraded("no symbols to monitor".to_string())
+        } else {
+            StrategyHealth::Healthy
+        }
+    }
+
+    fn dry_run(&self) -> Option<bool> {
+        Some(self.dry_run)
+    }
+
+    fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+}
+
+/// The venue's forecast for the next settlement, falling back to the current rate.
+fn predicted(rate: &FundingRate) -> FundingRate {
+    FundingRate {
+        rate: rate.predicted_rate.unwrap_or(rate.rate),
+        ..rate.clone()
+    }
 }
@@ -0,0 +1,199 @@
+use arb_core::types::{FundingPayment, Hedge, SharedState, Venue};
+use arb_core::RiskEngine;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use connectors::ConnectorRegistry;
+use rust_decimal::Decimal;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::sleep;
+use tracing::{error, info, warn};
+
+/// Position in a venue's funding history. Queries are inclusive of `since`,
+/// so ids already booked at exactly that time are remembered.
+struct Cursor {
+    since: DateTime<Utc>,
+    booked_at_since: HashSet<String>,
+    /// Latest leg settlement already on a restored hedge. Payments up to here
+    /// may have been booked by an earlier run, so each hedge is checked.
+    restored_until: Option<DateTime<Utc>>,
+}
+
+impl Cursor {
+    fn is_new(&self, payment: &FundingPayment) -> bool {
+        payment.timestamp > self.since
+            || (payment.timestamp == self.since && !self.booked_at_since.contains(&payment.id))
+    }
+
+    fn advance(&mut self, payment: &FundingPayment) {
+        if payment.timestamp > self.since {
+            self.since = payment.timestamp;
+            self.booked_at_since.clear();
+        }
+        self.booked_at_since.insert(payment.id.clone());
+    }
+}
+
+/// Pulls realised funding for every hedge leg, attributes it to the hedges
+/// holding that leg and feeds it into the risk engine's daily PnL.
+pub struct FundingLedger {
+    connectors: Arc<ConnectorRegistry>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+    poll_interval: Duration,
+    cursors: Mutex<HashMap<(Venue, String), Cursor>>,
+}
+
+impl FundingLedger {
+    pub fn new(
+        connectors: Arc<ConnectorRegistry>,
+        state: Arc<SharedState>,
+        risk_engine: Arc<RiskEngine>,
+        poll_interval_ms: u64,
+    ) -> Self {
+        Self {
+            connectors,
+            state,
+            risk_engine,
+            poll_interval: Duration::from_millis(poll_interval_ms),
+            cursors: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn run_loop(&self) {
+        info!("Starting funding ledger loop");
+
+        loop {
+            match self.sync().await {
+                Ok(booked) if !booked.is_zero() => info!("Booked {} of realised funding", booked),
+                Ok(_) => {}
+                Err(e) => error!("Funding ledger sync failed: {}", e),
+            }
+            sleep(self.poll_interval).await;
+        }
+    }
+
+    /// Books any funding settled since the last pass and returns its net amount.
+    pub async fn sync(&self) -> Result<Decimal> {
+        let mut cursors = self.cursors.lock().await;
+        let hedges: Vec<Hedge> = self.state.hedges.iter().map(|h| h.value().clone()).collect();
+
+        // One history query per venue leg, starting from the oldest hedge still accruing
+        let mut legs: HashMap<(Venue, String), Cursor> = HashMap::new();
+        for hedge in &hedges {
+            // Spot legs hold the token itself, which pays no funding
+            let venues = [&hedge.short_venue, &hedge.long_venue];
+            for venue in venues.into_iter().filter(|v| !v.is_spot()) {
+                let key = (venue.clone(), hedge.symbol.clone());
+                let settled = cursors.get(&key).map(|c| c.since);
+                if hedge.closed_at.zip(settled).is_some_and(|(closed, since)| closed < since) {
+                    continue;
+                }
+                let booked = hedge.leg_funding_at(venue);
+                let resume = booked.unwrap_or(hedge.opened_at);
+                legs.entry(key)
+                    .and_modify(|start| {
+                        start.since = start.since.min(resume);
+                        start.restored_until = start.restored_until.max(booked);
+                    })
+                    .or_insert(Cursor {
+                        since: resume,
+                        booked_at_since: HashSet::new(),
+                        restored_until: booked,
+                    });
+            }
+        }
+
+        let mut booked = Decimal::ZERO;
+        for ((venue, symbol), start) in legs {
+            // A leg already being followed keeps its own cursor
+            let cursor = cursors.entry((venue.clone(), symbol.clone())).or_insert(start);
+
+            let mut payments = match self.fetch(&venue, &symbol, cursor.since).await {
+                Ok(payments) => payments,
+                Err(e) => {
+                    warn!("Failed to fetch {:?} funding for {}: {}", venue, symbol, e);
+                    continue;
+                }
+            };
+            payments.sort_by_key(|p| p.timestamp);
+
+            for payment in payments {
+                if !cursor.is_new(&payment) {
+                    continue;
+                }
+                cursor.advance(&payment);
+                let restored = cursor.restored_until.is_some_and(|until| payment.timestamp <= until);
+                let amount = self.attribute(&payment, restored);
+                if !amount.is_zero() {
+                    self.risk_engine.update_daily_pnl(amount);
+                    booked += amount;
+                }
+            }
+        }
+        Ok(booked)
+    }
+
+    async fn fetch(
+        &self,
+        venue: &Venue,
+        symbol: &str,
+        since: DateTime<Utc>,
+    ) -> Result<Vec<FundingPayment>> {
+        self.connectors
+            .get(venue)?
+            .get_funding_payments(symbol, since)
+            .await
+    }
+
+    /// Splits a payment across the hedges that held the leg when it settled,
+    /// pro rata by size, and returns the amount booked. With `restored`, hedges
+    /// whose leg already booked funding at or after the payment are skipped.
+    fn attribute(&self, payment: &FundingPayment, restored: bool) -> Decimal {
+        let holders: Vec<(String, Decimal, bool)> = self
+            .state
+            .hedges
+            .iter()
+            .filter(|h| h.symbol == payment.symbol && h.was_open_at(&payment.venue, payment.timestamp))
+            .map(|h| {
+                let booked = restored
+                    && h.leg_funding_at(&payment.venue).is_some_and(|at| at >= payment.timestamp);
+                (h.id.clone(), h.size, booked)
+            })
+            .collect();
+        let total_size: Decimal = holders.iter().map(|(_, size, _)| *size).sum();
+        if holders.is_empty() || total_size.is_zero() {
+            return Decimal::ZERO;
+        }
+
+        let mut remaining = payment.amount;
+        let mut credited = Decimal::ZERO;
+        for (index, (id, size, booked)) in holders.iter().enumerate() {
+            // The last holder takes the remainder so shares sum exactly
+            let share = if index + 1 == holders.len() {
+                remaining
+            } else {
+                payment.amount * size / total_size
+            };
+            remaining -= share;
+            if *booked {
+                continue;
+            }
+            credited += share;
+
+            self.state.update_hedge(id, |hedge| {
+                if hedge.short_venue == payment.venue {
+                    hedge.short_funding += share;
+                    hedge.short_funding_at = Some(payment.timestamp);
+                } else {
+                    hedge.long_funding += share;
+                    hedge.long_funding_at = Some(payment.timestamp);
+                }
+                hedge.last_funding_at = Some(payment.timestamp);
+            });
+        }
+        credited
+    }
+}
@@ -1,86 +1,100 @@
-use arb_core::types::{ArbitrageOpportunity, MarketData, PositionSide, SharedState, StrategyType, Venue};
+use crate::execution::{reduce_position, ExecutionCoordinator, LegOrder};
+use crate::sizing::{contract_spec, own_leg_size};
+use crate::strategy::Strategy;
+use arb_core::config::HyperEvmSpotConfig;
+use arb_core::risk::RiskError;
+use arb_core::types::{
+    ArbitrageOpportunity, Hedge, MarketData, OpportunityStatus, PositionSide, SharedState,
+    StrategyType, TradeStatus, Venue,
+};
 use arb_core::RiskEngine;
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::Utc;
-use connectors::{HyperEvmConnector, HyperliquidConnector};
+use connectors::{ConnectorRegistry, HyperEvmConnector};
 use rust_decimal::Decimal;
 use rust_decimal::prelude::*;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
 use tracing::{error, info, warn};
 
+/// Spot pairs watched on HyperEVM, priced against the Hyperliquid perp of the base.
+const PAIRS: [&str; 3] = ["ETH-USDC", "BTC-USDC", "SOL-USDC"];
+
 pub struct HyperEvmSpotStrategy {
-    hyperliquid: HyperliquidConnector,
+    connectors: Arc<ConnectorRegistry>,
+    /// Also registered in `connectors`; kept for spot quotes and the swap size.
     hyperevm: HyperEvmConnector,
     state: Arc<SharedState>,
     risk_engine: Arc<RiskEngine>,
+    execution: ExecutionCoordinator,
     min_spread_bps: u64,
+    exit_spread_bps: i64,
     check_interval: Duration,
     dry_run: bool,
 }
 
 impl HyperEvmSpotStrategy {
     pub fn new(
-        hyperliquid: HyperliquidConnector,
+        connectors: Arc<ConnectorRegistry>,
         hyperevm: HyperEvmConnector,
         state: Arc<SharedState>,
         risk_engine: Arc<RiskEngine>,
-        min_spread_bps: u64,
-        check_interval_ms: u64,
+        config: HyperEvmSpotConfig,
         dry_run: bool,
     ) -> Self {
+        let execution = ExecutionCoordinator::new(
+            connectors.clone(),
+            state.clone(),
+            risk_engine.clone(),
+            config.execution,
+        );
         Self {
-            hyperliquid,
+            connectors,
             hyperevm,
             state,
             risk_engine,
-            min_spread_bps,
-            check_interval: Duration::from_millis(check_interval_ms),
+            execution,
+            min_spread_bps: config.min_spread_bps,
+            exit_spread_bps: config.exit_spread_bps,
+            check_interval: Duration::from_millis(config.check_interval_ms),
             dry_run,
         }
     }
 
-    pub async fn run_loop(&self) {
-        info!("Starting HyperEVM spot arbitrage loop");
-
-        // Monitor a few key pairs
-        let pairs = vec!["ETH-USDC", "BTC-USDC", "SOL-USDC"];
-
-        loop {
-            if self.risk_engine.is_kill_switch_active() {
-                warn!("Kill switch active, pausing HyperEVM spot arb loop");
-                sleep(Duration::from_secs(10)).await;
-                continue;
-            }
-
-            for pair in &pairs {
-                if let Err(e) = self.check_opportunity(pair).await {
-                    error!("Error checking opportunity for {}: {}", pair, e);
-                }
-            }
-
-            sleep(self.check_interval).await;
-        }
+    /// Open hedges this strategy holds, one per pair at most.
+    fn open_hedges(&self) -> Vec<Hedge> {
+        self.state
+            .hedges
+            .iter()
+            .filter(|h| h.is_open() && h.strategy == StrategyType::HyperEvmSpot)
+            .map(|h| h.value().clone())
+            .collect()
     }
 
-    async fn check_opportunity(&self, pair: &str) -> Result<()> {
+    /// The HyperEVM spot quote for `pair` and the Hyperliquid perp quote for its base.
+    async fn quotes(&self, pair: &str) -> Result<(MarketData, MarketData)> {
         // Get spot price from HyperEVM
         let evm_price = self.hyperevm.get_spot_price(pair).await?;
 
         // Get reference price from Hyperliquid (using first token as symbol)
-        let symbol = pair.split('-').next().unwrap_or("ETH");
-        let hl_price = self.hyperliquid.get_market_data(symbol).await?;
+        let symbol = base(pair);
+        let hl_price = self.connectors.get(&Venue::Hyperliquid)?.get_market_data(symbol).await?;
 
         // Update state
-        self.state.market_data.insert(
-            (pair.to_string(), Venue::HyperEvm),
-            evm_price.clone(),
-        );
-        self.state.market_data.insert(
-            (symbol.to_string(), Venue::Hyperliquid),
-            hl_price.clone(),
-        );
+        self.state.update_market_data(evm_price.clone());
+        self.state.update_market_data(hl_price.clone());
+        Ok((evm_price, hl_price))
+    }
+
+    async fn check_opportunity(&self, pair: &str) -> Result<()> {
+        let (evm_price, hl_price) = self.quotes(pair).await?;
+        let symbol = base(pair);
+
+        // One position per pair; manage_hedges unwinds it once the spread converges
+        if self.open_hedges().iter().any(|h| h.symbol == symbol) {
+            return Ok(());
+        }
 
         // Calculate spread
         let spread = if evm_price.price > hl_price.price {
@@ -105,34 +119,312 @@ impl HyperEvmSpotStrategy {
             spread_bps
         );
 
+        let opportunity = self.state.record_opportunity(ArbitrageOpportunity {
+            id: 0,
+            strategy: StrategyType::HyperEvmSpot,
+            symbol: pair.to_string(),
+            venue_a: Venue::HyperEvm,
+            venue_b: Venue::Hyperliquid,
+            price_a: evm_price.price,
+            price_b: hl_price.price,
+            spread_bps: spread_bps as i64,
+            estimated_profit: spread * self.hyperevm.quote_size(),
+            status: OpportunityStatus::Detected,
+            reason: None,
+            timestamp: Utc::now(),
+        });
+
         if self.dry_run {
             info!("DRY RUN: Would execute HyperEVM spot arbitrage");
+            self.state
+                .opportunities
+                .set_status(opportunity, OpportunityStatus::DryRun, None);
             return Ok(());
         }
 
         // Execute arbitrage
-        if evm_price.price > hl_price.price {
+        let evm_side = if evm_price.price > hl_price.price {
             // Buy on HL (cheaper), sell on EVM (more expensive)
-            self.execute_arb(pair, symbol, &hl_price, &evm_price, PositionSide::Long, PositionSide::Short).await?;
+            PositionSide::Short
         } else {
             // Buy on EVM (cheaper), sell on HL (more expensive)
-            self.execute_arb(pair, symbol, &evm_price, &hl_price, PositionSide::Long, PositionSide::Short).await?;
-        }
-
-        Ok(())
+            PositionSide::Long
+        };
+        let result = self.execute_arb(pair, symbol, evm_side, &evm_price, &hl_price).await;
+        let (status, reason) = match &result {
+            Ok(()) => (OpportunityStatus::Executed, None),
+            Err(e) if e.downcast_ref::<RiskError>().is_some() => {
+                warn!("Risk check failed for {} arbitrage: {:#}", pair, e);
+                (OpportunityStatus::SkippedByRisk, Some(format!("{:#}", e)))
+            }
+            Err(e) => (OpportunityStatus::Failed, Some(format!("{:#}", e))),
+        };
+        self.state.opportunities.set_status(opportunity, status, reason);
+        result
     }
 
+    /// Swaps `quote_size` of the base token on HyperEVM and takes the opposite
+    /// side on Hyperliquid through the execution coordinator, which reserves
+    /// both legs, unwinds a leg the other could not match and books the fills.
     async fn execute_arb(
         &self,
         pair: &str,
         symbol: &str,
-        buy_market: &MarketData,
-        sell_market: &MarketData,
-        buy_side: PositionSide,
-        sell_side: PositionSide,
+        evm_side: PositionSide,
+        evm_price: &MarketData,
+        hl_price: &MarketData,
     ) -> Result<()> {
-        // Simplified execution - in production, would handle both legs atomically
-        warn!("HyperEVM spot arbitrage execution not fully implemented");
+        let size = self.hyperevm.quote_size();
+        let hl_side = match evm_side {
+            PositionSide::Long => PositionSide::Short,
+            PositionSide::Short => PositionSide::Long,
+        };
+        let touch = |market: &MarketData, side: &PositionSide| match side {
+            PositionSide::Long => market.ask,
+            PositionSide::Short => market.bid,
+        };
+        let hl_spec =
+            contract_spec(&self.connectors, &self.state, &Venue::Hyperliquid, symbol).await?;
+        let multiplier = hl_spec.contract_multiplier;
+
+        // The swap goes first: it is the leg most likely to revert
+        let evm = LegOrder {
+            venue: Venue::HyperEvm,
+            symbol: pair.to_string(),
+            side: evm_side.clone(),
+            contracts: size,
+            base_size: size,
+            price: touch(evm_price, &evm_side),
+            spec: None,
+        };
+        let hl = LegOrder {
+            venue: Venue::Hyperliquid,
+            symbol: symbol.to_string(),
+            contracts: hl_spec.round_size_down(size / hl_spec.contract_multiplier),
+            base_size: size,
+            price: hl_spec.round_price_marketable(touch(hl_price, &hl_side), &hl_side),
+            side: hl_side,
+            spec: Some(hl_spec),
+        };
+        let execution = self.execution.execute_pair(&evm, &hl).await?;
+        if !execution.is_hedged() {
+            // A leg that could not be unwound is tracked with the other marked
+            // closed, so manage_hedges unwinds it on the next pass
+            let evm_open = execution.first.status.is_fill().then_some(execution.first.size);
+            let hl_open = execution.second.status.is_fill();
+            let hl_open = hl_open.then(|| execution.second.size * multiplier);
+            if let Some(size) = evm_open.into_iter().chain(hl_open).max() {
+                error!("{} {} left open after a failed unwind", size, pair);
+                let mut hedge = hedge(pair, &evm_side, size);
+                if evm_open.is_none() {
+                    hedge.closed_legs.push(Venue::HyperEvm);
+                }
+                if hl_open.is_none() {
+                    hedge.closed_legs.push(Venue::Hyperliquid);
+                }
+                self.state.record_hedge(hedge);
+            }
+            anyhow::bail!(
+                "HyperEVM leg {:?}, Hyperliquid leg {:?}",
+                execution.first.status,
+                execution.second.status
+            );
+        }
+        info!(
+            "Executed {} arbitrage: {:?} {} on HyperEVM @ {}, Hyperliquid @ {}",
+            pair, evm_side, execution.first.size, execution.first.price, execution.second.price
+        );
+
+        // Tracked as a hedge so it is unwound, and kept apart from funding-arb legs
+        let size = execution.first.size.min(execution.second.size * multiplier);
+        self.state.record_hedge(hedge(pair, &evm_side, size));
         Ok(())
     }
+
+    /// Unwinds every open hedge whose spread has converged to `exit_spread_bps`,
+    /// and finishes any unwind an earlier pass left half done.
+    async fn manage_hedges(&self) {
+        for hedge in self.open_hedges() {
+            let Some(pair) = PAIRS.into_iter().find(|p| base(p) == hedge.symbol) else {
+                warn!("Hedge {} is on {}, which is no longer watched", hedge.id, hedge.symbol);
+                continue;
+            };
+            let (evm_price, hl_price) = match self.quotes(pair).await {
+                Ok(quotes) => quotes,
+                Err(e) => {
+                    error!("Error pricing hedge {}: {}", hedge.id, e);
+                    continue;
+                }
+            };
+
+            // What is left of the edge the hedge was opened on, negative once it has flipped
+            let edge = if hedge.long_venue == Venue::HyperEvm {
+                hl_price.price - evm_price.price
+            } else {
+                evm_price.price - hl_price.price
+            };
+            let edge_bps = (edge / hl_price.price * Decimal::from(10000u64))
+                .to_i64()
+                .unwrap_or(0);
+            let closing = !hedge.closed_legs.is_empty();
+            if edge_bps > self.exit_spread_bps && !closing {
+                continue;
+            }
+
+            info!("Unwinding {} hedge {}: spread at {} bps", pair, hedge.id, edge_bps);
+            if let Err(e) = self.close_hedge(&hedge, pair, &evm_price).await {
+                error!("Error unwinding hedge {}: {:#}", hedge.id, e);
+            }
+        }
+    }
+
+    /// Swaps the spot leg back, then closes the perp leg with reduce-only
+    /// orders, skipping a leg already closed. A partial perp close shrinks
+    /// the hedge and the next pass closes the rest.
+    async fn close_hedge(&self, hedge: &Hedge, pair: &str, evm_price: &MarketData) -> Result<()> {
+        if self.dry_run {
+            info!("DRY RUN: Would unwind hedge {}", hedge.id);
+            return Ok(());
+        }
+        let (evm_side, hl_held) = if hedge.long_venue == Venue::HyperEvm {
+            (PositionSide::Short, PositionSide::Short)
+        } else {
+            (PositionSide::Long, PositionSide::Long)
+        };
+
+        // The swap goes first, as on the way in: it is the leg most likely to revert
+        if !hedge.leg_closed(&Venue::HyperEvm) {
+            let price = match evm_side {
+                PositionSide::Long => evm_price.ask,
+                PositionSide::Short => evm_price.bid,
+            };
+            let trade = self
+                .connectors
+                .get(&Venue::HyperEvm)?
+                .place_order(pair, evm_side, hedge.size, price)
+                .await?;
+            if !trade.status.is_complete() {
+                anyhow::bail!("Swap back on HyperEVM ended {:?}", trade.status);
+            }
+            self.risk_engine
+                .record_position_closed(&Venue::HyperEvm, pair, trade.size * trade.price);
+            self.state.record_fill(trade);
+            // On the hedge, so a restart does not swap the same tokens back twice
+            self.state.update_hedge(&hedge.id, |h| h.closed_legs.push(Venue::HyperEvm));
+        }
+
+        if !hedge.leg_closed(&Venue::Hyperliquid) {
+            self.close_perp_leg(hedge, hl_held).await?;
+        }
+
+        self.state.update_hedge(&hedge.id, |h| h.closed_at = Some(Utc::now()));
+        info!("Closed {} hedge {}", pair, hedge.id);
+        Ok(())
+    }
+
+    /// Closes what is still open of the hedge's Hyperliquid leg, held on `side`.
+    async fn close_perp_leg(&self, hedge: &Hedge, side: PositionSide) -> Result<()> {
+        let held = self
+            .connectors
+            .get(&Venue::Hyperliquid)?
+            .get_positions()
+            .await?
+            .into_iter()
+            .find(|p| p.symbol == hedge.symbol);
+        let venue = &Venue::Hyperliquid;
+        let open = own_leg_size(
+            &self.connectors,
+            &self.state,
+            hedge,
+            venue,
+            side.clone(),
+            held.as_ref(),
+        )
+        .await?;
+        let spec = contract_spec(&self.connectors, &self.state, venue, &hedge.symbol).await?;
+        let contracts = spec.round_size_down(open.min(hedge.size / spec.contract_multiplier));
+        let (size, notional) = if contracts.is_zero() {
+            // Already gone; the position slot is still released below
+            (Decimal::ZERO, Decimal::ZERO)
+        } else {
+            let close_side = match side {
+                PositionSide::Long => PositionSide::Short,
+                PositionSide::Short => PositionSide::Long,
+            };
+            let trade =
+                reduce_position(&self.connectors, venue, &hedge.symbol, close_side, contracts)
+                    .await?;
+            if trade.status != TradeStatus::Filled {
+                self.risk_engine.record_position_reduced(&hedge.symbol, trade.size * trade.price);
+                self.state.record_position_closed(
+                    venue.clone(),
+                    hedge.symbol.clone(),
+                    side,
+                    trade.size,
+                );
+                let filled = trade.size * spec.contract_multiplier;
+                self.state.update_hedge(&hedge.id, |h| h.size -= filled);
+                anyhow::bail!("Hyperliquid leg only partially closed ({})", trade.size);
+            }
+            (trade.size, trade.size * trade.price)
+        };
+        self.risk_engine.record_position_closed(venue, &hedge.symbol, notional);
+        self.state.record_position_closed(venue.clone(), hedge.symbol.clone(), side, size);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Strategy for HyperEvmSpotStrategy {
+    fn name(&self) -> &str {
+        "hyperevm_spot"
+    }
+
+    fn tick_interval(&self) -> Duration {
+        self.check_interval
+    }
+
+    async fn on_tick(&mut self) -> Result<()> {
+        // Unwinds reduce risk, so they keep running while the kill switch is active
+        self.manage_hedges().await;
+
+        if self.risk_engine.is_kill_switch_active() {
+            warn!("Kill switch active, skipping HyperEVM spot arb");
+            return Ok(());
+        }
+
+        for pair in PAIRS {
+            if let Err(e) = self.check_opportunity(pair).await {
+                error!("Error checking opportunity for {}: {}", pair, e);
+            }
+        }
+        Ok(())
+    }
+
+    fn dry_run(&self) -> Option<bool> {
+        Some(self.dry_run)
+    }
+
+    fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
+}
+
+/// The base token of a `BASE-QUOTE` pair, which names the Hyperliquid perp.
+fn base(pair: &str) -> &str {
+    pair.split('-').next().unwrap_or(pair)
+}
+
+/// A hedge of `size` base units between a HyperEVM swap on `evm_side` and the
+/// opposite Hyperliquid perp.
+fn hedge(pair: &str, evm_side: &PositionSide, size: Decimal) -> Hedge {
+    let (short_venue, long_venue) = match evm_side {
+        PositionSide::Long => (Venue::Hyperliquid, Venue::HyperEvm),
+        PositionSide::Short => (Venue::HyperEvm, Venue::Hyperliquid),
+    };
+    let mut hedge = Hedge::new(base(pair), short_venue, long_venue, size)
+        .with_strategy(StrategyType::HyperEvmSpot);
+    // Named after the pair so it cannot share an id with a funding-arb hedge on the base
+    hedge.id = format!("{}-{}", pair, hedge.opened_at.timestamp_millis());
+    hedge
 }
@@ -1,7 +1,22 @@
+pub mod execution;
+pub mod flatten;
 pub mod funding_arb;
+pub mod funding_ledger;
 pub mod hyperevm_spot;
+pub mod manager;
+pub mod margin_monitor;
+pub mod reconciliation;
+pub mod sizing;
 pub mod solana_jupiter;
+pub mod strategy;
 
-pub use funding_arb::FundingArbStrategy;
+pub use execution::{ExecutionCoordinator, LegOrder, PairExecution};
+pub use flatten::{flatten_all, FlattenReport};
+pub use funding_arb::{ExitReason, FundingArbStrategy};
+pub use funding_ledger::FundingLedger;
 pub use hyperevm_spot::HyperEvmSpotStrategy;
+pub use manager::{StrategyManager, StrategyState, StrategyStatus};
+pub use margin_monitor::MarginMonitor;
+pub use reconciliation::{reconcile_positions, PositionReconciler};
 pub use solana_jupiter::SolanaJupiterStrategy;
+pub use strategy::{builtin_strategies, Strategy, StrategyContext, StrategyFactory, StrategyHealth};
@@ -0,0 +1,345 @@
+use crate::strategy::{Strategy, StrategyFactory, StrategyHealth};
+use arb_core::config::RestartConfig;
+use arb_core::{Event, EventBus, EventSubscriber};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+use tracing::{error, info, warn};
+
+/// How long `shutdown` waits for strategies to stop before aborting them.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StrategyState {
+    Starting,
+    Running,
+    Paused,
+    /// Waiting out the backoff after a crash.
+    Restarting,
+    Stopped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StrategyStatus {
+    pub name: String,
+    pub state: StrategyState,
+    pub health: StrategyHealth,
+    /// Ticks completed by the current instance.
+    pub ticks: u64,
+    pub restarts: u32,
+    /// `None` for strategies without a dry-run mode.
+    pub dry_run: Option<bool>,
+    /// Latest tick error or crash.
+    pub last_error: Option<String>,
+    pub started_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Mode {
+    Run,
+    Pause,
+    Stop,
+}
+
+/// What the manager wants an instance to be doing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Control {
+    mode: Mode,
+    /// `None` for strategies without a dry-run mode.
+    dry_run: Option<bool>,
+}
+
+struct Managed {
+    control: watch::Sender<Control>,
+    status: Arc<RwLock<StrategyStatus>>,
+    task: JoinHandle<()>,
+}
+
+/// Runs strategies as supervised tasks that can be paused and resumed, and
+/// restarts any that panic or fail to initialise from a fresh instance.
+pub struct StrategyManager {
+    events: EventBus,
+    restart: RestartConfig,
+    strategies: RwLock<BTreeMap<String, Managed>>,
+}
+
+impl StrategyManager {
+    pub fn new(events: EventBus, restart: RestartConfig) -> Self {
+        Self {
+            events,
+            restart,
+            strategies: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Builds the strategy from `factory` and starts running it. Returns its name.
+    pub fn start(&self, factory: StrategyFactory) -> Result<String> {
+        let strategy = factory();
+        let name = strategy.name().to_string();
+        let mut strategies = self.strategies.write();
+        if strategies.contains_key(&name) {
+            anyhow::bail!("Strategy {} is already running", name);
+        }
+
+        let dry_run = strategy.dry_run();
+        let (control, control_rx) = watch::channel(Control {
+            mode: Mode::Run,
+            dry_run,
+        });
+        let status = Arc::new(RwLock::new(StrategyStatus {
+            name: name.clone(),
+            state: StrategyState::Starting,
+            health: StrategyHealth::Healthy,
+            ticks: 0,
+            restarts: 0,
+            dry_run,
+            last_error: None,
+            started_at: None,
+        }));
+        let supervisor = Supervisor {
+            factory,
+            control: control_rx,
+            status: status.clone(),
+            events: self.events.clone(),
+            restart: self.restart.clone(),
+        };
+        let task = tokio::spawn(supervisor.run(strategy));
+
+        info!("Started strategy {}", name);
+        strategies.insert(name.clone(), Managed { control, status, task });
+        Ok(name)
+    }
+
+    /// Stops ticking the strategy until `resume`; a tick in progress finishes.
+    pub fn pause(&self, name: &str) -> Result<()> {
+        self.update(name, |control| control.mode = Mode::Pause)
+    }
+
+    pub fn resume(&self, name: &str) -> Result<()> {
+        self.update(name, |control| control.mode = Mode::Run)
+    }
+
+    /// Switches the strategy in or out of dry-run; it survives restarts.
+    pub fn set_dry_run(&self, name: &str, dry_run: bool) -> Result<()> {
+        if self.status(name).is_some_and(|status| status.dry_run.is_none()) {
+            anyhow::bail!("Strategy {} has no dry-run mode", name);
+        }
+        self.update(name, |control| control.dry_run = Some(dry_run))
+    }
+
+    fn update(&self, name: &str, update: impl FnOnce(&mut Control)) -> Result<()> {
+        let strategies = self.strategies.read();
+        let managed = strategies
+            .get(name)
+            .with_context(|| format!("Unknown strategy {}", name))?;
+        if managed.control.borrow().mode == Mode::Stop {
+            anyhow::bail!("Strategy {} has been stopped", name);
+        }
+        managed.control.send_modify(update);
+        Ok(())
+    }
+
+    pub fn status(&self, name: &str) -> Option<StrategyStatus> {
+        self.strategies.read().get(name).map(|m| m.status.read().clone())
+    }
+
+    /// Every strategy's status, ordered by name.
+    pub fn statuses(&self) -> Vec<StrategyStatus> {
+        self.strategies
+            .read()
+            .values()
+            .map(|m| m.status.read().clone())
+            .collect()
+    }
+
+    /// Asks every strategy to stop and waits for their `shutdown` hooks,
+    /// aborting any still running after a grace period.
+    pub async fn shutdown(&self) {
+        let strategies = std::mem::take(&mut *self.strategies.write());
+        for managed in strategies.values() {
+            managed.control.send_modify(|control| control.mode = Mode::Stop);
+        }
+
+        for (name, mut managed) in strategies {
+            if tokio::time::timeout(SHUTDOWN_GRACE, &mut managed.task).await.is_err() {
+                warn!("Strategy {} did not stop in time, aborting it", name);
+                managed.task.abort();
+            }
+        }
+    }
+}
+
+struct Supervisor {
+    factory: StrategyFactory,
+    control: watch::Receiver<Control>,
+    status: Arc<RwLock<StrategyStatus>>,
+    events: EventBus,
+    restart: RestartConfig,
+}
+
+impl Supervisor {
+    async fn run(mut self, first: Box<dyn Strategy>) {
+        let initial_backoff = Duration::from_millis(self.restart.backoff_initial_ms);
+        let max_backoff = Duration::from_millis(self.restart.backoff_max_ms);
+        let mut backoff = initial_backoff;
+        let mut next = Some(first);
+
+        loop {
+            let strategy = next.take().unwrap_or_else(|| (self.factory)());
+            {
+                let mut status = self.status.write();
+                status.state = StrategyState::Starting;
+                status.ticks = 0;
+                status.started_at = Some(Utc::now());
+            }
+
+            // A separate task, so a panic surfaces as a join error
+            let name = self.status.read().name.clone();
+            let mut instance = AbortOnDrop(tokio::spawn(run_instance(
+                strategy,
+                self.control.clone(),
+                self.status.clone(),
+                self.events.clone(),
+            )));
+            let crash = match (&mut instance.0).await {
+                Ok(Ok(())) => {
+                    self.status.write().state = StrategyState::Stopped;
+                    info!("Stopped strategy {}", name);
+                    return;
+                }
+                Ok(Err(e)) => format!("{:#}", e),
+                Err(e) if e.is_panic() => format!("panicked: {}", panic_message(e.into_panic())),
+                Err(_) => return,
+            };
+
+            let ticked = {
+                let mut status = self.status.write();
+                status.state = StrategyState::Restarting;
+                status.restarts += 1;
+                status.last_error = Some(crash.clone());
+                status.ticks > 0
+            };
+            if ticked {
+                backoff = initial_backoff;
+            }
+            error!("Strategy {} crashed ({}), restarting in {:?}", name, crash, backoff);
+
+            tokio::select! {
+                _ = sleep(backoff) => {}
+                _ = self.control.wait_for(|c| c.mode == Mode::Stop) => {
+                    self.status.write().state = StrategyState::Stopped;
+                    return;
+                }
+            }
+            backoff = (backoff * 2).min(max_backoff);
+        }
+    }
+}
+
+/// Takes the instance down with the supervisor if that is aborted.
+struct AbortOnDrop(JoinHandle<Result<()>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Drives one instance until it is told to stop. Errors only if `init` fails.
+async fn run_instance(
+    mut strategy: Box<dyn Strategy>,
+    mut control: watch::Receiver<Control>,
+    status: Arc<RwLock<StrategyStatus>>,
+    events: EventBus,
+) -> Result<()> {
+    let kinds = strategy.event_kinds();
+    let mut subscriber = (!kinds.is_empty()).then(|| events.subscribe(strategy.name()));
+    // A fresh instance starts from its config, so carry over any toggle
+    let mut current = *control.borrow_and_update();
+    if let Some(dry_run) = current.dry_run {
+        strategy.set_dry_run(dry_run);
+    }
+    strategy.init().await.context("Failed to initialise")?;
+
+    let mut ticker = interval(strategy.tick_interval());
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    status.write().state = state_for(current.mode);
+
+    while current.mode != Mode::Stop {
+        tokio::select! {
+            changed = control.changed() => {
+                // The manager is gone, so nothing can resume or stop us later
+                let next = match changed {
+                    Ok(()) => *control.borrow_and_update(),
+                    Err(_) => Control { mode: Mode::Stop, ..current },
+                };
+                if let Some(dry_run) = next.dry_run.filter(|_| next.dry_run != current.dry_run) {
+                    info!("Strategy {} dry run {}", strategy.name(), dry_run);
+                    strategy.set_dry_run(dry_run);
+                }
+                current = next;
+                let mut status = status.write();
+                status.state = state_for(current.mode);
+                status.dry_run = current.dry_run;
+            }
+            _ = ticker.tick(), if current.mode == Mode::Run => {
+                let result = strategy.on_tick().await;
+                let mut status = status.write();
+                status.health = strategy.health();
+                match result {
+                    Ok(()) => status.ticks += 1,
+                    Err(e) => {
+                        error!("Strategy {} tick failed: {:#}", strategy.name(), e);
+                        status.last_error = Some(format!("{:#}", e));
+                    }
+                }
+            }
+            Some(event) = next_event(&mut subscriber) => {
+                // Events keep draining while paused so the subscriber never lags
+                if current.mode == Mode::Run && kinds.contains(&event.kind()) {
+                    if let Err(e) = strategy.on_event(&event).await {
+                        let kind = event.kind();
+                        error!("Strategy {} failed to handle {}: {:#}", strategy.name(), kind, e);
+                    }
+                }
+            }
+        }
+    }
+
+    if let Err(e) = strategy.shutdown().await {
+        error!("Strategy {} failed to shut down cleanly: {:#}", strategy.name(), e);
+    }
+    Ok(())
+}
+
+async fn next_event(subscriber: &mut Option<EventSubscriber>) -> Option<Event> {
+    match subscriber {
+        Some(subscriber) => subscriber.recv().await,
+        None => std::future::pending().await,
+    }
+}
+
+fn state_for(mode: Mode) -> StrategyState {
+    match mode {
+        Mode::Run => StrategyState::Running,
+        Mode::Pause => StrategyState::Paused,
+        Mode::Stop => StrategyState::Stopped,
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
@@ -0,0 +1,59 @@
+use arb_core::types::Venue;
+use arb_core::RiskEngine;
+use connectors::ConnectorRegistry;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{info, warn};
+
+/// Keeps the risk engine's per-venue margin snapshots fresh so leverage and
+/// liquidation-buffer checks see current account state.
+pub struct MarginMonitor {
+    connectors: Arc<ConnectorRegistry>,
+    venues: Vec<Venue>,
+    risk_engine: Arc<RiskEngine>,
+    refresh_interval: Duration,
+}
+
+impl MarginMonitor {
+    pub fn new(
+        connectors: Arc<ConnectorRegistry>,
+        venues: Vec<Venue>,
+        risk_engine: Arc<RiskEngine>,
+        refresh_interval_ms: u64,
+    ) -> Self {
+        Self {
+            connectors,
+            venues,
+            risk_engine,
+            refresh_interval: Duration::from_millis(refresh_interval_ms),
+        }
+    }
+
+    pub async fn run_loop(&self) {
+        info!("Starting margin monitor loop");
+
+        loop {
+            self.refresh().await;
+            sleep(self.refresh_interval).await;
+        }
+    }
+
+    /// Pulls one margin snapshot per venue. A venue that fails keeps its last
+    /// snapshot rather than dropping out of the checks.
+    pub async fn refresh(&self) {
+        for venue in &self.venues {
+            let connector = match self.connectors.get(venue) {
+                Ok(connector) => connector,
+                Err(e) => {
+                    warn!("{}", e);
+                    continue;
+                }
+            };
+            match connector.get_account_margin().await {
+                Ok(margin) => self.risk_engine.update_margin(margin),
+                Err(e) => warn!("Failed to refresh {:?} margin: {}", venue, e),
+            }
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use crate::execution::reduce_position;
+use crate::sizing::{contract_spec, own_leg_size};
+use arb_core::config::ReconciliationConfig;
+use arb_core::types::{
+    Hedge, OrphanedLeg, Position, PositionDiscrepancy, PositionSide, SharedState, TradeStatus,
+    Venue,
+};
+use arb_core::RiskEngine;
+use anyhow::Result;
+use chrono::Utc;
+use connectors::ConnectorRegistry;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, error, info, warn};
+
+/// Periodically brings local state in line with the venues: positions and
+/// balances are refreshed, risk bookkeeping is rebuilt from them and hedges
+/// left with a single leg are reported, and flattened if configured.
+pub struct PositionReconciler {
+    connectors: Arc<ConnectorRegistry>,
+    /// Derivative venues only; spot venues report no positions to compare.
+    venues: Vec<Venue>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+    config: ReconciliationConfig,
+    dry_run: bool,
+}
+
+impl PositionReconciler {
+    pub fn new(
+        connectors: Arc<ConnectorRegistry>,
+        venues: Vec<Venue>,
+        state: Arc<SharedState>,
+        risk_engine: Arc<RiskEngine>,
+        config: ReconciliationConfig,
+        dry_run: bool,
+    ) -> Self {
+        Self {
+            connectors,
+            venues,
+            state,
+            risk_engine,
+            config,
+            dry_run,
+        }
+    }
+
+    pub async fn run_loop(&self) {
+        info!("Starting position reconciliation loop");
+
+        loop {
+            sleep(Duration::from_millis(self.config.interval_ms)).await;
+            self.reconcile().await;
+        }
+    }
+
+    /// One full pass; returns the position discrepancies it found.
+    pub async fn reconcile(&self) -> Vec<PositionDiscrepancy> {
+        let discrepancies = reconcile_positions(&self.connectors, &self.venues, &self.state).await;
+        self.refresh_balances().await;
+
+        let positions: Vec<Position> =
+            self.state.positions.iter().map(|p| p.value().clone()).collect();
+        if self.risk_engine.sync_positions(&positions) {
+            warn!("Risk exposure had drifted from venue positions and was corrected");
+        }
+
+        let hedges: Vec<Hedge> = self
+            .state
+            .hedges
+            .iter()
+            // A spot leg is a token balance, with no position to find it missing from
+            .filter(|h| h.is_open() && !h.short_venue.is_spot() && !h.long_venue.is_spot())
+            .map(|h| h.value().clone())
+            .collect();
+        for hedge in hedges {
+            if let Err(e) = self.check_orphan(&hedge).await {
+                error!("Failed to flatten orphaned leg of hedge {}: {:#}", hedge.id, e);
+            }
+        }
+        discrepancies
+    }
+
+    async fn refresh_balances(&self) {
+        for venue in &self.venues {
+            let balances = match self.connectors.get(venue) {
+                Ok(connector) => connector.get_balances().await,
+                Err(e) => Err(e),
+            };
+            match balances {
+                Ok(balances) => {
+                    self.state.balances.retain(|(_, v), _| v != venue);
+                    for balance in balances {
+                        let key = (balance.asset.clone(), venue.clone());
+                        self.state.balances.insert(key, balance);
+                    }
+                }
+                Err(e) => debug!("No {:?} balances: {}", venue, e),
+            }
+        }
+    }
+
+    async fn check_orphan(&self, hedge: &Hedge) -> Result<()> {
+        let held = |venue: &Venue| {
+            self.state.positions.get(&Position::key(venue, &hedge.symbol)).map(|p| p.clone())
+        };
+        let (connectors, state) = (&self.connectors, &self.state);
+        let short_held = held(&hedge.short_venue);
+        let short = own_leg_size(
+            connectors,
+            state,
+            hedge,
+            &hedge.short_venue,
+            PositionSide::Short,
+            short_held.as_ref(),
+        )
+        .await?;
+        let long_held = held(&hedge.long_venue);
+        let long = own_leg_size(
+            connectors,
+            state,
+            hedge,
+            &hedge.long_venue,
+            PositionSide::Long,
+            long_held.as_ref(),
+        )
+        .await?;
+        let (venue, side, size, missing_venue) = match (short.is_zero(), long.is_zero()) {
+            (false, true) => (&hedge.short_venue, PositionSide::Short, short, &hedge.long_venue),
+            (true, false) => (&hedge.long_venue, PositionSide::Long, long, &hedge.short_venue),
+            _ => {
+                self.state.orphans.remove(&hedge.id);
+                return Ok(());
+            }
+        };
+
+        let detected_at = self
+            .state
+            .orphans
+            .entry(hedge.id.clone())
+            .and_modify(|orphan| orphan.size = size)
+            .or_insert_with(|| {
+                error!(
+                    "ALERT: hedge {} lost its {:?} leg; {:?} {} {} is unhedged on {:?}",
+                    hedge.id, missing_venue, side, size, hedge.symbol, venue
+                );
+                OrphanedLeg {
+                    hedge_id: hedge.id.clone(),
+                    symbol: hedge.symbol.clone(),
+                    venue: venue.clone(),
+                    side: side.clone(),
+                    size,
+                    missing_venue: missing_venue.clone(),
+                    detected_at: Utc::now(),
+                    flattened: false,
+                }
+            })
+            .detected_at;
+
+        if !self.config.auto_flatten_orphans {
+            return Ok(());
+        }
+        // A strategy unwinding the hedge itself closes one leg before the other
+        let grace = chrono::Duration::milliseconds(self.config.orphan_grace_ms as i64);
+        if Utc::now() - detected_at < grace {
+            debug!("Orphaned leg of hedge {} is within its grace period", hedge.id);
+            return Ok(());
+        }
+        if self.dry_run {
+            info!("DRY RUN: Would flatten orphaned leg of hedge {}", hedge.id);
+            return Ok(());
+        }
+
+        // The leg is held in the venue's contracts, the hedge size in the base asset
+        let spec = contract_spec(&self.connectors, &self.state, venue, &hedge.symbol).await?;
+        let close_size = spec.round_size_down(size.min(hedge.size / spec.contract_multiplier));
+        let close_side = match side {
+            PositionSide::Short => PositionSide::Long,
+            PositionSide::Long => PositionSide::Short,
+        };
+        let trade =
+            reduce_position(&self.connectors, venue, &hedge.symbol, close_side, close_size).await?;
+        if trade.status != TradeStatus::Filled {
+            // Book what filled; the next pass flattens the rest
+            self.risk_engine.record_position_reduced(&hedge.symbol, trade.size * trade.price);
+            self.state
+                .record_position_closed(venue.clone(), hedge.symbol.clone(), side, trade.size);
+            if let Some(mut orphan) = self.state.orphans.get_mut(&hedge.id) {
+                orphan.size -= trade.size;
+            }
+            anyhow::bail!("Flattening order on {:?} only filled {}", venue, trade.size);
+        }
+
+        self.risk_engine.record_position_closed(venue, &hedge.symbol, trade.size * trade.price);
+        self.state.update_hedge(&hedge.id, |h| h.closed_at = Some(Utc::now()));
+        if let Some(mut orphan) = self.state.orphans.get_mut(&hedge.id) {
+            orphan.flattened = true;
+        }
+        self.state
+            .record_position_closed(venue.clone(), hedge.symbol.clone(), side, trade.size);
+        info!("Flattened orphaned {:?} leg of hedge {}", venue, hedge.id);
+        Ok(())
+    }
+}
+
+/// Compares the local position book, built from our own fills, with what
+/// each venue reports, flags every mismatch in `SharedState.discrepancies`
+/// and then takes the venue's view as the truth. Venues that fail to answer,
+/// or book a fill while being queried, keep their booked positions and are
+/// not checked.
+pub async fn reconcile_positions(
+    connectors: &ConnectorRegistry,
+    venues: &[Venue],
+    state: &SharedState,
+) -> Vec<PositionDiscrepancy> {
+    let mut found = Vec::new();
+    for venue in venues {
+        let sequence = state.book_sequence(venue);
+        let reported = match connectors.get(venue) {
+            Ok(connector) => connector.get_positions().await,
+            Err(e) => Err(e),
+        };
+        let reported = match reported {
+            Ok(positions) => positions,
+            Err(e) => {
+                warn!("Skipping {:?} reconciliation, positions unavailable: {}", venue, e);
+                continue;
+            }
+        };
+        // A fill booked during the query may or may not be in the venue's answer
+        let Some(booked) = state.replace_positions(venue, sequence, reported.clone()) else {
+            debug!("Skipping {:?} reconciliation, positions were booked meanwhile", venue);
+            continue;
+        };
+
+        let mut expected: HashMap<String, Position> = booked
+            .into_iter()
+            .map(|p| (Position::key(&p.venue, &p.symbol), p))
+            .collect();
+        let mut discrepancies = Vec::new();
+        for position in reported {
+            let key = Position::key(&position.venue, &position.symbol);
+            let recorded = expected.remove(&key);
+            let matches = recorded
+                .as_ref()
+                .is_some_and(|r| r.side == position.side && r.size == position.size);
+            if !matches {
+                discrepancies.push((key, recorded, Some(position)));
+            }
+        }
+        for (key, recorded) in expected {
+            discrepancies.push((key, Some(recorded), None));
+        }
+
+        // Earlier findings on the venue are resolved unless flagged again below
+        state.discrepancies.retain(|_, d| &d.venue != venue);
+        for (key, expected, reported) in discrepancies {
+            let symbol = expected.as_ref().or(reported.as_ref()).map(|p| p.symbol.clone());
+            let discrepancy = PositionDiscrepancy {
+                venue: venue.clone(),
+                symbol: symbol.unwrap_or_default(),
+                expected,
+                reported,
+                detected_at: Utc::now(),
+            };
+            warn!(
+                "Position mismatch on {:?} {}: recorded {}, venue reports {}",
+                venue,
+                discrepancy.symbol,
+                describe(discrepancy.expected.as_ref()),
+                describe(discrepancy.reported.as_ref())
+            );
+            state.discrepancies.insert(key, discrepancy.clone());
+            found.push(discrepancy);
+        }
+    }
+
+    if found.is_empty() {
+        info!("Positions reconciled with {} venue(s), no discrepancies", venues.len());
+    }
+    found
+}
+
+fn describe(position: Option<&Position>) -> String {
+    match position {
+        Some(p) => format!("{:?} {}", p.side, p.size),
+        None => "none".to_string(),
+    }
+}
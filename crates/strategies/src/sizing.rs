@@ -0,0 +1,187 @@
+use arb_core::types::{
+    BookLevel, ContractSpec, Hedge, OrderBook, Position, PositionSide, SharedState, Venue,
+};
+use anyhow::{Context, Result};
+use connectors::ConnectorRegistry;
+use rust_decimal::Decimal;
+
+/// Everything needed to size one leg of a hedge.
+pub struct LegMarket<'a> {
+    pub spec: &'a ContractSpec,
+    pub book: &'a OrderBook,
+    /// Margin free to back new positions, in the quote asset.
+    pub available_margin: Decimal,
+}
+
+pub struct SizingLimits {
+    /// Notional each leg may add to the asset.
+    pub max_notional: Decimal,
+    pub max_leverage: Decimal,
+    /// How far past the touch either leg may walk the book.
+    pub max_slippage_bps: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HedgeSize {
+    /// Base-asset quantity held on each leg.
+    pub base_size: Decimal,
+    pub notional: Decimal,
+    pub short_contracts: Decimal,
+    pub long_contracts: Decimal,
+    /// Marketable limit prices covering the depth the size relies on.
+    pub short_price: Decimal,
+    pub long_price: Decimal,
+}
+
+/// Largest equal-sized hedge that fits the notional and leverage limits, the
+/// margin on both venues and the depth within `max_slippage_bps` of the touch,
+/// rounded down to both venues' lot sizes.
+pub fn size_hedge(short: &LegMarket, long: &LegMarket, limits: &SizingLimits) -> Result<HedgeSize> {
+    // The short leg sells into bids, the long leg buys from asks
+    let slippage = limits.max_slippage_bps;
+    let (short_depth, short_worst) = depth_within(short, &PositionSide::Short, slippage)?;
+    let (long_depth, long_worst) = depth_within(long, &PositionSide::Long, slippage)?;
+
+    // Value both legs at the highest price per base unit either is booked at, the
+    // short leg's touch or the long leg's limit, so neither exceeds the limits
+    let short_price = short.spec.round_price_marketable(short_worst, &PositionSide::Short);
+    let long_price = long.spec.round_price_marketable(long_worst, &PositionSide::Long);
+    let short_touch = short.book.bids[0].price / short.spec.contract_multiplier;
+    let reference_price = short_touch.max(long_price / long.spec.contract_multiplier);
+    let margin_notional = short.available_margin.min(long.available_margin) * limits.max_leverage;
+    let max_notional = limits.max_notional.min(margin_notional);
+    if max_notional <= Decimal::ZERO {
+        anyhow::bail!(
+            "No notional available: limit {}, margin {}",
+            limits.max_notional,
+            margin_notional
+        );
+    }
+
+    let target = (max_notional / reference_price).min(short_depth).min(long_depth);
+    let base_size = round_to_both(target, short.spec, long.spec);
+
+    let short_contracts = base_size / short.spec.contract_multiplier;
+    let long_contracts = base_size / long.spec.contract_multiplier;
+    for (leg, contracts) in [(short, short_contracts), (long, long_contracts)] {
+        if contracts.is_zero() || contracts < leg.spec.min_size {
+            anyhow::bail!(
+                "{:?} size {} is below the minimum {}",
+                leg.spec.venue,
+                contracts,
+                leg.spec.min_size
+            );
+        }
+        if base_size * reference_price < leg.spec.min_notional {
+            anyhow::bail!(
+                "{:?} notional {} is below the minimum {}",
+                leg.spec.venue,
+                base_size * reference_price,
+                leg.spec.min_notional
+            );
+        }
+    }
+
+    Ok(HedgeSize {
+        base_size,
+        notional: base_size * reference_price,
+        short_contracts: short_contracts.normalize(),
+        long_contracts: long_contracts.normalize(),
+        short_price,
+        long_price,
+    })
+}
+
+/// `symbol`'s contract spec on `venue`, from the instrument registry when it
+/// has been loaded and from the venue otherwise. Needed to convert between a
+/// venue's contracts, which positions and orders use, and the base asset.
+pub async fn contract_spec(
+    connectors: &ConnectorRegistry,
+    state: &SharedState,
+    venue: &Venue,
+    symbol: &str,
+) -> Result<ContractSpec> {
+    if let Some(spec) = state.instruments.spec(symbol, venue) {
+        return Ok(spec);
+    }
+    connectors
+        .get(venue)?
+        .get_contract_spec(symbol)
+        .await
+        .with_context(|| format!("No {:?} contract spec for {}", venue, symbol))
+}
+
+/// Contracts of `hedge`'s `side` leg within `held`, the venue's net position
+/// in the symbol. Venues net every strategy's legs into one position, so what
+/// other strategies' open hedges hold there is taken out first.
+pub async fn own_leg_size(
+    connectors: &ConnectorRegistry,
+    state: &SharedState,
+    hedge: &Hedge,
+    venue: &Venue,
+    side: PositionSide,
+    held: Option<&Position>,
+) -> Result<Decimal> {
+    let mut net = held.map_or(Decimal::ZERO, |p| match p.side {
+        PositionSide::Long => p.size,
+        PositionSide::Short => -p.size,
+    });
+    let foreign = state.foreign_hedge_size(&hedge.strategy, venue, &hedge.symbol);
+    if !foreign.is_zero() {
+        let spec = contract_spec(connectors, state, venue, &hedge.symbol).await?;
+        net -= foreign / spec.contract_multiplier;
+    }
+    Ok(match side {
+        PositionSide::Long => net.max(Decimal::ZERO),
+        PositionSide::Short => (-net).max(Decimal::ZERO),
+    })
+}
+
+/// Base quantity resting within the slippage band on the side `side` trades
+/// against, and the worst price reached.
+fn depth_within(
+    leg: &LegMarket,
+    side: &PositionSide,
+    max_slippage_bps: Decimal,
+) -> Result<(Decimal, Decimal)> {
+    let band = max_slippage_bps / Decimal::from(10_000);
+    let (levels, limit): (&[BookLevel], Decimal) = match side {
+        PositionSide::Short => {
+            let best = leg.book.best_bid().map(|l| l.price);
+            (&leg.book.bids, best.map(|p| p * (Decimal::ONE - band)).unwrap_or_default())
+        }
+        PositionSide::Long => {
+            let best = leg.book.best_ask().map(|l| l.price);
+            (&leg.book.asks, best.map(|p| p * (Decimal::ONE + band)).unwrap_or_default())
+        }
+    };
+    if levels.is_empty() {
+        anyhow::bail!("{:?} book for {} is empty", leg.spec.venue, leg.book.symbol);
+    }
+
+    let within = |level: &&BookLevel| match side {
+        PositionSide::Short => level.price >= limit,
+        PositionSide::Long => level.price <= limit,
+    };
+    let used: Vec<&BookLevel> = levels.iter().take_while(within).collect();
+    let contracts: Decimal = used.iter().map(|l| l.size).sum();
+    let worst = used.last().map(|l| l.price).unwrap_or(levels[0].price);
+    Ok((contracts * leg.spec.contract_multiplier, worst))
+}
+
+/// Rounds a base quantity down until it is a whole number of lots on both venues.
+fn round_to_both(base: Decimal, a: &ContractSpec, b: &ContractSpec) -> Decimal {
+    let round = |spec: &ContractSpec, base: Decimal| {
+        spec.round_size_down(base / spec.contract_multiplier) * spec.contract_multiplier
+    };
+    let mut size = base;
+    // Lot grids nest in practice, so this settles within a couple of passes
+    for _ in 0..4 {
+        let rounded = round(b, round(a, size));
+        if rounded == size {
+            break;
+        }
+        size = rounded;
+    }
+    size.normalize()
+}
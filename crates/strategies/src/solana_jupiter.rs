@@ -1,14 +1,22 @@
-use arb_core::types::{ArbitrageOpportunity, MarketData, SharedState, StrategyType, Venue};
+use crate::strategy::Strategy;
+use arb_core::types::{ArbitrageOpportunity, OpportunityStatus, SharedState, StrategyType, Venue};
 use arb_core::RiskEngine;
 use anyhow::Result;
+use async_trait::async_trait;
 use chrono::Utc;
+use connectors::jupiter::JupiterQuote;
 use connectors::JupiterConnector;
 use rust_decimal::Decimal;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
 use tracing::{error, info, warn};
 
+const SOL_MINT: &str = "So11111111111111111111111111111111111111112";
+const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+/// Round trips checked each tick, as (input, output) mints.
+const ROUTES: [(&str, &str); 2] = [(SOL_MINT, USDC_MINT), (USDC_MINT, SOL_MINT)];
+
 pub struct SolanaJupiterStrategy {
     jupiter: JupiterConnector,
     state: Arc<SharedState>,
@@ -40,32 +48,6 @@ impl SolanaJupiterStrategy {
         }
     }
 
-    pub async fn run_loop(&self) {
-        info!("Starting Solana Jupiter arbitrage loop");
-
-        // Monitor common token pairs for round-trip opportunities
-        let routes = vec![
-            ("So11111111111111111111111111111111111111112", "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v"), // SOL -> USDC
-            ("EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v", "So11111111111111111111111111111111111111112"), // USDC -> SOL
-        ];
-
-        loop {
-            if self.risk_engine.is_kill_switch_active() {
-                warn!("Kill switch active, pausing Solana Jupiter arb loop");
-                sleep(Duration::from_secs(10)).await;
-                continue;
-            }
-
-            for (input_mint, output_mint) in &routes {
-                if let Err(e) = self.check_opportunity(input_mint, output_mint).await {
-                    error!("Error checking opportunity: {}", e);
-                }
-            }
-
-            sleep(self.check_interval).await;
-        }
-    }
-
     async fn check_opportunity(&self, input_mint: &str, output_mint: &str) -> Result<()> {
         // Get quote for forward direction
         let amount = 1_000_000_000u64; // 1 SOL in lamports (or equivalent)
@@ -74,17 +56,18 @@ impl SolanaJupiterStrategy {
             .get_quote(input_mint, output_mint, amount, self.max_slippage_bps)
             .await?;
 
-        // Get quote for reverse direction (round-trip)
+        // Quote the way back on the forward leg's guaranteed output so the
+        // reverse leg is always funded when both land in one transaction
         let reverse_quote = self
             .jupiter
-            .get_quote(output_mint, input_mint, forward_quote.out_amount, self.max_slippage_bps)
+            .get_quote(output_mint, input_mint, forward_quote.other_amount_threshold, self.max_slippage_bps)
             .await?;
 
         // Check if round-trip is profitable
         let profit = reverse_quote.out_amount as i64 - amount as i64;
-        let profit_bps = (profit * 10000 / amount as i64) as u64;
+        let profit_bps = profit * 10000 / amount as i64;
 
-        if profit_bps < self.min_profit_bps {
+        if profit_bps < self.min_profit_bps as i64 {
             return Ok(());
         }
 
@@ -94,35 +77,99 @@ impl SolanaJupiterStrategy {
         );
 
         // Update state with market data
+        let route = format!("{}/{}", input_mint, output_mint);
         let market_data = self.jupiter.get_spot_price_from_quote(&forward_quote);
-        self.state.market_data.insert(
-            (format!("{}/{}", input_mint, output_mint), Venue::SolanaJupiter),
-            market_data,
-        );
+        self.state
+            .market_data
+            .insert((route.clone(), Venue::SolanaJupiter), market_data);
+
+        let opportunity = self.state.record_opportunity(ArbitrageOpportunity {
+            id: 0,
+            strategy: StrategyType::SolanaJupiter,
+            symbol: route,
+            venue_a: Venue::SolanaJupiter,
+            venue_b: Venue::SolanaJupiter,
+            price_a: Decimal::from(amount),
+            price_b: Decimal::from(reverse_quote.out_amount),
+            spread_bps: profit_bps,
+            estimated_profit: Decimal::from(profit),
+            status: OpportunityStatus::Detected,
+            reason: None,
+            timestamp: Utc::now(),
+        });
 
         if self.dry_run {
             info!("DRY RUN: Would execute Solana Jupiter arbitrage");
+            self.state
+                .opportunities
+                .set_status(opportunity, OpportunityStatus::DryRun, None);
             return Ok(());
         }
 
         // Execute swap
-        self.execute_swap(&forward_quote, &reverse_quote).await?;
-
-        Ok(())
+        let result = self.execute_swap(amount, &forward_quote, &reverse_quote).await;
+        let (status, reason) = match &result {
+            Ok(()) => (OpportunityStatus::Executed, None),
+            Err(e) => (OpportunityStatus::Failed, Some(format!("{:#}", e))),
+        };
+        self.state.opportunities.set_status(opportunity, status, reason);
+        result
     }
 
+    /// Executes both legs in one transaction. The reverse leg must return at
+    /// least `amount` plus `min_profit_bps`, otherwise the whole round trip
+    /// reverts on-chain.
     async fn execute_swap(
         &self,
-        forward_quote: &connectors::jupiter::JupiterQuote,
-        reverse_quote: &connectors::jupiter::JupiterQuote,
+        amount: u64,
+        forward_quote: &JupiterQuote,
+        reverse_quote: &JupiterQuote,
     ) -> Result<()> {
-        // In a real implementation, this would:
-        // 1. Get swap instructions from Jupiter
-        // 2. Build and sign transaction
-        // 3. Submit to Solana network
-        // 4. Monitor for confirmation
+        let min_out = amount + amount * self.min_profit_bps / 10000;
+        let guarded_reverse = reverse_quote.with_min_output(min_out)?;
+
+        let execution = self
+            .jupiter
+            .execute_round_trip(forward_quote, &guarded_reverse)
+            .await?;
+
+        info!(
+            "Executed Jupiter round trip {}: balance changes {:?}, fee {} lamports",
+            execution.signature, execution.balance_deltas, execution.fee_lamports
+        );
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Strategy for SolanaJupiterStrategy {
+    fn name(&self) -> &str {
+        "solana_jupiter"
+    }
+
+    fn tick_interval(&self) -> Duration {
+        self.check_interval
+    }
 
-        warn!("Solana Jupiter swap execution not fully implemented");
+    async fn on_tick(&mut self) -> Result<()> {
+        if self.risk_engine.is_kill_switch_active() {
+            warn!("Kill switch active, skipping Solana Jupiter arb");
+            return Ok(());
+        }
+
+        for (input_mint, output_mint) in ROUTES {
+            if let Err(e) = self.check_opportunity(input_mint, output_mint).await {
+                error!("Error checking opportunity: {}", e);
+            }
+        }
         Ok(())
     }
+
+    fn dry_run(&self) -> Option<bool> {
+        Some(self.dry_run)
+    }
+
+    fn set_dry_run(&mut self, dry_run: bool) {
+        self.dry_run = dry_run;
+    }
 }
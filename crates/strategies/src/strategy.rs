@@ -0,0 +1,130 @@
+use crate::{FundingArbStrategy, HyperEvmSpotStrategy, SolanaJupiterStrategy};
+use arb_core::config::StrategiesConfig;
+use arb_core::types::{SharedState, Venue};
+use arb_core::{Event, RiskEngine};
+use anyhow::Result;
+use async_trait::async_trait;
+use connectors::{ConnectorRegistry, HyperEvmConnector, JupiterConnector};
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Self-reported condition of a running strategy.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "state", content = "reason", rename_all = "snake_case")]
+pub enum StrategyHealth {
+    Healthy,
+    /// Running, but not doing everything it should.
+    Degraded(String),
+}
+
+/// A trading strategy driven by the `StrategyManager`, which calls `on_tick`
+/// every `tick_interval` and `on_event` for the event kinds it asks for.
+#[async_trait]
+pub trait Strategy: Send {
+    /// Unique among the strategies a manager runs.
+    fn name(&self) -> &str;
+
+    fn tick_interval(&self) -> Duration;
+
+    /// `Event::kind`s delivered to `on_event`; none by default.
+    fn event_kinds(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Runs before the first tick, and again on the fresh instance after a
+    /// restart. An error counts as a crash.
+    async fn init(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// An error is logged and reported, but does not restart the strategy.
+    async fn on_tick(&mut self) -> Result<()>;
+
+    async fn on_event(&mut self, _event: &Event) -> Result<()> {
+        Ok(())
+    }
+
+    /// Called once when the manager stops the strategy.
+    async fn shutdown(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn health(&self) -> StrategyHealth {
+        StrategyHealth::Healthy
+    }
+
+    /// Whether orders are only logged; `None` if the strategy has no such mode.
+    fn dry_run(&self) -> Option<bool> {
+        None
+    }
+
+    fn set_dry_run(&mut self, _dry_run: bool) {}
+}
+
+/// Builds a fresh instance, used at start and after every crash.
+pub type StrategyFactory = Arc<dyn Fn() -> Box<dyn Strategy> + Send + Sync>;
+
+/// Everything the built-in strategies are constructed from.
+#[derive(Clone)]
+pub struct StrategyContext {
+    pub connectors: Arc<ConnectorRegistry>,
+    pub perp_venues: Vec<Venue>,
+    pub hyperevm: HyperEvmConnector,
+    pub jupiter: JupiterConnector,
+    pub state: Arc<SharedState>,
+    pub risk_engine: Arc<RiskEngine>,
+    pub config: StrategiesConfig,
+    pub dry_run: bool,
+}
+
+/// Factories for the strategies enabled in `context.config`.
+pub fn builtin_strategies(context: &StrategyContext) -> Vec<StrategyFactory> {
+    let config = &context.config;
+    let mut factories: Vec<StrategyFactory> = Vec::new();
+
+    if config.funding_arb_enabled {
+        let context = context.clone();
+        factories.push(Arc::new(move || {
+            Box::new(FundingArbStrategy::new(
+                context.connectors.clone(),
+                context.perp_venues.clone(),
+                context.state.clone(),
+                context.risk_engine.clone(),
+                context.config.funding_arb.clone(),
+                context.dry_run,
+            ))
+        }));
+    }
+
+    if config.hyperevm_spot_enabled {
+        let context = context.clone();
+        factories.push(Arc::new(move || {
+            Box::new(HyperEvmSpotStrategy::new(
+                context.connectors.clone(),
+                context.hyperevm.clone(),
+                context.state.clone(),
+                context.risk_engine.clone(),
+                context.config.hyperevm_spot.clone(),
+                context.dry_run,
+            ))
+        }));
+    }
+
+    if config.solana_jupiter_enabled {
+        let context = context.clone();
+        factories.push(Arc::new(move || {
+            Box::new(SolanaJupiterStrategy::new(
+                context.jupiter.clone(),
+                context.state.clone(),
+                context.risk_engine.clone(),
+                context.config.solana_jupiter.min_profit_bps,
+                context.config.solana_jupiter.max_slippage_bps,
+                context.config.solana_jupiter.check_interval_ms,
+                context.dry_run,
+            ))
+        }));
+    }
+
+    factories
+}
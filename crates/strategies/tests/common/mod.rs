@@ -0,0 +1,162 @@
+// Each test binary uses only some of these
+#![allow(dead_code)]
+
+use arb_core::config::RiskConfig;
+use arb_core::types::{
+    Balance, ContractSpec, MarketData, OrderOptions, Position, PositionSide, Trade, TradeStatus,
+    Venue,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use connectors::VenueConnector;
+use rust_decimal::Decimal;
+use std::sync::{Arc, Mutex};
+
+/// Limits loose enough that only the field a test overrides can bind.
+pub fn risk_config() -> RiskConfig {
+    RiskConfig {
+        max_notional_per_asset: 1_000_000.0,
+        max_open_positions_per_venue: 10,
+        max_leverage: 3.0,
+        kill_switch_daily_loss_threshold: -1000.0,
+        liquidation_buffer: 0.5,
+        margin_refresh_interval_ms: 30000,
+        daily_rollover_hour_utc: 0,
+        kill_switch_cooldown_secs: 3600,
+        kill_switch_manual_rearm: false,
+    }
+}
+
+/// Reports a fixed set of positions, or fails when there are none to report,
+/// and fills every order it receives.
+pub struct Holdings {
+    pub venue: Venue,
+    pub positions: Option<Vec<Position>>,
+    /// Base units per contract; positions and orders are in contracts.
+    pub multiplier: Decimal,
+    /// Most any one order fills, if set.
+    pub fill_cap: Option<Decimal>,
+    pub orders: Mutex<Vec<(String, PositionSide, Decimal, OrderOptions)>>,
+}
+
+impl Holdings {
+    pub fn new(venue: Venue, positions: Option<Vec<Position>>) -> Arc<Self> {
+        Self::with_multiplier(venue, positions, Decimal::ONE)
+    }
+
+    pub fn with_multiplier(
+        venue: Venue,
+        positions: Option<Vec<Position>>,
+        multiplier: Decimal,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            venue,
+            positions,
+            multiplier,
+            fill_cap: None,
+            orders: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn with_fill_cap(
+        venue: Venue,
+        positions: Option<Vec<Position>>,
+        fill_cap: Decimal,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            venue,
+            positions,
+            multiplier: Decimal::ONE,
+            fill_cap: Some(fill_cap),
+            orders: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn orders(&self) -> Vec<(String, PositionSide, Decimal, OrderOptions)> {
+        self.orders.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl VenueConnector for Holdings {
+    fn venue(&self) -> Venue {
+        self.venue.clone()
+    }
+
+    async fn get_market_data(&self, _symbol: &str) -> Result<MarketData> {
+        unimplemented!()
+    }
+
+    async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        Ok(ContractSpec {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            tick_size: Decimal::ONE,
+            lot_size: Decimal::ONE,
+            min_size: Decimal::ONE,
+            min_notional: Decimal::ONE,
+            contract_multiplier: self.multiplier,
+        })
+    }
+
+    async fn place_order(&self, _: &str, _: PositionSide, _: Decimal, _: Decimal) -> Result<Trade> {
+        unimplemented!()
+    }
+
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        _price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        let order = (symbol.to_string(), side.clone(), size, options);
+        self.orders.lock().unwrap().push(order);
+        let filled = self.fill_cap.map_or(size, |cap| size.min(cap));
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            side,
+            size: filled,
+            price: Decimal::from(100),
+            timestamp: Utc::now(),
+            status: if filled < size {
+                TradeStatus::PartiallyFilled
+            } else {
+                TradeStatus::Filled
+            },
+            order_id: Some("1".to_string()),
+        })
+    }
+
+    async fn cancel_order(&self, _: &str, _: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        self.positions.clone().ok_or_else(|| anyhow::anyhow!("venue unavailable"))
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        Ok(vec![Balance {
+            venue: self.venue.clone(),
+            asset: "USDT".to_string(),
+            free: Decimal::from(500),
+            total: Decimal::from(1000),
+        }])
+    }
+}
+
+pub fn position(venue: Venue, symbol: &str, side: PositionSide, size: i64) -> Position {
+    Position {
+        symbol: symbol.to_string(),
+        venue,
+        side,
+        size: Decimal::from(size),
+        entry_price: Decimal::from(100),
+        leverage: Decimal::ONE,
+        timestamp: Utc::now(),
+    }
+}
@@ -0,0 +1,389 @@
+mod common;
+
+use arb_core::config::{ExecutionConfig, LegMode, RiskConfig};
+use arb_core::types::{
+    Balance, MarketData, OrderOptions, Position, PositionSide, SharedState, Trade, TradeStatus,
+    Venue,
+};
+use arb_core::RiskEngine;
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use common::risk_config;
+use connectors::{BinanceError, ConnectorRegistry, VenueConnector};
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use strategies::{ExecutionCoordinator, LegOrder};
+
+/// Answers each order with the next scripted error, then the next scripted
+/// fill quantity (all of it when the script runs out), and records what it
+/// was sent.
+struct ScriptedVenue {
+    venue: Venue,
+    fills: Mutex<VecDeque<Decimal>>,
+    errors: Mutex<VecDeque<anyhow::Error>>,
+    /// When set, the next order is only acknowledged and queries report it
+    /// settled with this fill, or fail on `None`.
+    acknowledged: Mutex<Option<Option<Decimal>>>,
+    orders: Mutex<Vec<(PositionSide, Decimal, Decimal, OrderOptions)>>,
+    cancels: Mutex<Vec<String>>,
+}
+
+impl ScriptedVenue {
+    fn new(venue: Venue, fills: &[&str]) -> Arc<Self> {
+        Arc::new(Self {
+            venue,
+            fills: Mutex::new(fills.iter().map(|f| dec(f)).collect()),
+            errors: Mutex::new(VecDeque::new()),
+            acknowledged: Mutex::new(None),
+            orders: Mutex::new(Vec::new()),
+            cancels: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Fails the first orders with `errors`, then fills everything.
+    fn failing(venue: Venue, errors: Vec<anyhow::Error>) -> Arc<Self> {
+        let scripted = Self::new(venue, &[]);
+        *scripted.errors.lock().unwrap() = errors.into();
+        scripted
+    }
+
+    /// Acknowledges the first order without a final state, then fills everything.
+    fn acknowledging(venue: Venue, settled_fill: Option<&str>) -> Arc<Self> {
+        let scripted = Self::new(venue, &[]);
+        *scripted.acknowledged.lock().unwrap() = Some(settled_fill.map(dec));
+        scripted
+    }
+
+    fn orders(&self) -> Vec<(PositionSide, Decimal, Decimal, OrderOptions)> {
+        self.orders.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl VenueConnector for ScriptedVenue {
+    fn venue(&self) -> Venue {
+        self.venue.clone()
+    }
+
+    async fn get_market_data(&self, _symbol: &str) -> Result<MarketData> {
+        unimplemented!()
+    }
+
+    async fn place_order(&self, _: &str, _: PositionSide, _: Decimal, _: Decimal) -> Result<Trade> {
+        unimplemented!()
+    }
+
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        self.orders.lock().unwrap().push((side.clone(), size, price, options));
+        if let Some(error) = self.errors.lock().unwrap().pop_front() {
+            return Err(error);
+        }
+        if self.acknowledged.lock().unwrap().is_some() && self.cancels.lock().unwrap().is_empty() {
+            return Ok(Trade {
+                symbol: symbol.to_string(),
+                venue: self.venue.clone(),
+                side,
+                size,
+                price,
+                timestamp: Utc::now(),
+                status: TradeStatus::Pending,
+                order_id: Some("acknowledged".to_string()),
+            });
+        }
+        let filled = self.fills.lock().unwrap().pop_front().unwrap_or(size).min(size);
+        let status = if filled == size {
+            TradeStatus::Filled
+        } else if filled.is_zero() {
+            TradeStatus::Rejected
+        } else {
+            TradeStatus::PartiallyFilled
+        };
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            side,
+            size: if filled.is_zero() { size } else { filled },
+            price: Decimal::from(100),
+            timestamp: Utc::now(),
+            status,
+            order_id: Some(format!("{:?}-{}", self.venue, self.orders.lock().unwrap().len())),
+        })
+    }
+
+    async fn cancel_order(&self, _symbol: &str, order_id: &str) -> Result<()> {
+        self.cancels.lock().unwrap().push(order_id.to_string());
+        Ok(())
+    }
+
+    async fn get_order(&self, symbol: &str, order_id: &str) -> Result<Trade> {
+        let settled = self.acknowledged.lock().unwrap().flatten();
+        let filled = settled.ok_or_else(|| anyhow::anyhow!("order query timed out"))?;
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            side: PositionSide::Long,
+            size: filled,
+            price: Decimal::from(100),
+            timestamp: Utc::now(),
+            status: TradeStatus::PartiallyFilled,
+            order_id: Some(order_id.to_string()),
+        })
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        unimplemented!()
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        unimplemented!()
+    }
+
+    fn order_not_placed(&self, error: &anyhow::Error) -> bool {
+        error.downcast_ref::<BinanceError>().is_some_and(BinanceError::is_rejection)
+    }
+}
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn risk_engine(max_notional_per_asset: f64) -> Arc<RiskEngine> {
+    Arc::new(RiskEngine::new(RiskConfig { max_notional_per_asset, ..risk_config() }))
+}
+
+fn coordinator(
+    first: Arc<ScriptedVenue>,
+    second: Arc<ScriptedVenue>,
+    risk_engine: Arc<RiskEngine>,
+    leg_mode: LegMode,
+) -> ExecutionCoordinator {
+    let mut registry = ConnectorRegistry::new();
+    registry.register(first);
+    registry.register(second);
+    ExecutionCoordinator::new(
+        Arc::new(registry),
+        Arc::new(SharedState::new()),
+        risk_engine,
+        ExecutionConfig {
+            leg_mode,
+            max_retries: 1,
+            retry_delay_ms: 0,
+            chase_slippage_bps: 100,
+        },
+    )
+}
+
+fn leg(venue: Venue, side: PositionSide) -> LegOrder {
+    LegOrder {
+        venue,
+        symbol: "BTC".to_string(),
+        side,
+        contracts: dec("2"),
+        base_size: dec("2"),
+        price: Decimal::from(100),
+        spec: None,
+    }
+}
+
+fn short_leg() -> LegOrder {
+    leg(Venue::Hyperliquid, PositionSide::Short)
+}
+
+fn long_leg() -> LegOrder {
+    leg(Venue::Binance, PositionSide::Long)
+}
+
+#[tokio::test]
+async fn second_leg_is_retried_then_chased() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let second = ScriptedVenue::new(Venue::Binance, &["0.5", "0.5", "1"]);
+    let risk = risk_engine(1_000_000.0);
+
+    let execution = coordinator(first.clone(), second.clone(), risk.clone(), LegMode::Sequential)
+        .execute_pair(&short_leg(), &long_leg())
+        .await
+        .unwrap();
+
+    assert!(execution.is_hedged());
+    assert_eq!(execution.first.status, TradeStatus::Filled);
+    assert_eq!(execution.second.status, TradeStatus::Chased);
+    assert_eq!(execution.second.size, dec("2"));
+    let sent: Vec<(Decimal, Decimal)> = second.orders().iter().map(|o| (o.1, o.2)).collect();
+    assert_eq!(
+        sent,
+        vec![(dec("2"), dec("100")), (dec("1.5"), dec("100")), (dec("1"), dec("101"))]
+    );
+    assert_eq!(risk.exposure("BTC"), Decimal::from(400));
+}
+
+#[tokio::test]
+async fn partial_retry_completes_as_filled_on_retry() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let second = ScriptedVenue::new(Venue::Binance, &["1.5", "0.5"]);
+
+    let execution =
+        coordinator(first, second, risk_engine(1_000_000.0), LegMode::Concurrent)
+            .execute_pair(&short_leg(), &long_leg())
+            .await
+            .unwrap();
+
+    assert!(execution.is_hedged());
+    assert_eq!(execution.second.status, TradeStatus::FilledOnRetry);
+}
+
+#[tokio::test]
+async fn unfillable_second_leg_unwinds_the_first() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let second = ScriptedVenue::new(Venue::Binance, &["0", "0", "0.5"]);
+    let risk = risk_engine(1_000_000.0);
+
+    let execution = coordinator(first.clone(), second.clone(), risk.clone(), LegMode::Sequential)
+        .execute_pair(&short_leg(), &long_leg())
+        .await
+        .unwrap();
+
+    assert!(!execution.is_hedged());
+    assert_eq!(execution.first.status, TradeStatus::Unwound);
+    assert_eq!(execution.second.status, TradeStatus::Unwound);
+
+    let first_orders = first.orders();
+    assert_eq!(first_orders.len(), 2);
+    assert_eq!((first_orders[1].0.clone(), first_orders[1].1), (PositionSide::Long, dec("2")));
+    assert!(first_orders[1].3.reduce_only);
+    let second_close = second.orders().last().cloned().unwrap();
+    assert_eq!((second_close.0, second_close.1), (PositionSide::Short, dec("0.5")));
+    assert_eq!(risk.exposure("BTC"), Decimal::ZERO);
+}
+
+#[tokio::test]
+async fn partly_unwound_leg_comes_back_with_what_is_still_open() {
+    // The unwind order only closes 0.5 of the first leg's 2
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &["2", "0.5"]);
+    let second = ScriptedVenue::new(Venue::Binance, &["0", "0", "0"]);
+    let risk = risk_engine(1_000_000.0);
+
+    let execution = coordinator(first.clone(), second, risk.clone(), LegMode::Sequential)
+        .execute_pair(&short_leg(), &long_leg())
+        .await
+        .unwrap();
+
+    assert!(!execution.is_hedged());
+    assert_eq!(execution.first.status, TradeStatus::PartiallyFilled);
+    assert_eq!(execution.first.size, dec("1.5"));
+    assert_eq!(execution.second.status, TradeStatus::Rejected);
+    assert_eq!(risk.exposure("BTC"), dec("150"));
+}
+
+#[tokio::test]
+async fn failed_first_leg_never_sends_the_second() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &["0", "0", "0"]);
+    let second = ScriptedVenue::new(Venue::Binance, &[]);
+
+    let execution =
+        coordinator(first, second.clone(), risk_engine(1_000_000.0), LegMode::Sequential)
+            .execute_pair(&short_leg(), &long_leg())
+            .await
+            .unwrap();
+
+    assert_eq!(execution.first.status, TradeStatus::Rejected);
+    assert_eq!(execution.second.status, TradeStatus::Cancelled);
+    assert!(second.orders().is_empty());
+}
+
+#[tokio::test]
+async fn known_rejection_is_retried() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let rejected = BinanceError {
+        code: -2019,
+        message: "Margin is insufficient.".to_string(),
+    };
+    let second = ScriptedVenue::failing(Venue::Binance, vec![rejected.into()]);
+
+    let execution =
+        coordinator(first, second.clone(), risk_engine(1_000_000.0), LegMode::Sequential)
+            .execute_pair(&short_leg(), &long_leg())
+            .await
+            .unwrap();
+
+    assert!(execution.is_hedged());
+    assert_eq!(execution.second.status, TradeStatus::FilledOnRetry);
+    assert_eq!(second.orders().len(), 2);
+}
+
+#[tokio::test]
+async fn order_with_an_unknown_outcome_is_not_sent_again() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let timeout = anyhow::anyhow!("operation timed out").context("Failed to send order");
+    let second = ScriptedVenue::failing(Venue::Binance, vec![timeout]);
+
+    let execution =
+        coordinator(first.clone(), second.clone(), risk_engine(1_000_000.0), LegMode::Sequential)
+            .execute_pair(&short_leg(), &long_leg())
+            .await
+            .unwrap();
+
+    // The first leg is unwound; a fill on the second is left to reconciliation
+    assert_eq!(second.orders().len(), 1);
+    assert_eq!(execution.second.status, TradeStatus::Failed);
+    assert_eq!(execution.first.status, TradeStatus::Unwound);
+    assert_eq!(first.orders().len(), 2);
+}
+
+#[tokio::test]
+async fn acknowledged_order_is_settled_before_the_rest_is_resent() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let second = ScriptedVenue::acknowledging(Venue::Binance, Some("0.5"));
+
+    let execution =
+        coordinator(first, second.clone(), risk_engine(1_000_000.0), LegMode::Sequential)
+            .execute_pair(&short_leg(), &long_leg())
+            .await
+            .unwrap();
+
+    assert!(execution.is_hedged());
+    assert_eq!(execution.second.size, dec("2"));
+    assert_eq!(second.cancels.lock().unwrap().clone(), vec!["acknowledged"]);
+    let sent: Vec<Decimal> = second.orders().iter().map(|o| o.1).collect();
+    assert_eq!(sent, vec![dec("2"), dec("1.5")]);
+}
+
+#[tokio::test]
+async fn acknowledged_order_that_cannot_be_settled_is_not_resent() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let second = ScriptedVenue::acknowledging(Venue::Binance, None);
+
+    let execution =
+        coordinator(first.clone(), second.clone(), risk_engine(1_000_000.0), LegMode::Sequential)
+            .execute_pair(&short_leg(), &long_leg())
+            .await
+            .unwrap();
+
+    assert_eq!(second.orders().len(), 1);
+    assert_eq!(execution.second.status, TradeStatus::Failed);
+    assert_eq!(execution.first.status, TradeStatus::Unwound);
+}
+
+#[tokio::test]
+async fn risk_rejection_sends_nothing() {
+    let first = ScriptedVenue::new(Venue::Hyperliquid, &[]);
+    let second = ScriptedVenue::new(Venue::Binance, &[]);
+    let risk = risk_engine(300.0);
+
+    let result = coordinator(first.clone(), second.clone(), risk.clone(), LegMode::Concurrent)
+        .execute_pair(&short_leg(), &long_leg())
+        .await;
+
+    assert!(result.is_err());
+    assert!(first.orders().is_empty() && second.orders().is_empty());
+    assert_eq!(risk.exposure("BTC"), Decimal::ZERO);
+}
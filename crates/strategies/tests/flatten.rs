@@ -0,0 +1,191 @@
+mod common;
+
+use arb_core::types::{Hedge, Position, PositionSide, SharedState, StrategyType, Venue};
+use arb_core::{KillSwitchReason, KillSwitchState, RiskEngine};
+use chrono::Duration;
+use common::{position, risk_config, Holdings};
+use connectors::ConnectorRegistry;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use strategies::flatten_all;
+
+fn risk_engine() -> RiskEngine {
+    RiskEngine::new(risk_config())
+}
+
+fn open_hedge(state: &SharedState, symbol: &str, short: Venue, long: Venue) -> String {
+    let hedge = Hedge::new(symbol, short, long, Decimal::from(2));
+    let id = hedge.id.clone();
+    state.hedges.insert(id.clone(), hedge);
+    id
+}
+
+fn registry(connectors: &[Arc<Holdings>]) -> ConnectorRegistry {
+    let mut registry = ConnectorRegistry::new();
+    for connector in connectors {
+        registry.register(connector.clone());
+    }
+    registry
+}
+
+#[tokio::test]
+async fn closes_every_position_reduce_only_and_closes_the_hedge() {
+    let short = Holdings::new(
+        Venue::Hyperliquid,
+        Some(vec![position(Venue::Hyperliquid, "BTC", PositionSide::Short, 2)]),
+    );
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![
+            position(Venue::Binance, "BTC", PositionSide::Long, 2),
+            position(Venue::Binance, "ETH", PositionSide::Long, 0),
+        ]),
+    );
+    let state = SharedState::new();
+    let btc = position(Venue::Binance, "BTC", PositionSide::Long, 2);
+    state.positions.insert(Position::key(&btc.venue, &btc.symbol), btc);
+    let id = open_hedge(&state, "BTC", Venue::Hyperliquid, Venue::Binance);
+    let mut events = state.events.subscribe("test");
+
+    let report = flatten_all(
+        &registry(&[short.clone(), long.clone()]),
+        &[Venue::Hyperliquid, Venue::Binance],
+        &state,
+        &risk_engine(),
+        false,
+    )
+    .await;
+
+    assert!(report.is_complete());
+    assert_eq!(report.closed.len(), 2);
+    let orders = long.orders();
+    assert_eq!(orders.len(), 1);
+    assert_eq!((orders[0].1.clone(), orders[0].2), (PositionSide::Short, Decimal::from(2)));
+    assert!(orders[0].3.reduce_only);
+    assert_eq!(short.orders()[0].1, PositionSide::Long);
+
+    assert!(state.positions.is_empty());
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+    let kinds: Vec<&str> = std::iter::from_fn(|| events.try_recv()).map(|e| e.kind()).collect();
+    assert_eq!(kinds.first(), Some(&"position_closed"));
+    assert_eq!(kinds.last(), Some(&"hedge_updated"));
+}
+
+#[tokio::test]
+async fn unreachable_venue_is_reported_and_its_hedges_stay_open() {
+    let short = Holdings::new(Venue::Hyperliquid, None);
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![
+            position(Venue::Binance, "BTC", PositionSide::Long, 2),
+            position(Venue::Binance, "SOL", PositionSide::Long, 5),
+        ]),
+    );
+    let state = SharedState::new();
+    let btc = open_hedge(&state, "BTC", Venue::Hyperliquid, Venue::Binance);
+    let sol = open_hedge(&state, "SOL", Venue::Bybit, Venue::Binance);
+
+    let report = flatten_all(
+        &registry(&[short, long.clone()]),
+        &[Venue::Hyperliquid, Venue::Binance],
+        &state,
+        &risk_engine(),
+        false,
+    )
+    .await;
+
+    assert!(!report.is_complete());
+    assert_eq!(report.failures.len(), 1);
+    assert!(report.failures[0].contains("venue unavailable"));
+    assert_eq!(long.orders().len(), 2);
+    assert!(state.hedges.get(&btc).unwrap().is_open());
+    assert!(!state.hedges.get(&sol).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn hedge_with_a_spot_leg_stays_open_and_is_reported() {
+    let perp = Holdings::new(
+        Venue::Hyperliquid,
+        Some(vec![position(Venue::Hyperliquid, "ETH", PositionSide::Short, 2)]),
+    );
+    let state = SharedState::new();
+    let hedge = Hedge::new("ETH", Venue::Hyperliquid, Venue::HyperEvm, Decimal::from(2))
+        .with_strategy(StrategyType::HyperEvmSpot);
+    let id = hedge.id.clone();
+    state.hedges.insert(id.clone(), hedge);
+
+    let connectors = registry(std::slice::from_ref(&perp));
+    let report =
+        flatten_all(&connectors, &[Venue::Hyperliquid], &state, &risk_engine(), false).await;
+
+    // The perp leg closes, but the tokens bought on HyperEVM are still held
+    assert_eq!(report.closed.len(), 1);
+    assert_eq!(report.failures.len(), 1);
+    assert!(report.failures[0].contains("HyperEvm spot leg"));
+    assert!(state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn dry_run_reports_positions_without_placing_orders() {
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 2)]),
+    );
+    let state = SharedState::new();
+    let id = open_hedge(&state, "BTC", Venue::Hyperliquid, Venue::Binance);
+
+    let connectors = registry(std::slice::from_ref(&long));
+
+    let report = flatten_all(&connectors, &[Venue::Binance], &state, &risk_engine(), true).await;
+
+    assert!(report.dry_run && report.is_complete());
+    assert_eq!(report.closed.len(), 1);
+    assert!(long.orders().is_empty());
+    assert!(state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn partial_closes_are_booked_and_only_the_remainder_resent() {
+    let long = Holdings::with_fill_cap(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 5)]),
+        Decimal::ONE,
+    );
+    let state = SharedState::new();
+    let btc = position(Venue::Binance, "BTC", PositionSide::Long, 5);
+    state.positions.insert(Position::key(&btc.venue, &btc.symbol), btc);
+    let id = open_hedge(&state, "BTC", Venue::Hyperliquid, Venue::Binance);
+
+    let connectors = registry(std::slice::from_ref(&long));
+    let report = flatten_all(&connectors, &[Venue::Binance], &state, &risk_engine(), false).await;
+
+    assert_eq!(report.failures.len(), 1);
+    let sizes: Vec<Decimal> = long.orders().iter().map(|o| o.2).collect();
+    assert_eq!(sizes, vec![Decimal::from(5), Decimal::from(4), Decimal::from(3)]);
+    let key = Position::key(&Venue::Binance, "BTC");
+    assert_eq!(state.positions.get(&key).unwrap().size, Decimal::from(2));
+    assert!(state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn flatten_all_during_a_daily_loss_trip_stays_tripped_after_rollover() {
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 2)]),
+    );
+    let state = SharedState::new();
+    let risk = risk_engine();
+    risk.update_daily_pnl(Decimal::from(-1500));
+
+    // As the control endpoint does: trip by hand, then flatten
+    assert!(risk.trip(KillSwitchReason::Manual("flatten all".to_string())));
+    let connectors = registry(std::slice::from_ref(&long));
+    let report = flatten_all(&connectors, &[Venue::Binance], &state, &risk, false).await;
+    assert!(report.is_complete());
+
+    risk.tick(risk.next_rollover() + Duration::days(1));
+    assert!(matches!(
+        risk.kill_switch_state(),
+        KillSwitchState::Tripped { reason: KillSwitchReason::Manual(_), .. }
+    ));
+}
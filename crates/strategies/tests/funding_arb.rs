@@ -0,0 +1,660 @@
+mod common;
+
+use arb_core::config::{FundingArbConfig, RiskConfig};
+use arb_core::types::{
+    AccountMargin, Balance, BookLevel, ContractSpec, FundingRate, Hedge, MarketData, OrderBook,
+    OrderOptions, Position, PositionSide, StrategyType, TimeInForce, Trade, TradeStatus, Venue,
+};
+use arb_core::{RiskEngine, SharedState};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use common::risk_config;
+use connectors::{ConnectorRegistry, VenueConnector};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use strategies::{FundingArbStrategy, Strategy};
+
+/// Venue stand-in with a fixed funding rate, one position, a margin ratio and a
+/// deep BTC book at 100, recording every order it receives.
+struct FakeVenue {
+    venue: Venue,
+    rate: Mutex<Decimal>,
+    interval_hours: u32,
+    position: Mutex<Option<(PositionSide, Decimal)>>,
+    maintenance_margin: Decimal,
+    /// Base units per contract; positions and orders are in contracts.
+    multiplier: Decimal,
+    orders: Mutex<Vec<(PositionSide, Decimal, OrderOptions)>>,
+    /// Fails every order while set.
+    rejecting: AtomicBool,
+    /// Fails reduce-only orders while set.
+    rejecting_reduce_only: AtomicBool,
+    predicted_rate: Mutex<Option<Decimal>>,
+    /// Most any one order fills, if set.
+    fill_cap: Mutex<Option<Decimal>>,
+}
+
+impl FakeVenue {
+    fn new(
+        venue: Venue,
+        rate: &str,
+        interval_hours: u32,
+        position: Option<(PositionSide, &str)>,
+    ) -> Arc<Self> {
+        Self::with_margin(venue, rate, interval_hours, position, 100)
+    }
+
+    /// Equity is fixed at 1000, so `maintenance_margin` is the ratio in thousandths.
+    fn with_margin(
+        venue: Venue,
+        rate: &str,
+        interval_hours: u32,
+        position: Option<(PositionSide, &str)>,
+        maintenance_margin: i64,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            venue,
+            rate: Mutex::new(dec(rate)),
+            interval_hours,
+            position: Mutex::new(position.map(|(side, size)| (side, dec(size)))),
+            maintenance_margin: Decimal::from(maintenance_margin),
+            multiplier: Decimal::ONE,
+            orders: Mutex::new(Vec::new()),
+            rejecting: AtomicBool::new(false),
+            rejecting_reduce_only: AtomicBool::new(false),
+            predicted_rate: Mutex::new(None),
+            fill_cap: Mutex::new(None),
+        })
+    }
+
+    /// A venue quoting BTC in contracts of `multiplier` BTC each.
+    fn with_multiplier(
+        venue: Venue,
+        rate: &str,
+        interval_hours: u32,
+        position: Option<(PositionSide, &str)>,
+        multiplier: &str,
+    ) -> Arc<Self> {
+        Arc::new(Self {
+            venue,
+            rate: Mutex::new(dec(rate)),
+            interval_hours,
+            position: Mutex::new(position.map(|(side, size)| (side, dec(size)))),
+            maintenance_margin: Decimal::from(100),
+            multiplier: dec(multiplier),
+            orders: Mutex::new(Vec::new()),
+            rejecting: AtomicBool::new(false),
+            rejecting_reduce_only: AtomicBool::new(false),
+            predicted_rate: Mutex::new(None),
+            fill_cap: Mutex::new(None),
+        })
+    }
+
+    fn orders(&self) -> Vec<(PositionSide, Decimal, OrderOptions)> {
+        self.orders.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl VenueConnector for FakeVenue {
+    fn venue(&self) -> Venue {
+        self.venue.clone()
+    }
+
+    async fn get_market_data(&self, _symbol: &str) -> Result<MarketData> {
+        unimplemented!()
+    }
+
+    async fn get_funding_rate(&self, symbol: &str) -> Result<FundingRate> {
+        Ok(FundingRate {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            rate: *self.rate.lock().unwrap(),
+            predicted_rate: *self.predicted_rate.lock().unwrap(),
+            interval_hours: self.interval_hours,
+            next_funding_time: None,
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_order_book(&self, symbol: &str, _depth: usize) -> Result<OrderBook> {
+        let level = |price| BookLevel {
+            price: Decimal::from(price),
+            size: Decimal::from(1000),
+        };
+        Ok(OrderBook {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            bids: vec![level(100)],
+            asks: vec![level(100)],
+            timestamp: Utc::now(),
+        })
+    }
+
+    async fn get_contract_spec(&self, symbol: &str) -> Result<ContractSpec> {
+        Ok(ContractSpec {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            tick_size: dec("0.1"),
+            lot_size: dec("0.001"),
+            min_size: dec("0.001"),
+            min_notional: Decimal::ONE,
+            contract_multiplier: self.multiplier,
+        })
+    }
+
+    async fn get_top_symbols_by_volume(&self, _limit: usize) -> Result<Vec<String>> {
+        Ok(vec!["BTC".to_string()])
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        price: Decimal,
+    ) -> Result<Trade> {
+        self.place_order_with_options(symbol, side, size, price, OrderOptions::default())
+            .await
+    }
+
+    async fn place_order_with_options(
+        &self,
+        symbol: &str,
+        side: PositionSide,
+        size: Decimal,
+        _price: Decimal,
+        options: OrderOptions,
+    ) -> Result<Trade> {
+        if self.rejecting.load(Ordering::SeqCst)
+            || (options.reduce_only && self.rejecting_reduce_only.load(Ordering::SeqCst))
+        {
+            anyhow::bail!("order rejected");
+        }
+        self.orders.lock().unwrap().push((side.clone(), size, options));
+        let filled = self.fill_cap.lock().unwrap().map_or(size, |cap| size.min(cap));
+        Ok(Trade {
+            symbol: symbol.to_string(),
+            venue: self.venue.clone(),
+            side,
+            size: filled,
+            price: Decimal::from(100),
+            timestamp: Utc::now(),
+            status: if filled < size {
+                TradeStatus::PartiallyFilled
+            } else {
+                TradeStatus::Filled
+            },
+            order_id: Some("1".to_string()),
+        })
+    }
+
+    async fn cancel_order(&self, _: &str, _: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        Ok(self
+            .position
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(side, size)| Position {
+                symbol: "BTC".to_string(),
+                venue: self.venue.clone(),
+                side: side.clone(),
+                size: *size,
+                entry_price: Decimal::from(100),
+                leverage: Decimal::ONE,
+                timestamp: Utc::now(),
+            })
+            .collect())
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        unimplemented!()
+    }
+
+    async fn get_account_margin(&self) -> Result<AccountMargin> {
+        Ok(AccountMargin {
+            venue: self.venue.clone(),
+            equity: Decimal::from(1000),
+            initial_margin: Decimal::from(200),
+            maintenance_margin: self.maintenance_margin,
+            position_notional: Decimal::from(400),
+            timestamp: Utc::now(),
+        })
+    }
+}
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn strategy(
+    short: Arc<FakeVenue>,
+    long: Arc<FakeVenue>,
+    state: Arc<SharedState>,
+) -> FundingArbStrategy {
+    strategy_with_risk(short, long, state, risk_engine(1_000_000.0))
+}
+
+fn strategy_with_risk(
+    short: Arc<FakeVenue>,
+    long: Arc<FakeVenue>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+) -> FundingArbStrategy {
+    let mut registry = ConnectorRegistry::new();
+    registry.register(short);
+    registry.register(long);
+    FundingArbStrategy::new(
+        Arc::new(registry),
+        vec![Venue::Hyperliquid, Venue::Binance],
+        state,
+        risk_engine,
+        FundingArbConfig::default(),
+        false,
+    )
+}
+
+fn risk_engine(max_notional_per_asset: f64) -> Arc<RiskEngine> {
+    Arc::new(RiskEngine::new(RiskConfig { max_notional_per_asset, ..risk_config() }))
+}
+
+fn open_hedge(state: &SharedState, size: &str) -> String {
+    let hedge = Hedge::new("BTC", Venue::Hyperliquid, Venue::Binance, dec(size));
+    let id = hedge.id.clone();
+    state.hedges.insert(id.clone(), hedge);
+    id
+}
+
+fn filled(venue: Venue, side: PositionSide, size: &str) -> Trade {
+    Trade {
+        symbol: "BTC".to_string(),
+        venue,
+        side,
+        size: dec(size),
+        price: dec("100"),
+        timestamp: Utc::now(),
+        status: TradeStatus::Filled,
+        order_id: Some("1".to_string()),
+    }
+}
+
+fn reduce_only_ioc() -> OrderOptions {
+    OrderOptions {
+        time_in_force: TimeInForce::ImmediateOrCancel,
+        reduce_only: true,
+    }
+}
+
+#[tokio::test]
+async fn compressed_spread_unwinds_both_legs_reduce_only() {
+    // 0.001%/h against 0.008%/8h: both annualise to 8.76%, so nothing is left to earn
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.00001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.00008", 8, Some((PositionSide::Long, "2")));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+    state.record_fill(filled(Venue::Hyperliquid, PositionSide::Short, "2"));
+    state.record_fill(filled(Venue::Binance, PositionSide::Long, "2"));
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(short.orders(), vec![(PositionSide::Long, dec("2"), reduce_only_ioc())]);
+    assert_eq!(long.orders(), vec![(PositionSide::Short, dec("2"), reduce_only_ioc())]);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+    // The closes come off the local book; the venues' view is left to reconciliation
+    assert!(state.positions.is_empty());
+}
+
+#[tokio::test]
+async fn closed_leg_is_booked_even_when_the_other_leg_fails() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.00001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.00008", 8, Some((PositionSide::Long, "2")));
+    long.rejecting.store(true, Ordering::SeqCst);
+    let state = Arc::new(SharedState::new());
+    let risk = risk_engine(1_000_000.0);
+    let id = open_hedge(&state, "2");
+    // An unrelated ETH position keeps a second slot taken on the short venue
+    let eth = Trade {
+        symbol: "ETH".to_string(),
+        ..filled(Venue::Hyperliquid, PositionSide::Long, "1")
+    };
+    let legs = [
+        filled(Venue::Hyperliquid, PositionSide::Short, "2"),
+        filled(Venue::Binance, PositionSide::Long, "2"),
+    ];
+    for trade in legs.into_iter().chain([eth]) {
+        risk.record_trade(&trade);
+        state.record_fill(trade);
+    }
+    let strategy = strategy_with_risk(short.clone(), long.clone(), state.clone(), risk.clone());
+
+    strategy.manage_hedges().await.unwrap();
+
+    let hedge = state.hedges.get(&id).unwrap().clone();
+    assert!(hedge.is_open());
+    assert_eq!(hedge.closed_legs, vec![Venue::Hyperliquid]);
+    assert!(!state.positions.contains_key(&Position::key(&Venue::Hyperliquid, "BTC")));
+    assert!(state.positions.contains_key(&Position::key(&Venue::Binance, "BTC")));
+    assert_eq!(risk.exposure("BTC"), dec("200"));
+    assert_eq!(risk.snapshot().position_counts[&Venue::Hyperliquid], 1);
+
+    // The venue now shows the short leg gone; finishing the close must not book it
+    // twice, and must go ahead even though the spread has since recovered and the
+    // strategy has been rebuilt, as after a restart
+    *short.position.lock().unwrap() = None;
+    *short.rate.lock().unwrap() = dec("0.0001");
+    long.rejecting.store(false, Ordering::SeqCst);
+    drop(strategy);
+    strategy_with_risk(short.clone(), long.clone(), state.clone(), risk.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(short.orders().len(), 1);
+    assert_eq!(long.orders().len(), 1);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+    assert_eq!(state.positions.len(), 1);
+    assert_eq!(risk.exposure("BTC"), Decimal::ZERO);
+    let counts = risk.snapshot().position_counts;
+    assert_eq!((counts[&Venue::Hyperliquid], counts[&Venue::Binance]), (1, 0));
+}
+
+#[tokio::test]
+async fn partially_filled_close_resends_only_the_remainder() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.00001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.00008", 8, Some((PositionSide::Long, "2")));
+    *long.fill_cap.lock().unwrap() = Some(dec("1.5"));
+    let state = Arc::new(SharedState::new());
+    let risk = risk_engine(1_000_000.0);
+    let id = open_hedge(&state, "2");
+    for trade in [
+        filled(Venue::Hyperliquid, PositionSide::Short, "2"),
+        filled(Venue::Binance, PositionSide::Long, "2"),
+    ] {
+        risk.record_trade(&trade);
+        state.record_fill(trade);
+    }
+
+    strategy_with_risk(short, long.clone(), state.clone(), risk.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    let sizes: Vec<Decimal> = long.orders().into_iter().map(|(_, size, _)| size).collect();
+    assert_eq!(sizes, vec![dec("2"), dec("0.5")]);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+    assert!(state.positions.is_empty());
+    assert_eq!(risk.exposure("BTC"), Decimal::ZERO);
+}
+
+#[tokio::test]
+async fn wide_spread_keeps_hedge_and_trims_drifted_leg() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "1.5")));
+    let state = Arc::new(SharedState::new());
+    let risk = risk_engine(1_000_000.0);
+    risk.record_trade(&filled(Venue::Hyperliquid, PositionSide::Short, "2"));
+    risk.record_trade(&filled(Venue::Binance, PositionSide::Long, "1.5"));
+    let id = open_hedge(&state, "2");
+
+    strategy_with_risk(short.clone(), long.clone(), state.clone(), risk.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(short.orders(), vec![(PositionSide::Long, dec("0.5"), reduce_only_ioc())]);
+    assert!(long.orders().is_empty());
+    let hedge = state.hedges.get(&id).unwrap().clone();
+    assert!(hedge.is_open());
+    assert_eq!(hedge.size, dec("1.5"));
+    // The trimmed notional is released, but both legs keep their slots
+    assert_eq!(risk.exposure("BTC"), dec("300"));
+    assert_eq!(risk.snapshot().position_counts[&Venue::Hyperliquid], 1);
+}
+
+#[tokio::test]
+async fn partly_filled_trim_shrinks_the_hedge_by_what_filled() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "1.5")));
+    *short.fill_cap.lock().unwrap() = Some(dec("0.1"));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+
+    let strategy = strategy(short.clone(), long.clone(), state.clone());
+    strategy.manage_hedges().await.unwrap();
+
+    // Three reduce-only orders of at most 0.1 take 0.3 of the 0.5 drift off
+    assert_eq!(short.orders().len(), 3);
+    assert_eq!(state.hedges.get(&id).unwrap().size, dec("1.7"));
+
+    // A trim that fills nothing leaves the hedge as it was
+    short.rejecting.store(true, Ordering::SeqCst);
+    *short.position.lock().unwrap() = Some((PositionSide::Short, dec("1.7")));
+    strategy.manage_hedges().await.unwrap();
+    assert_eq!(state.hedges.get(&id).unwrap().size, dec("1.7"));
+}
+
+#[tokio::test]
+async fn drift_below_one_lot_is_left_alone() {
+    // 0.0006 BTC apart is past the 5% tolerance but rounds to no 0.001 lot
+    let short_position = Some((PositionSide::Short, "0.01"));
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, short_position);
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "0.0094")));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "0.01");
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert!(short.orders().is_empty() && long.orders().is_empty());
+    assert_eq!(state.hedges.get(&id).unwrap().size, dec("0.01"));
+}
+
+#[tokio::test]
+async fn legs_in_different_contract_sizes_close_in_each_venues_contracts() {
+    // 20 contracts of 0.1 BTC against 2 BTC
+    let short_position = Some((PositionSide::Short, "20"));
+    let short = FakeVenue::with_multiplier(Venue::Hyperliquid, "0.00001", 1, short_position, "0.1");
+    let long = FakeVenue::new(Venue::Binance, "0.00008", 8, Some((PositionSide::Long, "2")));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(short.orders(), vec![(PositionSide::Long, dec("20"), reduce_only_ioc())]);
+    assert_eq!(long.orders(), vec![(PositionSide::Short, dec("2"), reduce_only_ioc())]);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn close_sizes_are_rounded_down_to_the_lot() {
+    // Legs shared with other hedges are larger than this one, whose size is off the 0.001 grid
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.00001", 1, Some((PositionSide::Short, "3")));
+    let long = FakeVenue::new(Venue::Binance, "0.00008", 8, Some((PositionSide::Long, "3")));
+    let state = Arc::new(SharedState::new());
+    open_hedge(&state, "2.0005");
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(short.orders(), vec![(PositionSide::Long, dec("2"), reduce_only_ioc())]);
+    assert_eq!(long.orders(), vec![(PositionSide::Short, dec("2"), reduce_only_ioc())]);
+}
+
+#[tokio::test]
+async fn drift_is_measured_and_trimmed_in_the_base_asset() {
+    // 20 contracts of 0.1 BTC is 2 BTC against 1.5 BTC, so 5 contracts come off
+    let short_position = Some((PositionSide::Short, "20"));
+    let short = FakeVenue::with_multiplier(Venue::Hyperliquid, "0.0001", 1, short_position, "0.1");
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "1.5")));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(short.orders(), vec![(PositionSide::Long, dec("5"), reduce_only_ioc())]);
+    assert!(long.orders().is_empty());
+    assert_eq!(state.hedges.get(&id).unwrap().size, dec("1.5"));
+}
+
+#[tokio::test]
+async fn another_strategys_leg_in_the_same_position_is_not_drift() {
+    // Hyperliquid nets a HyperEVM spot hedge's 0.5 BTC short into the funding-arb short
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, Some((PositionSide::Short, "2.5")));
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "2")));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+    let mut spot = Hedge::new("BTC", Venue::Hyperliquid, Venue::HyperEvm, dec("0.5"))
+        .with_strategy(StrategyType::HyperEvmSpot);
+    spot.id = "BTC-USDC-1".to_string();
+    let spot_id = spot.id.clone();
+    state.hedges.insert(spot_id.clone(), spot);
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert!(short.orders().is_empty() && long.orders().is_empty());
+    assert_eq!(state.hedges.get(&id).unwrap().size, dec("2"));
+    assert!(state.hedges.get(&spot_id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn lost_leg_is_left_to_the_reconciler() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, None);
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert!(short.orders().is_empty());
+    assert!(long.orders().is_empty());
+    assert!(state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn margin_breach_forces_exit() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, Some((PositionSide::Short, "2")));
+    let long_position = Some((PositionSide::Long, "2"));
+    let long = FakeVenue::with_margin(Venue::Binance, "0.0001", 8, long_position, 900);
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+
+    strategy(short.clone(), long.clone(), state.clone())
+        .manage_hedges()
+        .await
+        .unwrap();
+
+    assert_eq!(long.orders(), vec![(PositionSide::Short, dec("2"), reduce_only_ioc())]);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn predicted_funding_flip_forces_exit() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0001", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "2")));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state, "2");
+    let strategy = strategy(short.clone(), long.clone(), state.clone());
+
+    strategy.manage_hedges().await.unwrap();
+    assert!(state.hedges.get(&id).unwrap().is_open());
+
+    // The current spread is still wide, but the short venue is about to pay out
+    *short.predicted_rate.lock().unwrap() = Some(dec("-0.0001"));
+    strategy.manage_hedges().await.unwrap();
+
+    assert_eq!(short.orders(), vec![(PositionSide::Long, dec("2"), reduce_only_ioc())]);
+    assert_eq!(long.orders(), vec![(PositionSide::Short, dec("2"), reduce_only_ioc())]);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn hedge_bound_by_the_notional_limit_fits_both_legs() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0005", 1, None);
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, None);
+    let state = Arc::new(SharedState::new());
+    let risk_engine = risk_engine(1000.0);
+    let mut strategy =
+        strategy_with_risk(short.clone(), long.clone(), state.clone(), risk_engine.clone());
+
+    strategy.init().await.unwrap();
+    strategy.on_tick().await.unwrap();
+
+    // 1000 of headroom across two legs at 100 leaves 5 BTC each
+    let ioc = OrderOptions {
+        time_in_force: TimeInForce::ImmediateOrCancel,
+        reduce_only: false,
+    };
+    assert_eq!(short.orders(), vec![(PositionSide::Short, dec("5"), ioc)]);
+    assert_eq!(long.orders(), vec![(PositionSide::Long, dec("5"), ioc)]);
+    assert_eq!(risk_engine.exposure("BTC"), Decimal::from(1000));
+    let hedges: Vec<Hedge> = state.hedges.iter().map(|h| h.value().clone()).collect();
+    assert_eq!(hedges.len(), 1);
+    assert_eq!(hedges[0].size, dec("5"));
+}
+
+#[tokio::test]
+async fn leg_left_open_by_a_failed_unwind_is_tracked_as_a_hedge() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0005", 1, None);
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, None);
+    short.rejecting_reduce_only.store(true, Ordering::SeqCst);
+    long.rejecting.store(true, Ordering::SeqCst);
+    let state = Arc::new(SharedState::new());
+    let risk_engine = risk_engine(1000.0);
+    let mut strategy =
+        strategy_with_risk(short.clone(), long.clone(), state.clone(), risk_engine.clone());
+
+    strategy.init().await.unwrap();
+    strategy.on_tick().await.unwrap();
+
+    // The short leg filled and could not be unwound, so it stays booked and
+    // tracked for the reconciler to report as orphaned
+    assert_eq!(short.orders().len(), 1);
+    assert_eq!(risk_engine.exposure("BTC"), Decimal::from(500));
+    assert!(state.positions.contains_key(&Position::key(&Venue::Hyperliquid, "BTC")));
+    let hedges: Vec<Hedge> = state.hedges.iter().map(|h| h.value().clone()).collect();
+    assert_eq!(hedges.len(), 1);
+    assert!(hedges[0].is_open());
+    assert_eq!(hedges[0].size, dec("5"));
+}
+
+#[tokio::test]
+async fn symbol_with_an_open_hedge_is_not_entered_again() {
+    let short = FakeVenue::new(Venue::Hyperliquid, "0.0005", 1, Some((PositionSide::Short, "2")));
+    let long = FakeVenue::new(Venue::Binance, "0.0001", 8, Some((PositionSide::Long, "2")));
+    let state = Arc::new(SharedState::new());
+    open_hedge(&state, "2");
+    let mut strategy = strategy(short.clone(), long.clone(), state.clone());
+
+    strategy.init().await.unwrap();
+    strategy.on_tick().await.unwrap();
+
+    assert!(short.orders().is_empty());
+    assert!(long.orders().is_empty());
+    assert_eq!(state.hedges.len(), 1);
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use arb_core::config::RiskConfig;
+use arb_core::types::{
+    Balance, FundingPayment, Hedge, MarketData, Position, PositionSide, Trade, Venue,
+};
+use arb_core::{RiskEngine, SharedState};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use common::risk_config;
+use connectors::{ConnectorRegistry, VenueConnector};
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use strategies::FundingLedger;
+
+/// Serves a fixed funding history, honouring `since` like the real venues.
+struct History {
+    venue: Venue,
+    payments: Mutex<Vec<FundingPayment>>,
+}
+
+impl History {
+    fn new(venue: Venue) -> Arc<Self> {
+        Arc::new(Self {
+            venue,
+            payments: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn settle(&self, id: &str, amount: &str, timestamp: DateTime<Utc>) {
+        self.payments.lock().unwrap().push(FundingPayment {
+            id: id.to_string(),
+            symbol: "BTC".to_string(),
+            venue: self.venue.clone(),
+            amount: Decimal::from_str(amount).unwrap(),
+            asset: "USDT".to_string(),
+            timestamp,
+        });
+    }
+}
+
+#[async_trait]
+impl VenueConnector for History {
+    fn venue(&self) -> Venue {
+        self.venue.clone()
+    }
+
+    async fn get_market_data(&self, _symbol: &str) -> Result<MarketData> {
+        unimplemented!()
+    }
+
+    async fn get_funding_payments(&self, symbol: &str, since: DateTime<Utc>) -> Result<Vec<FundingPayment>> {
+        Ok(self
+            .payments
+            .lock().unwrap()
+            .iter()
+            .filter(|p| p.symbol == symbol && p.timestamp >= since)
+            .cloned()
+            .collect())
+    }
+
+    async fn place_order(&self, _: &str, _: PositionSide, _: Decimal, _: Decimal) -> Result<Trade> {
+        unimplemented!()
+    }
+
+    async fn cancel_order(&self, _: &str, _: &str) -> Result<()> {
+        unimplemented!()
+    }
+
+    async fn get_positions(&self) -> Result<Vec<Position>> {
+        unimplemented!()
+    }
+
+    async fn get_balances(&self) -> Result<Vec<Balance>> {
+        unimplemented!()
+    }
+}
+
+fn hedge(id: &str, size: i64, opened_at: DateTime<Utc>) -> Hedge {
+    let mut hedge = Hedge::new("BTC", Venue::Hyperliquid, Venue::Binance, Decimal::from(size));
+    hedge.id = id.to_string();
+    hedge.opened_at = opened_at;
+    hedge
+}
+
+fn risk_engine(daily_loss_threshold: f64) -> Arc<RiskEngine> {
+    Arc::new(RiskEngine::new(RiskConfig {
+        kill_switch_daily_loss_threshold: daily_loss_threshold,
+        ..risk_config()
+    }))
+}
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+#[tokio::test]
+async fn funding_is_attributed_to_hedges_and_booked_once() {
+    let start = Utc::now() - Duration::hours(10);
+    let short_leg = History::new(Venue::Hyperliquid);
+    let long_leg = History::new(Venue::Binance);
+    let mut registry = ConnectorRegistry::new();
+    registry.register(short_leg.clone());
+    registry.register(long_leg.clone());
+
+    let state = Arc::new(SharedState::new());
+    let risk_engine = risk_engine(-1000.0);
+    state.hedges.insert("a".to_string(), hedge("a", 1, start));
+    state.hedges.insert("b".to_string(), hedge("b", 3, start + Duration::hours(2)));
+
+    // Only hedge "a" was open for the first settlement
+    short_leg.settle("hl-1", "4", start + Duration::hours(1));
+    short_leg.settle("hl-2", "8", start + Duration::hours(3));
+    long_leg.settle("bn-1", "-2", start + Duration::hours(3));
+
+    let ledger = FundingLedger::new(Arc::new(registry), state.clone(), risk_engine.clone(), 1000);
+    assert_eq!(ledger.sync().await.unwrap(), dec("10"));
+
+    let a = state.hedges.get("a").unwrap().clone();
+    let b = state.hedges.get("b").unwrap().clone();
+    assert_eq!(a.short_funding, dec("6"));
+    assert_eq!(a.long_funding, dec("-0.5"));
+    assert_eq!(b.short_funding, dec("6"));
+    assert_eq!(b.long_funding, dec("-1.5"));
+    assert_eq!(a.funding_pnl() + b.funding_pnl(), dec("10"));
+    assert_eq!(b.last_funding_at, Some(start + Duration::hours(3)));
+
+    // A second pass re-reads the last settlement but does not book it again
+    long_leg.settle("bn-2", "-4", start + Duration::hours(3));
+    assert_eq!(ledger.sync().await.unwrap(), dec("-4"));
+    assert_eq!(ledger.sync().await.unwrap(), Decimal::ZERO);
+    assert_eq!(state.hedges.get("b").unwrap().long_funding, dec("-4.5"));
+}
+
+#[tokio::test]
+async fn realised_funding_feeds_the_daily_loss_limit() {
+    let start = Utc::now() - Duration::hours(2);
+    let short_leg = History::new(Venue::Hyperliquid);
+    let long_leg = History::new(Venue::Binance);
+    let mut registry = ConnectorRegistry::new();
+    registry.register(short_leg.clone());
+    registry.register(long_leg.clone());
+
+    let state = Arc::new(SharedState::new());
+    let risk_engine = risk_engine(-100.0);
+    state.hedges.insert("a".to_string(), hedge("a", 1, start));
+    short_leg.settle("hl-1", "-150", start + Duration::hours(1));
+
+    let ledger = FundingLedger::new(Arc::new(registry), state, risk_engine.clone(), 1000);
+    ledger.sync().await.unwrap();
+
+    let trade = Trade {
+        symbol: "ETH".to_string(),
+        venue: Venue::Binance,
+        side: PositionSide::Long,
+        size: Decimal::ONE,
+        price: Decimal::ONE,
+        timestamp: Utc::now(),
+        status: arb_core::types::TradeStatus::Pending,
+        order_id: None,
+    };
+    assert!(risk_engine.check_trade(&trade).is_err());
+    assert!(risk_engine.is_kill_switch_active());
+}
+
+#[tokio::test]
+async fn a_restarted_ledger_does_not_book_funding_twice() {
+    let start = Utc::now() - Duration::hours(10);
+    let short_leg = History::new(Venue::Hyperliquid);
+    let long_leg = History::new(Venue::Binance);
+    let mut registry = ConnectorRegistry::new();
+    registry.register(short_leg.clone());
+    registry.register(long_leg.clone());
+    let registry = Arc::new(registry);
+
+    let state = Arc::new(SharedState::new());
+    let risk_engine = risk_engine(-1000.0);
+    state.hedges.insert("a".to_string(), hedge("a", 1, start));
+    short_leg.settle("hl-1", "4", start + Duration::hours(1));
+    short_leg.settle("hl-2", "5", start + Duration::hours(2));
+    long_leg.settle("bn-1", "-2", start + Duration::hours(1));
+
+    let ledger = FundingLedger::new(registry.clone(), state.clone(), risk_engine.clone(), 1000);
+    assert_eq!(ledger.sync().await.unwrap(), dec("7"));
+
+    // The hedge comes back from persisted state into a fresh ledger
+    let restored = Arc::new(SharedState::new());
+    restored.hedges.insert("a".to_string(), state.hedges.get("a").unwrap().clone());
+    long_leg.settle("bn-2", "-3", start + Duration::hours(2));
+
+    let restarted = FundingLedger::new(registry, restored.clone(), risk_engine.clone(), 1000);
+    assert_eq!(restarted.sync().await.unwrap(), dec("-3"));
+    assert_eq!(restarted.sync().await.unwrap(), Decimal::ZERO);
+
+    let a = restored.hedges.get("a").unwrap().clone();
+    assert_eq!(a.short_funding, dec("9"));
+    assert_eq!(a.long_funding, dec("-5"));
+    assert_eq!(risk_engine.daily_pnl(), dec("4"));
+}
@@ -0,0 +1,241 @@
+use arb_core::config::RestartConfig;
+use arb_core::types::{FundingRate, Venue};
+use arb_core::{Event, EventBus};
+use anyhow::Result;
+use async_trait::async_trait;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use strategies::{Strategy, StrategyFactory, StrategyHealth, StrategyManager, StrategyState};
+use tokio::time::sleep;
+
+const TICK: Duration = Duration::from_millis(5);
+
+#[derive(Default)]
+struct Probe {
+    ticks: AtomicU64,
+    events: AtomicU64,
+    instances: AtomicU32,
+    shut_down: AtomicBool,
+}
+
+struct TestStrategy {
+    probe: Arc<Probe>,
+    /// Panics on this tick of the first instance.
+    panic_on_tick: Option<u64>,
+    ticks: u64,
+    /// `None` for a strategy without a dry-run mode.
+    dry_run: Option<bool>,
+}
+
+#[async_trait]
+impl Strategy for TestStrategy {
+    fn name(&self) -> &str {
+        "test"
+    }
+
+    fn tick_interval(&self) -> Duration {
+        TICK
+    }
+
+    fn event_kinds(&self) -> &'static [&'static str] {
+        &["funding_update"]
+    }
+
+    async fn on_tick(&mut self) -> Result<()> {
+        self.ticks += 1;
+        if self.panic_on_tick == Some(self.ticks) {
+            panic!("boom");
+        }
+        self.probe.ticks.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn on_event(&mut self, _event: &Event) -> Result<()> {
+        self.probe.events.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    async fn shutdown(&mut self) -> Result<()> {
+        self.probe.shut_down.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn health(&self) -> StrategyHealth {
+        StrategyHealth::Degraded("testing".to_string())
+    }
+
+    fn dry_run(&self) -> Option<bool> {
+        self.dry_run
+    }
+
+    fn set_dry_run(&mut self, dry_run: bool) {
+        if self.dry_run.is_some() {
+            self.dry_run = Some(dry_run);
+        }
+    }
+}
+
+fn factory(probe: &Arc<Probe>, panic_on_tick: Option<u64>) -> StrategyFactory {
+    dry_run_factory(probe, panic_on_tick, None)
+}
+
+fn dry_run_factory(
+    probe: &Arc<Probe>,
+    panic_on_tick: Option<u64>,
+    dry_run: Option<bool>,
+) -> StrategyFactory {
+    let probe = probe.clone();
+    Arc::new(move || {
+        let first = probe.instances.fetch_add(1, Ordering::SeqCst) == 0;
+        Box::new(TestStrategy {
+            probe: probe.clone(),
+            panic_on_tick: panic_on_tick.filter(|_| first),
+            ticks: 0,
+            dry_run,
+        })
+    })
+}
+
+fn manager(bus: &EventBus) -> StrategyManager {
+    StrategyManager::new(
+        bus.clone(),
+        RestartConfig {
+            backoff_initial_ms: 20,
+            backoff_max_ms: 100,
+        },
+    )
+}
+
+fn funding() -> Event {
+    Event::FundingUpdate(FundingRate {
+        symbol: "BTC".to_string(),
+        venue: Venue::Hyperliquid,
+        rate: Decimal::ZERO,
+        predicted_rate: None,
+        interval_hours: 1,
+        next_funding_time: None,
+        timestamp: Utc::now(),
+    })
+}
+
+#[tokio::test]
+async fn ticks_and_events_reach_a_running_strategy() {
+    let bus = EventBus::new(16);
+    let probe = Arc::new(Probe::default());
+    let manager = manager(&bus);
+    let name = manager.start(factory(&probe, None)).unwrap();
+
+    sleep(TICK * 6).await;
+    bus.publish(funding());
+    sleep(TICK * 2).await;
+
+    let status = manager.status(&name).unwrap();
+    assert_eq!(status.state, StrategyState::Running);
+    assert_eq!(status.health, StrategyHealth::Degraded("testing".to_string()));
+    assert!(status.ticks > 0);
+    assert_eq!(probe.events.load(Ordering::SeqCst), 1);
+    assert!(manager.start(factory(&probe, None)).is_err());
+}
+
+#[tokio::test]
+async fn paused_strategy_stops_ticking_until_resumed() {
+    let bus = EventBus::new(16);
+    let probe = Arc::new(Probe::default());
+    let manager = manager(&bus);
+    let name = manager.start(factory(&probe, None)).unwrap();
+    sleep(TICK * 4).await;
+
+    manager.pause(&name).unwrap();
+    sleep(TICK * 2).await;
+    let paused_at = probe.ticks.load(Ordering::SeqCst);
+    bus.publish(funding());
+    sleep(TICK * 6).await;
+
+    assert_eq!(manager.status(&name).unwrap().state, StrategyState::Paused);
+    assert_eq!(probe.ticks.load(Ordering::SeqCst), paused_at);
+    assert_eq!(probe.events.load(Ordering::SeqCst), 0);
+
+    manager.resume(&name).unwrap();
+    sleep(TICK * 6).await;
+    assert_eq!(manager.status(&name).unwrap().state, StrategyState::Running);
+    assert!(probe.ticks.load(Ordering::SeqCst) > paused_at);
+    assert!(manager.pause("missing").is_err());
+}
+
+#[tokio::test]
+async fn panicking_strategy_restarts_from_a_fresh_instance() {
+    let bus = EventBus::new(16);
+    let probe = Arc::new(Probe::default());
+    let manager = manager(&bus);
+    let name = manager.start(factory(&probe, Some(2))).unwrap();
+
+    // Reporting the panic can take a while, so wait for the replacement to tick
+    for _ in 0..400 {
+        if probe.ticks.load(Ordering::SeqCst) > 1 {
+            break;
+        }
+        sleep(TICK).await;
+    }
+
+    let status = manager.status(&name).unwrap();
+    assert_eq!(status.restarts, 1);
+    assert_eq!(status.state, StrategyState::Running);
+    assert!(status.last_error.unwrap().contains("boom"));
+    assert_eq!(probe.instances.load(Ordering::SeqCst), 2);
+    assert!(probe.ticks.load(Ordering::SeqCst) > 1);
+}
+
+#[tokio::test]
+async fn shutdown_runs_the_strategy_hook() {
+    let bus = EventBus::new(16);
+    let probe = Arc::new(Probe::default());
+    let manager = manager(&bus);
+    manager.start(factory(&probe, None)).unwrap();
+    sleep(TICK * 2).await;
+
+    manager.shutdown().await;
+
+    assert!(probe.shut_down.load(Ordering::SeqCst));
+    assert!(manager.statuses().is_empty());
+}
+
+#[tokio::test]
+async fn dry_run_toggle_reaches_the_instance_and_survives_a_restart() {
+    let bus = EventBus::new(16);
+    let probe = Arc::new(Probe::default());
+    let manager = manager(&bus);
+    let name = manager.start(dry_run_factory(&probe, Some(20), Some(true))).unwrap();
+    sleep(TICK * 2).await;
+    assert_eq!(manager.status(&name).unwrap().dry_run, Some(true));
+
+    manager.set_dry_run(&name, false).unwrap();
+    sleep(TICK * 2).await;
+    assert_eq!(manager.status(&name).unwrap().dry_run, Some(false));
+
+    // The replacement instance starts in dry-run mode but is switched back before init
+    for _ in 0..400 {
+        if probe.ticks.load(Ordering::SeqCst) > 19 {
+            break;
+        }
+        sleep(TICK).await;
+    }
+    let status = manager.status(&name).unwrap();
+    assert_eq!(status.restarts, 1);
+    assert_eq!(status.dry_run, Some(false));
+}
+
+#[tokio::test]
+async fn dry_run_cannot_be_set_on_a_strategy_without_the_mode() {
+    let bus = EventBus::new(16);
+    let probe = Arc::new(Probe::default());
+    let manager = manager(&bus);
+    let name = manager.start(factory(&probe, None)).unwrap();
+    sleep(TICK).await;
+
+    assert!(manager.set_dry_run(&name, true).is_err());
+    assert_eq!(manager.status(&name).unwrap().dry_run, None);
+    assert!(manager.set_dry_run("missing", true).is_err());
+}
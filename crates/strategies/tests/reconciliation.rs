@@ -0,0 +1,302 @@
+mod common;
+
+use arb_core::config::ReconciliationConfig;
+use arb_core::types::{Hedge, Position, PositionSide, SharedState, Trade, TradeStatus, Venue};
+use arb_core::RiskEngine;
+use chrono::Utc;
+use common::{position, risk_config, Holdings};
+use connectors::ConnectorRegistry;
+use rust_decimal::Decimal;
+use std::sync::Arc;
+use strategies::{reconcile_positions, PositionReconciler};
+
+fn record(state: &SharedState, position: Position) {
+    state.positions.insert(Position::key(&position.venue, &position.symbol), position);
+}
+
+#[tokio::test]
+async fn flags_mismatches_and_adopts_the_venue_view() {
+    let state = SharedState::new();
+    record(&state, position(Venue::Binance, "BTC", PositionSide::Long, 2));
+    record(&state, position(Venue::Binance, "ETH", PositionSide::Long, 5));
+    record(&state, position(Venue::Binance, "SOL", PositionSide::Short, 10));
+
+    let mut registry = ConnectorRegistry::new();
+    registry.register(Holdings::new(
+        Venue::Binance,
+        Some(vec![
+            position(Venue::Binance, "BTC", PositionSide::Long, 2),
+            position(Venue::Binance, "ETH", PositionSide::Long, 3),
+            position(Venue::Binance, "DOGE", PositionSide::Short, 100),
+        ]),
+    ));
+
+    let found = reconcile_positions(&registry, &[Venue::Binance], &state).await;
+
+    let mut flagged: Vec<String> = found.iter().map(|d| d.symbol.clone()).collect();
+    flagged.sort();
+    assert_eq!(flagged, vec!["DOGE", "ETH", "SOL"]);
+    let sol = state.discrepancies.get(&Position::key(&Venue::Binance, "SOL")).unwrap();
+    assert!(sol.expected.is_some() && sol.reported.is_none());
+
+    assert_eq!(state.positions.len(), 3);
+    let eth = state.positions.get(&Position::key(&Venue::Binance, "ETH")).unwrap();
+    assert_eq!(eth.size, Decimal::from(3));
+    assert!(!state.positions.contains_key(&Position::key(&Venue::Binance, "SOL")));
+}
+
+#[tokio::test]
+async fn resolved_mismatches_are_cleared() {
+    let state = SharedState::new();
+    record(&state, position(Venue::Binance, "ETH", PositionSide::Long, 5));
+    record(&state, position(Venue::Bybit, "ETH", PositionSide::Short, 5));
+
+    let mut registry = ConnectorRegistry::new();
+    registry.register(Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "ETH", PositionSide::Long, 3)]),
+    ));
+    registry.register(Holdings::new(Venue::Bybit, Some(Vec::new())));
+    let venues = [Venue::Binance, Venue::Bybit];
+
+    assert_eq!(reconcile_positions(&registry, &venues, &state).await.len(), 2);
+    assert_eq!(state.discrepancies.len(), 2);
+
+    // Having adopted the venues' view, the next pass finds them in agreement
+    assert!(reconcile_positions(&registry, &venues, &state).await.is_empty());
+    assert!(state.discrepancies.is_empty());
+}
+
+#[tokio::test]
+async fn unreachable_venues_keep_their_recorded_positions() {
+    let state = SharedState::new();
+    record(&state, position(Venue::Bybit, "BTC", PositionSide::Short, 1));
+
+    let mut registry = ConnectorRegistry::new();
+    registry.register(Holdings::new(Venue::Bybit, None));
+
+    let found = reconcile_positions(&registry, &[Venue::Bybit], &state).await;
+
+    assert!(found.is_empty());
+    assert!(state.positions.contains_key(&Position::key(&Venue::Bybit, "BTC")));
+}
+
+#[tokio::test]
+async fn positions_booked_from_our_fills_match_the_venue() {
+    let state = SharedState::new();
+    for size in [3, 2] {
+        state.record_fill(fill(Venue::Binance, "BTC", PositionSide::Long, size));
+    }
+    state.record_position_closed(
+        Venue::Binance,
+        "BTC".to_string(),
+        PositionSide::Long,
+        Decimal::ONE,
+    );
+    state.record_fill(fill(Venue::Binance, "ETH", PositionSide::Short, 4));
+    state.record_fill(fill(Venue::Binance, "ETH", PositionSide::Long, 4));
+
+    let mut registry = ConnectorRegistry::new();
+    registry.register(Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 4)]),
+    ));
+
+    let found = reconcile_positions(&registry, &[Venue::Binance], &state).await;
+
+    assert!(found.is_empty(), "{:?}", found);
+    assert_eq!(state.positions.len(), 1);
+}
+
+fn fill(venue: Venue, symbol: &str, side: PositionSide, size: i64) -> Trade {
+    Trade {
+        symbol: symbol.to_string(),
+        venue,
+        side,
+        size: Decimal::from(size),
+        price: Decimal::from(100),
+        timestamp: Utc::now(),
+        status: TradeStatus::Filled,
+        order_id: Some("1".to_string()),
+    }
+}
+
+fn reconciler(
+    short: Arc<Holdings>,
+    long: Arc<Holdings>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+    auto_flatten_orphans: bool,
+) -> PositionReconciler {
+    reconciler_with_grace(short, long, state, risk_engine, auto_flatten_orphans, 0)
+}
+
+fn reconciler_with_grace(
+    short: Arc<Holdings>,
+    long: Arc<Holdings>,
+    state: Arc<SharedState>,
+    risk_engine: Arc<RiskEngine>,
+    auto_flatten_orphans: bool,
+    orphan_grace_ms: u64,
+) -> PositionReconciler {
+    let mut registry = ConnectorRegistry::new();
+    registry.register(short);
+    registry.register(long);
+    PositionReconciler::new(
+        Arc::new(registry),
+        vec![Venue::Hyperliquid, Venue::Binance],
+        state,
+        risk_engine,
+        ReconciliationConfig {
+            interval_ms: 60000,
+            auto_flatten_orphans,
+            orphan_grace_ms,
+        },
+        false,
+    )
+}
+
+fn risk_engine() -> Arc<RiskEngine> {
+    Arc::new(RiskEngine::new(risk_config()))
+}
+
+fn open_hedge(state: &SharedState) -> String {
+    let hedge = Hedge::new("BTC", Venue::Hyperliquid, Venue::Binance, Decimal::from(2));
+    let id = hedge.id.clone();
+    state.hedges.insert(id.clone(), hedge);
+    id
+}
+
+#[tokio::test]
+async fn rebuilds_risk_exposure_and_balances_from_the_venues() {
+    let short = Holdings::new(
+        Venue::Hyperliquid,
+        Some(vec![position(Venue::Hyperliquid, "BTC", PositionSide::Short, 2)]),
+    );
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 2)]),
+    );
+    let state = Arc::new(SharedState::new());
+    let risk_engine = risk_engine();
+    // Bookkeeping from a trade the venues never saw
+    risk_engine.record_trade(&Trade {
+        symbol: "ETH".to_string(),
+        venue: Venue::Binance,
+        side: PositionSide::Long,
+        size: Decimal::from(10),
+        price: Decimal::from(100),
+        timestamp: Utc::now(),
+        status: TradeStatus::Filled,
+        order_id: None,
+    });
+
+    reconciler(short, long, state.clone(), risk_engine.clone(), false)
+        .reconcile()
+        .await;
+
+    assert_eq!(risk_engine.exposure("BTC"), Decimal::from(400));
+    assert_eq!(risk_engine.exposure("ETH"), Decimal::ZERO);
+    assert_eq!(state.balances.len(), 2);
+}
+
+#[tokio::test]
+async fn orphaned_leg_is_alerted_but_left_alone_by_default() {
+    let short = Holdings::new(
+        Venue::Hyperliquid,
+        Some(vec![position(Venue::Hyperliquid, "BTC", PositionSide::Short, 2)]),
+    );
+    let long = Holdings::new(Venue::Binance, Some(Vec::new()));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state);
+
+    reconciler(short.clone(), long, state.clone(), risk_engine(), false)
+        .reconcile()
+        .await;
+
+    let orphan = state.orphans.get(&id).unwrap().clone();
+    assert_eq!(orphan.venue, Venue::Hyperliquid);
+    assert_eq!(orphan.missing_venue, Venue::Binance);
+    assert!(!orphan.flattened);
+    assert!(short.orders().is_empty());
+    assert!(state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn orphaned_leg_is_flattened_when_enabled() {
+    let short = Holdings::new(Venue::Hyperliquid, Some(Vec::new()));
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 2)]),
+    );
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state);
+
+    reconciler(short, long.clone(), state.clone(), risk_engine(), true)
+        .reconcile()
+        .await;
+
+    let orders = long.orders();
+    assert_eq!(orders.len(), 1);
+    assert_eq!((orders[0].1.clone(), orders[0].2), (PositionSide::Short, Decimal::from(2)));
+    assert!(orders[0].3.reduce_only);
+    assert!(state.orphans.get(&id).unwrap().flattened);
+    assert!(!state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn orphaned_leg_is_not_flattened_within_its_grace_period() {
+    let short = Holdings::new(Venue::Hyperliquid, Some(Vec::new()));
+    let long = Holdings::new(
+        Venue::Binance,
+        Some(vec![position(Venue::Binance, "BTC", PositionSide::Long, 2)]),
+    );
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state);
+
+    // A close under way elsewhere may have taken the short leg off first
+    reconciler_with_grace(short, long.clone(), state.clone(), risk_engine(), true, 60000)
+        .reconcile()
+        .await;
+
+    assert!(long.orders().is_empty());
+    assert!(!state.orphans.get(&id).unwrap().flattened);
+    assert!(state.hedges.get(&id).unwrap().is_open());
+}
+
+#[tokio::test]
+async fn orphaned_leg_is_flattened_in_the_venues_contracts() {
+    // 20 contracts of 0.1 BTC are the whole 2 BTC of the hedge
+    let short = Holdings::with_multiplier(
+        Venue::Hyperliquid,
+        Some(vec![position(Venue::Hyperliquid, "BTC", PositionSide::Short, 20)]),
+        Decimal::new(1, 1),
+    );
+    let long = Holdings::new(Venue::Binance, Some(Vec::new()));
+    let state = Arc::new(SharedState::new());
+    let id = open_hedge(&state);
+
+    reconciler(short.clone(), long, state.clone(), risk_engine(), true)
+        .reconcile()
+        .await;
+
+    let orders = short.orders();
+    assert_eq!(orders.len(), 1);
+    assert_eq!((orders[0].1.clone(), orders[0].2), (PositionSide::Long, Decimal::from(20)));
+    assert!(state.orphans.get(&id).unwrap().flattened);
+}
+
+#[tokio::test]
+async fn spot_fills_are_not_reconciled_as_positions() {
+    let short = Holdings::new(Venue::Hyperliquid, Some(Vec::new()));
+    let long = Holdings::new(Venue::Binance, Some(Vec::new()));
+    let state = Arc::new(SharedState::new());
+    state.record_fill(fill(Venue::HyperEvm, "HYPE", PositionSide::Long, 10));
+
+    let found = reconciler(short, long, state.clone(), risk_engine(), false)
+        .reconcile()
+        .await;
+
+    assert!(found.is_empty());
+    assert!(state.positions.contains_key(&Position::key(&Venue::HyperEvm, "HYPE")));
+}
@@ -0,0 +1,118 @@
+use arb_core::types::{BookLevel, ContractSpec, OrderBook, Venue};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use std::str::FromStr;
+use strategies::sizing::{size_hedge, LegMarket, SizingLimits};
+
+fn dec(value: &str) -> Decimal {
+    Decimal::from_str(value).unwrap()
+}
+
+fn spec(venue: Venue, tick: &str, lot: &str, min_notional: &str) -> ContractSpec {
+    ContractSpec {
+        symbol: "ETH".to_string(),
+        venue,
+        tick_size: dec(tick),
+        lot_size: dec(lot),
+        min_size: dec(lot),
+        min_notional: dec(min_notional),
+        contract_multiplier: Decimal::ONE,
+    }
+}
+
+fn levels(levels: &[(&str, &str)]) -> Vec<BookLevel> {
+    levels
+        .iter()
+        .map(|(price, size)| BookLevel {
+            price: dec(price),
+            size: dec(size),
+        })
+        .collect()
+}
+
+fn book(venue: Venue, bids: &[(&str, &str)], asks: &[(&str, &str)]) -> OrderBook {
+    OrderBook {
+        symbol: "ETH".to_string(),
+        venue,
+        bids: levels(bids),
+        asks: levels(asks),
+        timestamp: Utc::now(),
+    }
+}
+
+fn limits(max_notional: &str) -> SizingLimits {
+    SizingLimits {
+        max_notional: dec(max_notional),
+        max_leverage: dec("3"),
+        max_slippage_bps: dec("10"),
+    }
+}
+
+#[test]
+fn depth_within_the_band_caps_size_on_both_lot_grids() {
+    let short_spec = spec(Venue::Hyperliquid, "0.01", "0.0001", "10");
+    let long_spec = spec(Venue::Binance, "0.01", "0.001", "20");
+    // 2999 is more than 10 bps below 3000, so only 1.23456 ETH of bids count
+    let short_book = book(
+        Venue::Hyperliquid,
+        &[("3000", "1"), ("2998.5", "0.23456"), ("2990", "50")],
+        &[("3000.5", "10")],
+    );
+    let long_book = book(Venue::Binance, &[("2999", "10")], &[("2999.5", "5")]);
+
+    let size = size_hedge(
+        &LegMarket { spec: &short_spec, book: &short_book, available_margin: dec("100000") },
+        &LegMarket { spec: &long_spec, book: &long_book, available_margin: dec("100000") },
+        &limits("1000000"),
+    )
+    .unwrap();
+
+    assert_eq!(size.base_size, dec("1.234"));
+    assert_eq!(size.short_contracts, dec("1.234"));
+    assert_eq!(size.long_contracts, dec("1.234"));
+    assert_eq!(size.notional, dec("3702"));
+    assert_eq!(size.short_price, dec("2998.5"));
+    assert_eq!(size.long_price, dec("2999.5"));
+}
+
+#[test]
+fn margin_and_leverage_cap_notional() {
+    let short_spec = spec(Venue::Hyperliquid, "0.1", "0.01", "10");
+    let long_spec = spec(Venue::Bybit, "0.01", "0.01", "5");
+    let short_book = book(Venue::Hyperliquid, &[("2000", "100")], &[("2000.1", "100")]);
+    let long_book = book(Venue::Bybit, &[("1999.9", "100")], &[("2000", "100")]);
+
+    // 1000 free on the thinner account at 3x leverage buys 1.5 ETH at 2000
+    let size = size_hedge(
+        &LegMarket { spec: &short_spec, book: &short_book, available_margin: dec("5000") },
+        &LegMarket { spec: &long_spec, book: &long_book, available_margin: dec("1000") },
+        &limits("1000000"),
+    )
+    .unwrap();
+    assert_eq!(size.base_size, dec("1.5"));
+
+    // The remaining per-asset notional binds before margin does
+    let size = size_hedge(
+        &LegMarket { spec: &short_spec, book: &short_book, available_margin: dec("5000") },
+        &LegMarket { spec: &long_spec, book: &long_book, available_margin: dec("1000") },
+        &limits("999"),
+    )
+    .unwrap();
+    assert_eq!(size.base_size, dec("0.49"));
+}
+
+#[test]
+fn sizes_below_venue_minimums_are_refused() {
+    let short_spec = spec(Venue::Hyperliquid, "0.1", "0.01", "10");
+    let long_spec = spec(Venue::Binance, "0.01", "0.001", "100");
+    let short_book = book(Venue::Hyperliquid, &[("2000", "100")], &[("2000.1", "100")]);
+    let long_book = book(Venue::Binance, &[("1999.9", "100")], &[("2000", "100")]);
+
+    let err = size_hedge(
+        &LegMarket { spec: &short_spec, book: &short_book, available_margin: dec("5000") },
+        &LegMarket { spec: &long_spec, book: &long_book, available_margin: dec("5000") },
+        &limits("50"),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("below the minimum"), "{}", err);
+}